@@ -0,0 +1,20 @@
+use starknet::syscalls::{
+    emit_event_syscall, get_execution_info_v2_syscall, storage_read_syscall, storage_write_syscall,
+};
+use starknet::{StorageAddress, SyscallResultTrait};
+
+// Increments the value stored at `key`, and emits an event with the old and new values and the
+// current block number.
+#[executable]
+fn main(key: felt252, increment: felt252) -> felt252 {
+    let address: StorageAddress = key.try_into().unwrap();
+    let value = storage_read_syscall(0, address).unwrap_syscall();
+    let new_value = value + increment;
+    storage_write_syscall(0, address, new_value).unwrap_syscall();
+
+    let block_info = get_execution_info_v2_syscall().unwrap_syscall().unbox().block_info.unbox();
+    let block_number: felt252 = block_info.block_number.into();
+    emit_event_syscall(array![key].span(), array![value, new_value, block_number].span())
+        .unwrap_syscall();
+    new_value
+}
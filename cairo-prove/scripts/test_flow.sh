@@ -13,6 +13,9 @@ echo "📦 Building cairo-prove..."
 echo "🔨 Compiling example..."
 cd example
 scarb --profile release build
+scarb --profile release-gas build
+cd ../example_syscalls
+scarb --profile release build
 cd ..
 
 # Generate proof
@@ -23,4 +26,16 @@ echo "🔍 Generating proof..."
 echo "✅ Verifying proof..."
 ./target/release/cairo-prove verify ./example_proof.json
 
+# Check the example with gas tracking
+echo "⛽ Checking example with gas..."
+./target/release/cairo-prove prove example/target/release-gas/example.executable.json \
+    --arguments 100 --gas 100000000 --check-only
+
+# Check the syscalls example against a local state
+echo "🗄️ Checking syscalls example..."
+echo '{"storage": {"0x7": "0xa"}}' > ./example_syscall_state.json
+./target/release/cairo-prove prove example_syscalls/target/release/example_syscalls.executable.json \
+    --arguments 7,5 --check-only \
+    --syscall-state ./example_syscall_state.json --syscall-output ./example_syscall_output.json
+
 echo "✨ Test flow completed successfully!"
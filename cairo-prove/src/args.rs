@@ -20,16 +20,37 @@ pub enum Commands {
     Prove {
         /// Path to the target file
         target: PathBuf,
-        /// Path to the proof file
-        proof: PathBuf,
+        /// Path to the proof file. Not needed with `--check-only`.
+        #[arg(required_unless_present = "check_only")]
+        proof: Option<PathBuf>,
         /// The format of the proof output.
         /// - json: Standard JSON format (default)
         /// - cairo_serde: Array of field elements serialized as hex strings, ex. `["0x1", "0x2"]`
         #[arg(long, value_enum, default_value_t = ProofFormat::Json)]
         proof_format: ProofFormat,
+        /// Only check that the program is provable, without generating a proof: all opcodes and
+        /// builtins are supported, the constraints are satisfied and the lookups are balanced.
+        #[arg(long)]
+        check_only: bool,
         /// Program arguments
         #[command(flatten)]
         program_arguments: ProgramArguments,
+        /// Starknet syscall emulation
+        #[command(flatten)]
+        syscall_arguments: SyscallArguments,
+    },
+    /// Profile the proving cost of a target file by Cairo function
+    Profile {
+        /// Path to the target file
+        target: PathBuf,
+        /// Path to the output file, in the folded stacks format of flamegraph tools.
+        output: PathBuf,
+        /// Program arguments
+        #[command(flatten)]
+        program_arguments: ProgramArguments,
+        /// Starknet syscall emulation
+        #[command(flatten)]
+        syscall_arguments: SyscallArguments,
     },
     /// Verify a proof
     Verify {
@@ -50,11 +71,15 @@ pub struct ProgramArguments {
     /// Serialized arguments to the executable function from a file.
     #[arg(long, conflicts_with = "arguments")]
     pub arguments_file: Option<Utf8PathBuf>,
+
+    /// Initial gas budget. Required by executables built with `enable-gas = true`.
+    #[arg(long)]
+    pub gas: Option<u32>,
 }
 impl ProgramArguments {
-    pub fn read_arguments(self) -> Vec<Arg> {
-        if let Some(path) = self.arguments_file {
-            let file = std::fs::File::open(&path).unwrap();
+    pub fn read_arguments(&self) -> Vec<Arg> {
+        if let Some(path) = &self.arguments_file {
+            let file = std::fs::File::open(path).unwrap();
             let as_vec: Vec<BigUintAsHex> = serde_json::from_reader(file).unwrap();
             as_vec
                 .into_iter()
@@ -68,3 +93,15 @@ impl ProgramArguments {
         }
     }
 }
+
+#[derive(Parser, Debug, Clone, Default)]
+pub struct SyscallArguments {
+    /// Serve the storage, `get_execution_info` and `emit_event` syscalls from a local JSON state
+    /// file.
+    #[arg(long)]
+    pub syscall_state: Option<PathBuf>,
+
+    /// Path to write the storage diff and events of the execution to, as JSON.
+    #[arg(long, requires = "syscall_state")]
+    pub syscall_output: Option<PathBuf>,
+}
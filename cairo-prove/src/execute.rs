@@ -1,24 +1,116 @@
 use std::collections::HashMap;
 
-use cairo_lang_casm::hints::Hint;
+use cairo_lang_casm::hints::{ExternalHint, Hint};
+use cairo_lang_casm::operand::{CellRef, Register, ResOperand};
 use cairo_lang_executable::executable::{EntryPointKind, Executable};
 use cairo_lang_runner::{Arg, CairoHintProcessor, build_hints_dict};
+use cairo_lang_utils::bigint::BigIntAsHex;
 use cairo_vm::Felt252;
 use cairo_vm::cairo_run::{CairoRunConfig, cairo_run_program};
+use cairo_vm::hint_processor::hint_processor_definition::HintProcessor;
+use cairo_vm::serde::deserialize_program::{ApTracking, FlowTrackingData, HintParams};
 use cairo_vm::types::layout_name::LayoutName;
 use cairo_vm::types::program::Program;
 use cairo_vm::types::relocatable::MaybeRelocatable;
 use cairo_vm::vm::runners::cairo_runner::CairoRunner;
 use log::info;
 
+use crate::syscalls::{SyscallHandler, SyscallOutput, SyscallState};
+
+/// The index of the initial gas in the run parameters, after the program arguments.
+const INITIAL_GAS_PARAM: usize = 1;
+/// The hint added to the entry code of gas-enabled executables, to write the initial gas.
+const INITIAL_GAS_HINT: &str = "initial_gas";
+/// The encoding of `ap += imm`, which starts the entry code of executables.
+const ADD_AP_IMM: u64 = 0x0407_8001_7fff_7fff;
+
 /// Executes a Cairo program and returns a `CairoRunner` that can be used to generate artifacts for
 /// the prover.
-pub fn execute(executable: Executable, args: Vec<Arg>) -> CairoRunner {
-    let (program, string_to_hint) = program_and_hints_from_executable(&executable);
+///
+/// `gas` is the initial gas budget, required by gas-enabled executables only. Returns an error if
+/// the executable's entry code is unexpected, or if `gas` doesn't match it.
+pub fn execute(
+    executable: Executable,
+    args: Vec<Arg>,
+    gas: Option<u32>,
+) -> Result<CairoRunner, String> {
+    let (program, string_to_hint) = program_and_hints_from_executable(&executable, gas)?;
+    let mut hint_processor = hint_processor(args, gas, string_to_hint);
+    Ok(run(&program, &mut hint_processor))
+}
+
+/// Executes a Cairo program, serving its Starknet syscalls from `state`. Returns a `CairoRunner`
+/// that can be used to generate artifacts for the prover, and the effects of the syscalls.
+pub fn execute_with_syscalls(
+    executable: Executable,
+    args: Vec<Arg>,
+    gas: Option<u32>,
+    state: SyscallState,
+) -> Result<(CairoRunner, SyscallOutput), String> {
+    let (program, string_to_hint) = program_and_hints_from_executable(&executable, gas)?;
+    let mut hint_processor = SyscallHandler::new(hint_processor(args, gas, string_to_hint), state);
+    let runner = run(&program, &mut hint_processor);
+    Ok((runner, hint_processor.into_output()))
+}
+
+/// Returns whether the executed `main` takes a gas counter. The execution starts with the entry
+/// code of the executable's standalone entry point, see [`allocates_gas_cell`].
+pub fn is_gas_enabled(runner: &CairoRunner) -> Result<bool, String> {
+    let initial_pc = runner
+        .relocated_trace
+        .as_ref()
+        .and_then(|trace| trace.first())
+        .expect("The execution trace is relocated")
+        .pc;
+    let entry_code = runner.relocated_memory[initial_pc..]
+        .iter()
+        .map_while(|value| *value);
+    allocates_gas_cell(entry_code, runner.get_program().iter_builtins().count())
+}
+
+/// Returns the gas left at the end of a gas-enabled execution. `main` returns it before the
+/// builtin pointers.
+pub fn remaining_gas(runner: &CairoRunner) -> Result<Option<Felt252>, String> {
+    if !is_gas_enabled(runner)? {
+        return Ok(None);
+    }
+    let n_builtins = runner.get_program().iter_builtins().count();
+    let address = (runner.vm.get_ap() - (n_builtins + 1)).unwrap();
+    Ok(Some(runner.vm.get_integer(address).unwrap().into_owned()))
+}
+
+/// Returns whether `entry_code` allocates a gas counter after the pointers of the entry point's
+/// `n_builtins` builtins. The entry point metadata of the executable lists the builtins only, so
+/// this reads the entry code's first instruction, which allocates `main`'s arguments: exactly
+/// `ap += n_builtins + 1` with a gas counter, and `ap += n_builtins` without one.
+fn allocates_gas_cell(
+    entry_code: impl IntoIterator<Item = Felt252>,
+    n_builtins: usize,
+) -> Result<bool, String> {
+    let mut entry_code = entry_code.into_iter();
+    if entry_code.next() != Some(Felt252::from(ADD_AP_IMM)) {
+        return Err("The entry code doesn't start by allocating the arguments of `main`".into());
+    }
+    match entry_code.next() {
+        Some(n) if n == Felt252::from(n_builtins + 1) => Ok(true),
+        Some(n) if n == Felt252::from(n_builtins) => Ok(false),
+        n => Err(format!(
+            "The entry code allocates {n:?} cells for {n_builtins} builtins"
+        )),
+    }
+}
+
+fn hint_processor(
+    args: Vec<Arg>,
+    gas: Option<u32>,
+    string_to_hint: HashMap<String, Hint>,
+) -> CairoHintProcessor<'static> {
+    let mut user_args = vec![vec![Arg::Array(args)]];
+    user_args.extend(gas.map(|gas| vec![Arg::Value(Felt252::from(gas))]));
 
-    let mut hint_processor = CairoHintProcessor {
+    CairoHintProcessor {
         runner: None,
-        user_args: vec![vec![Arg::Array(args)]],
+        user_args,
         string_to_hint,
         starknet_state: Default::default(),
         run_resources: Default::default(),
@@ -26,8 +118,10 @@ pub fn execute(executable: Executable, args: Vec<Arg>) -> CairoRunner {
         no_temporary_segments: false,
         markers: Default::default(),
         panic_traceback: Default::default(),
-    };
+    }
+}
 
+fn run(program: &Program, hint_processor: &mut dyn HintProcessor) -> CairoRunner {
     let cairo_run_config = CairoRunConfig {
         trace_enabled: true,
         relocate_mem: true,
@@ -41,14 +135,17 @@ pub fn execute(executable: Executable, args: Vec<Arg>) -> CairoRunner {
     };
 
     info!("Executing program...");
-    let runner = cairo_run_program(&program, &cairo_run_config, &mut hint_processor)
+    let runner = cairo_run_program(program, &cairo_run_config, hint_processor)
         .expect("Failed to execute program");
     info!("Program executed successfully.");
     runner
 }
 
 // TODO(Ohad): use cairo_lang_utils::program_and_hints_from_executable after updating `Cairo`.
-fn program_and_hints_from_executable(executable: &Executable) -> (Program, HashMap<String, Hint>) {
+fn program_and_hints_from_executable(
+    executable: &Executable,
+    gas: Option<u32>,
+) -> Result<(Program, HashMap<String, Hint>), String> {
     let data: Vec<MaybeRelocatable> = executable
         .program
         .bytecode
@@ -56,12 +153,53 @@ fn program_and_hints_from_executable(executable: &Executable) -> (Program, HashM
         .map(Felt252::from)
         .map(MaybeRelocatable::from)
         .collect();
-    let (hints, string_to_hint) = build_hints_dict(&executable.program.hints);
+    let (mut hints, mut string_to_hint) = build_hints_dict(&executable.program.hints);
     let entrypoint = executable
         .entrypoints
         .iter()
         .find(|e| matches!(e.kind, EntryPointKind::Standalone))
         .expect("Failed to find entrypoint");
+
+    let n_builtins = entrypoint.builtins.len();
+    let entry_code = executable.program.bytecode[entrypoint.offset..]
+        .iter()
+        .map(Felt252::from);
+    match (allocates_gas_cell(entry_code, n_builtins)?, gas) {
+        (true, Some(_)) => {
+            // Write the initial gas to its cell before the entry code skips over it.
+            hints.entry(entrypoint.offset).or_default().insert(
+                0,
+                HintParams {
+                    code: INITIAL_GAS_HINT.to_string(),
+                    accessible_scopes: vec![],
+                    flow_tracking_data: FlowTrackingData {
+                        ap_tracking: ApTracking::new(),
+                        reference_ids: HashMap::new(),
+                    },
+                },
+            );
+            string_to_hint.insert(
+                INITIAL_GAS_HINT.to_string(),
+                Hint::External(ExternalHint::WriteRunParam {
+                    index: ResOperand::Immediate(BigIntAsHex {
+                        value: INITIAL_GAS_PARAM.into(),
+                    }),
+                    dst: CellRef {
+                        register: Register::AP,
+                        offset: n_builtins as i16,
+                    },
+                }),
+            );
+        }
+        (true, None) => {
+            return Err("The executable is gas-enabled, an initial gas budget is required".into());
+        }
+        (false, Some(_)) => {
+            return Err("The executable isn't gas-enabled, but a gas budget was given".into());
+        }
+        (false, None) => {}
+    }
+
     let program = Program::new_for_proof(
         entrypoint.builtins.clone(),
         data,
@@ -74,5 +212,20 @@ fn program_and_hints_from_executable(executable: &Executable) -> (Program, HashM
         None,
     )
     .unwrap();
-    (program, string_to_hint)
+    Ok((program, string_to_hint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocates_gas_cell() {
+        let entry_code = |n_cells: usize| [Felt252::from(ADD_AP_IMM), Felt252::from(n_cells)];
+
+        assert_eq!(allocates_gas_cell(entry_code(3), 2), Ok(true));
+        assert_eq!(allocates_gas_cell(entry_code(2), 2), Ok(false));
+        assert!(allocates_gas_cell(entry_code(4), 2).is_err());
+        assert!(allocates_gas_cell([Felt252::from(2)], 2).is_err());
+    }
 }
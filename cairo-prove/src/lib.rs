@@ -1,3 +1,5 @@
 pub mod args;
 pub mod execute;
+pub mod profile;
 pub mod prove;
+pub mod syscalls;
@@ -4,50 +4,94 @@ use std::time::Instant;
 use cairo_air::utils::{ProofFormat, serialize_proof_to_file};
 use cairo_air::verifier::verify_cairo;
 use cairo_air::{CairoProof, PreProcessedTraceVariant};
+use cairo_lang_executable::executable::Executable;
 use cairo_lang_runner::Arg;
-use cairo_prove::args::{Cli, Commands, ProgramArguments};
-use cairo_prove::execute::execute;
-use cairo_prove::prove::{prove, prover_input_from_runner};
+use cairo_prove::args::{Cli, Commands, ProgramArguments, SyscallArguments};
+use cairo_prove::execute::{execute, execute_with_syscalls, remaining_gas};
+use cairo_prove::profile::{function_map, profile};
+use cairo_prove::prove::{check, prove, prover_input_from_runner, secure_pcs_config};
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
 use clap::Parser;
 use log::{error, info};
-use stwo_cairo_prover::stwo_prover::core::fri::FriConfig;
 use stwo_cairo_prover::stwo_prover::core::pcs::PcsConfig;
 use stwo_cairo_prover::stwo_prover::core::vcs::blake2_merkle::{
     Blake2sMerkleChannel, Blake2sMerkleHasher,
 };
 
+/// Executes the target, emulating its Starknet syscalls if a syscall state is given.
+fn execute_target(
+    executable: Executable,
+    args: Vec<Arg>,
+    gas: Option<u32>,
+    syscall_arguments: SyscallArguments,
+) -> CairoRunner {
+    let runner = match syscall_arguments.syscall_state {
+        None => execute(executable, args, gas),
+        Some(syscall_state) => {
+            let state = serde_json::from_reader(std::fs::File::open(&syscall_state).unwrap())
+                .expect("Failed to read syscall state");
+            execute_with_syscalls(executable, args, gas, state).map(|(runner, output)| {
+                info!(
+                    "Syscalls wrote {} storage values and emitted {} events",
+                    output.storage_diff.len(),
+                    output.events.len()
+                );
+
+                if let Some(syscall_output) = syscall_arguments.syscall_output {
+                    serde_json::to_writer_pretty(
+                        std::fs::File::create(&syscall_output).unwrap(),
+                        &output,
+                    )
+                    .expect("Failed to write syscall output");
+                    info!("Syscall output saved to: {:?}", syscall_output);
+                }
+                runner
+            })
+        }
+    };
+    let runner = runner.unwrap_or_else(|e| {
+        error!("Failed to execute target: {e}");
+        std::process::exit(1);
+    });
+
+    // The execution already checked the entry code.
+    if let Some(remaining_gas) = remaining_gas(&runner).unwrap() {
+        info!("Remaining gas: {remaining_gas}");
+    }
+    runner
+}
+
 fn execute_and_prove(
     target_path: &str,
     args: Vec<Arg>,
+    gas: Option<u32>,
+    syscall_arguments: SyscallArguments,
     pcs_config: PcsConfig,
 ) -> CairoProof<Blake2sMerkleHasher> {
     // Execute.
     let executable = serde_json::from_reader(std::fs::File::open(target_path).unwrap())
         .expect("Failed to read executable");
-    let runner = execute(executable, args);
+    let runner = execute_target(executable, args, gas, syscall_arguments);
 
     // Prove.
     let prover_input = prover_input_from_runner(&runner);
     prove(prover_input, pcs_config)
 }
 
-fn secure_pcs_config() -> PcsConfig {
-    PcsConfig {
-        pow_bits: 26,
-        fri_config: FriConfig {
-            log_last_layer_degree_bound: 0,
-            log_blowup_factor: 1,
-            n_queries: 70,
-        },
-    }
-}
-
-fn handle_prove(target: &Path, proof: &Path, proof_format: ProofFormat, args: ProgramArguments) {
+fn handle_prove(
+    target: &Path,
+    proof: &Path,
+    proof_format: ProofFormat,
+    args: ProgramArguments,
+    syscall_arguments: SyscallArguments,
+) {
     info!("Generating proof for target: {:?}", target);
     let start = Instant::now();
     let cairo_proof = execute_and_prove(
         target.to_str().unwrap(),
         args.read_arguments(),
+        args.gas,
+        syscall_arguments,
         secure_pcs_config(),
     );
     let elapsed = start.elapsed();
@@ -59,6 +103,58 @@ fn handle_prove(target: &Path, proof: &Path, proof_format: ProofFormat, args: Pr
     info!("Proof generation completed in {:.2?}", elapsed);
 }
 
+fn handle_check(target: &Path, args: ProgramArguments, syscall_arguments: SyscallArguments) {
+    info!("Checking target: {:?}", target);
+    let executable = serde_json::from_reader(std::fs::File::open(target).unwrap())
+        .expect("Failed to read executable");
+    let runner = execute_target(
+        executable,
+        args.read_arguments(),
+        args.gas,
+        syscall_arguments,
+    );
+
+    match check(&runner) {
+        Ok(report) if report.is_ok() => info!("{report}"),
+        Ok(report) => {
+            error!("{report}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("Adapter failed: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_profile(
+    target: &Path,
+    output: &Path,
+    args: ProgramArguments,
+    syscall_arguments: SyscallArguments,
+) {
+    info!("Profiling target: {:?}", target);
+    let executable: serde_json::Value =
+        serde_json::from_reader(std::fs::File::open(target).unwrap())
+            .expect("Failed to read executable");
+    let functions = function_map(&executable);
+    let executable = serde_json::from_value(executable).expect("Failed to read executable");
+    let runner = execute_target(
+        executable,
+        args.read_arguments(),
+        args.gas,
+        syscall_arguments,
+    );
+
+    let profile = profile(&runner);
+    profile
+        .write_folded(&functions, std::fs::File::create(output).unwrap())
+        .expect("Failed to write profile");
+
+    info!("Profile saved to: {:?}", output);
+    info!("Total trace cells: {}", profile.total_trace_cells());
+}
+
 fn handle_verify(proof: &Path, with_pedersen: bool) {
     info!("Verifying proof from: {:?}", proof);
     let cairo_proof =
@@ -85,10 +181,26 @@ fn main() {
             target,
             proof,
             proof_format,
+            check_only,
             program_arguments,
-        } => {
-            handle_prove(&target, &proof, proof_format, program_arguments);
-        }
+            syscall_arguments,
+        } => match (check_only, proof) {
+            (true, _) => handle_check(&target, program_arguments, syscall_arguments),
+            (false, Some(proof)) => handle_prove(
+                &target,
+                &proof,
+                proof_format,
+                program_arguments,
+                syscall_arguments,
+            ),
+            (false, None) => unreachable!("The proof path is required without `--check-only`"),
+        },
+        Commands::Profile {
+            target,
+            output,
+            program_arguments,
+            syscall_arguments,
+        } => handle_profile(&target, &output, program_arguments, syscall_arguments),
         Commands::Verify {
             proof,
             with_pedersen,
@@ -100,6 +212,9 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use cairo_prove::syscalls::{Event, SyscallState};
     use cairo_vm::Felt252;
     use num_bigint::BigInt;
 
@@ -109,7 +224,86 @@ mod tests {
     fn test_e2e() {
         let target_path = "./example/target/release/example.executable.json";
         let args = vec![Arg::Value(Felt252::from(BigInt::from(100)))];
-        let proof = execute_and_prove(target_path, args, PcsConfig::default());
+        let proof = execute_and_prove(
+            target_path,
+            args,
+            None,
+            SyscallArguments::default(),
+            PcsConfig::default(),
+        );
+        let pcs_config = PcsConfig::default();
+        let preprocessed_trace = PreProcessedTraceVariant::CanonicalWithoutPedersen;
+        let result = verify_cairo::<Blake2sMerkleChannel>(proof, pcs_config, preprocessed_trace);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_check_only() {
+        let target_path = "./example/target/release/example.executable.json";
+        let executable = serde_json::from_reader(std::fs::File::open(target_path).unwrap())
+            .expect("Failed to read executable");
+        let args = vec![Arg::Value(Felt252::from(BigInt::from(100)))];
+        let runner = execute(executable, args, None).unwrap();
+
+        let report = check(&runner).unwrap();
+
+        assert!(report.is_ok(), "{report}");
+    }
+
+    #[test]
+    fn test_profile() {
+        let target_path = "./example/target/release/example.executable.json";
+        let executable = serde_json::from_reader(std::fs::File::open(target_path).unwrap())
+            .expect("Failed to read executable");
+        let args = vec![Arg::Value(Felt252::from(BigInt::from(100)))];
+        let runner = execute(executable, args, None).unwrap();
+
+        let profile = profile(&runner);
+
+        assert!(profile.total_trace_cells() > 0);
+    }
+
+    #[test]
+    fn test_syscalls() {
+        let target_path = "./example_syscalls/target/release/example_syscalls.executable.json";
+        let executable = serde_json::from_reader(std::fs::File::open(target_path).unwrap())
+            .expect("Failed to read executable");
+        let key = Felt252::from(7);
+        let args = vec![Arg::Value(key), Arg::Value(Felt252::from(5))];
+        let mut state = SyscallState::default();
+        state.storage.insert(key, Felt252::from(10));
+        state.execution_info.block_info.block_number = 1234;
+
+        let (runner, output) = execute_with_syscalls(executable, args, None, state).unwrap();
+
+        assert_eq!(
+            output.storage_diff,
+            BTreeMap::from([(key, Felt252::from(15))])
+        );
+        assert_eq!(
+            output.events,
+            vec![Event {
+                keys: vec![key],
+                data: [10, 15, 1234].map(Felt252::from).to_vec(),
+            }]
+        );
+        let report = check(&runner).unwrap();
+        assert!(report.is_ok(), "{report}");
+    }
+
+    #[test]
+    fn test_gas() {
+        let target_path = "./example/target/release-gas/example.executable.json";
+        let executable = serde_json::from_reader(std::fs::File::open(target_path).unwrap())
+            .expect("Failed to read executable");
+        let args = vec![Arg::Value(Felt252::from(BigInt::from(100)))];
+        let initial_gas = 100_000_000;
+        let runner = execute(executable, args, Some(initial_gas)).unwrap();
+
+        let remaining_gas = remaining_gas(&runner).unwrap().unwrap();
+        assert!(remaining_gas < Felt252::from(initial_gas));
+
+        let proof = prove(prover_input_from_runner(&runner), PcsConfig::default());
         let pcs_config = PcsConfig::default();
         let preprocessed_trace = PreProcessedTraceVariant::CanonicalWithoutPedersen;
         let result = verify_cairo::<Blake2sMerkleChannel>(proof, pcs_config, preprocessed_trace);
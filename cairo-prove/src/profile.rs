@@ -0,0 +1,51 @@
+use cairo_vm::vm::runners::cairo_runner::CairoRunner;
+use log::warn;
+use serde_json::Value;
+use stwo_cairo_prover::profiler::{ExecutionProfile, FunctionMap, profile_execution};
+
+use crate::prove::prover_input_from_runner;
+
+/// The address the program segment is relocated to.
+const PROGRAM_BASE: u32 = 1;
+
+/// The annotation Scarb uses to map sierra statements to the functions they belong to.
+const PROFILER_ANNOTATION: &str = "github.com/software-mansion/cairo-profiler";
+
+/// Builds a function map from the debug info of a Scarb executable: every sierra statement maps
+/// its first casm instruction to the functions it's inlined in.
+///
+/// Returns an empty map, whose frames are pcs, if the executable wasn't built with the profiler
+/// annotations (`[cairo] unstable-add-statements-functions-debug-info = true`).
+pub fn function_map(executable: &Value) -> FunctionMap {
+    let debug_info = &executable["debug_info"];
+    let statement_offsets = debug_info["sierra_statement_info"].as_array();
+    let statement_functions =
+        debug_info["annotations"][PROFILER_ANNOTATION]["statements_functions"].as_object();
+    let (Some(statement_offsets), Some(statement_functions)) =
+        (statement_offsets, statement_functions)
+    else {
+        warn!("The executable has no function debug info, profiling by pc.");
+        return FunctionMap::default();
+    };
+
+    let functions = statement_functions
+        .iter()
+        .filter_map(|(statement, functions)| {
+            let statement: usize = statement.parse().ok()?;
+            let offset = statement_offsets.get(statement)?["start_offset"].as_u64()?;
+            // The annotation lists the innermost function first.
+            let functions = functions
+                .as_array()?
+                .iter()
+                .rev()
+                .map(|function| Some(function.as_str()?.to_string()))
+                .collect::<Option<Vec<_>>>()?;
+            Some((offset as u32, functions))
+        });
+    FunctionMap::new(PROGRAM_BASE, functions)
+}
+
+/// Profiles the proving cost of a finished execution by call stack.
+pub fn profile(runner: &CairoRunner) -> ExecutionProfile {
+    profile_execution(&prover_input_from_runner(runner))
+}
@@ -1,21 +1,28 @@
 use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
 
 use cairo_air::{CairoProof, PreProcessedTraceVariant};
 use cairo_vm::vm::runners::cairo_runner::CairoRunner;
-use log::{debug, info};
+use log::{Level, debug, info, log_enabled};
 use stwo_cairo_adapter::builtins::MemorySegmentAddresses;
 use stwo_cairo_adapter::memory::{MemoryBuilder, MemoryConfig, MemoryEntry};
 use stwo_cairo_adapter::vm_import::{RelocatedTraceEntry, adapt_to_stwo_input};
 use stwo_cairo_adapter::{ProverInput, PublicSegmentContext};
+use stwo_cairo_prover::check::{CairoCheckReport, check_cairo};
+use stwo_cairo_prover::hooks::CairoProvingError;
+use stwo_cairo_prover::panic_utils::panic_message;
+use stwo_cairo_prover::stwo_prover::core::fri::FriConfig;
 use stwo_cairo_prover::stwo_prover::core::pcs::PcsConfig;
 use stwo_cairo_prover::stwo_prover::core::vcs::blake2_merkle::{
     Blake2sMerkleChannel, Blake2sMerkleHasher,
 };
 
+use crate::execute::is_gas_enabled;
+
 /// Exctracts artifacts from a finished cairo runner, to later be used for proving.
 pub fn prover_input_from_runner(runner: &CairoRunner) -> ProverInput {
     let public_input = runner.get_air_public_input().unwrap();
-    let addresses = public_input
+    let mut addresses = public_input
         .public_memory
         .iter()
         .map(|entry| entry.address as u32)
@@ -60,42 +67,99 @@ pub fn prover_input_from_runner(runner: &CairoRunner) -> ProverInput {
         .iter_builtins()
         .copied()
         .collect::<Vec<_>>();
-    let public_segment_context = PublicSegmentContext::new(&main_args);
+    let mut public_segment_context = PublicSegmentContext::new(&main_args);
+    if is_gas_enabled(runner).unwrap() {
+        public_segment_context = public_segment_context.with_gas();
+
+        // The VM doesn't make the gas counter public, unlike the builtin pointers around it.
+        let n_builtins = public_segment_context
+            .iter()
+            .filter(|&&present| present)
+            .count() as u32;
+        let initial_ap = trace.first().unwrap().ap as u32;
+        let final_ap = trace.last().unwrap().ap as u32;
+        addresses.extend([initial_ap + n_builtins, final_ap - n_builtins - 1]);
+    }
 
     info!("Generating input for the prover...");
-    let input =
-        adapt_to_stwo_input(&trace, mem, addresses, &segments, public_segment_context).unwrap();
+    // Explaining the generic opcode fallbacks slows down the adapter, so only do it when logged.
+    let record_generic_fallbacks = log_enabled!(Level::Debug);
+    let input = adapt_to_stwo_input(
+        &trace,
+        mem,
+        addresses,
+        &segments,
+        public_segment_context,
+        record_generic_fallbacks,
+    )
+    .unwrap();
     info!("Input for the prover generated successfully.");
     debug!(
         "State transitions: {}",
         input.state_transitions.casm_states_by_opcode
     );
+    if let Some(generic_fallbacks) = &input.state_transitions.generic_fallbacks {
+        debug!("{generic_fallbacks}");
+    }
     debug!("Builtins: {:#?}", input.builtins_segments.get_counts());
     input
 }
 
+/// Checks that a finished execution is provable, without generating a proof. Returns an error if
+/// the adapter fails, e.g. on an unsupported builtin.
+pub fn check(runner: &CairoRunner) -> Result<CairoCheckReport, String> {
+    let input = panic::catch_unwind(AssertUnwindSafe(|| prover_input_from_runner(runner)))
+        .map_err(|panic| panic_message(&*panic))?;
+    let preprocessed_trace = preprocessed_trace_variant(&input);
+    Ok(check_cairo(input, preprocessed_trace))
+}
+
 /// Deduces the preprocessed trace variant needed for the specific execution, and proves.
 pub fn prove(input: ProverInput, pcs_config: PcsConfig) -> CairoProof<Blake2sMerkleHasher> {
+    try_prove(input, pcs_config).unwrap()
+}
+
+/// Same as [`prove`], returning the prover's error instead of panicking.
+pub fn try_prove(
+    input: ProverInput,
+    pcs_config: PcsConfig,
+) -> Result<CairoProof<Blake2sMerkleHasher>, CairoProvingError> {
+    let preprocessed_trace = preprocessed_trace_variant(&input);
+    prove_inner(input, preprocessed_trace, pcs_config)
+}
+
+/// Deduces the preprocessed trace variant needed to prove (and verify) the specific execution.
+pub fn preprocessed_trace_variant(input: &ProverInput) -> PreProcessedTraceVariant {
     // Currently there are two variants of the preprocessed trace:
     // - Canonical: Pedersen is included in the program.
     // - CanonicalWithoutPedersen: Pedersen is not included in the program.
     // We deduce the variant based on weather the pedersen builtin is included in the program.
-    let preprocessed_trace = match input.public_segment_context[1] {
+    match input.public_segment_context[1] {
         true => PreProcessedTraceVariant::Canonical,
         false => PreProcessedTraceVariant::CanonicalWithoutPedersen,
-    };
-    prove_inner(input, preprocessed_trace, pcs_config)
+    }
+}
+
+/// The commitment scheme parameters used for production proofs.
+pub fn secure_pcs_config() -> PcsConfig {
+    PcsConfig {
+        pow_bits: 26,
+        fri_config: FriConfig {
+            log_last_layer_degree_bound: 0,
+            log_blowup_factor: 1,
+            n_queries: 70,
+        },
+    }
 }
 
 fn prove_inner(
     input: ProverInput,
     preprocessed_trace: PreProcessedTraceVariant,
     pcs_config: PcsConfig,
-) -> CairoProof<Blake2sMerkleHasher> {
+) -> Result<CairoProof<Blake2sMerkleHasher>, CairoProvingError> {
     stwo_cairo_prover::prover::prove_cairo::<Blake2sMerkleChannel>(
         input,
         pcs_config,
         preprocessed_trace,
     )
-    .unwrap()
 }
@@ -0,0 +1,754 @@
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use cairo_lang_casm::hints::{Hint, StarknetHint};
+use cairo_lang_casm::operand::{
+    BinOpOperand, CellRef, DerefOrImmediate, Operation, Register, ResOperand,
+};
+use cairo_lang_runner::CairoHintProcessor;
+use cairo_vm::Felt252;
+use cairo_vm::hint_processor::hint_processor_definition::{HintProcessorLogic, HintReference};
+use cairo_vm::serde::deserialize_program::ApTracking;
+use cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::cairo_runner::{ResourceTracker, RunResources};
+use cairo_vm::vm::vm_core::VirtualMachine;
+use serde::{Deserialize, Serialize};
+
+/// The gas cost of a Cairo step.
+const STEP_GAS_COST: u64 = 100;
+// The gas costs of the syscalls served from the local state, as charged by the Starknet sequencer.
+// `CairoHintProcessor` charges the costs of the other syscalls.
+const STORAGE_READ_GAS_COST: u64 = 50 * STEP_GAS_COST;
+const STORAGE_WRITE_GAS_COST: u64 = 50 * STEP_GAS_COST;
+const EMIT_EVENT_GAS_COST: u64 = 10 * STEP_GAS_COST;
+const GET_EXECUTION_INFO_GAS_COST: u64 = 10 * STEP_GAS_COST;
+
+/// The local Starknet state the syscalls of an execution are served from.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyscallState {
+    /// The storage of the executed contract, by key.
+    pub storage: BTreeMap<Felt252, Felt252>,
+    /// The value returned by `get_execution_info`.
+    pub execution_info: ExecutionInfo,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionInfo {
+    pub block_info: BlockInfo,
+    pub tx_info: TxInfo,
+    pub caller_address: Felt252,
+    pub contract_address: Felt252,
+    pub entry_point_selector: Felt252,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockInfo {
+    pub block_number: u64,
+    pub block_timestamp: u64,
+    pub sequencer_address: Felt252,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TxInfo {
+    pub version: Felt252,
+    pub account_contract_address: Felt252,
+    pub max_fee: u128,
+    pub signature: Vec<Felt252>,
+    pub transaction_hash: Felt252,
+    pub chain_id: Felt252,
+    pub nonce: Felt252,
+    pub resource_bounds: Vec<ResourceBounds>,
+    pub tip: u128,
+    pub paymaster_data: Vec<Felt252>,
+    pub nonce_data_availability_mode: u32,
+    pub fee_data_availability_mode: u32,
+    pub account_deployment_data: Vec<Felt252>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceBounds {
+    pub resource: Felt252,
+    pub max_amount: u64,
+    pub max_price_per_unit: u128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub keys: Vec<Felt252>,
+    pub data: Vec<Felt252>,
+}
+
+/// The effects of an execution's syscalls on the local state.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SyscallOutput {
+    /// The storage values changed by the execution, by key.
+    pub storage_diff: BTreeMap<Felt252, Felt252>,
+    /// The events emitted by the execution, in order.
+    pub events: Vec<Event>,
+}
+
+/// A hint processor that serves the storage, `get_execution_info` and `emit_event` syscalls from
+/// a [`SyscallState`], and delegates all other hints, including the `keccak`,
+/// `sha256_process_block` and `secp` syscalls, to a [`CairoHintProcessor`].
+///
+/// Syscalls served from the local state are charged their standard gas costs, and fail with
+/// `Syscall out of gas` if the gas counter doesn't cover them.
+pub struct SyscallHandler<'a> {
+    inner: CairoHintProcessor<'a>,
+    initial_storage: BTreeMap<Felt252, Felt252>,
+    state: SyscallState,
+    events: Vec<Event>,
+}
+
+impl<'a> SyscallHandler<'a> {
+    pub fn new(inner: CairoHintProcessor<'a>, state: SyscallState) -> Self {
+        Self {
+            inner,
+            initial_storage: state.storage.clone(),
+            state,
+            events: vec![],
+        }
+    }
+
+    pub fn into_output(self) -> SyscallOutput {
+        let storage_diff = self
+            .state
+            .storage
+            .into_iter()
+            .filter(|(key, value)| self.initial_storage.get(key).unwrap_or(&Felt252::ZERO) != value)
+            .collect();
+        SyscallOutput {
+            storage_diff,
+            events: self.events,
+        }
+    }
+
+    /// Executes the syscall at `system_ptr` if it is served from the local state. Returns whether
+    /// it was.
+    fn execute_syscall(
+        &mut self,
+        vm: &mut VirtualMachine,
+        system_ptr: Relocatable,
+    ) -> Result<bool, HintError> {
+        let mut buffer = SyscallBuffer {
+            vm,
+            ptr: system_ptr,
+        };
+        let selector = buffer.next_felt()?.to_bytes_be();
+        let selector = std::str::from_utf8(&selector)
+            .unwrap_or_default()
+            .trim_start_matches('\0');
+        let mut gas_counter = buffer.next_felt()?;
+        // The request is read before the gas is charged, as the response follows it.
+        let mut deduct_gas = |gas_cost: u64| {
+            let gas_cost = Felt252::from(gas_cost);
+            if gas_counter < gas_cost {
+                return Err(Felt252::from_bytes_be_slice(b"Syscall out of gas"));
+            }
+            gas_counter -= gas_cost;
+            Ok(())
+        };
+
+        let result: Result<Vec<MaybeRelocatable>, Felt252> = match selector {
+            "StorageRead" => {
+                let address_domain = buffer.next_felt()?;
+                let key = buffer.next_felt()?;
+                deduct_gas(STORAGE_READ_GAS_COST)
+                    .and_then(|()| check_address_domain(address_domain))
+                    .map(|()| {
+                        let value = self.state.storage.get(&key).copied().unwrap_or_default();
+                        vec![value.into()]
+                    })
+            }
+            "StorageWrite" => {
+                let address_domain = buffer.next_felt()?;
+                let key = buffer.next_felt()?;
+                let value = buffer.next_felt()?;
+                deduct_gas(STORAGE_WRITE_GAS_COST)
+                    .and_then(|()| check_address_domain(address_domain))
+                    .map(|()| {
+                        self.state.storage.insert(key, value);
+                        vec![]
+                    })
+            }
+            "EmitEvent" => {
+                let keys = buffer.next_felt_span()?;
+                let data = buffer.next_felt_span()?;
+                deduct_gas(EMIT_EVENT_GAS_COST).map(|()| {
+                    self.events.push(Event { keys, data });
+                    vec![]
+                })
+            }
+            "GetExecutionInfo" => match deduct_gas(GET_EXECUTION_INFO_GAS_COST) {
+                Ok(()) => {
+                    let execution_info_ptr =
+                        write_execution_info(buffer.vm, &self.state.execution_info)?;
+                    Ok(vec![execution_info_ptr.into()])
+                }
+                Err(revert_reason) => Err(revert_reason),
+            },
+            _ => return Ok(false),
+        };
+
+        buffer.write(gas_counter)?;
+        match result {
+            Ok(response) => {
+                buffer.write(Felt252::ZERO)?;
+                for value in response {
+                    buffer.write(value)?;
+                }
+            }
+            Err(revert_reason) => {
+                buffer.write(Felt252::ONE)?;
+                let start = buffer.vm.add_memory_segment();
+                let end = buffer.vm.load_data(start, &[revert_reason.into()])?;
+                buffer.write(start)?;
+                buffer.write(end)?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn check_address_domain(address_domain: Felt252) -> Result<(), Felt252> {
+    if address_domain != Felt252::ZERO {
+        return Err(Felt252::from_bytes_be_slice(b"Unsupported address domain"));
+    }
+    Ok(())
+}
+
+/// Writes `execution_info`, as laid out by `starknet::info::v2::ExecutionInfo`, to a new segment
+/// and returns a pointer to it.
+fn write_execution_info(
+    vm: &mut VirtualMachine,
+    execution_info: &ExecutionInfo,
+) -> Result<Relocatable, HintError> {
+    let ExecutionInfo {
+        block_info,
+        tx_info,
+        caller_address,
+        contract_address,
+        entry_point_selector,
+    } = execution_info;
+    let mut segment = SyscallBuffer {
+        ptr: vm.add_memory_segment(),
+        vm,
+    };
+
+    let signature = segment.write_span(tx_info.signature.iter().copied())?;
+    let resource_bounds =
+        segment.write_span(tx_info.resource_bounds.iter().flat_map(|bounds| {
+            [
+                bounds.resource,
+                bounds.max_amount.into(),
+                bounds.max_price_per_unit.into(),
+            ]
+        }))?;
+    let paymaster_data = segment.write_span(tx_info.paymaster_data.iter().copied())?;
+    let account_deployment_data =
+        segment.write_span(tx_info.account_deployment_data.iter().copied())?;
+
+    let tx_info_ptr = segment.ptr;
+    segment.write(tx_info.version)?;
+    segment.write(tx_info.account_contract_address)?;
+    segment.write(Felt252::from(tx_info.max_fee))?;
+    segment.write_pair(signature)?;
+    segment.write(tx_info.transaction_hash)?;
+    segment.write(tx_info.chain_id)?;
+    segment.write(tx_info.nonce)?;
+    segment.write_pair(resource_bounds)?;
+    segment.write(Felt252::from(tx_info.tip))?;
+    segment.write_pair(paymaster_data)?;
+    segment.write(Felt252::from(tx_info.nonce_data_availability_mode))?;
+    segment.write(Felt252::from(tx_info.fee_data_availability_mode))?;
+    segment.write_pair(account_deployment_data)?;
+
+    let block_info_ptr = segment.ptr;
+    segment.write(Felt252::from(block_info.block_number))?;
+    segment.write(Felt252::from(block_info.block_timestamp))?;
+    segment.write(block_info.sequencer_address)?;
+
+    let execution_info_ptr = segment.ptr;
+    segment.write(block_info_ptr)?;
+    segment.write(tx_info_ptr)?;
+    segment.write(*caller_address)?;
+    segment.write(*contract_address)?;
+    segment.write(*entry_point_selector)?;
+    Ok(execution_info_ptr)
+}
+
+/// A cursor over consecutive memory cells.
+struct SyscallBuffer<'vm> {
+    vm: &'vm mut VirtualMachine,
+    ptr: Relocatable,
+}
+
+impl SyscallBuffer<'_> {
+    fn next_felt(&mut self) -> Result<Felt252, HintError> {
+        let value = self.vm.get_integer(self.ptr)?.into_owned();
+        self.ptr = (self.ptr + 1_usize)?;
+        Ok(value)
+    }
+
+    fn next_ptr(&mut self) -> Result<Relocatable, HintError> {
+        let value = self.vm.get_relocatable(self.ptr)?;
+        self.ptr = (self.ptr + 1_usize)?;
+        Ok(value)
+    }
+
+    /// Reads a span, given by its start and end pointers.
+    fn next_felt_span(&mut self) -> Result<Vec<Felt252>, HintError> {
+        let start = self.next_ptr()?;
+        let end = self.next_ptr()?;
+        Ok(self
+            .vm
+            .get_integer_range(start, (end - start)?)?
+            .into_iter()
+            .map(|value| value.into_owned())
+            .collect())
+    }
+
+    fn write(&mut self, value: impl Into<MaybeRelocatable>) -> Result<(), HintError> {
+        self.vm.insert_value(self.ptr, value)?;
+        self.ptr = (self.ptr + 1_usize)?;
+        Ok(())
+    }
+
+    fn write_pair(&mut self, (start, end): (Relocatable, Relocatable)) -> Result<(), HintError> {
+        self.write(start)?;
+        self.write(end)
+    }
+
+    /// Writes the values and returns their start and end pointers.
+    fn write_span(
+        &mut self,
+        values: impl IntoIterator<Item = Felt252>,
+    ) -> Result<(Relocatable, Relocatable), HintError> {
+        let start = self.ptr;
+        for value in values {
+            self.write(value)?;
+        }
+        Ok((start, self.ptr))
+    }
+}
+
+fn cell_address(vm: &VirtualMachine, cell: &CellRef) -> Result<Relocatable, HintError> {
+    let base = match cell.register {
+        Register::AP => vm.get_ap(),
+        Register::FP => vm.get_fp(),
+    };
+    Ok((base + cell.offset as i32)?)
+}
+
+fn extract_relocatable(
+    vm: &VirtualMachine,
+    operand: &ResOperand,
+) -> Result<Relocatable, HintError> {
+    match operand {
+        ResOperand::Deref(cell) => Ok(vm.get_relocatable(cell_address(vm, cell)?)?),
+        ResOperand::BinOp(BinOpOperand {
+            op: Operation::Add,
+            a,
+            b,
+        }) => {
+            let base = vm.get_relocatable(cell_address(vm, a)?)?;
+            let offset = match b {
+                DerefOrImmediate::Deref(cell) => {
+                    vm.get_integer(cell_address(vm, cell)?)?.into_owned()
+                }
+                DerefOrImmediate::Immediate(value) => Felt252::from(&value.value),
+            };
+            Ok((base + &offset)?)
+        }
+        _ => Err(HintError::CustomHint(
+            "Unsupported syscall pointer operand.".into(),
+        )),
+    }
+}
+
+impl HintProcessorLogic for SyscallHandler<'_> {
+    fn execute_hint(
+        &mut self,
+        vm: &mut VirtualMachine,
+        exec_scopes: &mut ExecutionScopes,
+        hint_data: &Box<dyn Any>,
+    ) -> Result<(), HintError> {
+        if let Some(Hint::Starknet(StarknetHint::SystemCall { system })) =
+            hint_data.downcast_ref::<Hint>()
+        {
+            let system_ptr = extract_relocatable(vm, system)?;
+            if self.execute_syscall(vm, system_ptr)? {
+                return Ok(());
+            }
+        }
+        self.inner.execute_hint(vm, exec_scopes, hint_data)
+    }
+
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &[HintReference],
+        constants: Rc<HashMap<String, Felt252>>,
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        self.inner.compile_hint(
+            hint_code,
+            ap_tracking_data,
+            reference_ids,
+            references,
+            constants,
+        )
+    }
+}
+
+impl ResourceTracker for SyscallHandler<'_> {
+    fn consumed(&self) -> bool {
+        self.inner.consumed()
+    }
+
+    fn consume_step(&mut self) {
+        self.inner.consume_step()
+    }
+
+    fn get_n_steps(&self) -> Option<usize> {
+        self.inner.get_n_steps()
+    }
+
+    fn run_resources(&self) -> &RunResources {
+        self.inner.run_resources()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INITIAL_GAS: u64 = 10_000_000;
+
+    /// Runs syscalls through a [`SyscallHandler`]. The request of the i-th syscall is written to a
+    /// new segment, pointed to by `[ap + i]`.
+    struct SyscallRunner {
+        handler: SyscallHandler<'static>,
+        vm: VirtualMachine,
+        exec_scopes: ExecutionScopes,
+        n_syscalls: i16,
+    }
+
+    impl SyscallRunner {
+        fn new(state: SyscallState) -> Self {
+            let inner = CairoHintProcessor {
+                runner: None,
+                user_args: vec![],
+                string_to_hint: HashMap::new(),
+                starknet_state: Default::default(),
+                run_resources: Default::default(),
+                syscalls_used_resources: Default::default(),
+                no_temporary_segments: false,
+                markers: Default::default(),
+                panic_traceback: Default::default(),
+            };
+            let mut vm = VirtualMachine::new(false, false);
+            // The program and execution segments.
+            vm.add_memory_segment();
+            vm.add_memory_segment();
+            Self {
+                handler: SyscallHandler::new(inner, state),
+                vm,
+                exec_scopes: ExecutionScopes::new(),
+                n_syscalls: 0,
+            }
+        }
+
+        /// Writes `values` to a new segment and returns their start and end pointers.
+        fn span(&mut self, values: &[Felt252]) -> [MaybeRelocatable; 2] {
+            let start = self.vm.add_memory_segment();
+            let values = values
+                .iter()
+                .map(MaybeRelocatable::from)
+                .collect::<Vec<_>>();
+            let end = self.vm.load_data(start, &values).unwrap();
+            [start.into(), end.into()]
+        }
+
+        /// Runs the syscall `selector` with `gas`. Returns the remaining gas, and either the first
+        /// `response_len` cells of the response or the revert reason.
+        fn call(
+            &mut self,
+            selector: &[u8],
+            gas: u64,
+            request: &[MaybeRelocatable],
+            response_len: usize,
+        ) -> (Felt252, Result<Vec<MaybeRelocatable>, Vec<Felt252>>) {
+            let system_ptr = self.vm.add_memory_segment();
+            let mut cells = vec![
+                Felt252::from_bytes_be_slice(selector).into(),
+                Felt252::from(gas).into(),
+            ];
+            cells.extend_from_slice(request);
+            let response_ptr = self.vm.load_data(system_ptr, &cells).unwrap();
+            let system = CellRef {
+                register: Register::AP,
+                offset: self.n_syscalls,
+            };
+            self.vm
+                .insert_value(cell_address(&self.vm, &system).unwrap(), system_ptr)
+                .unwrap();
+            self.n_syscalls += 1;
+            let hint: Box<dyn Any> = Box::new(Hint::Starknet(StarknetHint::SystemCall {
+                system: ResOperand::Deref(system),
+            }));
+
+            self.handler
+                .execute_hint(&mut self.vm, &mut self.exec_scopes, &hint)
+                .unwrap();
+
+            let mut response = SyscallBuffer {
+                vm: &mut self.vm,
+                ptr: response_ptr,
+            };
+            let remaining_gas = response.next_felt().unwrap();
+            let result = if response.next_felt().unwrap() == Felt252::ZERO {
+                Ok(response
+                    .vm
+                    .get_continuous_range(response.ptr, response_len)
+                    .unwrap())
+            } else {
+                Err(response.next_felt_span().unwrap())
+            };
+            (remaining_gas, result)
+        }
+    }
+
+    fn felts(values: impl IntoIterator<Item = u128>) -> Vec<MaybeRelocatable> {
+        values
+            .into_iter()
+            .map(|value| Felt252::from(value).into())
+            .collect()
+    }
+
+    #[test]
+    fn test_storage_read_write() {
+        let mut state = SyscallState::default();
+        state.storage.insert(Felt252::from(1), Felt252::from(10));
+        state.storage.insert(Felt252::from(2), Felt252::from(20));
+        let mut runner = SyscallRunner::new(state);
+
+        let (remaining_gas, value) = runner.call(b"StorageRead", INITIAL_GAS, &felts([0, 1]), 1);
+        runner.call(b"StorageWrite", INITIAL_GAS, &felts([0, 1, 11]), 0);
+        runner.call(b"StorageWrite", INITIAL_GAS, &felts([0, 2, 20]), 0);
+        runner.call(b"StorageWrite", INITIAL_GAS, &felts([0, 3, 30]), 0);
+        let (_, new_value) = runner.call(b"StorageRead", INITIAL_GAS, &felts([0, 1]), 1);
+
+        assert_eq!(
+            remaining_gas,
+            Felt252::from(INITIAL_GAS - STORAGE_READ_GAS_COST)
+        );
+        assert_eq!(value, Ok(felts([10])));
+        assert_eq!(new_value, Ok(felts([11])));
+        assert_eq!(
+            runner.handler.into_output().storage_diff,
+            BTreeMap::from(
+                [(1, 11), (3, 30)]
+                    .map(|(key, value)| { (Felt252::from(key), Felt252::from(value)) })
+            )
+        );
+    }
+
+    #[test]
+    fn test_storage_unsupported_address_domain() {
+        let mut runner = SyscallRunner::new(SyscallState::default());
+
+        let (_, result) = runner.call(b"StorageWrite", INITIAL_GAS, &felts([1, 1, 10]), 0);
+
+        assert_eq!(
+            result,
+            Err(vec![Felt252::from_bytes_be_slice(
+                b"Unsupported address domain"
+            )])
+        );
+        assert!(runner.handler.into_output().storage_diff.is_empty());
+    }
+
+    #[test]
+    fn test_syscall_out_of_gas() {
+        let mut runner = SyscallRunner::new(SyscallState::default());
+        let gas = STORAGE_WRITE_GAS_COST - 1;
+
+        let (remaining_gas, result) = runner.call(b"StorageWrite", gas, &felts([0, 1, 10]), 0);
+
+        assert_eq!(remaining_gas, Felt252::from(gas));
+        assert_eq!(
+            result,
+            Err(vec![Felt252::from_bytes_be_slice(b"Syscall out of gas")])
+        );
+        assert!(runner.handler.into_output().storage_diff.is_empty());
+    }
+
+    #[test]
+    fn test_emit_event() {
+        let mut runner = SyscallRunner::new(SyscallState::default());
+        let keys = [1, 2].map(Felt252::from);
+        let data = [3, 4, 5].map(Felt252::from);
+        let mut request = runner.span(&keys).to_vec();
+        request.extend(runner.span(&data));
+
+        let (remaining_gas, result) = runner.call(b"EmitEvent", INITIAL_GAS, &request, 0);
+
+        assert_eq!(
+            remaining_gas,
+            Felt252::from(INITIAL_GAS - EMIT_EVENT_GAS_COST)
+        );
+        assert_eq!(result, Ok(vec![]));
+        assert_eq!(
+            runner.handler.into_output().events,
+            vec![Event {
+                keys: keys.to_vec(),
+                data: data.to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_get_execution_info() {
+        let mut state = SyscallState::default();
+        state.execution_info.block_info.block_number = 1234;
+        state.execution_info.tx_info.signature = vec![Felt252::from(5), Felt252::from(6)];
+        state.execution_info.caller_address = Felt252::from(7);
+        let mut runner = SyscallRunner::new(state);
+
+        let (remaining_gas, result) = runner.call(b"GetExecutionInfo", INITIAL_GAS, &[], 1);
+
+        assert_eq!(
+            remaining_gas,
+            Felt252::from(INITIAL_GAS - GET_EXECUTION_INFO_GAS_COST)
+        );
+        let MaybeRelocatable::RelocatableValue(execution_info_ptr) = result.unwrap()[0] else {
+            panic!("The response isn't a pointer");
+        };
+        let vm = &mut runner.vm;
+        let mut execution_info = SyscallBuffer {
+            ptr: execution_info_ptr,
+            vm,
+        };
+        let block_info_ptr = execution_info.next_ptr().unwrap();
+        let tx_info_ptr = execution_info.next_ptr().unwrap();
+        let caller_address = execution_info.next_felt().unwrap();
+        assert_eq!(caller_address, Felt252::from(7));
+        execution_info.ptr = block_info_ptr;
+        assert_eq!(execution_info.next_felt().unwrap(), Felt252::from(1234));
+        // The signature follows the version, the account contract address and the max fee.
+        execution_info.ptr = (tx_info_ptr + 3_usize).unwrap();
+        assert_eq!(
+            execution_info.next_felt_span().unwrap(),
+            [5, 6].map(Felt252::from)
+        );
+    }
+
+    #[test]
+    fn test_keccak() {
+        let mut runner = SyscallRunner::new(SyscallState::default());
+        // The padded empty input, a single block of 17 u64 words.
+        let mut block = [Felt252::ZERO; 17];
+        block[0] = Felt252::ONE;
+        block[16] = Felt252::from(1_u64 << 63);
+        let request = runner.span(&block);
+
+        let (remaining_gas, result) = runner.call(b"Keccak", INITIAL_GAS, &request, 2);
+
+        assert!(remaining_gas < Felt252::from(INITIAL_GAS));
+        // The keccak256 hash of the empty input, as a little-endian u256.
+        assert_eq!(
+            result,
+            Ok(felts([
+                0xc003c7dcb27d7e923c23f7860146d2c5,
+                0x70a4855d04d8fa7b3b2782ca53b600e5,
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_sha256_process_block() {
+        let mut runner = SyscallRunner::new(SyscallState::default());
+        let initial_state = [
+            0x6a09e667_u32,
+            0xbb67ae85,
+            0x3c6ef372,
+            0xa54ff53a,
+            0x510e527f,
+            0x9b05688c,
+            0x1f83d9ab,
+            0x5be0cd19,
+        ]
+        .map(Felt252::from);
+        // The padded empty input.
+        let mut block = [Felt252::ZERO; 16];
+        block[0] = Felt252::from(0x80000000_u32);
+        let [state_ptr, _] = runner.span(&initial_state);
+        let [block_ptr, _] = runner.span(&block);
+
+        let (remaining_gas, result) = runner.call(
+            b"Sha256ProcessBlock",
+            INITIAL_GAS,
+            &[state_ptr, block_ptr],
+            1,
+        );
+
+        assert!(remaining_gas < Felt252::from(INITIAL_GAS));
+        let MaybeRelocatable::RelocatableValue(new_state_ptr) = result.unwrap()[0] else {
+            panic!("The response isn't a pointer");
+        };
+        // The SHA-256 hash of the empty input.
+        let new_state = runner
+            .vm
+            .get_integer_range(new_state_ptr, 8)
+            .unwrap()
+            .into_iter()
+            .map(|word| word.into_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            new_state,
+            [
+                0xe3b0c442_u32,
+                0x98fc1c14,
+                0x9afbf4c8,
+                0x996fb924,
+                0x27ae41e4,
+                0x649b934c,
+                0xa495991b,
+                0x7852b855,
+            ]
+            .map(Felt252::from)
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_new_and_get_xy() {
+        let mut runner = SyscallRunner::new(SyscallState::default());
+        // The generator, as low and high u128 limbs of its coordinates.
+        let generator = felts([
+            0x029bfcdb2dce28d959f2815b16f81798,
+            0x79be667ef9dcbbac55a06295ce870b07,
+            0xfd17b448a68554199c47d08ffb10d4b8,
+            0x483ada7726a3c4655da4fbfc0e1108a8,
+        ]);
+
+        let (_, point) = runner.call(b"Secp256k1New", INITIAL_GAS, &generator, 2);
+        // `Option::Some` of the point's id.
+        let [is_none, id] = point.unwrap().try_into().unwrap();
+        let (remaining_gas, xy) = runner.call(b"Secp256k1GetXy", INITIAL_GAS, &[id], 4);
+
+        assert_eq!(is_none, MaybeRelocatable::from(Felt252::ZERO));
+        assert!(remaining_gas < Felt252::from(INITIAL_GAS));
+        assert_eq!(xy, Ok(generator));
+    }
+}
@@ -0,0 +1,4 @@
+#!/bin/bash
+
+cargo clippy "$@" --all-targets --all-features -- -D warnings \
+    -D future-incompatible -D nonstandard-style -D rust-2018-idioms -D unused
@@ -0,0 +1,3 @@
+#!/bin/bash
+
+cargo fmt --all -- "$@"
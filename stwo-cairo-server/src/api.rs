@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use cairo_air::CairoProof;
+use cairo_air::verifier::verify_cairo;
+use serde::{Deserialize, Serialize};
+use stwo_cairo_prover::stwo_prover::core::pcs::PcsConfig;
+use stwo_cairo_prover::stwo_prover::core::vcs::blake2_merkle::{
+    Blake2sMerkleChannel, Blake2sMerkleHasher,
+};
+
+use crate::jobs::{JobError, JobId, JobQueue, JobStatus, ProveRequest};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub queue: Arc<JobQueue>,
+    /// The commitment scheme parameters proofs are verified with. Must match the ones of the
+    /// queue.
+    pub pcs_config: PcsConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitResponse {
+    pub job_id: JobId,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyResponse {
+    pub verified: bool,
+    pub error: Option<String>,
+}
+
+/// The HTTP API:
+/// - `POST /jobs`: submit a [`ProveRequest`], returns a [`SubmitResponse`].
+/// - `GET /jobs/{id}`: the [`JobStatus`] of a job.
+/// - `GET /jobs/{id}/proof`: the [`CairoProof`] of a succeeded job.
+/// - `POST /verify`: verify a [`CairoProof`] against its preprocessed trace variant, returns a
+///   [`VerifyResponse`].
+pub fn router(state: AppState, max_request_bytes: usize) -> Router {
+    Router::new()
+        .route("/jobs", post(submit_job))
+        .route("/jobs/{id}", get(job_status))
+        .route("/jobs/{id}/proof", get(job_proof))
+        .route("/verify", post(verify))
+        .layer(DefaultBodyLimit::max(max_request_bytes))
+        .with_state(state)
+}
+
+async fn submit_job(
+    State(state): State<AppState>,
+    Json(request): Json<ProveRequest>,
+) -> Result<(StatusCode, Json<SubmitResponse>), JobError> {
+    let job_id = state.queue.submit(request)?;
+    Ok((StatusCode::ACCEPTED, Json(SubmitResponse { job_id })))
+}
+
+async fn job_status(
+    State(state): State<AppState>,
+    Path(id): Path<JobId>,
+) -> Result<Json<JobStatus>, JobError> {
+    state.queue.status(id).map(Json)
+}
+
+async fn job_proof(State(state): State<AppState>, Path(id): Path<JobId>) -> Response {
+    match state.queue.proof(id) {
+        // The proof is shared with the queue, serialize it in place.
+        Ok(proof) => match serde_json::to_vec(&*proof) {
+            Ok(body) => ([("content-type", "application/json")], body).into_response(),
+            Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        },
+        Err(err) => err.into_response(),
+    }
+}
+
+async fn verify(
+    State(state): State<AppState>,
+    Json(proof): Json<CairoProof<Blake2sMerkleHasher>>,
+) -> Result<Json<VerifyResponse>, (StatusCode, String)> {
+    let pcs_config = state.pcs_config;
+    let preprocessed_trace = proof.preprocessed_trace;
+    let result = tokio::task::spawn_blocking(move || {
+        verify_cairo::<Blake2sMerkleChannel>(proof, pcs_config, preprocessed_trace)
+    })
+    .await
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    Ok(Json(VerifyResponse {
+        verified: result.is_ok(),
+        error: result.err().map(|err| err.to_string()),
+    }))
+}
+
+impl IntoResponse for JobError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            JobError::QueueFull | JobError::QueueClosed => StatusCode::SERVICE_UNAVAILABLE,
+            JobError::UnknownJob(_) => StatusCode::NOT_FOUND,
+            JobError::NoProof(..) => StatusCode::CONFLICT,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use cairo_air::CairoProof;
+use cairo_lang_executable::executable::Executable;
+use cairo_lang_runner::Arg;
+use cairo_lang_utils::bigint::BigUintAsHex;
+use cairo_prove::execute::execute;
+use cairo_prove::prove::{prover_input_from_runner, try_prove};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use stwo_cairo_adapter::ProverInput;
+use stwo_cairo_prover::panic_utils::panic_message;
+use stwo_cairo_prover::stwo_prover::core::pcs::PcsConfig;
+use stwo_cairo_prover::stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use thiserror::Error;
+use tokio::sync::{Semaphore, mpsc};
+
+pub type JobId = u64;
+
+/// What to prove.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProveRequest {
+    /// An already adapted execution.
+    ProverInput(Box<ProverInput>),
+    /// A Cairo executable (as produced by `scarb build`), executed before proving.
+    Executable {
+        program: Box<Executable>,
+        /// Serialized arguments to the executable function, ex. `["0x1", "0x2"]`.
+        #[serde(default)]
+        arguments: Vec<BigUintAsHex>,
+        /// The initial gas budget, required by gas-enabled executables only.
+        #[serde(default)]
+        gas: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed { error: String },
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum JobError {
+    #[error("The job queue is full")]
+    QueueFull,
+    #[error("The job queue is closed")]
+    QueueClosed,
+    #[error("Unknown job: {0}")]
+    UnknownJob(JobId),
+    #[error("Job {0} has no proof, its status is {1:?}")]
+    NoProof(JobId, JobStatus),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct JobQueueConfig {
+    /// Maximal number of jobs waiting to be proved. Submissions beyond it are rejected.
+    pub max_queued_jobs: usize,
+    /// Maximal number of jobs proved in parallel.
+    pub max_concurrent_jobs: usize,
+    /// Maximal number of finished jobs kept in memory, along with their proofs. Beyond it, the
+    /// oldest finished jobs are evicted and become unknown.
+    pub max_finished_jobs: usize,
+    pub pcs_config: PcsConfig,
+}
+
+struct Job {
+    status: JobStatus,
+    proof: Option<Arc<CairoProof<Blake2sMerkleHasher>>>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    jobs: HashMap<JobId, Job>,
+    /// The finished jobs, oldest first.
+    finished: VecDeque<JobId>,
+}
+
+struct JobTable {
+    jobs: Mutex<Jobs>,
+    max_finished_jobs: usize,
+}
+impl JobTable {
+    fn set_running(&self, id: JobId) {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.jobs.get_mut(&id).unwrap().status = JobStatus::Running;
+    }
+
+    /// Records the result of a job, evicting the oldest finished jobs beyond
+    /// `max_finished_jobs`.
+    fn finish(&self, id: JobId, result: Result<CairoProof<Blake2sMerkleHasher>, String>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.jobs.get_mut(&id).unwrap();
+        match result {
+            Ok(proof) => {
+                job.status = JobStatus::Succeeded;
+                job.proof = Some(Arc::new(proof));
+            }
+            Err(error) => job.status = JobStatus::Failed { error },
+        }
+        jobs.finished.push_back(id);
+        while jobs.finished.len() > self.max_finished_jobs {
+            let evicted = jobs.finished.pop_front().unwrap();
+            jobs.jobs.remove(&evicted);
+        }
+    }
+}
+
+/// A bounded queue of proving jobs. Finished jobs are kept in memory up to
+/// [`JobQueueConfig::max_finished_jobs`].
+///
+/// The queue only accepts jobs; they are proved by the [`JobWorker`] returned along with it by
+/// [`JobQueue::new`].
+pub struct JobQueue {
+    jobs: Arc<JobTable>,
+    next_id: AtomicU64,
+    sender: mpsc::Sender<(JobId, ProveRequest)>,
+}
+impl JobQueue {
+    pub fn new(config: JobQueueConfig) -> (Self, JobWorker) {
+        assert!(
+            config.max_queued_jobs > 0,
+            "The job queue must have a capacity"
+        );
+        assert!(
+            config.max_concurrent_jobs > 0,
+            "At least one job must run at a time"
+        );
+        assert!(
+            config.max_finished_jobs > 0,
+            "The result of the last finished job must be kept"
+        );
+        let (sender, receiver) = mpsc::channel(config.max_queued_jobs);
+        let jobs = Arc::new(JobTable {
+            jobs: Mutex::default(),
+            max_finished_jobs: config.max_finished_jobs,
+        });
+        let worker = JobWorker {
+            jobs: jobs.clone(),
+            receiver,
+            concurrency: Arc::new(Semaphore::new(config.max_concurrent_jobs)),
+            pcs_config: config.pcs_config,
+        };
+        let queue = Self {
+            jobs,
+            next_id: AtomicU64::new(0),
+            sender,
+        };
+        (queue, worker)
+    }
+
+    pub fn submit(&self, request: ProveRequest) -> Result<JobId, JobError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        // Hold the lock while sending, so the worker can't observe the job before it's in the
+        // table.
+        let mut jobs = self.jobs.jobs.lock().unwrap();
+        self.sender
+            .try_send((id, request))
+            .map_err(|err| match err {
+                mpsc::error::TrySendError::Full(_) => JobError::QueueFull,
+                mpsc::error::TrySendError::Closed(_) => JobError::QueueClosed,
+            })?;
+        jobs.jobs.insert(
+            id,
+            Job {
+                status: JobStatus::Queued,
+                proof: None,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn status(&self, id: JobId) -> Result<JobStatus, JobError> {
+        let jobs = self.jobs.jobs.lock().unwrap();
+        let job = jobs.jobs.get(&id).ok_or(JobError::UnknownJob(id))?;
+        Ok(job.status.clone())
+    }
+
+    pub fn proof(&self, id: JobId) -> Result<Arc<CairoProof<Blake2sMerkleHasher>>, JobError> {
+        let jobs = self.jobs.jobs.lock().unwrap();
+        let job = jobs.jobs.get(&id).ok_or(JobError::UnknownJob(id))?;
+        job.proof
+            .clone()
+            .ok_or_else(|| JobError::NoProof(id, job.status.clone()))
+    }
+}
+
+/// Proves the jobs submitted to a [`JobQueue`], at most `max_concurrent_jobs` at a time.
+pub struct JobWorker {
+    jobs: Arc<JobTable>,
+    receiver: mpsc::Receiver<(JobId, ProveRequest)>,
+    concurrency: Arc<Semaphore>,
+    pcs_config: PcsConfig,
+}
+impl JobWorker {
+    /// Runs until the [`JobQueue`] is dropped and all its queued jobs are started.
+    pub async fn run(mut self) {
+        loop {
+            // Acquire a permit before taking a job, so waiting jobs stay in the bounded queue.
+            let permit = self
+                .concurrency
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("The semaphore is never closed");
+            let Some((id, request)) = self.receiver.recv().await else {
+                break;
+            };
+
+            let jobs = self.jobs.clone();
+            let pcs_config = self.pcs_config;
+            tokio::spawn(async move {
+                info!("Proving job {id}...");
+                jobs.set_running(id);
+                let result =
+                    tokio::task::spawn_blocking(move || prove_request(request, pcs_config)).await;
+                let result = match result {
+                    Ok(result) => result,
+                    // Adaptation reports invalid programs as panics.
+                    Err(err) => Err(match err.try_into_panic() {
+                        Ok(panic) => panic_message(&*panic),
+                        Err(err) => err.to_string(),
+                    }),
+                };
+                match &result {
+                    Ok(_) => info!("Job {id} proved successfully."),
+                    Err(error) => error!("Job {id} failed: {error}"),
+                }
+                jobs.finish(id, result);
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// Executes (if needed) and proves a single request. Adaptation failures are reported by the
+/// adapter as panics.
+fn prove_request(
+    request: ProveRequest,
+    pcs_config: PcsConfig,
+) -> Result<CairoProof<Blake2sMerkleHasher>, String> {
+    let input = match request {
+        ProveRequest::ProverInput(input) => *input,
+        ProveRequest::Executable {
+            program,
+            arguments,
+            gas,
+        } => {
+            let args = arguments
+                .into_iter()
+                .map(|v| Arg::Value(v.value.into()))
+                .collect();
+            prover_input_from_runner(&execute(*program, args, gas)?)
+        }
+    };
+    try_prove(input, pcs_config).map_err(|err| err.to_string())
+}
@@ -0,0 +1,59 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use cairo_prove::prove::secure_pcs_config;
+use clap::Parser;
+use log::info;
+use stwo_cairo_server::api::{AppState, router};
+use stwo_cairo_server::jobs::{JobQueue, JobQueueConfig};
+
+/// A local HTTP/JSON service proving and verifying Cairo executions.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// The address to listen on. Keep it a loopback address unless the service is protected
+    /// otherwise, it has no authentication.
+    #[arg(long, default_value = "127.0.0.1")]
+    host: IpAddr,
+    #[arg(long, default_value_t = 3000)]
+    port: u16,
+    /// Maximal number of jobs waiting to be proved.
+    #[arg(long, default_value_t = 16)]
+    max_queued_jobs: usize,
+    /// Maximal number of jobs proved in parallel. Proving is memory intensive, raise with care.
+    #[arg(long, default_value_t = 1)]
+    max_concurrent_jobs: usize,
+    /// Maximal number of finished jobs whose status and proof are kept. The oldest finished jobs
+    /// are evicted beyond it.
+    #[arg(long, default_value_t = 64)]
+    max_finished_jobs: usize,
+    /// Maximal size of a request body (a prover input, an executable or a proof), in bytes.
+    #[arg(long, default_value_t = 1 << 30)]
+    max_request_bytes: usize,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let pcs_config = secure_pcs_config();
+    let (queue, worker) = JobQueue::new(JobQueueConfig {
+        max_queued_jobs: args.max_queued_jobs,
+        max_concurrent_jobs: args.max_concurrent_jobs,
+        max_finished_jobs: args.max_finished_jobs,
+        pcs_config,
+    });
+    tokio::spawn(worker.run());
+    let app = router(
+        AppState {
+            queue: Arc::new(queue),
+            pcs_config,
+        },
+        args.max_request_bytes,
+    );
+
+    let listener = tokio::net::TcpListener::bind((args.host, args.port)).await?;
+    info!("Listening on {}", listener.local_addr()?);
+    axum::serve(listener, app).await
+}
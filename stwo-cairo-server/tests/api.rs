@@ -0,0 +1,180 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use cairo_lang_runner::Arg;
+use cairo_prove::execute::execute;
+use cairo_prove::prove::prover_input_from_runner;
+use cairo_vm::Felt252;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use stwo_cairo_prover::stwo_prover::core::pcs::PcsConfig;
+use stwo_cairo_server::api::{AppState, router};
+use stwo_cairo_server::jobs::{JobQueue, JobQueueConfig, JobStatus, JobWorker};
+use tower::ServiceExt;
+
+const EXAMPLE_EXECUTABLE: &str = "../cairo-prove/example/target/release/example.executable.json";
+
+/// Builds the service. Its jobs are proved only once the returned worker runs.
+fn app(max_queued_jobs: usize) -> (Router, JobWorker) {
+    app_with_finished_jobs(max_queued_jobs, 16)
+}
+
+fn app_with_finished_jobs(max_queued_jobs: usize, max_finished_jobs: usize) -> (Router, JobWorker) {
+    let pcs_config = PcsConfig::default();
+    let (queue, worker) = JobQueue::new(JobQueueConfig {
+        max_queued_jobs,
+        max_concurrent_jobs: 1,
+        max_finished_jobs,
+        pcs_config,
+    });
+    let app = router(
+        AppState {
+            queue: Arc::new(queue),
+            pcs_config,
+        },
+        1 << 30,
+    );
+    (app, worker)
+}
+
+/// Sends a request to the service in-process, returning the status and the (JSON, if any) body.
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(match body {
+            Some(body) => Body::from(serde_json::to_vec(&body).unwrap()),
+            None => Body::empty(),
+        })
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+    (status, body)
+}
+
+fn example_executable() -> Value {
+    serde_json::from_reader(std::fs::File::open(EXAMPLE_EXECUTABLE).unwrap()).unwrap()
+}
+
+async fn wait_for_job(app: &Router, job_id: u64) -> JobStatus {
+    loop {
+        let (status, body) = send(app, Method::GET, &format!("/jobs/{job_id}"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let job_status: JobStatus = serde_json::from_value(body).unwrap();
+        if !matches!(job_status, JobStatus::Queued | JobStatus::Running) {
+            return job_status;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+async fn prove_and_verify(app: &Router, request: Value) {
+    let (status, body) = send(app, Method::POST, "/jobs", Some(request)).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let job_id = body["job_id"].as_u64().unwrap();
+
+    assert_eq!(wait_for_job(app, job_id).await, JobStatus::Succeeded);
+    let (status, proof) = send(app, Method::GET, &format!("/jobs/{job_id}/proof"), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(app, Method::POST, "/verify", Some(proof)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "verified": true, "error": null }));
+}
+
+#[tokio::test]
+async fn test_prove_and_verify_executable() {
+    let (app, worker) = app(4);
+    tokio::spawn(worker.run());
+    let request = json!({
+        "executable": { "program": example_executable(), "arguments": ["0x64"] }
+    });
+
+    prove_and_verify(&app, request).await;
+}
+
+#[tokio::test]
+async fn test_prove_and_verify_prover_input() {
+    let (app, worker) = app(4);
+    tokio::spawn(worker.run());
+    let executable = serde_json::from_value(example_executable()).unwrap();
+    let args = vec![Arg::Value(Felt252::from(100u32))];
+    let input = prover_input_from_runner(&execute(executable, args, None).unwrap());
+
+    prove_and_verify(&app, json!({ "prover_input": input })).await;
+}
+
+#[tokio::test]
+async fn test_failed_job() {
+    let (app, worker) = app(4);
+    tokio::spawn(worker.run());
+    let mut program = example_executable();
+    program["entrypoints"] = json!([]);
+    let request = json!({ "executable": { "program": program, "arguments": ["0x64"] } });
+
+    let (status, body) = send(&app, Method::POST, "/jobs", Some(request)).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let job_id = body["job_id"].as_u64().unwrap();
+
+    assert!(matches!(
+        wait_for_job(&app, job_id).await,
+        JobStatus::Failed { .. }
+    ));
+    let (status, _) = send(&app, Method::GET, &format!("/jobs/{job_id}/proof"), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_finished_jobs_evicted() {
+    let (app, worker) = app_with_finished_jobs(4, 1);
+    tokio::spawn(worker.run());
+    let mut program = example_executable();
+    program["entrypoints"] = json!([]);
+    let request = || json!({ "executable": { "program": program.clone() } });
+    let mut job_ids = vec![];
+    for _ in 0..2 {
+        let (status, body) = send(&app, Method::POST, "/jobs", Some(request())).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let job_id = body["job_id"].as_u64().unwrap();
+        wait_for_job(&app, job_id).await;
+        job_ids.push(job_id);
+    }
+
+    let (status, _) = send(&app, Method::GET, &format!("/jobs/{}", job_ids[0]), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, Method::GET, &format!("/jobs/{}", job_ids[1]), None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_queue_full() {
+    let (app, _worker) = app(1);
+    let request = || json!({ "executable": { "program": example_executable() } });
+
+    let (status, body) = send(&app, Method::POST, "/jobs", Some(request())).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let (status, _) = send(&app, Method::POST, "/jobs", Some(request())).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let job_id = body["job_id"].as_u64().unwrap();
+    let (status, body) = send(&app, Method::GET, &format!("/jobs/{job_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "status": "queued" }));
+}
+
+#[tokio::test]
+async fn test_unknown_job() {
+    let (app, _worker) = app(1);
+
+    let (status, _) = send(&app, Method::GET, "/jobs/7", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, Method::GET, "/jobs/7/proof", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
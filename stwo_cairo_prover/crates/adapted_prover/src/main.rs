@@ -2,14 +2,18 @@ use std::io::Write;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
+use cairo_air::air::USAGE_AWARE_NOT_CAIRO_SERIALIZABLE;
 use cairo_air::verifier::{verify_cairo, CairoVerificationError};
 use cairo_air::PreProcessedTraceVariant;
 use clap::Parser;
 use serde::Serialize;
 use stwo_cairo_adapter::vm_import::{adapt_vm_output, VmImportError};
 use stwo_cairo_adapter::ProverInput;
+use stwo_cairo_prover::debug_tools::relation_diagnostics::diagnose_cairo_relations;
+use stwo_cairo_prover::hooks::CairoProvingError;
 use stwo_cairo_prover::prover::{
-    default_prod_prover_parameters, prove_cairo, ChannelHash, ProverParameters,
+    default_prod_prover_parameters, prove_cairo, prove_cairo_with_report, ChannelHash,
+    ProverParameters,
 };
 use stwo_cairo_serialize::CairoSerialize;
 use stwo_cairo_utils::binary_utils::run_binary;
@@ -18,7 +22,6 @@ use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::backend::BackendForChannel;
 use stwo_prover::core::channel::MerkleChannel;
 use stwo_prover::core::pcs::PcsConfig;
-use stwo_prover::core::prover::ProvingError;
 use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
 use stwo_prover::core::vcs::ops::MerkleHasher;
 use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleChannel;
@@ -66,8 +69,8 @@ struct Args {
     #[structopt(long = "params_json")]
     params_json: Option<PathBuf>,
     /// The output file path for the proof.
-    #[structopt(long = "proof_path")]
-    proof_path: PathBuf,
+    #[structopt(long = "proof_path", required_unless_present = "diagnose_relations")]
+    proof_path: Option<PathBuf>,
     /// The format of the proof output.
     /// - json: Standard JSON format (default)
     /// - cairo_serde: Array of field elements serialized as hex strings, ex. `["0x1", "0x2"]`
@@ -78,6 +81,14 @@ struct Args {
     /// Verify the generated proof.
     #[structopt(long = "verify")]
     verify: bool,
+    /// The output file path for a JSON proving report (optional).
+    /// The report contains per-component trace sizes, phase timings and peak memory usage.
+    #[structopt(long = "report_path")]
+    report_path: Option<PathBuf>,
+    /// Generate the trace without proving, and report the relation tuples that aren't balanced,
+    /// along with the components and rows using them. Fails if any relation is unbalanced.
+    #[structopt(long = "diagnose_relations")]
+    diagnose_relations: bool,
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -96,7 +107,7 @@ enum Error {
     #[error("IO failed: {0}")]
     IO(#[from] std::io::Error),
     #[error("Proving failed: {0}")]
-    Proving(#[from] ProvingError),
+    Proving(#[from] CairoProvingError),
     #[error("Serialization failed: {0}")]
     Serializing(#[from] sonic_rs::error::Error),
     #[error("Verification failed: {0}")]
@@ -105,6 +116,10 @@ enum Error {
     VmImport(#[from] VmImportError),
     #[error("File IO failed: {0}")]
     File(#[from] IoErrorWithPath),
+    #[error("Unbalanced relations")]
+    UnbalancedRelations,
+    #[error("{USAGE_AWARE_NOT_CAIRO_SERIALIZABLE}")]
+    NotCairoVerifiable,
 }
 
 fn main() -> ExitCode {
@@ -132,6 +147,15 @@ fn run(args: impl Iterator<Item = String>) -> Result<(), Error> {
         None => default_prod_prover_parameters(),
     };
 
+    if args.diagnose_relations {
+        let diagnostics = diagnose_cairo_relations(vm_output, preprocessed_trace);
+        log::info!("Relation diagnostics:\n{diagnostics}");
+        return match diagnostics.is_balanced() {
+            true => Ok(()),
+            false => Err(Error::UnbalancedRelations),
+        };
+    }
+
     let run_inner_fn = match channel_hash {
         ChannelHash::Blake2s => run_inner::<Blake2sMerkleChannel>,
         ChannelHash::Poseidon252 => run_inner::<Poseidon252MerkleChannel>,
@@ -142,8 +166,10 @@ fn run(args: impl Iterator<Item = String>) -> Result<(), Error> {
         pcs_config,
         preprocessed_trace,
         args.verify,
-        args.proof_path,
+        args.proof_path
+            .expect("The proof path is required unless diagnosing relations"),
         args.proof_format,
+        args.report_path,
     )?;
 
     Ok(())
@@ -152,6 +178,7 @@ fn run(args: impl Iterator<Item = String>) -> Result<(), Error> {
 /// Generates proof given the Cairo VM output and prover config/parameters.
 /// Serializes the proof as JSON and write to the output path.
 /// Verifies the proof in case the respective flag is set.
+/// Writes a proving report as JSON in case a report path is given.
 fn run_inner<MC: MerkleChannel>(
     vm_output: ProverInput,
     pcs_config: PcsConfig,
@@ -159,13 +186,29 @@ fn run_inner<MC: MerkleChannel>(
     verify: bool,
     proof_path: PathBuf,
     proof_format: ProofFormat,
+    report_path: Option<PathBuf>,
 ) -> Result<(), Error>
 where
     SimdBackend: BackendForChannel<MC>,
     MC::H: Serialize,
     <MC::H as MerkleHasher>::Hash: CairoSerialize,
 {
-    let proof = prove_cairo::<MC>(vm_output, pcs_config, preprocessed_trace)?;
+    if matches!(proof_format, ProofFormat::CairoSerde)
+        && preprocessed_trace == PreProcessedTraceVariant::UsageAware
+    {
+        return Err(Error::NotCairoVerifiable);
+    }
+
+    let proof = match report_path {
+        Some(report_path) => {
+            let (proof, report) =
+                prove_cairo_with_report::<MC>(vm_output, pcs_config, preprocessed_trace)?;
+            create_file(&report_path)?
+                .write_all(sonic_rs::to_string_pretty(&report)?.as_bytes())?;
+            proof
+        }
+        None => prove_cairo::<MC>(vm_output, pcs_config, preprocessed_trace)?,
+    };
     let mut proof_file = create_file(&proof_path)?;
 
     let span = span!(Level::INFO, "Serialize proof").entered();
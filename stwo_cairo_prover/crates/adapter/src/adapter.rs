@@ -25,7 +25,8 @@ pub fn adapter(prover_input_info: &mut ProverInputInfo) -> Result<ProverInput, V
     let relocated_trace = relocator.relocate_trace(&prover_input_info.relocatable_trace);
 
     let memory = MemoryBuilder::from_iter(MemoryConfig::default(), relocated_memory);
-    let state_transitions = StateTransitions::from_slice_parallel(&relocated_trace, &memory);
+    let state_transitions =
+        StateTransitions::from_slice_parallel(&relocated_trace, &memory, false)?;
 
     let builtins_segments = relocator.get_builtin_segments();
 
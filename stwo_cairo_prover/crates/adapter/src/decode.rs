@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::fields::m31::M31;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum OpcodeExtension {
     Stone,
     Blake,
@@ -21,9 +22,19 @@ impl OpcodeExtension {
             _ => panic!("Invalid opcode extension number: {}", value),
         }
     }
+
+    /// The inverse of [`Self::from_instruction_trailing_bits`].
+    pub fn to_instruction_trailing_bits(&self) -> u128 {
+        match self {
+            OpcodeExtension::Stone => 0,
+            OpcodeExtension::Blake => 1,
+            OpcodeExtension::BlakeFinalize => 2,
+            OpcodeExtension::QM31Operation => 3,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Instruction {
     pub offset0: i16,
     pub offset1: i16,
@@ -85,6 +96,37 @@ impl Instruction {
             opcode_extension: OpcodeExtension::from_instruction_trailing_bits(encoded_instr),
         }
     }
+
+    /// The inverse of [`Self::decode`].
+    pub fn encode(&self) -> u128 {
+        let offsets = [self.offset0, self.offset1, self.offset2];
+        let flags = [
+            self.dst_base_fp,
+            self.op0_base_fp,
+            self.op_1_imm,
+            self.op_1_base_fp,
+            self.op_1_base_ap,
+            self.res_add,
+            self.res_mul,
+            self.pc_update_jump,
+            self.pc_update_jump_rel,
+            self.pc_update_jnz,
+            self.ap_update_add,
+            self.ap_update_add_1,
+            self.opcode_call,
+            self.opcode_ret,
+            self.opcode_assert_eq,
+        ];
+
+        let mut encoded_instr = self.opcode_extension.to_instruction_trailing_bits();
+        for flag in flags.into_iter().rev() {
+            encoded_instr = (encoded_instr << 1) | flag as u128;
+        }
+        for offset in offsets.into_iter().rev() {
+            encoded_instr = (encoded_instr << 16) | (offset as u16).wrapping_add(0x8000) as u128;
+        }
+        encoded_instr
+    }
 }
 
 /// Constructs the input for the DecodeInstruction routine.
@@ -131,7 +173,7 @@ pub fn deconstruct_instruction(mut encoded_instr: u128) -> ([M31; 3], [M31; 2],
 mod tests {
     use stwo_prover::core::fields::m31::M31;
 
-    use crate::decode::deconstruct_instruction;
+    use crate::decode::{deconstruct_instruction, Instruction};
 
     #[test]
     fn test_deconstruct_instruction() {
@@ -146,4 +188,17 @@ mod tests {
         assert_eq!(flags, expected_flags);
         assert_eq!(opcode_extension, expected_opcode_extension);
     }
+
+    #[test]
+    fn test_encode_instruction() {
+        for encoded_instr in [
+            0b10100011010001010000000000000000100000000000000110000000000000111,
+            // [ap + 0] = imm, ap++.
+            0x480680017fff8000,
+            // ret.
+            0x208b7fff7fff7ffe,
+        ] {
+            assert_eq!(Instruction::decode(encoded_instr).encode(), encoded_instr);
+        }
+    }
 }
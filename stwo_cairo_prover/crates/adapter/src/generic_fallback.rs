@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::decode::Instruction;
+use crate::opcodes::OpcodePattern;
+
+/// An instruction proved by the `generic_opcode` component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenericFallback {
+    /// Why none of the specialized opcode components matched the instruction.
+    pub reason: String,
+    /// The number of times the instruction was executed.
+    pub count: usize,
+}
+
+/// Explains why instructions fell back to the `generic_opcode` component, by pc. Recorded on
+/// request by [`crate::opcodes::StateTransitions::from_slice_parallel`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GenericFallbackReport {
+    pub fallbacks: BTreeMap<u32, GenericFallback>,
+}
+impl GenericFallbackReport {
+    /// Records an execution of `instruction`, at `pc`, by the `generic_opcode` component.
+    pub fn record(&mut self, pc: u32, instruction: &Instruction) {
+        self.fallbacks
+            .entry(pc)
+            .or_insert_with(|| GenericFallback {
+                reason: fallback_reason(instruction),
+                count: 0,
+            })
+            .count += 1;
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        for (pc, fallback) in other.fallbacks {
+            self.fallbacks
+                .entry(pc)
+                .and_modify(|own| own.count += fallback.count)
+                .or_insert(fallback);
+        }
+    }
+
+    /// The total hit count of every reason, most hit first.
+    pub fn by_reason(&self) -> Vec<(&str, usize)> {
+        self.fallbacks
+            .values()
+            .into_group_map_by(|fallback| fallback.reason.as_str())
+            .into_iter()
+            .map(|(reason, fallbacks)| (reason, fallbacks.iter().map(|f| f.count).sum()))
+            .sorted_by_key(|&(reason, count)| (std::cmp::Reverse(count), reason))
+            .collect()
+    }
+}
+
+impl Display for GenericFallbackReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = self.fallbacks.values().map(|f| f.count).sum::<usize>();
+        writeln!(f, "Generic opcode steps: {total}")?;
+        for (reason, count) in self.by_reason() {
+            writeln!(f, "{count}: {reason}")?;
+            for (pc, fallback) in &self.fallbacks {
+                if fallback.reason == reason {
+                    writeln!(f, "    pc {pc}: {}", fallback.count)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Explains why `instruction` matches none of the [`OpcodePattern`]s, by naming the opcode it's
+/// meant as and the fields that pattern doesn't support. If it's meant as several opcodes, the one
+/// with the fewest mismatching fields is named.
+pub fn fallback_reason(instruction: &Instruction) -> String {
+    let closest = OpcodePattern::ALL
+        .iter()
+        .filter(|pattern| pattern.is_kind_of(instruction))
+        .map(|pattern| (pattern.name(), pattern.mismatches(instruction)))
+        .min_by_key(|(_, mismatches)| mismatches.len());
+    match closest {
+        Some((opcode, mismatches)) if mismatches.is_empty() => opcode.to_string(),
+        Some((opcode, mismatches)) => format!("{opcode} with {}", mismatches.join(" and ")),
+        None => "no matching opcode".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{u128_to_4_limbs, MemoryBuilder, MemoryConfig, MemoryValue};
+    use crate::opcodes::StateTransitions;
+    use crate::relocated_trace_entry;
+    use crate::vm_import::RelocatedTraceEntry;
+
+    #[test]
+    fn test_generic_fallback_report() {
+        // `jmp rel [ap - 1] if [ap - 2] != 0`, which `casm!` can't express.
+        let jnz = Instruction {
+            offset0: -2,
+            offset1: -1,
+            offset2: -1,
+            op0_base_fp: true,
+            op_1_base_ap: true,
+            pc_update_jnz: true,
+            // All offsets are zero, and all flags are off.
+            ..Instruction::decode(0x800080008000)
+        };
+        let x = u128_to_4_limbs(jnz.encode());
+        let mut memory_builder = MemoryBuilder::new(MemoryConfig::default());
+        memory_builder.set(1, MemoryValue::F252([x[0], x[1], x[2], x[3], 0, 0, 0, 0]));
+        // Two steps at the instruction, followed by the final state.
+        let trace = [relocated_trace_entry!(1, 1, 1); 3];
+
+        let state_transitions =
+            StateTransitions::from_slice_parallel(&trace, &memory_builder, true).unwrap();
+
+        assert_eq!(
+            state_transitions.generic_fallbacks.unwrap().by_reason(),
+            vec![(
+                "jnz with offset2 = -1 and op_1_imm = false and op_1_base_ap = true",
+                2
+            )]
+        );
+        let state_transitions =
+            StateTransitions::from_slice_parallel(&trace, &memory_builder, false).unwrap();
+        assert!(state_transitions.generic_fallbacks.is_none());
+    }
+
+    #[test]
+    fn test_fallback_reason() {
+        let jump_with_ap_add = Instruction {
+            offset0: -1,
+            offset1: -1,
+            offset2: 1,
+            dst_base_fp: true,
+            op0_base_fp: true,
+            op_1_imm: true,
+            op_1_base_fp: false,
+            op_1_base_ap: false,
+            res_add: false,
+            res_mul: false,
+            pc_update_jump: false,
+            pc_update_jump_rel: true,
+            pc_update_jnz: false,
+            ap_update_add: true,
+            ap_update_add_1: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_assert_eq: false,
+            opcode_extension: crate::decode::OpcodeExtension::Stone,
+        };
+
+        assert_eq!(
+            fallback_reason(&jump_with_ap_add),
+            "jump with ap_update_add = true"
+        );
+    }
+}
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 pub mod adapter;
 pub mod builtins;
 pub mod decode;
+pub mod generic_fallback;
 pub mod memory;
 pub mod opcodes;
 pub mod relocator;
@@ -31,10 +32,17 @@ pub struct ProverInput {
 
 const N_PUBLIC_SEGMENTS: usize = 11;
 
-/// Represents the pointer arguments of the `main` function.
+/// Represents the pointer arguments of the `main` function, and whether they're followed by a gas
+/// counter.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PublicSegmentContext {
     present: [bool; N_PUBLIC_SEGMENTS],
+    #[serde(default)]
+    gas: bool,
+    /// The number of pointers to segments of registered component families, which follow the
+    /// builtin pointers.
+    #[serde(default)]
+    n_extension_segments: usize,
 }
 impl PublicSegmentContext {
     pub fn new(vm_format: &[BuiltinName]) -> Self {
@@ -57,13 +65,42 @@ impl PublicSegmentContext {
                 }
             }
         }
-        Self { present }
+        Self {
+            present,
+            gas: false,
+            n_extension_segments: 0,
+        }
+    }
+
+    /// Marks `main` as taking the gas counter after the pointer arguments, and returning the
+    /// remaining gas before them.
+    pub fn with_gas(self) -> Self {
+        Self { gas: true, ..self }
+    }
+
+    pub fn has_gas(&self) -> bool {
+        self.gas
+    }
+
+    /// Marks `main` as taking `n_extension_segments` pointers to the segments of registered
+    /// component families after the builtin pointers, and returning their ends.
+    pub fn with_extension_segments(self, n_extension_segments: usize) -> Self {
+        Self {
+            n_extension_segments,
+            ..self
+        }
+    }
+
+    pub fn n_extension_segments(&self) -> usize {
+        self.n_extension_segments
     }
 
     pub const fn bootloader_context() -> Self {
         // Bootloader always uses every builtin.
         Self {
             present: [true; N_PUBLIC_SEGMENTS],
+            gas: false,
+            n_extension_segments: 0,
         }
     }
 }
@@ -9,7 +9,8 @@ use tracing::{span, Level};
 
 use super::decode::{Instruction, OpcodeExtension};
 use super::memory::{MemoryBuilder, MemoryValue};
-use super::vm_import::RelocatedTraceEntry;
+use super::vm_import::{RelocatedTraceEntry, VmImportError};
+use crate::generic_fallback::GenericFallbackReport;
 use crate::memory::limbs_to_u128;
 
 // Small add operands are 27 bits.
@@ -20,579 +21,713 @@ const SMALL_ADD_MIN_VALUE: i32 = -(2_i32.pow(27));
 const SMALL_MUL_MAX_VALUE: u64 = 2_u64.pow(36) - 1;
 const SMALL_MUL_MIN_VALUE: u64 = 0;
 
-// TODO (Stav): Ensure it stays synced with that opcdode AIR's list.
-/// This struct holds the components used to prove the opcodes in a Cairo program,
-/// and should match the opcode's air used by `stwo-cairo-air`.
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
-pub struct CasmStatesByOpcode {
-    pub generic_opcode: Vec<CasmState>,
-    pub add_ap_opcode: Vec<CasmState>,
-    pub add_opcode: Vec<CasmState>,
-    pub add_opcode_small: Vec<CasmState>,
-    pub assert_eq_opcode: Vec<CasmState>,
-    pub assert_eq_opcode_double_deref: Vec<CasmState>,
-    pub assert_eq_opcode_imm: Vec<CasmState>,
-    pub call_opcode: Vec<CasmState>,
-    pub call_opcode_rel_imm: Vec<CasmState>,
-    pub jnz_opcode: Vec<CasmState>,
-    pub jnz_opcode_taken: Vec<CasmState>,
-    pub jump_opcode_rel_imm: Vec<CasmState>,
-    pub jump_opcode_rel: Vec<CasmState>,
-    pub jump_opcode_double_deref: Vec<CasmState>,
-    pub jump_opcode: Vec<CasmState>,
-    pub mul_opcode_small: Vec<CasmState>,
-    pub mul_opcode: Vec<CasmState>,
-    pub ret_opcode: Vec<CasmState>,
-    pub blake_compress_opcode: Vec<CasmState>,
-    pub qm_31_add_mul_opcode: Vec<CasmState>,
+/// Invokes `$callback!` on the opcode components, the single list from which [`CasmStatesByOpcode`]
+/// and `stwo-cairo-air`'s opcode claims and components are declared, and by which
+/// `CasmStatesByOpcode::push_instr` routes the instructions. The components are listed in
+/// `OpcodeClaim` order, which the Cairo verifier relies on.
+///
+/// Each component is listed as `field: module [Pattern, selector]`, where `field` names it in
+/// `OpcodeClaim` and `module` names its states in [`CasmStatesByOpcode`] and its AIR and witness
+/// modules, which are resolved at the call site. Its instructions are the ones matching the
+/// [`OpcodePattern`] `Pattern` for which the `InstructionStep` method `selector` holds. The
+/// selectors of a pattern are mutually exclusive, and `generic_opcode` has none, as it proves the
+/// instructions matching no pattern.
+///
+/// `opcode_components!(callback)` passes the components as `field: module` pairs, and
+/// `opcode_components!(@routes callback)` as `module [Pattern, selector]`.
+// TODO(alonf): canonicalize the name of field and module.
+#[macro_export]
+macro_rules! opcode_components {
+    ($callback:ident) => {
+        $crate::opcode_components!(@table fields $callback)
+    };
+    (@routes $callback:ident) => {
+        $crate::opcode_components!(@table routes $callback)
+    };
+    (@table $mode:ident $callback:ident) => {
+        $crate::opcode_components! {
+            @$mode $callback;
+            add: add_opcode [Add, is_big_add],
+            add_small: add_opcode_small [Add, is_small_add],
+            add_ap: add_ap_opcode [AddAp, is_add_ap],
+            assert_eq: assert_eq_opcode [AssertEq, is_assert_eq],
+            assert_eq_imm: assert_eq_opcode_imm [AssertEq, is_assert_eq_imm],
+            assert_eq_double_deref:
+                assert_eq_opcode_double_deref [AssertEq, is_assert_eq_double_deref],
+            blake: blake_compress_opcode [Blake, is_blake],
+            call: call_opcode [Call, is_call],
+            call_rel_imm: call_opcode_rel_imm [Call, is_call_rel_imm],
+            generic: generic_opcode [],
+            jnz: jnz_opcode [Jnz, is_jnz_not_taken],
+            jnz_taken: jnz_opcode_taken [Jnz, is_jnz_taken],
+            jump: jump_opcode [Jump, is_jump],
+            jump_double_deref: jump_opcode_double_deref [Jump, is_jump_double_deref],
+            jump_rel: jump_opcode_rel [Jump, is_jump_rel],
+            jump_rel_imm: jump_opcode_rel_imm [Jump, is_jump_rel_imm],
+            mul: mul_opcode [Mul, is_big_mul],
+            mul_small: mul_opcode_small [Mul, is_small_mul],
+            qm31: qm_31_add_mul_opcode [QM31AddMul, is_qm31_add_mul],
+            ret: ret_opcode [Ret, is_ret],
+        }
+    };
+    (@fields $callback:ident; $($field:ident: $module:ident [$($route:tt)*],)*) => {
+        $callback! { $($field: $module,)* }
+    };
+    (@routes $callback:ident; $($field:ident: $module:ident [$($route:tt)*],)*) => {
+        $callback! { $($module [$($route)*],)* }
+    };
 }
-impl CasmStatesByOpcode {
-    fn from_iter(
-        iter: impl DoubleEndedIterator<Item = RelocatedTraceEntry>,
-        memory: &MemoryBuilder,
-    ) -> Self {
-        let mut res = CasmStatesByOpcode::default();
-        for entry in iter {
-            res.push_instr(memory, entry.into());
+
+macro_rules! casm_states_by_opcode {
+    ($($field:ident: $module:ident,)*) => {
+        /// This struct holds the components used to prove the opcodes in a Cairo program,
+        /// and should match the opcode's air used by `stwo-cairo-air`.
+        #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+        pub struct CasmStatesByOpcode {
+            $(pub $module: Vec<CasmState>,)*
         }
-        res
-    }
-
-    /// Pushes the state transition at pc into the appropriate opcode component.
-    fn push_instr(&mut self, memory: &MemoryBuilder, state: CasmState) {
-        let CasmState { ap, fp, pc } = state;
-        let encoded_instruction = memory.get_inst(pc.0);
-        let instruction = Instruction::decode(encoded_instruction);
-
-        match instruction {
-            // ret.
-            Instruction {
-                offset0: -2,
-                offset1: -1,
-                offset2: -1,
-                dst_base_fp: true,
-                op0_base_fp: true,
-                op_1_imm: false,
-                op_1_base_fp: true,
-                op_1_base_ap: false,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump: true,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: false,
-                opcode_call: false,
-                opcode_ret: true,
-                opcode_assert_eq: false,
-                opcode_extension: OpcodeExtension::Stone,
-            } => self.ret_opcode.push(state),
-
-            // add ap.
-            Instruction {
-                offset0: -1,
-                offset1: -1,
-                offset2,
-                dst_base_fp: true,
-                op0_base_fp: true,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: true,
-                ap_update_add_1: false,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: false,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                // ap += imm.
-                // ap += [ap/fp + offset2].
-                assert_eq!(
-                    (op_1_imm as u8) + (op_1_base_fp as u8) + (op_1_base_ap as u8),
-                    1,
-                    "add_ap opcode requires exactly one of op_1_imm, op_1_base_fp, op_1_base_ap must be true"
-                );
-                assert!(
-                    (!op_1_imm) || offset2 == 1,
-                    "add_ap opcode requires that if op_1_imm is true, offset2 must be 1"
-                );
-                let mem1_base = if op_1_imm {
-                    pc
-                } else if op_1_base_fp {
-                    fp
-                } else {
-                    ap
-                };
-                let op_1 = memory.get(mem1_base.0.checked_add_signed(offset2 as i32).unwrap());
-                // next ap = ap + op1 must be in the range [0, 2^27 - 1].
-                if !is_within_range(op_1, -(ap.0 as i128), ((1 << 27) - 1) - ap.0 as i128) {
-                    panic!(
-                        "add_ap opcode requires that next_ap is within the range of [0, 2^27 - 1]"
-                    );
-                }
-                self.add_ap_opcode.push(state);
-            }
-            // jump.
-            Instruction {
-                offset0: -1,
-                offset1,
-                offset2,
-                dst_base_fp: true,
-                op0_base_fp,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump,
-                pc_update_jump_rel,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: false,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                if op_1_imm {
-                    // jump rel imm.
-                    assert!(
-                        pc_update_jump_rel
-                            && !pc_update_jump
-                            && !op_1_base_fp
-                            && !op_1_base_ap
-                            && op0_base_fp
-                            && offset1 == -1
-                            && offset2 == 1
-                    );
-                    self.jump_opcode_rel_imm.push(state);
-                } else if pc_update_jump_rel {
-                    // jump rel [ap/fp + offset2].
-                    assert!(
-                        !pc_update_jump
-                            && (op_1_base_fp || op_1_base_ap)
-                            && op0_base_fp
-                            && offset1 == -1
-                    );
-                    self.jump_opcode_rel.push(state);
-                } else if !op_1_base_fp && !op_1_base_ap {
-                    // jump abs [[ap/fp + offset1] + offset2].
-                    assert!(pc_update_jump);
-                    self.jump_opcode_double_deref.push(state);
-                } else {
-                    // jump abs [ap/fp + offset2].
-                    assert!(
-                        (op_1_base_fp || op_1_base_ap)
-                            && op0_base_fp
-                            && pc_update_jump
-                            && offset1 == -1
-                    );
-                    self.jump_opcode.push(state);
-                }
+        impl CasmStatesByOpcode {
+            /// The names of the opcode components, in declaration order.
+            pub const COMPONENTS: &'static [&'static str] = &[$(stringify!($module)),*];
+
+            pub fn merge(&mut self, other: &Self) {
+                $(self.$module.extend(&other.$module);)*
             }
 
-            // call.
-            Instruction {
-                offset0: 0,
-                offset1: 1,
-                offset2,
-                dst_base_fp: false,
-                op0_base_fp: false,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump,
-                pc_update_jump_rel,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: false,
-                opcode_call: true,
-                opcode_ret: false,
-                opcode_assert_eq: false,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                if pc_update_jump_rel {
-                    // call rel imm.
-                    assert!(
-                        op_1_imm
-                            && !op_1_base_fp
-                            && !op_1_base_ap
-                            && offset2 == 1
-                            && !pc_update_jump
-                    );
-                    self.call_opcode_rel_imm.push(state);
-                } else {
-                    // call abs [ap/fp + offset2].
-                    assert!((op_1_base_ap ^ op_1_base_fp) && !op_1_imm && pc_update_jump);
-                    self.call_opcode.push(state);
-                }
+            pub fn counts(&self) -> Vec<(String, usize)> {
+                vec![$((stringify!($module).to_string(), self.$module.len())),*]
             }
 
-            // jnz.
-            Instruction {
-                offset0,
-                offset1: -1,
-                offset2: 1,
-                dst_base_fp,
-                op0_base_fp: true,
-                op_1_imm: true,
-                op_1_base_fp: false,
-                op_1_base_ap: false,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: true,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: false,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                // jump rel imm if [ap/fp + offset0] != 0.
-                let dst_addr = if dst_base_fp { fp } else { ap };
-                let dst = memory.get(dst_addr.0.checked_add_signed(offset0 as i32).unwrap());
-                let taken = !dst.is_zero();
-                if taken {
-                    self.jnz_opcode_taken.push(state);
-                } else {
-                    self.jnz_opcode.push(state);
+            /// The states routed to the opcode component named `component`, in trace order.
+            /// `None` if there is no such opcode component.
+            pub fn states(&self, component: &str) -> Option<&[CasmState]> {
+                $(
+                    if component == stringify!($module) {
+                        return Some(self.$module.as_slice());
+                    }
+                )*
+                None
+            }
+        }
+    };
+}
+opcode_components!(casm_states_by_opcode);
+
+/// Declares [`OpcodePattern`] from a single table of instruction flag patterns. The `kind` fields
+/// of a pattern identify the instructions meant as its opcode, and its `operands` fields restrict
+/// those to the ones its components support. Fields a pattern doesn't list may take any value.
+///
+/// [`CasmStatesByOpcode::push_instr`] routes the instructions matching a pattern to its
+/// components, and [`crate::generic_fallback::fallback_reason`] explains the others by the
+/// `operands` fields they mismatch.
+macro_rules! opcode_patterns {
+    ($(
+        $(#[$attr:meta])*
+        $pattern:ident($name:literal) {
+            kind: { $($kind_field:ident: $kind_value:pat),* $(,)? },
+            operands: { $($operand_field:ident: $operand_value:pat),* $(,)? } $(,)?
+        }
+    )*) => {
+        /// The instruction patterns of the specialized opcode components.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum OpcodePattern {
+            $($(#[$attr])* $pattern,)*
+        }
+        impl OpcodePattern {
+            /// All the patterns, in table order.
+            pub const ALL: &'static [Self] = &[$(Self::$pattern),*];
+
+            /// The name of the pattern's opcode, e.g. `"jnz"`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$pattern => $name,)*
                 }
             }
 
-            // assert equal.
-            Instruction {
-                offset0: _,
-                offset1,
-                offset2,
-                dst_base_fp: _,
-                op0_base_fp,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: true,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                if op_1_imm {
-                    // [ap/fp + offset0] = imm.
-                    assert!(
-                        !op_1_base_fp
-                            && !op_1_base_ap
-                            && offset2 == 1
-                            && op0_base_fp
-                            && offset1 == -1
-                    );
-                    self.assert_eq_opcode_imm.push(state);
-                } else if !op_1_base_fp && !op_1_base_ap {
-                    // [ap/fp + offset0] = [[ap/fp + offset1] + offset2].
-                    self.assert_eq_opcode_double_deref.push(state);
-                } else {
-                    // [ap/fp + offset0] = [ap/fp + offset1].
-                    assert!((op_1_base_fp || op_1_base_ap) && offset1 == -1 && op0_base_fp);
-                    self.assert_eq_opcode.push(state);
+            /// The pattern `instruction` matches, if any.
+            pub fn classify(instruction: &Instruction) -> Option<Self> {
+                match instruction {
+                    $(
+                        Instruction {
+                            $($kind_field: $kind_value,)*
+                            $($operand_field: $operand_value,)*
+                            ..
+                        } => Some(Self::$pattern),
+                    )*
+                    _ => None,
                 }
             }
 
-            // mul.
-            Instruction {
-                offset0: _,
-                offset1,
-                offset2,
-                dst_base_fp: _,
-                op0_base_fp,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: false,
-                res_mul: true,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: true,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                let (op0_addr, op_1_addr) = (
-                    if op0_base_fp { fp } else { ap },
-                    if op_1_imm {
-                        pc
-                    } else if op_1_base_fp {
-                        fp
-                    } else {
-                        ap
-                    },
-                );
-                let (op0, op_1) = (
-                    memory.get(op0_addr.0.checked_add_signed(offset1 as i32).unwrap()),
-                    memory.get(op_1_addr.0.checked_add_signed(offset2 as i32).unwrap()),
-                );
-
-                // [ap/fp + offset0] = [ap/fp + offset1] * imm.
-                // [ap/fp + offset0] = [ap/fp + offset1] * [ap/fp + offset2].
-                assert_eq!(
-                    (op_1_imm as u8) + (op_1_base_fp as u8) + (op_1_base_ap as u8),
-                    1,
-                    "mul opcode requires exactly one of op_1_imm, op_1_base_fp, op_1_base_ap must be true"
-                );
-                assert!(
-                    (!op_1_imm) || offset2 == 1,
-                    "mul opcode requires that if op_1_imm is true, offset2 must be 1"
-                );
-                if is_small_mul(op0, op_1) {
-                    self.mul_opcode_small.push(state);
-                } else {
-                    self.mul_opcode.push(state);
+            /// Whether `instruction` is meant as the pattern's opcode, i.e. matches its `kind`
+            /// fields.
+            pub fn is_kind_of(self, instruction: &Instruction) -> bool {
+                match self {
+                    $(Self::$pattern => [$(matches!(instruction.$kind_field, $kind_value)),*]
+                        .into_iter()
+                        .all(|is_match| is_match),)*
                 }
             }
 
-            // add.
-            Instruction {
-                offset0,
-                offset1,
-                offset2,
-                dst_base_fp,
-                op0_base_fp,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: true,
-                res_mul: false,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: true,
-                opcode_extension: OpcodeExtension::Stone,
-            } => {
-                let (dst_addr, op0_addr, op_1_addr) = (
-                    if dst_base_fp { fp } else { ap },
-                    if op0_base_fp { fp } else { ap },
-                    if op_1_imm {
-                        pc
-                    } else if op_1_base_fp {
-                        fp
-                    } else {
-                        ap
-                    },
-                );
-                let (dst, op0, op_1) = (
-                    memory.get(dst_addr.0.checked_add_signed(offset0 as i32).unwrap()),
-                    memory.get(op0_addr.0.checked_add_signed(offset1 as i32).unwrap()),
-                    memory.get(op_1_addr.0.checked_add_signed(offset2 as i32).unwrap()),
-                );
-
-                // [ap/fp + offset0] = [ap/fp + offset1] + imm.
-                // [ap/fp + offset0] = [ap/fp + offset1] + [ap/fp + offset2].
-                assert_eq!(
-                    (op_1_imm as u8) + (op_1_base_fp as u8) + (op_1_base_ap as u8),
-                    1,
-                    "add opcode requires exactly one of op_1_imm, op_1_base_fp, op_1_base_ap must be true"
-                );
-                assert!(
-                    (!op_1_imm) || offset2 == 1,
-                    "add opcode requires that if op_1_imm is true, offset2 must be 1"
-                );
-                if is_small_add(dst, op0, op_1) {
-                    self.add_opcode_small.push(state);
-                } else {
-                    self.add_opcode.push(state);
+            /// The `operands` fields of `instruction` that don't match the pattern, with their
+            /// values.
+            pub fn mismatches(self, instruction: &Instruction) -> Vec<String> {
+                let mut mismatches = vec![];
+                match self {
+                    $(Self::$pattern => {
+                        $(
+                            if !matches!(instruction.$operand_field, $operand_value) {
+                                mismatches.push(format!(
+                                    "{} = {:?}",
+                                    stringify!($operand_field),
+                                    instruction.$operand_field
+                                ));
+                            }
+                        )*
+                    })*
                 }
+                mismatches
             }
+        }
+    };
+}
 
-            // Blake.
-            Instruction {
-                offset0: _,
-                offset1: _,
-                offset2: _,
-                dst_base_fp: _,
-                op0_base_fp: _,
-                op_1_imm: false,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add: false,
-                res_mul: false,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: false,
-                opcode_extension: OpcodeExtension::Blake | OpcodeExtension::BlakeFinalize,
-            } => {
-                assert!(
-                    op_1_base_fp ^ op_1_base_ap,
-                    "Blake opcode requires exactly one of op_1_base_fp and op_1_base_ap to be true"
-                );
-                self.blake_compress_opcode.push(state);
-            }
+opcode_patterns! {
+    /// `ret`.
+    Ret("ret") {
+        kind: { opcode_ret: true },
+        operands: {
+            offset0: -2,
+            offset1: -1,
+            offset2: -1,
+            dst_base_fp: true,
+            op0_base_fp: true,
+            op_1_imm: false,
+            op_1_base_fp: true,
+            op_1_base_ap: false,
+            res_add: false,
+            res_mul: false,
+            pc_update_jump: true,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            ap_update_add_1: false,
+            opcode_call: false,
+            opcode_assert_eq: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `ap += imm` and `ap += [ap/fp + offset2]`.
+    AddAp("add_ap") {
+        kind: {
+            ap_update_add: true,
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_assert_eq: false,
+        },
+        operands: {
+            offset0: -1,
+            offset1: -1,
+            dst_base_fp: true,
+            op0_base_fp: true,
+            res_add: false,
+            res_mul: false,
+            ap_update_add_1: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `jmp rel imm`, `jmp rel/abs [ap/fp + offset2]` and `jmp abs [[ap/fp + offset1] + offset2]`.
+    Jump("jump") {
+        kind: {
+            pc_update_jnz: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_assert_eq: false,
+        },
+        operands: {
+            offset0: -1,
+            dst_base_fp: true,
+            res_add: false,
+            res_mul: false,
+            ap_update_add: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `call rel imm` and `call abs [ap/fp + offset2]`.
+    Call("call") {
+        kind: { opcode_call: true },
+        operands: {
+            offset0: 0,
+            offset1: 1,
+            dst_base_fp: false,
+            op0_base_fp: false,
+            res_add: false,
+            res_mul: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            ap_update_add_1: false,
+            opcode_ret: false,
+            opcode_assert_eq: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `jmp rel imm if [ap/fp + offset0] != 0`.
+    Jnz("jnz") {
+        kind: {
+            pc_update_jnz: true,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_assert_eq: false,
+        },
+        operands: {
+            offset1: -1,
+            offset2: 1,
+            op0_base_fp: true,
+            op_1_imm: true,
+            op_1_base_fp: false,
+            op_1_base_ap: false,
+            res_add: false,
+            res_mul: false,
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            ap_update_add: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `[ap/fp + offset0] = imm/[ap/fp + offset2]/[[ap/fp + offset1] + offset2]`.
+    AssertEq("assert_eq") {
+        kind: { opcode_assert_eq: true, res_add: false, res_mul: false },
+        operands: {
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `[ap/fp + offset0] = [ap/fp + offset1] * imm/[ap/fp + offset2]`.
+    Mul("mul") {
+        kind: { opcode_assert_eq: true, res_add: false, res_mul: true },
+        operands: {
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// `[ap/fp + offset0] = [ap/fp + offset1] + imm/[ap/fp + offset2]`.
+    Add("add") {
+        kind: { opcode_assert_eq: true, res_add: true, res_mul: false },
+        operands: {
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_extension: OpcodeExtension::Stone,
+        },
+    }
+    /// Blake2s compression (and finalization) of a message block.
+    Blake("blake") {
+        kind: { opcode_extension: OpcodeExtension::Blake | OpcodeExtension::BlakeFinalize },
+        operands: {
+            op_1_imm: false,
+            res_add: false,
+            res_mul: false,
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_assert_eq: false,
+        },
+    }
+    /// QM31 `[ap/fp + offset0] = [ap/fp + offset1] +/* [ap/fp/pc + offset2]`.
+    QM31AddMul("qm31_add_mul") {
+        kind: { opcode_extension: OpcodeExtension::QM31Operation },
+        operands: {
+            pc_update_jump: false,
+            pc_update_jump_rel: false,
+            pc_update_jnz: false,
+            ap_update_add: false,
+            opcode_call: false,
+            opcode_ret: false,
+            opcode_assert_eq: true,
+        },
+    }
+}
 
-            // QM31 add mul.
-            Instruction {
-                offset0: _,
-                offset1: _,
-                offset2,
-                dst_base_fp: _,
-                op0_base_fp: _,
-                op_1_imm,
-                op_1_base_fp,
-                op_1_base_ap,
-                res_add,
-                res_mul,
-                pc_update_jump: false,
-                pc_update_jump_rel: false,
-                pc_update_jnz: false,
-                ap_update_add: false,
-                ap_update_add_1: _,
-                opcode_call: false,
-                opcode_ret: false,
-                opcode_assert_eq: true,
-                opcode_extension: OpcodeExtension::QM31Operation,
-            } => {
-                // [ap/fp + offset0] = [ap/fp + offset1] +/* [ap/fp/pc + offset2]
-                assert_eq!(
-                    (op_1_imm as u8) + (op_1_base_fp as u8) + (op_1_base_ap as u8),
-                    1,
-                    "qm31_add_mul opcode requires exactly one of op_1_imm, op_1_base_fp, op_1_base_ap must be true"
-                );
-                assert!(
-                    res_add ^ res_mul,
-                    "qm31_add_mul opcode requires exactly one of res_add, res_mul must be true"
-                );
-                assert!(
-                    (!op_1_imm) || offset2 == 1,
-                    "qm31_add_mul opcode requires that if op_1_imm is true, offset2 must be 1"
-                );
-                self.qm_31_add_mul_opcode.push(state);
-            }
+impl CasmStatesByOpcode {
+    /// Splits the states of `iter` into opcode components, recording why instructions fell back
+    /// to `generic_opcode` into `fallbacks`, if given.
+    fn from_iter(
+        iter: impl DoubleEndedIterator<Item = RelocatedTraceEntry>,
+        memory: &MemoryBuilder,
+        mut fallbacks: Option<&mut GenericFallbackReport>,
+    ) -> Result<Self, VmImportError> {
+        let mut res = CasmStatesByOpcode::default();
+        for entry in iter {
+            res.push_instr(memory, entry.into(), fallbacks.as_deref_mut())?;
+        }
+        Ok(res)
+    }
 
-            // generic opcode.
-            _ => {
-                if !matches!(instruction.opcode_extension, OpcodeExtension::Stone) {
-                    panic!("`generic_opcode` component supports `Stone` opcodes only.");
-                }
-                self.generic_opcode.push(state);
+    /// Pushes the state transition at pc into the component of the [`OpcodePattern`] its
+    /// instruction matches that selects it, or into `generic_opcode`. Fails on opcode extension
+    /// instructions that match no pattern, as `generic_opcode` only supports `Stone` instructions.
+    fn push_instr(
+        &mut self,
+        memory: &MemoryBuilder,
+        state: CasmState,
+        fallbacks: Option<&mut GenericFallbackReport>,
+    ) -> Result<(), VmImportError> {
+        let instruction = Instruction::decode(memory.get_inst(state.pc.0));
+        let Some(pattern) = OpcodePattern::classify(&instruction) else {
+            // The VM rejects opcode extension instructions with flags outside their patterns,
+            // so only `Stone` instructions are expected here.
+            if !matches!(instruction.opcode_extension, OpcodeExtension::Stone) {
+                return Err(VmImportError::UnsupportedInstruction {
+                    pc: state.pc.0,
+                    opcode_extension: instruction.opcode_extension,
+                });
             }
+            if let Some(fallbacks) = fallbacks {
+                fallbacks.record(state.pc.0, &instruction);
+            }
+            self.generic_opcode.push(state);
+            return Ok(());
+        };
+
+        let step = InstructionStep {
+            instruction: &instruction,
+            state,
+            memory,
+        };
+        macro_rules! push_to_selected_component {
+            ($($module:ident [$($pattern:ident, $selector:ident)?],)*) => {
+                $($(
+                    if pattern == OpcodePattern::$pattern && step.$selector() {
+                        self.$module.push(state);
+                        return Ok(());
+                    }
+                )?)*
+            };
         }
+        opcode_components!(@routes push_to_selected_component);
+        unreachable!("No component selected a {} instruction", pattern.name())
+    }
+}
+
+/// A state transition routed by [`CasmStatesByOpcode::push_instr`]. Its selectors choose among
+/// the components of the [`OpcodePattern`] its instruction matches, asserting the operands the
+/// chosen component requires.
+struct InstructionStep<'a> {
+    instruction: &'a Instruction,
+    state: CasmState,
+    memory: &'a MemoryBuilder,
+}
+impl InstructionStep<'_> {
+    fn read(&self, base: M31, offset: i16) -> MemoryValue {
+        self.memory
+            .get(base.0.checked_add_signed(offset as i32).unwrap())
     }
 
-    pub fn merge(
-        &mut self,
-        CasmStatesByOpcode {
-            generic_opcode,
-            add_ap_opcode,
-            add_opcode,
-            add_opcode_small,
-            assert_eq_opcode,
-            assert_eq_opcode_double_deref,
-            assert_eq_opcode_imm,
-            call_opcode,
-            call_opcode_rel_imm,
-            jnz_opcode,
-            jnz_opcode_taken,
-            jump_opcode_rel_imm,
-            jump_opcode_rel,
-            jump_opcode_double_deref,
-            jump_opcode,
-            mul_opcode_small,
-            mul_opcode,
-            ret_opcode,
-            blake_compress_opcode,
-            qm_31_add_mul_opcode,
-        }: &Self,
-    ) {
-        self.generic_opcode.extend(generic_opcode);
-        self.add_ap_opcode.extend(add_ap_opcode);
-        self.add_opcode.extend(add_opcode);
-        self.add_opcode_small.extend(add_opcode_small);
-        self.assert_eq_opcode.extend(assert_eq_opcode);
-        self.assert_eq_opcode_double_deref
-            .extend(assert_eq_opcode_double_deref);
-        self.assert_eq_opcode_imm.extend(assert_eq_opcode_imm);
-        self.call_opcode.extend(call_opcode);
-        self.call_opcode_rel_imm.extend(call_opcode_rel_imm);
-        self.jnz_opcode.extend(jnz_opcode);
-        self.jnz_opcode_taken.extend(jnz_opcode_taken);
-        self.jump_opcode_rel_imm.extend(jump_opcode_rel_imm);
-        self.jump_opcode_rel.extend(jump_opcode_rel);
-        self.jump_opcode_double_deref
-            .extend(jump_opcode_double_deref);
-        self.jump_opcode.extend(jump_opcode);
-        self.mul_opcode_small.extend(mul_opcode_small);
-        self.mul_opcode.extend(mul_opcode);
-        self.ret_opcode.extend(ret_opcode);
-        self.blake_compress_opcode.extend(blake_compress_opcode);
-        self.qm_31_add_mul_opcode.extend(qm_31_add_mul_opcode);
-    }
-
-    pub fn counts(&self) -> Vec<(String, usize)> {
-        vec![
-            ("generic_opcode".to_string(), self.generic_opcode.len()),
-            ("add_ap_opcode".to_string(), self.add_ap_opcode.len()),
-            ("add_opcode".to_string(), self.add_opcode.len()),
-            ("add_opcode_small".to_string(), self.add_opcode_small.len()),
-            ("assert_eq_opcode".to_string(), self.assert_eq_opcode.len()),
-            (
-                "assert_eq_opcode_double_deref".to_string(),
-                self.assert_eq_opcode_double_deref.len(),
-            ),
-            (
-                "assert_eq_opcode_imm".to_string(),
-                self.assert_eq_opcode_imm.len(),
-            ),
-            ("call_opcode".to_string(), self.call_opcode.len()),
-            (
-                "call_opcode_rel_imm".to_string(),
-                self.call_opcode_rel_imm.len(),
-            ),
-            ("jnz_opcode".to_string(), self.jnz_opcode.len()),
-            ("jnz_opcode_taken".to_string(), self.jnz_opcode_taken.len()),
-            (
-                "jump_opcode_rel_imm".to_string(),
-                self.jump_opcode_rel_imm.len(),
-            ),
-            ("jump_opcode_rel".to_string(), self.jump_opcode_rel.len()),
-            (
-                "jump_opcode_double_deref".to_string(),
-                self.jump_opcode_double_deref.len(),
-            ),
-            ("jump_opcode".to_string(), self.jump_opcode.len()),
-            ("mul_opcode_small".to_string(), self.mul_opcode_small.len()),
-            ("mul_opcode".to_string(), self.mul_opcode.len()),
-            ("ret_opcode".to_string(), self.ret_opcode.len()),
-            (
-                "blake_compress_opcode".to_string(),
-                self.blake_compress_opcode.len(),
-            ),
-            (
-                "qm_31_add_mul_opcode".to_string(),
-                self.qm_31_add_mul_opcode.len(),
-            ),
-        ]
+    fn dst(&self) -> MemoryValue {
+        let CasmState { ap, fp, .. } = self.state;
+        let Instruction {
+            dst_base_fp,
+            offset0,
+            ..
+        } = *self.instruction;
+        self.read(if dst_base_fp { fp } else { ap }, offset0)
+    }
+
+    fn op0(&self) -> MemoryValue {
+        let CasmState { ap, fp, .. } = self.state;
+        let Instruction {
+            op0_base_fp,
+            offset1,
+            ..
+        } = *self.instruction;
+        self.read(if op0_base_fp { fp } else { ap }, offset1)
+    }
+
+    fn op_1(&self) -> MemoryValue {
+        let CasmState { ap, fp, pc } = self.state;
+        let Instruction {
+            op_1_imm,
+            op_1_base_fp,
+            offset2,
+            ..
+        } = *self.instruction;
+        let base = if op_1_imm {
+            pc
+        } else if op_1_base_fp {
+            fp
+        } else {
+            ap
+        };
+        self.read(base, offset2)
+    }
+
+    /// Asserts that op1 is either `imm` or `[ap/fp + offset2]`.
+    fn assert_op_1_is_imm_or_deref(&self, opcode: &str) {
+        let Instruction {
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            offset2,
+            ..
+        } = *self.instruction;
+        assert_eq!(
+            (op_1_imm as u8) + (op_1_base_fp as u8) + (op_1_base_ap as u8),
+            1,
+            "{opcode} opcode requires exactly one of op_1_imm, op_1_base_fp, op_1_base_ap must be true"
+        );
+        assert!(
+            (!op_1_imm) || offset2 == 1,
+            "{opcode} opcode requires that if op_1_imm is true, offset2 must be 1"
+        );
+    }
+
+    /// `ret`.
+    fn is_ret(&self) -> bool {
+        true
+    }
+
+    /// `ap += imm` and `ap += [ap/fp + offset2]`.
+    fn is_add_ap(&self) -> bool {
+        self.assert_op_1_is_imm_or_deref("add_ap");
+        // next ap = ap + op1 must be in the range [0, 2^27 - 1].
+        let ap = self.state.ap.0 as i128;
+        if !is_within_range(self.op_1(), -ap, ((1 << 27) - 1) - ap) {
+            panic!("add_ap opcode requires that next_ap is within the range of [0, 2^27 - 1]");
+        }
+        true
+    }
+
+    /// `jmp rel imm`.
+    fn is_jump_rel_imm(&self) -> bool {
+        let Instruction {
+            offset1,
+            offset2,
+            op0_base_fp,
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            pc_update_jump,
+            pc_update_jump_rel,
+            ..
+        } = *self.instruction;
+        if !op_1_imm {
+            return false;
+        }
+        assert!(
+            pc_update_jump_rel
+                && !pc_update_jump
+                && !op_1_base_fp
+                && !op_1_base_ap
+                && op0_base_fp
+                && offset1 == -1
+                && offset2 == 1
+        );
+        true
+    }
+
+    /// `jmp rel [ap/fp + offset2]`.
+    fn is_jump_rel(&self) -> bool {
+        let Instruction {
+            offset1,
+            op0_base_fp,
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            pc_update_jump,
+            pc_update_jump_rel,
+            ..
+        } = *self.instruction;
+        if op_1_imm || !pc_update_jump_rel {
+            return false;
+        }
+        assert!(!pc_update_jump && (op_1_base_fp || op_1_base_ap) && op0_base_fp && offset1 == -1);
+        true
+    }
+
+    /// `jmp abs [[ap/fp + offset1] + offset2]`.
+    fn is_jump_double_deref(&self) -> bool {
+        let Instruction {
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            pc_update_jump,
+            pc_update_jump_rel,
+            ..
+        } = *self.instruction;
+        if op_1_imm || pc_update_jump_rel || op_1_base_fp || op_1_base_ap {
+            return false;
+        }
+        assert!(pc_update_jump);
+        true
+    }
+
+    /// `jmp abs [ap/fp + offset2]`.
+    fn is_jump(&self) -> bool {
+        let Instruction {
+            offset1,
+            op0_base_fp,
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            pc_update_jump,
+            pc_update_jump_rel,
+            ..
+        } = *self.instruction;
+        if op_1_imm || pc_update_jump_rel || !(op_1_base_fp || op_1_base_ap) {
+            return false;
+        }
+        assert!(op0_base_fp && pc_update_jump && offset1 == -1);
+        true
+    }
+
+    /// `call rel imm`.
+    fn is_call_rel_imm(&self) -> bool {
+        let Instruction {
+            offset2,
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            pc_update_jump,
+            pc_update_jump_rel,
+            ..
+        } = *self.instruction;
+        if !pc_update_jump_rel {
+            return false;
+        }
+        assert!(op_1_imm && !op_1_base_fp && !op_1_base_ap && offset2 == 1 && !pc_update_jump);
+        true
+    }
+
+    /// `call abs [ap/fp + offset2]`.
+    fn is_call(&self) -> bool {
+        let Instruction {
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            pc_update_jump,
+            pc_update_jump_rel,
+            ..
+        } = *self.instruction;
+        if pc_update_jump_rel {
+            return false;
+        }
+        assert!((op_1_base_ap ^ op_1_base_fp) && !op_1_imm && pc_update_jump);
+        true
+    }
+
+    /// `jmp rel imm if [ap/fp + offset0] != 0`, when the jump is taken.
+    fn is_jnz_taken(&self) -> bool {
+        !self.dst().is_zero()
+    }
+
+    /// `jmp rel imm if [ap/fp + offset0] != 0`, when the jump is not taken.
+    fn is_jnz_not_taken(&self) -> bool {
+        self.dst().is_zero()
+    }
+
+    /// `[ap/fp + offset0] = imm`.
+    fn is_assert_eq_imm(&self) -> bool {
+        let Instruction {
+            offset1,
+            offset2,
+            op0_base_fp,
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            ..
+        } = *self.instruction;
+        if !op_1_imm {
+            return false;
+        }
+        assert!(!op_1_base_fp && !op_1_base_ap && offset2 == 1 && op0_base_fp && offset1 == -1);
+        true
+    }
+
+    /// `[ap/fp + offset0] = [[ap/fp + offset1] + offset2]`.
+    fn is_assert_eq_double_deref(&self) -> bool {
+        let Instruction {
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            ..
+        } = *self.instruction;
+        !op_1_imm && !op_1_base_fp && !op_1_base_ap
+    }
+
+    /// `[ap/fp + offset0] = [ap/fp + offset2]`.
+    fn is_assert_eq(&self) -> bool {
+        let Instruction {
+            offset1,
+            op0_base_fp,
+            op_1_imm,
+            op_1_base_fp,
+            op_1_base_ap,
+            ..
+        } = *self.instruction;
+        if op_1_imm || !(op_1_base_fp || op_1_base_ap) {
+            return false;
+        }
+        assert!(offset1 == -1 && op0_base_fp);
+        true
+    }
+
+    /// `[ap/fp + offset0] = [ap/fp + offset1] * imm/[ap/fp + offset2]`, for small operands.
+    fn is_small_mul(&self) -> bool {
+        self.assert_op_1_is_imm_or_deref("mul");
+        is_small_mul(self.op0(), self.op_1())
+    }
+
+    /// `[ap/fp + offset0] = [ap/fp + offset1] * imm/[ap/fp + offset2]`, for big operands.
+    fn is_big_mul(&self) -> bool {
+        !self.is_small_mul()
+    }
+
+    /// `[ap/fp + offset0] = [ap/fp + offset1] + imm/[ap/fp + offset2]`, for small operands.
+    fn is_small_add(&self) -> bool {
+        self.assert_op_1_is_imm_or_deref("add");
+        is_small_add(self.dst(), self.op0(), self.op_1())
+    }
+
+    /// `[ap/fp + offset0] = [ap/fp + offset1] + imm/[ap/fp + offset2]`, for big operands.
+    fn is_big_add(&self) -> bool {
+        !self.is_small_add()
+    }
+
+    /// Blake2s compression (and finalization) of a message block.
+    fn is_blake(&self) -> bool {
+        assert!(
+            self.instruction.op_1_base_fp ^ self.instruction.op_1_base_ap,
+            "Blake opcode requires exactly one of op_1_base_fp and op_1_base_ap to be true"
+        );
+        true
+    }
+
+    /// QM31 `[ap/fp + offset0] = [ap/fp + offset1] +/* [ap/fp/pc + offset2]`.
+    fn is_qm31_add_mul(&self) -> bool {
+        self.assert_op_1_is_imm_or_deref("qm31_add_mul");
+        assert!(
+            self.instruction.res_add ^ self.instruction.res_mul,
+            "qm31_add_mul opcode requires exactly one of res_add, res_mul must be true"
+        );
+        true
     }
 }
 
@@ -625,6 +760,9 @@ pub struct StateTransitions {
     pub initial_state: CasmState,
     pub final_state: CasmState,
     pub casm_states_by_opcode: CasmStatesByOpcode,
+    /// Why instructions fell back to `generic_opcode`, if recording it was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generic_fallbacks: Option<GenericFallbackReport>,
 }
 
 impl StateTransitions {
@@ -639,7 +777,7 @@ impl StateTransitions {
     pub fn from_iter(
         iter: impl DoubleEndedIterator<Item = RelocatedTraceEntry>,
         memory: &MemoryBuilder,
-    ) -> Self {
+    ) -> Result<Self, VmImportError> {
         let _span = span!(Level::INFO, "StateTransitions::from_iter").entered();
         let mut iter = iter.peekable();
 
@@ -648,16 +786,24 @@ impl StateTransitions {
         // Assuming the last instruction is jrl0, no need to push it.
         let final_state = iter.next_back().unwrap().into();
 
-        let states = CasmStatesByOpcode::from_iter(iter, memory);
+        let states = CasmStatesByOpcode::from_iter(iter, memory, None)?;
 
-        StateTransitions {
+        Ok(StateTransitions {
             initial_state,
             final_state,
             casm_states_by_opcode: states,
-        }
+            generic_fallbacks: None,
+        })
     }
 
-    pub fn from_slice_parallel(trace: &[RelocatedTraceEntry], memory: &MemoryBuilder) -> Self {
+    /// Splits the states of `trace` into opcode components, in parallel. If
+    /// `record_generic_fallbacks` is set, also records why instructions fell back to
+    /// `generic_opcode`, which slows down the split.
+    pub fn from_slice_parallel(
+        trace: &[RelocatedTraceEntry],
+        memory: &MemoryBuilder,
+        record_generic_fallbacks: bool,
+    ) -> Result<Self, VmImportError> {
         let _span = span!(Level::INFO, "StateTransitions::from_slice_parallel").entered();
         let initial_state = trace.first().copied().unwrap().into();
 
@@ -667,19 +813,37 @@ impl StateTransitions {
 
         let n_workers = rayon::current_num_threads();
         let chunk_size = trace.len().div_ceil(n_workers);
-        let casm_states_by_opcode = trace
+        let new_fallbacks = || record_generic_fallbacks.then(GenericFallbackReport::default);
+        let (casm_states_by_opcode, generic_fallbacks) = trace
             .par_chunks(chunk_size)
-            .map(|chunk| CasmStatesByOpcode::from_iter(chunk.iter().copied(), memory))
-            .reduce(Default::default, |mut acc, chunk| {
-                acc.merge(&chunk);
-                acc
-            });
-
-        StateTransitions {
+            .map(|chunk| {
+                let mut fallbacks = new_fallbacks();
+                let states = CasmStatesByOpcode::from_iter(
+                    chunk.iter().copied(),
+                    memory,
+                    fallbacks.as_mut(),
+                )?;
+                Ok((states, fallbacks))
+            })
+            .try_reduce(
+                || (Default::default(), new_fallbacks()),
+                |(mut acc, mut acc_fallbacks), (chunk, chunk_fallbacks)| {
+                    acc.merge(&chunk);
+                    if let (Some(acc_fallbacks), Some(chunk_fallbacks)) =
+                        (&mut acc_fallbacks, chunk_fallbacks)
+                    {
+                        acc_fallbacks.merge(chunk_fallbacks);
+                    }
+                    Ok((acc, acc_fallbacks))
+                },
+            )?;
+
+        Ok(StateTransitions {
             initial_state,
             final_state,
             casm_states_by_opcode,
-        }
+            generic_fallbacks,
+        })
     }
 }
 
@@ -721,43 +885,22 @@ fn is_small_mul(op0: MemoryValue, op_1: MemoryValue) -> bool {
 mod mappings_tests {
 
     use cairo_lang_casm::casm;
-    use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor;
-    use cairo_vm::types::layout_name::LayoutName;
-    use cairo_vm::vm::runners::cairo_runner::CairoRunner;
     use stwo_cairo_common::prover_types::cpu::CasmState;
     use stwo_prover::core::fields::m31::M31;
 
-    use crate::adapter::adapter;
     use crate::decode::{Instruction, OpcodeExtension};
     use crate::memory::*;
     use crate::opcodes::{CasmStatesByOpcode, StateTransitions};
     use crate::relocator::relocator_tests::{create_test_relocator, get_test_relocatble_trace};
-    use crate::test_utils::program_from_casm;
-    use crate::vm_import::RelocatedTraceEntry;
+    use crate::test_utils::{program_from_casm, run_program_until_end};
+    use crate::vm_import::{RelocatedTraceEntry, VmImportError};
     use crate::{casm_state, relocated_trace_entry, ProverInput};
 
     /// Translates a plain casm into a ProverInput by running the program and extracting the memory
     /// and the state transitions.
     fn input_from_plain_casm(casm: Vec<cairo_lang_casm::instructions::Instruction>) -> ProverInput {
         let (program, program_len) = program_from_casm(casm);
-
-        let mut runner =
-            CairoRunner::new(&program, LayoutName::all_cairo_stwo, None, true, true, true)
-                .expect("Runner creation failed");
-        runner.initialize(true).expect("Initialization failed");
-        runner
-            .run_until_pc(
-                (runner.program_base.unwrap() + program_len).unwrap(),
-                &mut BuiltinHintProcessor::new_empty(),
-            )
-            .expect("Run failed");
-        runner.relocate(true).unwrap();
-        adapter(
-            &mut runner
-                .get_prover_input_info()
-                .expect("Failed to get prover input info from finished runner"),
-        )
-        .expect("Failed to run adapter")
+        run_program_until_end(&program, program_len).expect("Failed to run program")
     }
 
     #[test]
@@ -771,7 +914,9 @@ mod mappings_tests {
         memory_builder.set(1, MemoryValue::F252([x[0], x[1], x[2], x[3], 0, 0, 0, 0]));
 
         let trace_entry = relocated_trace_entry!(1, 1, 1);
-        let states = CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+        let states =
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
         assert_eq!(states.jump_opcode_rel.len(), 1);
     }
 
@@ -786,7 +931,9 @@ mod mappings_tests {
         memory_builder.set(1, MemoryValue::F252([x[0], x[1], x[2], x[3], 0, 0, 0, 0]));
 
         let trace_entry = relocated_trace_entry!(1, 1, 1);
-        let states = CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+        let states =
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
         assert_eq!(states.jump_opcode_double_deref.len(), 1);
     }
 
@@ -853,7 +1000,8 @@ mod mappings_tests {
 
         let trace_entry = relocated_trace_entry!(ap as usize, fp as usize, pc as usize);
         let casm_states_by_opcode =
-            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
         assert_eq!(casm_states_by_opcode.add_ap_opcode.len(), 1);
     }
 
@@ -874,7 +1022,8 @@ mod mappings_tests {
 
         let trace_entry = relocated_trace_entry!(ap as usize, fp as usize, pc as usize);
         let _casm_states_by_opcode =
-            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
     }
 
     #[test]
@@ -891,7 +1040,8 @@ mod mappings_tests {
 
         let trace_entry = relocated_trace_entry!(ap as usize, fp as usize, pc as usize);
         let casm_states_by_opcode =
-            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
         assert_eq!(casm_states_by_opcode.add_ap_opcode.len(), 1);
     }
 
@@ -912,7 +1062,8 @@ mod mappings_tests {
 
         let trace_entry = relocated_trace_entry!(ap as usize, fp as usize, pc as usize);
         let _casm_states_by_opcode =
-            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
     }
 
     #[test]
@@ -1153,7 +1304,9 @@ mod mappings_tests {
         let instruction = Instruction::decode(memory_builder.get_inst(1));
         let trace_entry = relocated_trace_entry!(1, 1, 1);
 
-        let states = CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+        let states =
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
 
         matches!(instruction.opcode_extension, OpcodeExtension::BlakeFinalize);
         assert_eq!(states.blake_compress_opcode.len(), 1);
@@ -1169,12 +1322,40 @@ mod mappings_tests {
 
         let instruction = Instruction::decode(memory_builder.get_inst(1));
         let trace_entry = relocated_trace_entry!(1, 1, 1);
-        let states = CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder);
+        let states =
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None)
+                .unwrap();
 
         matches!(instruction.opcode_extension, OpcodeExtension::QM31Operation);
         assert_eq!(states.qm_31_add_mul_opcode.len(), 1);
     }
 
+    #[test]
+    fn test_unsupported_opcode_extension() {
+        let encoded_qm_31_add_mul_inst =
+            0b11100000001001010011111111111110101111111111111001000000000000000;
+        // A QM31 operation that also jumps, which no opcode component supports.
+        let qm_31_jump_inst = Instruction {
+            pc_update_jump: true,
+            ..Instruction::decode(encoded_qm_31_add_mul_inst)
+        };
+        let x = u128_to_4_limbs(qm_31_jump_inst.encode());
+        let mut memory_builder = MemoryBuilder::new(MemoryConfig::default());
+        memory_builder.set(1, MemoryValue::F252([x[0], x[1], x[2], x[3], 0, 0, 0, 0]));
+
+        let trace_entry = relocated_trace_entry!(1, 1, 1);
+        let result =
+            CasmStatesByOpcode::from_iter([trace_entry].into_iter(), &memory_builder, None);
+
+        assert!(matches!(
+            result,
+            Err(VmImportError::UnsupportedInstruction {
+                pc: 1,
+                opcode_extension: OpcodeExtension::QM31Operation,
+            })
+        ));
+    }
+
     #[test]
     fn test_casm_state_from_relocator() {
         let relocator = create_test_relocator();
@@ -1193,7 +1374,8 @@ mod mappings_tests {
                 .relocate_trace(&get_test_relocatble_trace())
                 .into_iter(),
             &memory_builder,
-        );
+        )
+        .unwrap();
         assert_eq!(
             state_transitions.casm_states_by_opcode.qm_31_add_mul_opcode,
             vec![casm_state!(1, 5, 5), casm_state!(5, 6, 6)]
@@ -1201,4 +1383,27 @@ mod mappings_tests {
         assert_eq!(state_transitions.final_state, casm_state!(85, 6, 6));
         assert_eq!(state_transitions.initial_state, casm_state!(1, 5, 5));
     }
+
+    #[test]
+    fn test_casm_states_by_opcode_components() {
+        let mut states = CasmStatesByOpcode::default();
+        states.jnz_opcode_taken.push(casm_state!(1, 2, 3));
+        let mut merged = CasmStatesByOpcode::default();
+        merged.merge(&states);
+        merged.merge(&states);
+
+        let counts = merged.counts();
+        assert_eq!(
+            counts
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            CasmStatesByOpcode::COMPONENTS
+        );
+        for (name, count) in counts {
+            assert_eq!(merged.states(&name).unwrap().len(), count);
+        }
+        assert_eq!(merged.states("jnz_opcode_taken").unwrap().len(), 2);
+        assert!(merged.states("jnz_taken").is_none());
+    }
 }
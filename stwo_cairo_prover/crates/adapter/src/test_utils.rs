@@ -7,22 +7,31 @@ use cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_def
 use cairo_vm::stdlib::collections::HashMap;
 use cairo_vm::types::layout_name::LayoutName;
 use cairo_vm::types::relocatable::MaybeRelocatable;
-use cairo_vm::vm::runners::cairo_runner::ProverInputInfo;
+use cairo_vm::vm::runners::cairo_runner::{CairoRunner, ProverInputInfo};
+use cairo_vm::Felt252;
 use itertools::Itertools;
 use serde_json::{to_string_pretty, Value};
+use thiserror::Error;
 use tracing::{span, Level};
 
 use crate::adapter::adapter;
+use crate::vm_import::VmImportError;
 use crate::ProverInput;
 
 pub fn program_from_casm(
     casm: Vec<cairo_lang_casm::instructions::Instruction>,
 ) -> (cairo_vm::types::program::Program, usize) {
-    let felt_code = casm
-        .into_iter()
-        .flat_map(|instruction| instruction.assemble().encode())
-        .map(|felt| MaybeRelocatable::Int(felt.into()))
-        .collect_vec();
+    program_from_encoded(
+        casm.into_iter()
+            .flat_map(|instruction| instruction.assemble().encode())
+            .map(Felt252::from)
+            .collect(),
+    )
+}
+
+/// Builds a program from its encoded instructions and immediates.
+pub fn program_from_encoded(code: Vec<Felt252>) -> (cairo_vm::types::program::Program, usize) {
+    let felt_code = code.into_iter().map(MaybeRelocatable::Int).collect_vec();
     let program_len = felt_code.len();
     let program = cairo_vm::types::program::Program::new_for_proof(
         vec![],
@@ -39,6 +48,33 @@ pub fn program_from_casm(
     (program, program_len)
 }
 
+#[derive(Debug, Error)]
+pub enum RunProgramError {
+    #[error("VM run failed: {0}")]
+    Vm(String),
+    #[error("Adapter failed: {0}")]
+    Adapter(#[from] VmImportError),
+}
+
+/// Runs a program built by [`program_from_casm`] or [`program_from_encoded`] until it reaches its
+/// end, and runs the adapter on the execution.
+pub fn run_program_until_end(
+    program: &cairo_vm::types::program::Program,
+    program_len: usize,
+) -> Result<ProverInput, RunProgramError> {
+    let vm_error = |e: &dyn std::fmt::Display| RunProgramError::Vm(e.to_string());
+    let mut runner = CairoRunner::new(program, LayoutName::all_cairo_stwo, None, true, true, true)
+        .map_err(|e| vm_error(&e))?;
+    runner.initialize(true).map_err(|e| vm_error(&e))?;
+    let end = (runner.program_base.unwrap() + program_len).map_err(|e| vm_error(&e))?;
+    runner
+        .run_until_pc(end, &mut BuiltinHintProcessor::new_empty())
+        .map_err(|e| vm_error(&e))?;
+    runner.relocate(true).map_err(|e| vm_error(&e))?;
+    let mut prover_input_info = runner.get_prover_input_info().map_err(|e| vm_error(&e))?;
+    Ok(adapter(&mut prover_input_info)?)
+}
+
 pub fn read_prover_input_info_file(prover_input_info_path: &Path) -> ProverInputInfo {
     let _span: span::EnteredSpan = span!(Level::INFO, "read_prover_input_info_file").entered();
 
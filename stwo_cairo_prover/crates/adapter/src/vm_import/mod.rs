@@ -13,6 +13,7 @@ use thiserror::Error;
 use tracing::{span, Level};
 
 use super::builtins::BuiltinSegments;
+use super::decode::OpcodeExtension;
 use super::memory::MemoryConfig;
 use super::opcodes::StateTransitions;
 use super::ProverInput;
@@ -37,6 +38,12 @@ pub enum VmImportError {
 
     #[error("Cannot get public input from runner: {0}")]
     PublicInput(#[from] PublicInputError),
+
+    #[error("Unsupported {opcode_extension:?} instruction at pc {pc}")]
+    UnsupportedInstruction {
+        pc: u32,
+        opcode_extension: OpcodeExtension,
+    },
 }
 
 fn deserialize_inputs<'a>(
@@ -124,11 +131,14 @@ pub fn adapt_vm_output(
             .map(|(k, v)| (k, v.into()))
             .collect(),
         public_segment_context,
+        false,
     );
     res
 }
 
 /// Creates Cairo input for Stwo, utilized by `adapt_vm_output` in the prover.
+/// If `record_generic_fallbacks` is set, the state transitions record why instructions fell back
+/// to `generic_opcode`.
 /// TODO(Stav): delete when 'adapt_prover_input_info_vm_output' is used.
 pub fn adapt_to_stwo_input(
     trace: &[RelocatedTraceEntry],
@@ -136,8 +146,10 @@ pub fn adapt_to_stwo_input(
     public_memory_addresses: Vec<u32>,
     memory_segments: &HashMap<&str, MemorySegmentAddresses>,
     public_segment_context: PublicSegmentContext,
+    record_generic_fallbacks: bool,
 ) -> Result<ProverInput, VmImportError> {
-    let state_transitions = StateTransitions::from_slice_parallel(trace, &memory);
+    let state_transitions =
+        StateTransitions::from_slice_parallel(trace, &memory, record_generic_fallbacks)?;
     let mut builtins_segments = BuiltinSegments::from_memory_segments(memory_segments);
     builtins_segments.fill_memory_holes(&mut memory);
     builtins_segments.pad_builtin_segments(&mut memory);
@@ -0,0 +1,84 @@
+//! Generates the names of the trace columns of the generated components, from the
+//! `next_trace_mask` calls of each component's `evaluate`. See `src/components/column_names.rs`.
+
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+const COMPONENTS_DIR: &str = "src/components";
+
+fn main() {
+    println!("cargo:rerun-if-changed={COMPONENTS_DIR}");
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(COMPONENTS_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rs"))
+        .collect();
+    paths.sort();
+
+    let mut output = String::new();
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        // Hand-written components compute their number of columns, and name them themselves.
+        let Some(n_trace_columns) = n_trace_columns(&source) else {
+            continue;
+        };
+        let component = path.file_stem().unwrap().to_str().unwrap();
+        let names = trace_column_names(&source);
+        assert_eq!(
+            names.len(),
+            n_trace_columns,
+            "`{component}` reads {} trace columns, but declares {n_trace_columns}",
+            names.len(),
+        );
+        output += &column_names_module(component, &names);
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("column_names.rs"), output).unwrap();
+}
+
+/// The `N_TRACE_COLUMNS` of a generated component, which is a literal.
+fn n_trace_columns(source: &str) -> Option<usize> {
+    source.lines().find_map(|line| {
+        line.strip_prefix("pub const N_TRACE_COLUMNS: usize = ")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    })
+}
+
+/// The names of the variables the trace columns are read to, in order.
+fn trace_column_names(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("let ")?
+                .strip_suffix(" = eval.next_trace_mask();")
+        })
+        .collect()
+}
+
+fn column_names_module(component: &str, names: &[&str]) -> String {
+    let names: String = names.iter().map(|name| format!("\"{name}\", ")).collect();
+    format!(
+        "pub mod {component} {{
+    use crate::components::prelude::*;
+    use crate::components::{component}::{{Eval, N_TRACE_COLUMNS}};
+
+    pub const TRACE_COLUMN_NAMES: [&str; N_TRACE_COLUMNS] = [{names}];
+
+    impl ColumnNames for Eval {{
+        fn trace_column_names(&self) -> Vec<String> {{
+            TRACE_COLUMN_NAMES.map(String::from).to_vec()
+        }}
+
+        fn interaction_column_names(&self) -> Vec<String> {{
+            interaction_column_names(self.claim.log_sizes()[INTERACTION_TRACE_IDX].len())
+        }}
+    }}
+}}
+"
+    )
+}
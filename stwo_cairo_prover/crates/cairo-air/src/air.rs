@@ -6,7 +6,9 @@ use stwo_cairo_common::prover_types::cpu::CasmState;
 use stwo_cairo_common::prover_types::felt::split_f252;
 use stwo_cairo_serialize::CairoSerialize;
 use stwo_prover::constraint_framework::preprocessed_columns::PreProcessedColumnId;
-use stwo_prover::constraint_framework::{Relation, TraceLocationAllocator};
+use stwo_prover::constraint_framework::{
+    FrameworkComponent, FrameworkEval, Relation, TraceLocationAllocator,
+};
 use stwo_prover::core::air::{Component, ComponentProver};
 use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::channel::Channel;
@@ -19,7 +21,10 @@ use stwo_prover::core::vcs::ops::MerkleHasher;
 
 use super::blake::air::{BlakeContextClaim, BlakeContextComponents, BlakeContextInteractionClaim};
 use super::builtins_air::{BuiltinComponents, BuiltinsClaim, BuiltinsInteractionClaim};
-use super::components::indented_component_display;
+use super::component_registry::{ComponentRegistry, ExtensionClaim, ExtensionInteractionClaim};
+use super::components::{
+    indented_component_display, ColumnNames, ComponentProverBackend, UnsupportedExtensionsError,
+};
 use super::opcodes_air::{OpcodeClaim, OpcodeComponents, OpcodeInteractionClaim};
 use super::pedersen::air::{
     PedersenContextClaim, PedersenContextComponents, PedersenContextInteractionClaim,
@@ -35,8 +40,8 @@ use crate::components::{
     memory_address_to_id, memory_id_to_big, verify_bitwise_xor_4, verify_bitwise_xor_7,
     verify_bitwise_xor_8, verify_bitwise_xor_9, verify_instruction,
 };
-use crate::relations;
 use crate::verifier::RelationUse;
+use crate::{relations, PreProcessedTraceVariant};
 
 #[derive(Serialize, Deserialize)]
 pub struct CairoProof<H: MerkleHasher> {
@@ -44,8 +49,27 @@ pub struct CairoProof<H: MerkleHasher> {
     pub interaction_pow: u64,
     pub interaction_claim: CairoInteractionClaim,
     pub stark_proof: StarkProof<H>,
+    /// The preprocessed trace the proof was generated with.
+    pub preprocessed_trace: PreProcessedTraceVariant,
+}
+
+/// The Cairo verifier has a fixed, canonical, preprocessed trace, so it can't verify proofs using
+/// [`PreProcessedTraceVariant::UsageAware`].
+pub const USAGE_AWARE_NOT_CAIRO_SERIALIZABLE: &str =
+    "Proofs using the usage-aware preprocessed trace can't be verified by the Cairo verifier, \
+     prove with a canonical preprocessed trace instead";
+
+impl<H: MerkleHasher> CairoProof<H> {
+    /// Whether the Cairo verifier can verify the proof, see
+    /// [`USAGE_AWARE_NOT_CAIRO_SERIALIZABLE`]. Callers serializing the proof for the Cairo
+    /// verifier should reject other proofs first.
+    pub fn is_cairo_verifiable(&self) -> bool {
+        self.preprocessed_trace != PreProcessedTraceVariant::UsageAware
+    }
 }
 
+/// The preprocessed trace variant isn't serialized, as the Cairo verifier always checks the
+/// commitment to its canonical trace, see [`CairoProof::is_cairo_verifiable`].
 impl<H: MerkleHasher> CairoSerialize for CairoProof<H>
 where
     H::Hash: CairoSerialize,
@@ -56,6 +80,7 @@ where
             interaction_pow,
             interaction_claim,
             stark_proof,
+            preprocessed_trace: _,
         } = self;
         CairoSerialize::serialize(claim, output);
         CairoSerialize::serialize(interaction_pow, output);
@@ -80,11 +105,17 @@ pub fn accumulate_relation_uses<const N: usize>(
     }
 }
 
+/// Lookup tables are only part of the claim if they are looked up, i.e. if one of the other
+/// components uses their relation.
+pub fn is_looked_up(relation_uses: &RelationUsesDict, relation_id: &str) -> bool {
+    relation_uses.contains_key(relation_id)
+}
+
 #[derive(Serialize, Deserialize, CairoSerialize)]
 pub struct CairoClaim {
     pub public_data: PublicData,
     pub opcodes: OpcodeClaim,
-    pub verify_instruction: verify_instruction::Claim,
+    pub verify_instruction: Option<verify_instruction::Claim>,
     pub blake_context: BlakeContextClaim,
     pub builtins: BuiltinsClaim,
     pub pedersen_context: PedersenContextClaim,
@@ -92,10 +123,12 @@ pub struct CairoClaim {
     pub memory_address_to_id: memory_address_to_id::Claim,
     pub memory_id_to_value: memory_id_to_big::Claim,
     pub range_checks: RangeChecksClaim,
-    pub verify_bitwise_xor_4: verify_bitwise_xor_4::Claim,
-    pub verify_bitwise_xor_7: verify_bitwise_xor_7::Claim,
-    pub verify_bitwise_xor_8: verify_bitwise_xor_8::Claim,
-    pub verify_bitwise_xor_9: verify_bitwise_xor_9::Claim,
+    pub verify_bitwise_xor_4: Option<verify_bitwise_xor_4::Claim>,
+    pub verify_bitwise_xor_7: Option<verify_bitwise_xor_7::Claim>,
+    pub verify_bitwise_xor_8: Option<verify_bitwise_xor_8::Claim>,
+    pub verify_bitwise_xor_9: Option<verify_bitwise_xor_9::Claim>,
+    /// The claims of the families of a [`ComponentRegistry`], in registration order.
+    pub extensions: Vec<ExtensionClaim>,
     // ...
 }
 
@@ -116,10 +149,16 @@ impl CairoClaim {
             verify_bitwise_xor_7,
             verify_bitwise_xor_8,
             verify_bitwise_xor_9,
+            extensions,
         } = self;
         public_data.mix_into(channel);
         opcodes.mix_into(channel);
-        verify_instruction.mix_into(channel);
+        // Lookup tables are omitted when unused, and some of them have empty claims, so their
+        // presence is mixed explicitly.
+        channel.mix_u64(verify_instruction.is_some() as u64);
+        if let Some(verify_instruction) = verify_instruction {
+            verify_instruction.mix_into(channel);
+        }
         blake_context.mix_into(channel);
         builtins.mix_into(channel);
         pedersen_context.mix_into(channel);
@@ -127,32 +166,49 @@ impl CairoClaim {
         memory_address_to_id.mix_into(channel);
         memory_id_to_value.mix_into(channel);
         range_checks.mix_into(channel);
-        verify_bitwise_xor_4.mix_into(channel);
-        verify_bitwise_xor_7.mix_into(channel);
-        verify_bitwise_xor_8.mix_into(channel);
-        verify_bitwise_xor_9.mix_into(channel);
+        channel.mix_u64(verify_bitwise_xor_4.is_some() as u64);
+        channel.mix_u64(verify_bitwise_xor_7.is_some() as u64);
+        channel.mix_u64(verify_bitwise_xor_8.is_some() as u64);
+        channel.mix_u64(verify_bitwise_xor_9.is_some() as u64);
+        channel.mix_u64(extensions.len() as u64);
+        for extension in extensions {
+            extension.mix_into(channel);
+        }
     }
 
     /// Returns the log sizes of the components.
-    /// Does not include the preprocessed trace log sizes.
+    /// Does not include the preprocessed trace log sizes, nor those of the extension components,
+    /// see [`Self::log_sizes_with_extensions`].
     pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
-        let log_sizes_list = vec![
-            self.opcodes.log_sizes(),
-            self.verify_instruction.log_sizes(),
-            self.blake_context.log_sizes(),
-            self.builtins.log_sizes(),
-            self.pedersen_context.log_sizes(),
-            self.poseidon_context.log_sizes(),
-            self.memory_address_to_id.log_sizes(),
-            self.memory_id_to_value.log_sizes(),
-            self.range_checks.log_sizes(),
-            self.verify_bitwise_xor_4.log_sizes(),
-            self.verify_bitwise_xor_7.log_sizes(),
-            self.verify_bitwise_xor_8.log_sizes(),
-            self.verify_bitwise_xor_9.log_sizes(),
-        ];
+        TreeVec::concat_cols(chain!(
+            [self.opcodes.log_sizes()],
+            self.verify_instruction
+                .map(|verify_instruction| verify_instruction.log_sizes()),
+            [
+                self.blake_context.log_sizes(),
+                self.builtins.log_sizes(),
+                self.pedersen_context.log_sizes(),
+                self.poseidon_context.log_sizes(),
+                self.memory_address_to_id.log_sizes(),
+                self.memory_id_to_value.log_sizes(),
+                self.range_checks.log_sizes(),
+            ],
+            self.verify_bitwise_xor_4
+                .map(|verify_bitwise_xor_4| verify_bitwise_xor_4.log_sizes()),
+            self.verify_bitwise_xor_7
+                .map(|verify_bitwise_xor_7| verify_bitwise_xor_7.log_sizes()),
+            self.verify_bitwise_xor_8
+                .map(|verify_bitwise_xor_8| verify_bitwise_xor_8.log_sizes()),
+            self.verify_bitwise_xor_9
+                .map(|verify_bitwise_xor_9| verify_bitwise_xor_9.log_sizes()),
+        ))
+    }
 
-        TreeVec::concat_cols(log_sizes_list.into_iter())
+    /// Returns the log sizes of the components, followed by those of the components of the
+    /// families of `registry`.
+    /// Does not include the preprocessed trace log sizes.
+    pub fn log_sizes_with_extensions(&self, registry: &ComponentRegistry) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols([self.log_sizes(), registry.log_sizes(&self.extensions)].into_iter())
     }
 
     pub fn accumulate_relation_uses(&self, relation_uses: &mut RelationUsesDict) {
@@ -171,6 +227,8 @@ impl CairoClaim {
             verify_bitwise_xor_7: _,
             verify_bitwise_xor_8: _,
             verify_bitwise_xor_9: _,
+            // Accumulated by the registry, see [`ComponentRegistry::accumulate_relation_uses`].
+            extensions: _,
         } = self;
         // NOTE: The following components do not USE relations:
         // - range_checks
@@ -182,11 +240,13 @@ impl CairoClaim {
         blake_context.accumulate_relation_uses(relation_uses);
         pedersen_context.accumulate_relation_uses(relation_uses);
         poseidon_context.accumulate_relation_uses(relation_uses);
-        accumulate_relation_uses(
-            relation_uses,
-            verify_instruction::RELATION_USES_PER_ROW,
-            verify_instruction.log_size,
-        );
+        if let Some(verify_instruction) = verify_instruction {
+            accumulate_relation_uses(
+                relation_uses,
+                verify_instruction::RELATION_USES_PER_ROW,
+                verify_instruction.log_size,
+            );
+        }
 
         // TODO(ShaharS): Look into the file name of memory_id_to_big.
         // memory_id_to_value has a big value component and a small value component.
@@ -303,7 +363,7 @@ impl SegmentRange {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Copy, CairoSerialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, CairoSerialize)]
 pub struct PublicSegmentRanges {
     pub output: SegmentRange,
     pub pedersen: Option<SegmentRange>,
@@ -316,6 +376,21 @@ pub struct PublicSegmentRanges {
     pub range_check_96: Option<SegmentRange>,
     pub add_mod: Option<SegmentRange>,
     pub mul_mod: Option<SegmentRange>,
+    /// The initial and remaining gas of gas-enabled programs. `main` takes the gas counter after
+    /// the builtin pointers, and returns the remaining gas before them.
+    ///
+    /// Added after the other ranges, which changes the Cairo serialization of proofs: the Cairo
+    /// verifier doesn't accept proofs serialized without it. JSON proofs without it have no gas.
+    #[serde(default)]
+    pub gas: Option<SegmentRange>,
+    /// The segments of the registered component families with a public segment, in
+    /// registration order. `main` takes their pointers after the builtin pointers and before the
+    /// gas counter, see [`ComponentFamily::has_public_segment`].
+    ///
+    /// [`ComponentFamily::has_public_segment`]:
+    /// crate::component_registry::ComponentFamily::has_public_segment
+    #[serde(default)]
+    pub extensions: Vec<SegmentRange>,
 }
 
 impl PublicSegmentRanges {
@@ -328,6 +403,21 @@ impl PublicSegmentRanges {
 
         let n_segments = segments.len() as u32;
 
+        let gas_entries = self.gas.into_iter().flat_map(move |gas| {
+            [
+                (
+                    initial_ap + n_segments,
+                    gas.start_ptr.id,
+                    gas.start_ptr.value,
+                ),
+                (
+                    final_ap - n_segments - 1,
+                    gas.stop_ptr.id,
+                    gas.stop_ptr.value,
+                ),
+            ]
+        });
+
         segments
             .into_iter()
             .enumerate()
@@ -347,6 +437,7 @@ impl PublicSegmentRanges {
                     ]
                 },
             )
+            .chain(gas_entries)
             .map(|(addr, id, value)| (addr, id, [value, 0, 0, 0, 0, 0, 0, 0]))
     }
 
@@ -354,8 +445,12 @@ impl PublicSegmentRanges {
         for segment in self.present_segments() {
             segment.mix_into(channel);
         }
+        if let Some(gas) = self.gas {
+            gas.mix_into(channel);
+        }
     }
 
+    /// Returns the present builtin segments, followed by the extension segments.
     pub fn present_segments(&self) -> Vec<SegmentRange> {
         let Self {
             output,
@@ -369,22 +464,25 @@ impl PublicSegmentRanges {
             range_check_96,
             add_mod,
             mul_mod,
-        } = *self;
-        vec![
-            Some(output),
-            pedersen,
-            range_check_128,
-            ecdsa,
-            bitwise,
-            ec_op,
-            keccak,
-            poseidon,
-            range_check_96,
-            add_mod,
-            mul_mod,
+            gas: _,
+            extensions,
+        } = self;
+        [
+            Some(*output),
+            *pedersen,
+            *range_check_128,
+            *ecdsa,
+            *bitwise,
+            *ec_op,
+            *keccak,
+            *poseidon,
+            *range_check_96,
+            *add_mod,
+            *mul_mod,
         ]
         .into_iter()
         .flatten()
+        .chain(extensions.iter().copied())
         .collect_vec()
     }
 }
@@ -503,7 +601,7 @@ impl CairoInteractionElements {
 #[derive(Serialize, Deserialize, CairoSerialize)]
 pub struct CairoInteractionClaim {
     pub opcodes: OpcodeInteractionClaim,
-    pub verify_instruction: verify_instruction::InteractionClaim,
+    pub verify_instruction: Option<verify_instruction::InteractionClaim>,
     pub blake_context: BlakeContextInteractionClaim,
     pub builtins: BuiltinsInteractionClaim,
     pub pedersen_context: PedersenContextInteractionClaim,
@@ -511,15 +609,68 @@ pub struct CairoInteractionClaim {
     pub memory_address_to_id: memory_address_to_id::InteractionClaim,
     pub memory_id_to_value: memory_id_to_big::InteractionClaim,
     pub range_checks: RangeChecksInteractionClaim,
-    pub verify_bitwise_xor_4: verify_bitwise_xor_4::InteractionClaim,
-    pub verify_bitwise_xor_7: verify_bitwise_xor_7::InteractionClaim,
-    pub verify_bitwise_xor_8: verify_bitwise_xor_8::InteractionClaim,
-    pub verify_bitwise_xor_9: verify_bitwise_xor_9::InteractionClaim,
+    pub verify_bitwise_xor_4: Option<verify_bitwise_xor_4::InteractionClaim>,
+    pub verify_bitwise_xor_7: Option<verify_bitwise_xor_7::InteractionClaim>,
+    pub verify_bitwise_xor_8: Option<verify_bitwise_xor_8::InteractionClaim>,
+    pub verify_bitwise_xor_9: Option<verify_bitwise_xor_9::InteractionClaim>,
+    /// The interaction claims of the families of a [`ComponentRegistry`], in registration order.
+    pub extensions: Vec<ExtensionInteractionClaim>,
 }
 impl CairoInteractionClaim {
+    /// Returns the interaction claim of the built-in components of `claim`, with zero claimed
+    /// sums. Used to build the components of a claim whose interaction trace isn't written yet.
+    pub(crate) fn zero(claim: &CairoClaim) -> Self {
+        Self {
+            opcodes: OpcodeInteractionClaim::zero(&claim.opcodes),
+            verify_instruction: claim.verify_instruction.as_ref().map(|_| {
+                verify_instruction::InteractionClaim {
+                    claimed_sum: SecureField::zero(),
+                }
+            }),
+            blake_context: BlakeContextInteractionClaim::zero(&claim.blake_context),
+            builtins: BuiltinsInteractionClaim::zero(&claim.builtins),
+            pedersen_context: PedersenContextInteractionClaim::zero(&claim.pedersen_context),
+            poseidon_context: PoseidonContextInteractionClaim::zero(&claim.poseidon_context),
+            memory_address_to_id: memory_address_to_id::InteractionClaim {
+                claimed_sum: SecureField::zero(),
+            },
+            memory_id_to_value: memory_id_to_big::InteractionClaim {
+                big_claimed_sums: vec![
+                    SecureField::zero();
+                    claim.memory_id_to_value.big_log_sizes.len()
+                ],
+                small_claimed_sum: SecureField::zero(),
+            },
+            range_checks: RangeChecksInteractionClaim::zero(&claim.range_checks),
+            verify_bitwise_xor_4: claim.verify_bitwise_xor_4.as_ref().map(|_| {
+                verify_bitwise_xor_4::InteractionClaim {
+                    claimed_sum: SecureField::zero(),
+                }
+            }),
+            verify_bitwise_xor_7: claim.verify_bitwise_xor_7.as_ref().map(|_| {
+                verify_bitwise_xor_7::InteractionClaim {
+                    claimed_sum: SecureField::zero(),
+                }
+            }),
+            verify_bitwise_xor_8: claim.verify_bitwise_xor_8.as_ref().map(|_| {
+                verify_bitwise_xor_8::InteractionClaim {
+                    claimed_sum: SecureField::zero(),
+                }
+            }),
+            verify_bitwise_xor_9: claim.verify_bitwise_xor_9.as_ref().map(|_| {
+                verify_bitwise_xor_9::InteractionClaim {
+                    claimed_sum: SecureField::zero(),
+                }
+            }),
+            extensions: vec![],
+        }
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
         self.opcodes.mix_into(channel);
-        self.verify_instruction.mix_into(channel);
+        if let Some(verify_instruction) = &self.verify_instruction {
+            verify_instruction.mix_into(channel);
+        }
         self.blake_context.mix_into(channel);
         self.builtins.mix_into(channel);
         self.pedersen_context.mix_into(channel);
@@ -527,10 +678,21 @@ impl CairoInteractionClaim {
         self.memory_address_to_id.mix_into(channel);
         self.memory_id_to_value.mix_into(channel);
         self.range_checks.mix_into(channel);
-        self.verify_bitwise_xor_4.mix_into(channel);
-        self.verify_bitwise_xor_7.mix_into(channel);
-        self.verify_bitwise_xor_8.mix_into(channel);
-        self.verify_bitwise_xor_9.mix_into(channel);
+        if let Some(verify_bitwise_xor_4) = &self.verify_bitwise_xor_4 {
+            verify_bitwise_xor_4.mix_into(channel);
+        }
+        if let Some(verify_bitwise_xor_7) = &self.verify_bitwise_xor_7 {
+            verify_bitwise_xor_7.mix_into(channel);
+        }
+        if let Some(verify_bitwise_xor_8) = &self.verify_bitwise_xor_8 {
+            verify_bitwise_xor_8.mix_into(channel);
+        }
+        if let Some(verify_bitwise_xor_9) = &self.verify_bitwise_xor_9 {
+            verify_bitwise_xor_9.mix_into(channel);
+        }
+        for extension in &self.extensions {
+            extension.mix_into(channel);
+        }
     }
 }
 
@@ -545,7 +707,9 @@ pub fn lookup_sum(
     // If the table is padded, take the sum of the non-padded values.
     // Otherwise, the claimed_sum is the total_sum.
     sum += interaction_claim.opcodes.sum();
-    sum += interaction_claim.verify_instruction.claimed_sum;
+    if let Some(verify_instruction) = &interaction_claim.verify_instruction {
+        sum += verify_instruction.claimed_sum;
+    }
     sum += interaction_claim.blake_context.sum();
     sum += interaction_claim.builtins.sum();
     sum += interaction_claim.pedersen_context.sum();
@@ -553,17 +717,38 @@ pub fn lookup_sum(
     sum += interaction_claim.memory_address_to_id.claimed_sum;
     sum += interaction_claim.memory_id_to_value.claimed_sum();
     sum += interaction_claim.range_checks.sum();
-    sum += interaction_claim.verify_bitwise_xor_4.claimed_sum;
-    sum += interaction_claim.verify_bitwise_xor_7.claimed_sum;
-    sum += interaction_claim.verify_bitwise_xor_8.claimed_sum;
-    sum += interaction_claim.verify_bitwise_xor_9.claimed_sum;
+    if let Some(verify_bitwise_xor_4) = &interaction_claim.verify_bitwise_xor_4 {
+        sum += verify_bitwise_xor_4.claimed_sum;
+    }
+    if let Some(verify_bitwise_xor_7) = &interaction_claim.verify_bitwise_xor_7 {
+        sum += verify_bitwise_xor_7.claimed_sum;
+    }
+    if let Some(verify_bitwise_xor_8) = &interaction_claim.verify_bitwise_xor_8 {
+        sum += verify_bitwise_xor_8.claimed_sum;
+    }
+    if let Some(verify_bitwise_xor_9) = &interaction_claim.verify_bitwise_xor_9 {
+        sum += verify_bitwise_xor_9.claimed_sum;
+    }
+    for extension in &interaction_claim.extensions {
+        sum += extension.sum();
+    }
 
     sum
 }
 
+/// Visits the components of a [`CairoComponents`] in trace order. Each component is named after
+/// the module defining it, so that reports, trace dumps and diagnostics agree on names.
+pub trait CairoComponentVisitor {
+    fn visit<E: FrameworkEval + ColumnNames + Sync>(
+        &mut self,
+        name: &'static str,
+        component: &FrameworkComponent<E>,
+    );
+}
+
 pub struct CairoComponents {
     pub opcodes: OpcodeComponents,
-    pub verify_instruction: verify_instruction::Component,
+    pub verify_instruction: Option<verify_instruction::Component>,
     pub blake_context: BlakeContextComponents,
     pub builtins: BuiltinComponents,
     pub pedersen_context: PedersenContextComponents,
@@ -574,10 +759,11 @@ pub struct CairoComponents {
         memory_id_to_big::SmallComponent,
     ),
     pub range_checks: RangeChecksComponents,
-    pub verify_bitwise_xor_4: verify_bitwise_xor_4::Component,
-    pub verify_bitwise_xor_7: verify_bitwise_xor_7::Component,
-    pub verify_bitwise_xor_8: verify_bitwise_xor_8::Component,
-    pub verify_bitwise_xor_9: verify_bitwise_xor_9::Component,
+    pub verify_bitwise_xor_4: Option<verify_bitwise_xor_4::Component>,
+    pub verify_bitwise_xor_7: Option<verify_bitwise_xor_7::Component>,
+    pub verify_bitwise_xor_8: Option<verify_bitwise_xor_8::Component>,
+    pub verify_bitwise_xor_9: Option<verify_bitwise_xor_9::Component>,
+    pub extensions: Vec<Box<dyn ComponentProver<SimdBackend>>>,
     // ...
 }
 impl CairoComponents {
@@ -587,10 +773,49 @@ impl CairoComponents {
         interaction_claim: &CairoInteractionClaim,
         // Describes the structure of the preprocessed trace. Sensitive to order.
         preprocessed_column_ids: &[PreProcessedColumnId],
+    ) -> Self {
+        Self::new_with_registry(
+            cairo_claim,
+            interaction_elements,
+            interaction_claim,
+            preprocessed_column_ids,
+            &ComponentRegistry::default(),
+        )
+    }
+
+    /// Same as [`Self::new`], followed by the components of the families of `registry`.
+    pub fn new_with_registry(
+        cairo_claim: &CairoClaim,
+        interaction_elements: &CairoInteractionElements,
+        interaction_claim: &CairoInteractionClaim,
+        // Describes the structure of the preprocessed trace. Sensitive to order.
+        preprocessed_column_ids: &[PreProcessedColumnId],
+        registry: &ComponentRegistry,
     ) -> Self {
         let tree_span_provider =
             &mut TraceLocationAllocator::new_with_preproccessed_columns(preprocessed_column_ids);
+        let mut components = Self::new_built_in(
+            tree_span_provider,
+            cairo_claim,
+            interaction_elements,
+            interaction_claim,
+        );
+        components.extensions = registry.components(
+            tree_span_provider,
+            &cairo_claim.extensions,
+            interaction_elements,
+            &interaction_claim.extensions,
+        );
+        components
+    }
 
+    /// Builds the built-in components of `cairo_claim`, without those of registered families.
+    pub(crate) fn new_built_in(
+        tree_span_provider: &mut TraceLocationAllocator,
+        cairo_claim: &CairoClaim,
+        interaction_elements: &CairoInteractionElements,
+        interaction_claim: &CairoInteractionClaim,
+    ) -> Self {
         let opcode_components = OpcodeComponents::new(
             tree_span_provider,
             &cairo_claim.opcodes,
@@ -598,23 +823,33 @@ impl CairoComponents {
             &interaction_claim.opcodes,
         );
 
-        let verify_instruction_component = verify_instruction::Component::new(
-            tree_span_provider,
-            verify_instruction::Eval {
-                claim: cairo_claim.verify_instruction,
-                memory_address_to_id_lookup_elements: interaction_elements
-                    .memory_address_to_id
-                    .clone(),
-                verify_instruction_lookup_elements: interaction_elements.verify_instruction.clone(),
-                memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value.clone(),
-                range_check_4_3_lookup_elements: interaction_elements.range_checks.rc_4_3.clone(),
-                range_check_7_2_5_lookup_elements: interaction_elements
-                    .range_checks
-                    .rc_7_2_5
-                    .clone(),
-            },
-            interaction_claim.verify_instruction.claimed_sum,
-        );
+        let verify_instruction_component =
+            cairo_claim.verify_instruction.map(|verify_instruction| {
+                verify_instruction::Component::new(
+                    tree_span_provider,
+                    verify_instruction::Eval {
+                        claim: verify_instruction,
+                        memory_address_to_id_lookup_elements: interaction_elements
+                            .memory_address_to_id
+                            .clone(),
+                        verify_instruction_lookup_elements: interaction_elements
+                            .verify_instruction
+                            .clone(),
+                        memory_id_to_big_lookup_elements: interaction_elements
+                            .memory_id_to_value
+                            .clone(),
+                        range_check_4_3_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_4_3
+                            .clone(),
+                        range_check_7_2_5_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_7_2_5
+                            .clone(),
+                    },
+                    interaction_claim.verify_instruction.unwrap().claimed_sum,
+                )
+            });
 
         let blake_context = BlakeContextComponents::new(
             tree_span_provider,
@@ -670,49 +905,70 @@ impl CairoComponents {
         );
         let range_checks_component = RangeChecksComponents::new(
             tree_span_provider,
+            &cairo_claim.range_checks,
             &interaction_elements.range_checks,
             &interaction_claim.range_checks,
         );
-        let verify_bitwise_xor_4_component = verify_bitwise_xor_4::Component::new(
-            tree_span_provider,
-            verify_bitwise_xor_4::Eval {
-                claim: cairo_claim.verify_bitwise_xor_4,
-                verify_bitwise_xor_4_lookup_elements: interaction_elements
-                    .verify_bitwise_xor_4
-                    .clone(),
-            },
-            interaction_claim.verify_bitwise_xor_4.claimed_sum,
-        );
-        let verify_bitwise_xor_7_component = verify_bitwise_xor_7::Component::new(
-            tree_span_provider,
-            verify_bitwise_xor_7::Eval {
-                claim: cairo_claim.verify_bitwise_xor_7,
-                verify_bitwise_xor_7_lookup_elements: interaction_elements
-                    .verify_bitwise_xor_7
-                    .clone(),
-            },
-            interaction_claim.verify_bitwise_xor_7.claimed_sum,
-        );
-        let verify_bitwise_xor_8_component = verify_bitwise_xor_8::Component::new(
-            tree_span_provider,
-            verify_bitwise_xor_8::Eval {
-                claim: cairo_claim.verify_bitwise_xor_8,
-                verify_bitwise_xor_8_lookup_elements: interaction_elements
-                    .verify_bitwise_xor_8
-                    .clone(),
-            },
-            interaction_claim.verify_bitwise_xor_8.claimed_sum,
-        );
-        let verify_bitwise_xor_9_component = verify_bitwise_xor_9::Component::new(
-            tree_span_provider,
-            verify_bitwise_xor_9::Eval {
-                claim: cairo_claim.verify_bitwise_xor_9,
-                verify_bitwise_xor_9_lookup_elements: interaction_elements
-                    .verify_bitwise_xor_9
-                    .clone(),
-            },
-            interaction_claim.verify_bitwise_xor_9.claimed_sum,
-        );
+        let verify_bitwise_xor_4_component =
+            cairo_claim
+                .verify_bitwise_xor_4
+                .map(|verify_bitwise_xor_4| {
+                    verify_bitwise_xor_4::Component::new(
+                        tree_span_provider,
+                        verify_bitwise_xor_4::Eval {
+                            claim: verify_bitwise_xor_4,
+                            verify_bitwise_xor_4_lookup_elements: interaction_elements
+                                .verify_bitwise_xor_4
+                                .clone(),
+                        },
+                        interaction_claim.verify_bitwise_xor_4.unwrap().claimed_sum,
+                    )
+                });
+        let verify_bitwise_xor_7_component =
+            cairo_claim
+                .verify_bitwise_xor_7
+                .map(|verify_bitwise_xor_7| {
+                    verify_bitwise_xor_7::Component::new(
+                        tree_span_provider,
+                        verify_bitwise_xor_7::Eval {
+                            claim: verify_bitwise_xor_7,
+                            verify_bitwise_xor_7_lookup_elements: interaction_elements
+                                .verify_bitwise_xor_7
+                                .clone(),
+                        },
+                        interaction_claim.verify_bitwise_xor_7.unwrap().claimed_sum,
+                    )
+                });
+        let verify_bitwise_xor_8_component =
+            cairo_claim
+                .verify_bitwise_xor_8
+                .map(|verify_bitwise_xor_8| {
+                    verify_bitwise_xor_8::Component::new(
+                        tree_span_provider,
+                        verify_bitwise_xor_8::Eval {
+                            claim: verify_bitwise_xor_8,
+                            verify_bitwise_xor_8_lookup_elements: interaction_elements
+                                .verify_bitwise_xor_8
+                                .clone(),
+                        },
+                        interaction_claim.verify_bitwise_xor_8.unwrap().claimed_sum,
+                    )
+                });
+        let verify_bitwise_xor_9_component =
+            cairo_claim
+                .verify_bitwise_xor_9
+                .map(|verify_bitwise_xor_9| {
+                    verify_bitwise_xor_9::Component::new(
+                        tree_span_provider,
+                        verify_bitwise_xor_9::Eval {
+                            claim: verify_bitwise_xor_9,
+                            verify_bitwise_xor_9_lookup_elements: interaction_elements
+                                .verify_bitwise_xor_9
+                                .clone(),
+                        },
+                        interaction_claim.verify_bitwise_xor_9.unwrap().claimed_sum,
+                    )
+                });
         Self {
             opcodes: opcode_components,
             verify_instruction: verify_instruction_component,
@@ -730,36 +986,77 @@ impl CairoComponents {
             verify_bitwise_xor_7: verify_bitwise_xor_7_component,
             verify_bitwise_xor_8: verify_bitwise_xor_8_component,
             verify_bitwise_xor_9: verify_bitwise_xor_9_component,
+            extensions: vec![],
         }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
-        chain!(
-            self.opcodes.provers(),
-            [&self.verify_instruction as &dyn ComponentProver<SimdBackend>,],
-            self.blake_context.provers(),
-            self.builtins.provers(),
-            self.pedersen_context.provers(),
-            self.poseidon_context.provers(),
-            [&self.memory_address_to_id as &dyn ComponentProver<SimdBackend>,],
+    /// Returns the provers of the components on the backend `B`, or an error if `B` can't prove
+    /// the components of the registered families.
+    pub fn provers<B: ComponentProverBackend>(
+        &self,
+    ) -> Result<Vec<B::Prover<'_>>, UnsupportedExtensionsError> {
+        Ok(chain!(
+            self.opcodes.provers::<B>(),
+            self.verify_instruction
+                .iter()
+                .map(|component| B::component_prover(component)),
+            self.blake_context.provers::<B>(),
+            self.builtins.provers::<B>(),
+            self.pedersen_context.provers::<B>(),
+            self.poseidon_context.provers::<B>(),
+            [B::component_prover(&self.memory_address_to_id)],
             self.memory_id_to_value
                 .0
                 .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-            [&self.memory_id_to_value.1 as &dyn ComponentProver<SimdBackend>,],
-            self.range_checks.provers(),
-            [
-                &self.verify_bitwise_xor_4 as &dyn ComponentProver<SimdBackend>,
-                &self.verify_bitwise_xor_7 as &dyn ComponentProver<SimdBackend>,
-                &self.verify_bitwise_xor_8 as &dyn ComponentProver<SimdBackend>,
-                &self.verify_bitwise_xor_9 as &dyn ComponentProver<SimdBackend>,
-            ]
+                .map(|component| B::component_prover(component)),
+            [B::component_prover(&self.memory_id_to_value.1)],
+            self.range_checks.provers::<B>(),
+            chain!(
+                &self.verify_bitwise_xor_4,
+                &self.verify_bitwise_xor_7,
+                &self.verify_bitwise_xor_8,
+                &self.verify_bitwise_xor_9,
+            )
+            .map(|component| B::component_prover(component)),
+            B::extension_provers(&self.extensions)?,
         )
-        .collect()
+        .collect())
+    }
+
+    /// Visits the components in the order of [`Self::provers`]. Components of registered families
+    /// are not visited.
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        self.opcodes.visit(visitor);
+        if let Some(verify_instruction) = &self.verify_instruction {
+            visitor.visit("verify_instruction", verify_instruction);
+        }
+        self.blake_context.visit(visitor);
+        self.builtins.visit(visitor);
+        self.pedersen_context.visit(visitor);
+        self.poseidon_context.visit(visitor);
+        visitor.visit("memory_address_to_id", &self.memory_address_to_id);
+        for component in &self.memory_id_to_value.0 {
+            visitor.visit("memory_id_to_big", component);
+        }
+        visitor.visit("memory_id_to_small", &self.memory_id_to_value.1);
+        self.range_checks.visit(visitor);
+        if let Some(verify_bitwise_xor_4) = &self.verify_bitwise_xor_4 {
+            visitor.visit("verify_bitwise_xor_4", verify_bitwise_xor_4);
+        }
+        if let Some(verify_bitwise_xor_7) = &self.verify_bitwise_xor_7 {
+            visitor.visit("verify_bitwise_xor_7", verify_bitwise_xor_7);
+        }
+        if let Some(verify_bitwise_xor_8) = &self.verify_bitwise_xor_8 {
+            visitor.visit("verify_bitwise_xor_8", verify_bitwise_xor_8);
+        }
+        if let Some(verify_bitwise_xor_9) = &self.verify_bitwise_xor_9 {
+            visitor.visit("verify_bitwise_xor_9", verify_bitwise_xor_9);
+        }
     }
 
     pub fn components(&self) -> Vec<&dyn Component> {
-        self.provers()
+        self.provers::<SimdBackend>()
+            .expect("The SIMD backend proves all components")
             .into_iter()
             .map(|component| component as &dyn Component)
             .collect()
@@ -770,11 +1067,13 @@ impl std::fmt::Display for CairoComponents {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "CairoComponents")?;
         writeln!(f, "Opcodes: {}", self.opcodes)?;
-        writeln!(
-            f,
-            "VerifyInstruction: {}",
-            indented_component_display(&self.verify_instruction)
-        )?;
+        if let Some(verify_instruction) = &self.verify_instruction {
+            writeln!(
+                f,
+                "VerifyInstruction: {}",
+                indented_component_display(verify_instruction)
+            )?;
+        }
         writeln!(f, "BlakeContext: {}", self.blake_context)?;
         writeln!(f, "Builtins: {}", self.builtins)?;
         writeln!(f, "PedersenContext: {}", self.pedersen_context)?;
@@ -797,35 +1096,47 @@ impl std::fmt::Display for CairoComponents {
             indented_component_display(&self.memory_id_to_value.1)
         )?;
         writeln!(f, "RangeChecks: {}", self.range_checks)?;
-        writeln!(
-            f,
-            "VerifyBitwiseXor4: {}",
-            indented_component_display(&self.verify_bitwise_xor_4)
-        )?;
-        writeln!(
-            f,
-            "VerifyBitwiseXor7: {}",
-            indented_component_display(&self.verify_bitwise_xor_7)
-        )?;
-        writeln!(
-            f,
-            "VerifyBitwiseXor8: {}",
-            indented_component_display(&self.verify_bitwise_xor_8)
-        )?;
-        writeln!(
-            f,
-            "VerifyBitwiseXor9: {}",
-            indented_component_display(&self.verify_bitwise_xor_9)
-        )?;
+        if let Some(verify_bitwise_xor_4) = &self.verify_bitwise_xor_4 {
+            writeln!(
+                f,
+                "VerifyBitwiseXor4: {}",
+                indented_component_display(verify_bitwise_xor_4)
+            )?;
+        }
+        if let Some(verify_bitwise_xor_7) = &self.verify_bitwise_xor_7 {
+            writeln!(
+                f,
+                "VerifyBitwiseXor7: {}",
+                indented_component_display(verify_bitwise_xor_7)
+            )?;
+        }
+        if let Some(verify_bitwise_xor_8) = &self.verify_bitwise_xor_8 {
+            writeln!(
+                f,
+                "VerifyBitwiseXor8: {}",
+                indented_component_display(verify_bitwise_xor_8)
+            )?;
+        }
+        if let Some(verify_bitwise_xor_9) = &self.verify_bitwise_xor_9 {
+            writeln!(
+                f,
+                "VerifyBitwiseXor9: {}",
+                indented_component_display(verify_bitwise_xor_9)
+            )?;
+        }
+        writeln!(f, "Extensions: {} components", self.extensions.len())?;
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use stwo_cairo_adapter::HashMap;
 
-    use crate::air::accumulate_relation_uses;
+    use crate::air::{
+        accumulate_relation_uses, MemorySmallValue, PublicSegmentRanges, SegmentRange,
+    };
     use crate::verifier::RelationUse;
 
     #[test]
@@ -849,4 +1160,96 @@ mod tests {
         assert_eq!(relation_uses.get("relation_1"), Some(&12));
         assert_eq!(relation_uses.get("relation_2"), Some(&26));
     }
+
+    #[test]
+    fn test_public_segment_ranges_gas_entries() {
+        let range = |id, start, stop| SegmentRange {
+            start_ptr: MemorySmallValue { id, value: start },
+            stop_ptr: MemorySmallValue {
+                id: id + 1,
+                value: stop,
+            },
+        };
+        let public_segments = PublicSegmentRanges {
+            output: range(0, 100, 102),
+            pedersen: None,
+            range_check_128: Some(range(2, 200, 210)),
+            ecdsa: None,
+            bitwise: None,
+            ec_op: None,
+            keccak: None,
+            poseidon: None,
+            range_check_96: None,
+            add_mod: None,
+            mul_mod: None,
+            gas: Some(range(4, 1000, 400)),
+            extensions: vec![],
+        };
+
+        let entries = public_segments
+            .memory_entries(10, 50)
+            .map(|(addr, id, value)| (addr, id, value[0]))
+            .sorted()
+            .collect_vec();
+
+        // The gas counter follows the builtin pointers on entry, and precedes them on return.
+        assert_eq!(
+            entries,
+            [
+                (10, 0, 100),
+                (11, 2, 200),
+                (12, 4, 1000),
+                (47, 5, 400),
+                (48, 1, 102),
+                (49, 3, 210),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_public_segment_ranges_extension_entries() {
+        let range = |id, start, stop| SegmentRange {
+            start_ptr: MemorySmallValue { id, value: start },
+            stop_ptr: MemorySmallValue {
+                id: id + 1,
+                value: stop,
+            },
+        };
+        let public_segments = PublicSegmentRanges {
+            output: range(0, 100, 102),
+            pedersen: None,
+            range_check_128: Some(range(2, 200, 210)),
+            ecdsa: None,
+            bitwise: None,
+            ec_op: None,
+            keccak: None,
+            poseidon: None,
+            range_check_96: None,
+            add_mod: None,
+            mul_mod: None,
+            gas: Some(range(4, 1000, 400)),
+            extensions: vec![range(6, 300, 304)],
+        };
+
+        let entries = public_segments
+            .memory_entries(10, 50)
+            .map(|(addr, id, value)| (addr, id, value[0]))
+            .sorted()
+            .collect_vec();
+
+        // The extension pointers follow the builtin pointers, and precede the gas counter.
+        assert_eq!(
+            entries,
+            [
+                (10, 0, 100),
+                (11, 2, 200),
+                (12, 6, 300),
+                (13, 4, 1000),
+                (46, 5, 400),
+                (47, 1, 102),
+                (48, 3, 210),
+                (49, 7, 304),
+            ]
+        );
+    }
 }
@@ -0,0 +1,1213 @@
+//! A machine-readable description of the Cairo AIR, for auditing and tooling.
+//!
+//! The description is computed by evaluating every component's constraints with
+//! [`DescriptionEvaluator`], which records the columns, relations and constraints used by a row.
+
+use std::any::type_name;
+use std::collections::BTreeMap;
+
+use num_traits::{One, Zero};
+use serde::Serialize;
+use stwo_prover::constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_prover::constraint_framework::{
+    Batching, EvalAtRow, FrameworkEval, Relation, RelationEntry, ORIGINAL_TRACE_IDX,
+};
+use stwo_prover::core::fields::m31::{BaseField, M31, P};
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::lookups::utils::Fraction;
+
+use crate::components::{
+    add_ap_opcode, add_mod_builtin, add_opcode, add_opcode_small, assert_eq_opcode,
+    assert_eq_opcode_double_deref, assert_eq_opcode_imm, bitwise_builtin, blake_compress_opcode,
+    blake_g, blake_round, blake_round_sigma, call_opcode, call_opcode_rel_imm, cube_252,
+    generic_opcode, jnz_opcode, jnz_opcode_taken, jump_opcode, jump_opcode_double_deref,
+    jump_opcode_rel, jump_opcode_rel_imm, memory_address_to_id, memory_id_to_big, mul_mod_builtin,
+    mul_opcode, mul_opcode_small, partial_ec_mul, pedersen_builtin, pedersen_points_table,
+    poseidon_3_partial_rounds_chain, poseidon_builtin, poseidon_full_round_chain,
+    poseidon_round_keys, qm_31_add_mul_opcode, range_check_11, range_check_12, range_check_18,
+    range_check_19, range_check_3_3_3_3_3, range_check_3_6_6_3, range_check_4_3, range_check_4_4,
+    range_check_4_4_4_4, range_check_5_4, range_check_6, range_check_7_2_5, range_check_8,
+    range_check_9_9, range_check_builtin_bits_128, range_check_builtin_bits_96,
+    range_check_felt_252_width_27, ret_opcode, triple_xor_32, verify_bitwise_xor_12,
+    verify_bitwise_xor_4, verify_bitwise_xor_7, verify_bitwise_xor_8, verify_bitwise_xor_9,
+    verify_instruction, ColumnNames,
+};
+use crate::relations::{self, RELATION_SIZES};
+use crate::verifier::RelationUse;
+
+/// The log size of the components built for describing the AIR. Only affects the ids of
+/// size-dependent preprocessed columns (e.g. `seq_4`).
+pub const DESCRIPTION_LOG_SIZE: u32 = 4;
+
+/// Constraints are evaluated on this many points to measure their degree, so degrees up to
+/// `N_DEGREE_POINTS - 1` are measured exactly.
+const N_DEGREE_POINTS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AirDescription {
+    pub relations: Vec<RelationDescription>,
+    pub components: Vec<ComponentDescription>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelationDescription {
+    pub name: &'static str,
+    /// The maximal number of values in a tuple of the relation.
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentDescription {
+    pub name: &'static str,
+    pub n_trace_columns: usize,
+    pub n_interaction_columns: usize,
+    /// The ids of the preprocessed columns the component reads.
+    pub preprocessed_columns: Vec<String>,
+    /// The relations a row consumes from.
+    pub uses: Vec<RelationUsage>,
+    /// The relations a row yields to.
+    pub yields: Vec<RelationUsage>,
+    /// The component's declared `RELATION_USES_PER_ROW`.
+    pub relation_uses_per_row: Vec<RelationUsage>,
+    /// The number of constraints, excluding the logup constraints.
+    pub n_constraints: usize,
+    /// The maximal degree of the constraints, excluding the logup constraints.
+    pub max_constraint_degree: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelationUsage {
+    pub relation: String,
+    pub size: usize,
+    /// The number of entries per row.
+    pub count: u64,
+}
+impl RelationUsage {
+    fn new(relation: &str, count: u64) -> Self {
+        let size = RELATION_SIZES
+            .iter()
+            .find(|(name, _)| *name == relation)
+            .map_or(0, |(_, size)| *size);
+        Self {
+            relation: relation.to_string(),
+            size,
+            count,
+        }
+    }
+}
+
+/// Describes every relation and component of the Cairo AIR.
+pub fn air_description() -> AirDescription {
+    let mut describer = ComponentDescriber::default();
+    visit_cairo_components(&mut describer);
+    AirDescription {
+        relations: RELATION_SIZES
+            .iter()
+            .map(|&(name, size)| RelationDescription { name, size })
+            .collect(),
+        components: describer.components,
+    }
+}
+
+/// Visits the evaluators of all the Cairo AIR components.
+pub trait ComponentVisitor {
+    fn visit<C: Serialize, E: FrameworkEval + ColumnNames>(
+        &mut self,
+        name: &'static str,
+        claim: &C,
+        eval: &E,
+        relation_uses_per_row: &[RelationUse],
+    );
+}
+
+#[derive(Default)]
+struct ComponentDescriber {
+    components: Vec<ComponentDescription>,
+}
+impl ComponentVisitor for ComponentDescriber {
+    fn visit<C: Serialize, E: FrameworkEval + ColumnNames>(
+        &mut self,
+        name: &'static str,
+        _claim: &C,
+        eval: &E,
+        relation_uses_per_row: &[RelationUse],
+    ) {
+        self.components
+            .push(describe_component(name, eval, relation_uses_per_row));
+    }
+}
+
+/// Describes a single component by evaluating its constraints on a row of ones, and then on
+/// points of a random line to measure the constraints' degrees.
+pub fn describe_component<E: FrameworkEval>(
+    name: &'static str,
+    eval: &E,
+    relation_uses_per_row: &[RelationUse],
+) -> ComponentDescription {
+    let row = eval.evaluate(DescriptionEvaluator::new(MaskPoint::Ones));
+
+    let mut uses = BTreeMap::<&str, u64>::new();
+    let mut yields = BTreeMap::<&str, u64>::new();
+    for (relation, multiplicity) in &row.relation_entries {
+        // All multiplicities are `±enabler`, `±multiplicity` or `±1`, so on a row of ones they
+        // are `±1`.
+        let usages = if multiplicity.is_one() {
+            &mut uses
+        } else {
+            &mut yields
+        };
+        *usages.entry(*relation).or_default() += 1;
+    }
+
+    let line_evaluations = (0..N_DEGREE_POINTS)
+        .map(|t| {
+            let point = MaskPoint::Line(M31::from_u32_unchecked(t as u32));
+            eval.evaluate(DescriptionEvaluator::new(point)).constraints
+        })
+        .collect::<Vec<_>>();
+    let max_constraint_degree = (0..row.constraints.len())
+        .map(|i| degree(&line_evaluations.iter().map(|c| c[i]).collect::<Vec<_>>()))
+        .max()
+        .unwrap_or(0);
+
+    let to_usages = |relations: BTreeMap<&str, u64>| {
+        relations
+            .into_iter()
+            .map(|(relation, count)| RelationUsage::new(relation, count))
+            .collect()
+    };
+    ComponentDescription {
+        name,
+        n_trace_columns: row.n_columns(ORIGINAL_TRACE_IDX),
+        n_interaction_columns: row.n_logup_batches * SECURE_EXTENSION_DEGREE,
+        preprocessed_columns: row.preprocessed_columns,
+        uses: to_usages(uses),
+        yields: to_usages(yields),
+        relation_uses_per_row: relation_uses_per_row
+            .iter()
+            .map(|relation_use| RelationUsage::new(relation_use.relation_id, relation_use.uses))
+            .collect(),
+        n_constraints: row.constraints.len(),
+        max_constraint_degree,
+    }
+}
+
+/// Returns the degree of the polynomial whose evaluations on `0, 1, 2, ...` are `evals`, or
+/// `evals.len() - 1` if it's higher.
+fn degree(evals: &[SecureField]) -> usize {
+    let mut differences = evals.to_vec();
+    let mut degree = 0;
+    for k in 0..evals.len() {
+        if differences.iter().any(|d| !d.is_zero()) {
+            degree = k;
+        }
+        differences = differences.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    degree
+}
+
+/// The values of the mask items.
+#[derive(Clone, Copy)]
+enum MaskPoint {
+    /// All the mask items are one.
+    Ones,
+    /// The `i`th mask item is `a_i + t * b_i`, for pseudo random `a_i`, `b_i`.
+    Line(M31),
+}
+
+/// An [`EvalAtRow`] that records the columns, relation entries and constraints of a single row.
+struct DescriptionEvaluator {
+    point: MaskPoint,
+    n_mask_items: u64,
+    n_columns_per_interaction: Vec<usize>,
+    preprocessed_columns: Vec<String>,
+    relation_entries: Vec<(&'static str, SecureField)>,
+    n_logup_fracs: usize,
+    n_logup_batches: usize,
+    constraints: Vec<SecureField>,
+}
+impl DescriptionEvaluator {
+    fn new(point: MaskPoint) -> Self {
+        Self {
+            point,
+            n_mask_items: 0,
+            n_columns_per_interaction: vec![],
+            preprocessed_columns: vec![],
+            relation_entries: vec![],
+            n_logup_fracs: 0,
+            n_logup_batches: 0,
+            constraints: vec![],
+        }
+    }
+
+    fn n_columns(&self, interaction: usize) -> usize {
+        self.n_columns_per_interaction
+            .get(interaction)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn next_mask_item(&mut self) -> BaseField {
+        let i = self.n_mask_items;
+        self.n_mask_items += 1;
+        match self.point {
+            MaskPoint::Ones => BaseField::one(),
+            MaskPoint::Line(t) => pseudo_random(2 * i) + t * pseudo_random(2 * i + 1),
+        }
+    }
+}
+
+impl EvalAtRow for DescriptionEvaluator {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        _offsets: [isize; N],
+    ) -> [Self::F; N] {
+        if self.n_columns_per_interaction.len() <= interaction {
+            self.n_columns_per_interaction.resize(interaction + 1, 0);
+        }
+        self.n_columns_per_interaction[interaction] += 1;
+        std::array::from_fn(|_| self.next_mask_item())
+    }
+
+    fn get_preprocessed_column(&mut self, column: PreProcessedColumnId) -> Self::F {
+        self.preprocessed_columns.push(column.id);
+        self.next_mask_item()
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: std::ops::Mul<G, Output = Self::EF>,
+    {
+        self.constraints.push(SecureField::one() * constraint);
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        entry: RelationEntry<'_, Self::F, Self::EF, R>,
+    ) {
+        // The relation types are named after their relations.
+        let relation = type_name::<R>().rsplit("::").next().unwrap();
+        let mut recorder = MultiplicityRecorder::default();
+        recorder.add_to_relation(entry);
+        self.relation_entries
+            .push((relation, recorder.multiplicity.unwrap()));
+        self.n_logup_fracs += 1;
+    }
+
+    fn write_logup_frac(&mut self, _fraction: Fraction<Self::EF, Self::EF>) {
+        self.n_logup_fracs += 1;
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        self.n_logup_batches += batching.iter().max().map_or(0, |max| max + 1);
+    }
+
+    fn finalize_logup(&mut self) {
+        self.n_logup_batches += self.n_logup_fracs;
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        self.n_logup_batches += self.n_logup_fracs.div_ceil(2);
+    }
+}
+
+/// Records the multiplicity of a single relation entry.
+#[derive(Default)]
+struct MultiplicityRecorder {
+    multiplicity: Option<SecureField>,
+}
+impl EvalAtRow for MultiplicityRecorder {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        _interaction: usize,
+        _offsets: [isize; N],
+    ) -> [Self::F; N] {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn add_constraint<G>(&mut self, _constraint: G)
+    where
+        Self::EF: std::ops::Mul<G, Output = Self::EF>,
+    {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.multiplicity = Some(fraction.numerator);
+    }
+
+    fn finalize_logup_batched(&mut self, _batching: &Batching) {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn finalize_logup(&mut self) {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        unreachable!("Only relation entries are recorded")
+    }
+}
+
+/// A deterministic pseudo random field element (splitmix64).
+fn pseudo_random(seed: u64) -> M31 {
+    let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^= z >> 31;
+    M31::from_u32_unchecked((z % P as u64) as u32)
+}
+
+/// Visits all the Cairo AIR components, built with dummy claims and lookup elements.
+pub fn visit_cairo_components(visitor: &mut impl ComponentVisitor) {
+    let claim = add_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "add_opcode",
+        &claim,
+        &add_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &add_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = add_opcode_small::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "add_opcode_small",
+        &claim,
+        &add_opcode_small::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &add_opcode_small::RELATION_USES_PER_ROW,
+    );
+    let claim = add_ap_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "add_ap_opcode",
+        &claim,
+        &add_ap_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_19_lookup_elements: relations::RangeCheck_19::dummy(),
+            range_check_8_lookup_elements: relations::RangeCheck_8::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &add_ap_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = assert_eq_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "assert_eq_opcode",
+        &claim,
+        &assert_eq_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &assert_eq_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = assert_eq_opcode_imm::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "assert_eq_opcode_imm",
+        &claim,
+        &assert_eq_opcode_imm::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &assert_eq_opcode_imm::RELATION_USES_PER_ROW,
+    );
+    let claim = assert_eq_opcode_double_deref::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "assert_eq_opcode_double_deref",
+        &claim,
+        &assert_eq_opcode_double_deref::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &assert_eq_opcode_double_deref::RELATION_USES_PER_ROW,
+    );
+    let claim = blake_compress_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "blake_compress_opcode",
+        &claim,
+        &blake_compress_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_7_2_5_lookup_elements: relations::RangeCheck_7_2_5::dummy(),
+            verify_bitwise_xor_8_lookup_elements: relations::VerifyBitwiseXor_8::dummy(),
+            blake_round_lookup_elements: relations::BlakeRound::dummy(),
+            triple_xor_32_lookup_elements: relations::TripleXor32::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &blake_compress_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = call_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "call_opcode",
+        &claim,
+        &call_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &call_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = call_opcode_rel_imm::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "call_opcode_rel_imm",
+        &claim,
+        &call_opcode_rel_imm::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &call_opcode_rel_imm::RELATION_USES_PER_ROW,
+    );
+    let claim = generic_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "generic_opcode",
+        &claim,
+        &generic_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_9_9_lookup_elements: relations::RangeCheck_9_9::dummy(),
+            range_check_19_lookup_elements: relations::RangeCheck_19::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &generic_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = jnz_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "jnz_opcode",
+        &claim,
+        &jnz_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &jnz_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = jnz_opcode_taken::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "jnz_opcode_taken",
+        &claim,
+        &jnz_opcode_taken::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &jnz_opcode_taken::RELATION_USES_PER_ROW,
+    );
+    let claim = jump_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "jump_opcode",
+        &claim,
+        &jump_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &jump_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = jump_opcode_double_deref::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "jump_opcode_double_deref",
+        &claim,
+        &jump_opcode_double_deref::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &jump_opcode_double_deref::RELATION_USES_PER_ROW,
+    );
+    let claim = jump_opcode_rel::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "jump_opcode_rel",
+        &claim,
+        &jump_opcode_rel::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &jump_opcode_rel::RELATION_USES_PER_ROW,
+    );
+    let claim = jump_opcode_rel_imm::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "jump_opcode_rel_imm",
+        &claim,
+        &jump_opcode_rel_imm::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &jump_opcode_rel_imm::RELATION_USES_PER_ROW,
+    );
+    let claim = mul_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "mul_opcode",
+        &claim,
+        &mul_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_19_lookup_elements: relations::RangeCheck_19::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &mul_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = mul_opcode_small::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "mul_opcode_small",
+        &claim,
+        &mul_opcode_small::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_11_lookup_elements: relations::RangeCheck_11::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &mul_opcode_small::RELATION_USES_PER_ROW,
+    );
+    let claim = qm_31_add_mul_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "qm_31_add_mul_opcode",
+        &claim,
+        &qm_31_add_mul_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_4_4_4_4_lookup_elements: relations::RangeCheck_4_4_4_4::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &qm_31_add_mul_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = ret_opcode::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "ret_opcode",
+        &claim,
+        &ret_opcode::Eval {
+            claim,
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            opcodes_lookup_elements: relations::Opcodes::dummy(),
+        },
+        &ret_opcode::RELATION_USES_PER_ROW,
+    );
+    let claim = verify_instruction::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "verify_instruction",
+        &claim,
+        &verify_instruction::Eval {
+            claim,
+            range_check_7_2_5_lookup_elements: relations::RangeCheck_7_2_5::dummy(),
+            range_check_4_3_lookup_elements: relations::RangeCheck_4_3::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            verify_instruction_lookup_elements: relations::VerifyInstruction::dummy(),
+        },
+        &verify_instruction::RELATION_USES_PER_ROW,
+    );
+    visitor.visit(
+        "range_check_6",
+        &range_check_6::Claim {},
+        &range_check_6::Eval::new(relations::RangeCheck_6::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_8",
+        &range_check_8::Claim {},
+        &range_check_8::Eval::new(relations::RangeCheck_8::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_11",
+        &range_check_11::Claim {},
+        &range_check_11::Eval::new(relations::RangeCheck_11::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_12",
+        &range_check_12::Claim {},
+        &range_check_12::Eval::new(relations::RangeCheck_12::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_18",
+        &range_check_18::Claim {},
+        &range_check_18::Eval::new(relations::RangeCheck_18::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_19",
+        &range_check_19::Claim {},
+        &range_check_19::Eval::new(relations::RangeCheck_19::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_4_3",
+        &range_check_4_3::Claim {},
+        &range_check_4_3::Eval::new(relations::RangeCheck_4_3::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_4_4",
+        &range_check_4_4::Claim {},
+        &range_check_4_4::Eval::new(relations::RangeCheck_4_4::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_5_4",
+        &range_check_5_4::Claim {},
+        &range_check_5_4::Eval::new(relations::RangeCheck_5_4::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_9_9",
+        &range_check_9_9::Claim {},
+        &range_check_9_9::Eval::new(relations::RangeCheck_9_9::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_7_2_5",
+        &range_check_7_2_5::Claim {},
+        &range_check_7_2_5::Eval::new(relations::RangeCheck_7_2_5::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_3_6_6_3",
+        &range_check_3_6_6_3::Claim {},
+        &range_check_3_6_6_3::Eval::new(relations::RangeCheck_3_6_6_3::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_4_4_4_4",
+        &range_check_4_4_4_4::Claim {},
+        &range_check_4_4_4_4::Eval::new(relations::RangeCheck_4_4_4_4::dummy()),
+        &[],
+    );
+    visitor.visit(
+        "range_check_3_3_3_3_3",
+        &range_check_3_3_3_3_3::Claim {},
+        &range_check_3_3_3_3_3::Eval::new(relations::RangeCheck_3_3_3_3_3::dummy()),
+        &[],
+    );
+    let claim = verify_bitwise_xor_4::Claim {};
+    visitor.visit(
+        "verify_bitwise_xor_4",
+        &claim,
+        &verify_bitwise_xor_4::Eval {
+            claim,
+            verify_bitwise_xor_4_lookup_elements: relations::VerifyBitwiseXor_4::dummy(),
+        },
+        &[],
+    );
+    let claim = verify_bitwise_xor_7::Claim {};
+    visitor.visit(
+        "verify_bitwise_xor_7",
+        &claim,
+        &verify_bitwise_xor_7::Eval {
+            claim,
+            verify_bitwise_xor_7_lookup_elements: relations::VerifyBitwiseXor_7::dummy(),
+        },
+        &[],
+    );
+    let claim = verify_bitwise_xor_8::Claim {};
+    visitor.visit(
+        "verify_bitwise_xor_8",
+        &claim,
+        &verify_bitwise_xor_8::Eval {
+            claim,
+            verify_bitwise_xor_8_lookup_elements: relations::VerifyBitwiseXor_8::dummy(),
+        },
+        &[],
+    );
+    let claim = verify_bitwise_xor_9::Claim {};
+    visitor.visit(
+        "verify_bitwise_xor_9",
+        &claim,
+        &verify_bitwise_xor_9::Eval {
+            claim,
+            verify_bitwise_xor_9_lookup_elements: relations::VerifyBitwiseXor_9::dummy(),
+        },
+        &[],
+    );
+    let claim = memory_address_to_id::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "memory_address_to_id",
+        &claim,
+        &memory_address_to_id::Eval::new(claim.clone(), relations::MemoryAddressToId::dummy()),
+        &[],
+    );
+    let memory_id_to_big_claim = memory_id_to_big::Claim {
+        big_log_sizes: vec![DESCRIPTION_LOG_SIZE],
+        small_log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "memory_id_to_big",
+        &memory_id_to_big_claim,
+        &memory_id_to_big::BigEval::new(
+            DESCRIPTION_LOG_SIZE,
+            0,
+            relations::MemoryIdToBig::dummy(),
+            relations::RangeCheck_9_9::dummy(),
+        ),
+        &memory_id_to_big::RELATION_USES_PER_ROW_BIG,
+    );
+    visitor.visit(
+        "memory_id_to_small",
+        &memory_id_to_big_claim,
+        &memory_id_to_big::SmallEval {
+            log_n_rows: DESCRIPTION_LOG_SIZE,
+            lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_9_9_relation: relations::RangeCheck_9_9::dummy(),
+        },
+        &memory_id_to_big::RELATION_USES_PER_ROW_SMALL,
+    );
+    let claim = blake_round::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "blake_round",
+        &claim,
+        &blake_round::Eval {
+            claim,
+            blake_round_sigma_lookup_elements: relations::BlakeRoundSigma::dummy(),
+            range_check_7_2_5_lookup_elements: relations::RangeCheck_7_2_5::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            blake_g_lookup_elements: relations::BlakeG::dummy(),
+            blake_round_lookup_elements: relations::BlakeRound::dummy(),
+        },
+        &blake_round::RELATION_USES_PER_ROW,
+    );
+    let claim = blake_g::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "blake_g",
+        &claim,
+        &blake_g::Eval {
+            claim,
+            verify_bitwise_xor_8_lookup_elements: relations::VerifyBitwiseXor_8::dummy(),
+            verify_bitwise_xor_12_lookup_elements: relations::VerifyBitwiseXor_12::dummy(),
+            verify_bitwise_xor_4_lookup_elements: relations::VerifyBitwiseXor_4::dummy(),
+            verify_bitwise_xor_7_lookup_elements: relations::VerifyBitwiseXor_7::dummy(),
+            verify_bitwise_xor_9_lookup_elements: relations::VerifyBitwiseXor_9::dummy(),
+            blake_g_lookup_elements: relations::BlakeG::dummy(),
+        },
+        &blake_g::RELATION_USES_PER_ROW,
+    );
+    let claim = blake_round_sigma::Claim {};
+    visitor.visit(
+        "blake_round_sigma",
+        &claim,
+        &blake_round_sigma::Eval {
+            claim,
+            blake_round_sigma_lookup_elements: relations::BlakeRoundSigma::dummy(),
+        },
+        &[],
+    );
+    let claim = triple_xor_32::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "triple_xor_32",
+        &claim,
+        &triple_xor_32::Eval {
+            claim,
+            verify_bitwise_xor_8_lookup_elements: relations::VerifyBitwiseXor_8::dummy(),
+            triple_xor_32_lookup_elements: relations::TripleXor32::dummy(),
+        },
+        &triple_xor_32::RELATION_USES_PER_ROW,
+    );
+    let claim = verify_bitwise_xor_12::Claim {};
+    visitor.visit(
+        "verify_bitwise_xor_12",
+        &claim,
+        &verify_bitwise_xor_12::Eval {
+            claim,
+            verify_bitwise_xor_12_lookup_elements: relations::VerifyBitwiseXor_12::dummy(),
+        },
+        &[],
+    );
+    let claim = add_mod_builtin::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        add_mod_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "add_mod_builtin",
+        &claim,
+        &add_mod_builtin::Eval {
+            claim,
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+        },
+        &add_mod_builtin::RELATION_USES_PER_ROW,
+    );
+    let claim = mul_mod_builtin::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        mul_mod_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "mul_mod_builtin",
+        &claim,
+        &mul_mod_builtin::Eval {
+            claim,
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_12_lookup_elements: relations::RangeCheck_12::dummy(),
+            range_check_3_6_6_3_lookup_elements: relations::RangeCheck_3_6_6_3::dummy(),
+            range_check_18_lookup_elements: relations::RangeCheck_18::dummy(),
+        },
+        &mul_mod_builtin::RELATION_USES_PER_ROW,
+    );
+    let claim = bitwise_builtin::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        bitwise_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "bitwise_builtin",
+        &claim,
+        &bitwise_builtin::Eval {
+            claim,
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            verify_bitwise_xor_9_lookup_elements: relations::VerifyBitwiseXor_9::dummy(),
+        },
+        &bitwise_builtin::RELATION_USES_PER_ROW,
+    );
+    let claim = pedersen_builtin::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        pedersen_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "pedersen_builtin",
+        &claim,
+        &pedersen_builtin::Eval {
+            claim,
+            range_check_5_4_lookup_elements: relations::RangeCheck_5_4::dummy(),
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            range_check_8_lookup_elements: relations::RangeCheck_8::dummy(),
+            partial_ec_mul_lookup_elements: relations::PartialEcMul::dummy(),
+        },
+        &pedersen_builtin::RELATION_USES_PER_ROW,
+    );
+    let claim = poseidon_builtin::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        poseidon_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "poseidon_builtin",
+        &claim,
+        &poseidon_builtin::Eval {
+            claim,
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+            poseidon_full_round_chain_lookup_elements: relations::PoseidonFullRoundChain::dummy(),
+            range_check_felt_252_width_27_lookup_elements:
+                relations::RangeCheckFelt252Width27::dummy(),
+            cube_252_lookup_elements: relations::Cube252::dummy(),
+            range_check_3_3_3_3_3_lookup_elements: relations::RangeCheck_3_3_3_3_3::dummy(),
+            range_check_4_4_4_4_lookup_elements: relations::RangeCheck_4_4_4_4::dummy(),
+            range_check_4_4_lookup_elements: relations::RangeCheck_4_4::dummy(),
+            poseidon_3_partial_rounds_chain_lookup_elements:
+                relations::Poseidon3PartialRoundsChain::dummy(),
+        },
+        &poseidon_builtin::RELATION_USES_PER_ROW,
+    );
+    let claim = range_check_builtin_bits_96::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        range_check96_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "range_check_builtin_bits_96",
+        &claim,
+        &range_check_builtin_bits_96::Eval {
+            claim,
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            range_check_6_lookup_elements: relations::RangeCheck_6::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+        },
+        &range_check_builtin_bits_96::RELATION_USES_PER_ROW,
+    );
+    let claim = range_check_builtin_bits_128::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+        range_check_builtin_segment_start: 0,
+    };
+    visitor.visit(
+        "range_check_builtin_bits_128",
+        &claim,
+        &range_check_builtin_bits_128::Eval {
+            claim,
+            memory_address_to_id_lookup_elements: relations::MemoryAddressToId::dummy(),
+            memory_id_to_big_lookup_elements: relations::MemoryIdToBig::dummy(),
+        },
+        &range_check_builtin_bits_128::RELATION_USES_PER_ROW,
+    );
+    let claim = partial_ec_mul::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "partial_ec_mul",
+        &claim,
+        &partial_ec_mul::Eval {
+            claim,
+            pedersen_points_table_lookup_elements: relations::PedersenPointsTable::dummy(),
+            range_check_9_9_lookup_elements: relations::RangeCheck_9_9::dummy(),
+            range_check_19_lookup_elements: relations::RangeCheck_19::dummy(),
+            partial_ec_mul_lookup_elements: relations::PartialEcMul::dummy(),
+        },
+        &partial_ec_mul::RELATION_USES_PER_ROW,
+    );
+    let claim = pedersen_points_table::Claim {};
+    visitor.visit(
+        "pedersen_points_table",
+        &claim,
+        &pedersen_points_table::Eval {
+            claim,
+            pedersen_points_table_lookup_elements: relations::PedersenPointsTable::dummy(),
+        },
+        &[],
+    );
+    let claim = poseidon_3_partial_rounds_chain::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "poseidon_3_partial_rounds_chain",
+        &claim,
+        &poseidon_3_partial_rounds_chain::Eval {
+            claim,
+            poseidon_round_keys_lookup_elements: relations::PoseidonRoundKeys::dummy(),
+            cube_252_lookup_elements: relations::Cube252::dummy(),
+            range_check_4_4_4_4_lookup_elements: relations::RangeCheck_4_4_4_4::dummy(),
+            range_check_4_4_lookup_elements: relations::RangeCheck_4_4::dummy(),
+            range_check_felt_252_width_27_lookup_elements:
+                relations::RangeCheckFelt252Width27::dummy(),
+            poseidon_3_partial_rounds_chain_lookup_elements:
+                relations::Poseidon3PartialRoundsChain::dummy(),
+        },
+        &poseidon_3_partial_rounds_chain::RELATION_USES_PER_ROW,
+    );
+    let claim = poseidon_full_round_chain::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "poseidon_full_round_chain",
+        &claim,
+        &poseidon_full_round_chain::Eval {
+            claim,
+            cube_252_lookup_elements: relations::Cube252::dummy(),
+            poseidon_round_keys_lookup_elements: relations::PoseidonRoundKeys::dummy(),
+            range_check_3_3_3_3_3_lookup_elements: relations::RangeCheck_3_3_3_3_3::dummy(),
+            poseidon_full_round_chain_lookup_elements: relations::PoseidonFullRoundChain::dummy(),
+        },
+        &poseidon_full_round_chain::RELATION_USES_PER_ROW,
+    );
+    let claim = cube_252::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "cube_252",
+        &claim,
+        &cube_252::Eval {
+            claim,
+            range_check_9_9_lookup_elements: relations::RangeCheck_9_9::dummy(),
+            range_check_19_lookup_elements: relations::RangeCheck_19::dummy(),
+            cube_252_lookup_elements: relations::Cube252::dummy(),
+        },
+        &cube_252::RELATION_USES_PER_ROW,
+    );
+    let claim = poseidon_round_keys::Claim {};
+    visitor.visit(
+        "poseidon_round_keys",
+        &claim,
+        &poseidon_round_keys::Eval {
+            claim,
+            poseidon_round_keys_lookup_elements: relations::PoseidonRoundKeys::dummy(),
+        },
+        &[],
+    );
+    let claim = range_check_felt_252_width_27::Claim {
+        log_size: DESCRIPTION_LOG_SIZE,
+    };
+    visitor.visit(
+        "range_check_felt_252_width_27",
+        &claim,
+        &range_check_felt_252_width_27::Eval {
+            claim,
+            range_check_9_9_lookup_elements: relations::RangeCheck_9_9::dummy(),
+            range_check_18_lookup_elements: relations::RangeCheck_18::dummy(),
+            range_check_felt_252_width_27_lookup_elements:
+                relations::RangeCheckFelt252Width27::dummy(),
+        },
+        &range_check_felt_252_width_27::RELATION_USES_PER_ROW,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    struct ColumnCountChecker;
+    impl ComponentVisitor for ColumnCountChecker {
+        fn visit<C: Serialize, E: FrameworkEval + ColumnNames>(
+            &mut self,
+            name: &'static str,
+            _claim: &C,
+            eval: &E,
+            relation_uses_per_row: &[RelationUse],
+        ) {
+            let description = describe_component(name, eval, relation_uses_per_row);
+            assert_eq!(
+                description.n_trace_columns,
+                eval.trace_column_names().len(),
+                "{name}"
+            );
+            assert_eq!(
+                description.n_interaction_columns,
+                eval.interaction_column_names().len(),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_column_counts_match_column_names() {
+        visit_cairo_components(&mut ColumnCountChecker);
+    }
+
+    #[test]
+    fn test_degree() {
+        let evals = (0..N_DEGREE_POINTS as u32)
+            .map(|t| {
+                let t = SecureField::from(M31::from_u32_unchecked(t));
+                t * t * t + t + SecureField::one()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(degree(&evals), 3);
+        assert_eq!(degree(&[SecureField::zero(); N_DEGREE_POINTS]), 0);
+    }
+
+    #[test]
+    fn test_air_description_snapshot() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("air_description.json");
+        let description = serde_json::to_string_pretty(&air_description()).unwrap();
+
+        let is_fix_mode = std::env::var("FIX") == Ok("1".to_string());
+        if is_fix_mode {
+            std::fs::write(&path, &description).unwrap();
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+            panic!(
+                "Failed to read {}: {err}. To create it, run the test with FIX=1.",
+                path.display()
+            )
+        });
+        assert!(
+            description == expected,
+            "The AIR description doesn't match {}. To update it, run the test with FIX=1.",
+            path.display()
+        );
+    }
+}
@@ -2,15 +2,16 @@ use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 use stwo_cairo_serialize::CairoSerialize;
 use stwo_prover::constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::air::ComponentProver;
-use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::channel::Channel;
 use stwo_prover::core::fields::qm31::QM31;
 use stwo_prover::core::pcs::TreeVec;
 
-use crate::air::{accumulate_relation_uses, CairoInteractionElements, RelationUsesDict};
+use crate::air::{
+    accumulate_relation_uses, CairoComponentVisitor, CairoInteractionElements, RelationUsesDict,
+};
 use crate::components::{
     blake_g, blake_round, blake_round_sigma, triple_xor_32, verify_bitwise_xor_12,
+    ComponentProverBackend,
 };
 
 #[derive(Serialize, Deserialize, CairoSerialize)]
@@ -104,6 +105,29 @@ pub struct BlakeContextInteractionClaim {
     pub claim: Option<InteractionClaim>,
 }
 impl BlakeContextInteractionClaim {
+    /// Returns the interaction claim of the components of `claim`, with zero claimed sums.
+    pub(crate) fn zero(claim: &BlakeContextClaim) -> Self {
+        Self {
+            claim: claim.claim.as_ref().map(|_| InteractionClaim {
+                blake_round: blake_round::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                blake_g: blake_g::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                blake_sigma: blake_round_sigma::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                triple_xor_32: triple_xor_32::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                verify_bitwise_xor_12: verify_bitwise_xor_12::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+            }),
+        }
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
         if let Some(claim) = &self.claim {
             claim.mix_into(channel);
@@ -161,12 +185,18 @@ impl BlakeContextComponents {
         Self { components }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
         self.components
             .as_ref()
             .map(|c| c.provers())
             .unwrap_or_default()
     }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        if let Some(components) = &self.components {
+            components.visit(visitor);
+        }
+    }
 }
 
 impl std::fmt::Display for BlakeContextComponents {
@@ -274,15 +304,23 @@ impl Components {
         }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
         vec![
-            &self.blake_round,
-            &self.blake_g,
-            &self.blake_sigma,
-            &self.triple_xor_32,
-            &self.verify_bitwise_xor_12,
+            B::component_prover(&self.blake_round),
+            B::component_prover(&self.blake_g),
+            B::component_prover(&self.blake_sigma),
+            B::component_prover(&self.triple_xor_32),
+            B::component_prover(&self.verify_bitwise_xor_12),
         ]
     }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        visitor.visit("blake_round", &self.blake_round);
+        visitor.visit("blake_g", &self.blake_g);
+        visitor.visit("blake_round_sigma", &self.blake_sigma);
+        visitor.visit("triple_xor_32", &self.triple_xor_32);
+        visitor.visit("verify_bitwise_xor_12", &self.verify_bitwise_xor_12);
+    }
 }
 
 impl std::fmt::Display for Components {
@@ -1,436 +1,494 @@
 use itertools::chain;
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
+use stwo_cairo_adapter::builtins::{
+    BuiltinSegments, MemorySegmentAddresses, ADD_MOD_MEMORY_CELLS, BITWISE_MEMORY_CELLS,
+    MUL_MOD_MEMORY_CELLS, PEDERSEN_MEMORY_CELLS, POSEIDON_MEMORY_CELLS, RANGE_CHECK_MEMORY_CELLS,
+};
 use stwo_cairo_serialize::CairoSerialize;
-use stwo_prover::constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::air::ComponentProver;
-use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::constraint_framework::{FrameworkComponent, TraceLocationAllocator};
 use stwo_prover::core::channel::Channel;
 use stwo_prover::core::fields::qm31::{SecureField, QM31};
 use stwo_prover::core::pcs::TreeVec;
 
 use super::air::CairoInteractionElements;
-use crate::air::{accumulate_relation_uses, RelationUsesDict};
+use crate::air::{
+    accumulate_relation_uses, CairoComponentVisitor, PublicData, PublicSegmentRanges,
+    RelationUsesDict, SegmentRange,
+};
+use crate::component_registry::{ComponentFamily, ExtensionInteractionClaim, FamilyClaim};
 use crate::components::{
     add_mod_builtin, bitwise_builtin, indented_component_display, mul_mod_builtin,
     pedersen_builtin, poseidon_builtin, range_check_builtin_bits_128, range_check_builtin_bits_96,
+    ComponentProverBackend,
 };
+use crate::verifier::{check_builtin, BuiltinClaim, CairoVerificationError};
 
-#[derive(Serialize, Deserialize, CairoSerialize)]
-pub struct BuiltinsClaim {
-    pub add_mod_builtin: Option<add_mod_builtin::Claim>,
-    pub bitwise_builtin: Option<bitwise_builtin::Claim>,
-    pub mul_mod_builtin: Option<mul_mod_builtin::Claim>,
-    pub pedersen_builtin: Option<pedersen_builtin::Claim>,
-    pub poseidon_builtin: Option<poseidon_builtin::Claim>,
-    pub range_check_96_builtin: Option<range_check_builtin_bits_96::Claim>,
-    pub range_check_128_builtin: Option<range_check_builtin_bits_128::Claim>,
+/// Calls `$callback` with the builtins of the Cairo AIR, as `field: module => family` entries: the
+/// field of the builtin in [`BuiltinsClaim`], the module of its component, and its
+/// [`BuiltinFamily`]. The builtins are written to the trace in this order.
+///
+/// Adding a builtin only requires a family and an entry here, as well as its witness.
+#[macro_export]
+macro_rules! builtin_families {
+    ($callback:ident) => {
+        $callback! {
+            add_mod_builtin: add_mod_builtin => AddModBuiltinFamily,
+            bitwise_builtin: bitwise_builtin => BitwiseBuiltinFamily,
+            mul_mod_builtin: mul_mod_builtin => MulModBuiltinFamily,
+            pedersen_builtin: pedersen_builtin => PedersenBuiltinFamily,
+            poseidon_builtin: poseidon_builtin => PoseidonBuiltinFamily,
+            range_check_96_builtin: range_check_builtin_bits_96 => RangeCheck96BuiltinFamily,
+            range_check_128_builtin: range_check_builtin_bits_128 => RangeCheck128BuiltinFamily,
+        }
+    };
 }
-impl BuiltinsClaim {
-    pub fn mix_into(&self, channel: &mut impl Channel) {
-        if let Some(add_mod_builtin) = &self.add_mod_builtin {
-            add_mod_builtin.mix_into(channel);
-        }
-        if let Some(bitwise_builtin) = &self.bitwise_builtin {
-            bitwise_builtin.mix_into(channel);
-        }
-        if let Some(mul_mod_builtin) = &self.mul_mod_builtin {
-            mul_mod_builtin.mix_into(channel);
-        }
-        if let Some(pedersen_builtin) = &self.pedersen_builtin {
-            pedersen_builtin.mix_into(channel);
-        }
-        if let Some(poseidon_builtin) = &self.poseidon_builtin {
-            poseidon_builtin.mix_into(channel);
-        }
-        if let Some(range_check_96_builtin) = &self.range_check_96_builtin {
-            range_check_96_builtin.mix_into(channel);
-        }
-        if let Some(range_check_128_builtin) = &self.range_check_128_builtin {
-            range_check_128_builtin.mix_into(channel);
-        }
+
+/// A builtin of the Cairo AIR, as a [`ComponentFamily`] with a single component over the
+/// builtin's segment. Its claim is encoded as `[log_size, segment_start]`.
+///
+/// The builtins of the Cairo AIR keep their typed claims in [`BuiltinsClaim`]. Registered with a
+/// [`ComponentRegistry`], a builtin family is an additional builtin over a segment in
+/// [`PublicSegmentRanges::extensions`].
+///
+/// [`ComponentRegistry`]: crate::component_registry::ComponentRegistry
+pub trait BuiltinFamily: ComponentFamily + Default {
+    /// The name of the builtin's component.
+    const NAME: &'static str;
+    /// The number of memory cells of a builtin instance.
+    const MEMORY_CELLS: usize;
+
+    /// Returns the range of the builtin's segment, if the program takes the builtin.
+    fn segment(segments: &PublicSegmentRanges) -> Option<SegmentRange>;
+
+    /// Returns the addresses of the builtin's segment in the execution, if it isn't empty.
+    fn builtin_segment(segments: &BuiltinSegments) -> Option<MemorySegmentAddresses>;
+
+    /// Returns the start of the builtin's segment, according to the claim.
+    fn segment_start(claim: &Self::Claim) -> u32;
+
+    fn eval(
+        &self,
+        claim: &Self::Claim,
+        interaction_elements: &CairoInteractionElements,
+    ) -> Self::Eval;
+
+    fn component(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        claim: &Self::Claim,
+        interaction_elements: &CairoInteractionElements,
+        claimed_sum: SecureField,
+    ) -> FrameworkComponent<Self::Eval> {
+        FrameworkComponent::new(
+            tree_span_provider,
+            self.eval(claim, interaction_elements),
+            claimed_sum,
+        )
     }
+}
 
-    pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
-        TreeVec::concat_cols(chain!(
-            self.add_mod_builtin
-                .map(|add_mod_builtin| add_mod_builtin.log_sizes())
-                .into_iter(),
-            self.bitwise_builtin
-                .map(|bitwise_builtin| bitwise_builtin.log_sizes())
-                .into_iter(),
-            self.mul_mod_builtin
-                .map(|mul_mod_builtin| mul_mod_builtin.log_sizes())
-                .into_iter(),
-            self.pedersen_builtin
-                .map(|pedersen_builtin| pedersen_builtin.log_sizes())
-                .into_iter(),
-            self.poseidon_builtin
-                .map(|poseidon_builtin| poseidon_builtin.log_sizes())
-                .into_iter(),
-            self.range_check_96_builtin
-                .map(|range_check_96_builtin| range_check_96_builtin.log_sizes())
-                .into_iter(),
-            self.range_check_128_builtin
-                .map(|range_check_128_builtin| range_check_128_builtin.log_sizes())
-                .into_iter(),
-        ))
+/// Verifies the claim of a builtin of the Cairo AIR against its segment, which must be empty if
+/// the claim is omitted.
+fn verify_builtin<F: BuiltinFamily>(
+    claim: Option<&F::Claim>,
+    public_data: &PublicData,
+) -> Result<(), CairoVerificationError> {
+    let segment = F::segment(&public_data.public_memory.public_segments);
+    match claim {
+        Some(claim) => F::default().verify_claim(claim, public_data, segment.as_ref()),
+        None => check_builtin(None, segment, F::NAME, F::MEMORY_CELLS),
     }
-    pub fn accumulate_relation_uses(&self, relation_uses: &mut RelationUsesDict) {
-        let Self {
-            add_mod_builtin,
-            bitwise_builtin,
-            mul_mod_builtin,
-            pedersen_builtin,
-            poseidon_builtin,
-            range_check_96_builtin,
-            range_check_128_builtin,
-        } = self;
-
-        // TODO(alonf): canonicalize the name of field and module.
-        macro_rules! relation_uses {
-            ($field:ident, $module:ident) => {
-                if let Some(field) = $field {
-                    accumulate_relation_uses(
-                        relation_uses,
-                        $module::RELATION_USES_PER_ROW,
-                        field.log_size,
-                    );
+}
+
+/// The ids of the [`BuiltinFamily`]s. Ids from `1 << 31` are reserved for the builtins of the
+/// Cairo AIR.
+const BUILTIN_FAMILY_ID_BASE: u32 = 1 << 31;
+
+/// Defines a [`BuiltinFamily`], given the lookup elements of its component's evaluator.
+macro_rules! builtin_family {
+    (
+        $(#[$attr:meta])*
+        $family:ident {
+            id: $id:expr,
+            module: $module:ident,
+            segment: $segment:ident,
+            builtin_segment: $builtin_segment:ident,
+            segment_start: $segment_start:ident,
+            memory_cells: $memory_cells:expr,
+            lookup_elements: |$interaction_elements:ident| {
+                $($lookup_elements:ident: $value:expr,)*
+            },
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $family;
+
+        impl FamilyClaim for $module::Claim {
+            fn encode(&self) -> Vec<u32> {
+                vec![self.log_size, self.$segment_start]
+            }
+
+            fn decode(values: &[u32]) -> Result<Self, CairoVerificationError> {
+                let [log_size, $segment_start] = values[..] else {
+                    return Err(CairoVerificationError::Malformed(format!(
+                        "Malformed {} claim: {values:?}",
+                        stringify!($module)
+                    )));
                 };
-            };
+                Ok(Self {
+                    log_size,
+                    $segment_start,
+                })
+            }
+        }
+
+        impl From<$module::InteractionClaim> for ExtensionInteractionClaim {
+            fn from(interaction_claim: $module::InteractionClaim) -> Self {
+                Self {
+                    claimed_sums: vec![interaction_claim.claimed_sum],
+                }
+            }
+        }
+
+        impl ComponentFamily for $family {
+            type Claim = $module::Claim;
+            type Eval = $module::Eval;
+
+            fn id(&self) -> u32 {
+                BUILTIN_FAMILY_ID_BASE + $id
+            }
+
+            fn log_sizes(&self, claim: &Self::Claim) -> TreeVec<Vec<u32>> {
+                claim.log_sizes()
+            }
+
+            fn accumulate_relation_uses(
+                &self,
+                claim: &Self::Claim,
+                relation_uses: &mut RelationUsesDict,
+            ) {
+                accumulate_relation_uses(
+                    relation_uses,
+                    $module::RELATION_USES_PER_ROW,
+                    claim.log_size,
+                );
+            }
+
+            fn has_public_segment(&self) -> bool {
+                true
+            }
+
+            fn verify_claim(
+                &self,
+                claim: &Self::Claim,
+                _public_data: &PublicData,
+                segment: Option<&SegmentRange>,
+            ) -> Result<(), CairoVerificationError> {
+                check_builtin(
+                    Some(BuiltinClaim {
+                        segment_start: Self::segment_start(claim),
+                        log_size: claim.log_size,
+                    }),
+                    segment.copied(),
+                    Self::NAME,
+                    Self::MEMORY_CELLS,
+                )
+            }
+
+            fn n_components(&self, _claim: &Self::Claim) -> usize {
+                1
+            }
+
+            fn evals(
+                &self,
+                claim: &Self::Claim,
+                interaction_elements: &CairoInteractionElements,
+            ) -> Vec<Self::Eval> {
+                vec![self.eval(claim, interaction_elements)]
+            }
         }
-        relation_uses!(add_mod_builtin, add_mod_builtin);
-        relation_uses!(bitwise_builtin, bitwise_builtin);
-        relation_uses!(mul_mod_builtin, mul_mod_builtin);
-        relation_uses!(pedersen_builtin, pedersen_builtin);
-        relation_uses!(poseidon_builtin, poseidon_builtin);
-        relation_uses!(range_check_96_builtin, range_check_builtin_bits_96);
-        relation_uses!(range_check_128_builtin, range_check_builtin_bits_128);
+
+        impl BuiltinFamily for $family {
+            const NAME: &'static str = stringify!($module);
+            const MEMORY_CELLS: usize = $memory_cells;
+
+            fn segment(segments: &PublicSegmentRanges) -> Option<SegmentRange> {
+                segments.$segment
+            }
+
+            fn builtin_segment(segments: &BuiltinSegments) -> Option<MemorySegmentAddresses> {
+                segments.$builtin_segment.clone()
+            }
+
+            fn segment_start(claim: &Self::Claim) -> u32 {
+                claim.$segment_start
+            }
+
+            fn eval(
+                &self,
+                claim: &Self::Claim,
+                $interaction_elements: &CairoInteractionElements,
+            ) -> Self::Eval {
+                $module::Eval {
+                    claim: *claim,
+                    $($lookup_elements: $value.clone(),)*
+                }
+            }
+        }
+    };
+}
+
+builtin_family! {
+    AddModBuiltinFamily {
+        id: 2,
+        module: add_mod_builtin,
+        segment: add_mod,
+        builtin_segment: add_mod,
+        segment_start: add_mod_builtin_segment_start,
+        memory_cells: ADD_MOD_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+        },
     }
 }
 
-#[derive(Serialize, Deserialize, CairoSerialize)]
-pub struct BuiltinsInteractionClaim {
-    pub add_mod_builtin: Option<add_mod_builtin::InteractionClaim>,
-    pub bitwise_builtin: Option<bitwise_builtin::InteractionClaim>,
-    pub mul_mod_builtin: Option<mul_mod_builtin::InteractionClaim>,
-    pub pedersen_builtin: Option<pedersen_builtin::InteractionClaim>,
-    pub poseidon_builtin: Option<poseidon_builtin::InteractionClaim>,
-    pub range_check_96_builtin: Option<range_check_builtin_bits_96::InteractionClaim>,
-    pub range_check_128_builtin: Option<range_check_builtin_bits_128::InteractionClaim>,
+builtin_family! {
+    BitwiseBuiltinFamily {
+        id: 3,
+        module: bitwise_builtin,
+        segment: bitwise,
+        builtin_segment: bitwise,
+        segment_start: bitwise_builtin_segment_start,
+        memory_cells: BITWISE_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+            verify_bitwise_xor_9_lookup_elements: interaction_elements.verify_bitwise_xor_9,
+        },
+    }
 }
-impl BuiltinsInteractionClaim {
-    pub fn mix_into(&self, channel: &mut impl Channel) {
-        if let Some(add_mod_builtin) = &self.add_mod_builtin {
-            add_mod_builtin.mix_into(channel);
-        }
-        if let Some(bitwise_builtin) = self.bitwise_builtin {
-            bitwise_builtin.mix_into(channel)
-        }
-        if let Some(mul_mod_builtin) = &self.mul_mod_builtin {
-            mul_mod_builtin.mix_into(channel);
-        }
-        if let Some(pedersen_builtin) = &self.pedersen_builtin {
-            pedersen_builtin.mix_into(channel);
-        }
-        if let Some(poseidon_builtin) = self.poseidon_builtin {
-            poseidon_builtin.mix_into(channel)
-        }
-        if let Some(range_check_96_builtin) = &self.range_check_96_builtin {
-            range_check_96_builtin.mix_into(channel);
-        }
-        if let Some(range_check_128_builtin) = self.range_check_128_builtin {
-            range_check_128_builtin.mix_into(channel)
-        }
+
+builtin_family! {
+    MulModBuiltinFamily {
+        id: 4,
+        module: mul_mod_builtin,
+        segment: mul_mod,
+        builtin_segment: mul_mod,
+        segment_start: mul_mod_builtin_segment_start,
+        memory_cells: MUL_MOD_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+            range_check_12_lookup_elements: interaction_elements.range_checks.rc_12,
+            range_check_18_lookup_elements: interaction_elements.range_checks.rc_18,
+            range_check_3_6_6_3_lookup_elements: interaction_elements.range_checks.rc_3_6_6_3,
+        },
     }
+}
 
-    pub fn sum(&self) -> SecureField {
-        let mut sum = QM31::zero();
-        if let Some(add_mod_builtin) = &self.add_mod_builtin {
-            sum += add_mod_builtin.claimed_sum;
-        }
-        if let Some(bitwise_builtin) = &self.bitwise_builtin {
-            sum += bitwise_builtin.claimed_sum;
-        }
-        if let Some(mul_mod_builtin) = &self.mul_mod_builtin {
-            sum += mul_mod_builtin.claimed_sum;
-        }
-        if let Some(pedersen_builtin) = &self.pedersen_builtin {
-            sum += pedersen_builtin.claimed_sum;
-        }
-        if let Some(poseidon_builtin) = &self.poseidon_builtin {
-            sum += poseidon_builtin.claimed_sum;
-        }
-        if let Some(range_check_96_builtin) = &self.range_check_96_builtin {
-            sum += range_check_96_builtin.claimed_sum;
-        }
-        if let Some(range_check_128_builtin) = &self.range_check_128_builtin {
-            sum += range_check_128_builtin.claimed_sum;
-        }
-        sum
+builtin_family! {
+    PedersenBuiltinFamily {
+        id: 5,
+        module: pedersen_builtin,
+        segment: pedersen,
+        builtin_segment: pedersen,
+        segment_start: pedersen_builtin_segment_start,
+        memory_cells: PEDERSEN_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+            partial_ec_mul_lookup_elements: interaction_elements.partial_ec_mul,
+            range_check_5_4_lookup_elements: interaction_elements.range_checks.rc_5_4,
+            range_check_8_lookup_elements: interaction_elements.range_checks.rc_8,
+        },
     }
 }
-pub struct BuiltinComponents {
-    pub add_mod_builtin: Option<add_mod_builtin::Component>,
-    pub bitwise_builtin: Option<bitwise_builtin::Component>,
-    pub mul_mod_builtin: Option<mul_mod_builtin::Component>,
-    pub pedersen_builtin: Option<pedersen_builtin::Component>,
-    pub poseidon_builtin: Option<poseidon_builtin::Component>,
-    pub range_check_96_builtin: Option<range_check_builtin_bits_96::Component>,
-    pub range_check_128_builtin: Option<range_check_builtin_bits_128::Component>,
+
+builtin_family! {
+    PoseidonBuiltinFamily {
+        id: 6,
+        module: poseidon_builtin,
+        segment: poseidon,
+        builtin_segment: poseidon,
+        segment_start: poseidon_builtin_segment_start,
+        memory_cells: POSEIDON_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            cube_252_lookup_elements: interaction_elements.cube_252,
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+            poseidon_3_partial_rounds_chain_lookup_elements:
+                interaction_elements.poseidon_3_partial_rounds_chain,
+            poseidon_full_round_chain_lookup_elements:
+                interaction_elements.poseidon_full_round_chain,
+            range_check_3_3_3_3_3_lookup_elements:
+                interaction_elements.range_checks.rc_3_3_3_3_3,
+            range_check_4_4_lookup_elements: interaction_elements.range_checks.rc_4_4,
+            range_check_4_4_4_4_lookup_elements: interaction_elements.range_checks.rc_4_4_4_4,
+            range_check_felt_252_width_27_lookup_elements:
+                interaction_elements.range_check_felt_252_width_27,
+        },
+    }
 }
-impl BuiltinComponents {
-    pub fn new(
-        tree_span_provider: &mut TraceLocationAllocator,
-        claim: &BuiltinsClaim,
-        interaction_elements: &CairoInteractionElements,
-        interaction_claim: &BuiltinsInteractionClaim,
-    ) -> Self {
-        let add_mod_builtin_component = claim.add_mod_builtin.map(|add_mod_builtin| {
-            add_mod_builtin::Component::new(
-                tree_span_provider,
-                add_mod_builtin::Eval {
-                    claim: add_mod_builtin,
-                    memory_address_to_id_lookup_elements: interaction_elements
-                        .memory_address_to_id
-                        .clone(),
-                    memory_id_to_big_lookup_elements: interaction_elements
-                        .memory_id_to_value
-                        .clone(),
-                },
-                interaction_claim.add_mod_builtin.unwrap().claimed_sum,
-            )
-        });
-        let bitwise_builtin_component = claim.bitwise_builtin.map(|bitwise_builtin| {
-            bitwise_builtin::Component::new(
-                tree_span_provider,
-                bitwise_builtin::Eval {
-                    claim: bitwise_builtin,
-                    memory_address_to_id_lookup_elements: interaction_elements
-                        .memory_address_to_id
-                        .clone(),
-                    memory_id_to_big_lookup_elements: interaction_elements
-                        .memory_id_to_value
-                        .clone(),
-                    verify_bitwise_xor_9_lookup_elements: interaction_elements
-                        .verify_bitwise_xor_9
-                        .clone(),
-                },
-                interaction_claim.bitwise_builtin.unwrap().claimed_sum,
-            )
-        });
-        let mul_mod_builtin_component = claim.mul_mod_builtin.map(|mul_mod_builtin| {
-            mul_mod_builtin::Component::new(
-                tree_span_provider,
-                mul_mod_builtin::Eval {
-                    claim: mul_mod_builtin,
-                    memory_address_to_id_lookup_elements: interaction_elements
-                        .memory_address_to_id
-                        .clone(),
-                    memory_id_to_big_lookup_elements: interaction_elements
-                        .memory_id_to_value
-                        .clone(),
-                    range_check_12_lookup_elements: interaction_elements.range_checks.rc_12.clone(),
-                    range_check_18_lookup_elements: interaction_elements.range_checks.rc_18.clone(),
-                    range_check_3_6_6_3_lookup_elements: interaction_elements
-                        .range_checks
-                        .rc_3_6_6_3
-                        .clone(),
-                },
-                interaction_claim.mul_mod_builtin.unwrap().claimed_sum,
-            )
-        });
-        let pedersen_builtin_component = claim.pedersen_builtin.map(|pedersen_builtin| {
-            pedersen_builtin::Component::new(
-                tree_span_provider,
-                pedersen_builtin::Eval {
-                    claim: pedersen_builtin,
-                    memory_address_to_id_lookup_elements: interaction_elements
-                        .memory_address_to_id
-                        .clone(),
-                    memory_id_to_big_lookup_elements: interaction_elements
-                        .memory_id_to_value
-                        .clone(),
-                    partial_ec_mul_lookup_elements: interaction_elements.partial_ec_mul.clone(),
-                    range_check_5_4_lookup_elements: interaction_elements
-                        .range_checks
-                        .rc_5_4
-                        .clone(),
-                    range_check_8_lookup_elements: interaction_elements.range_checks.rc_8.clone(),
-                },
-                interaction_claim.pedersen_builtin.unwrap().claimed_sum,
-            )
-        });
-        let poseidon_builtin_component = claim.poseidon_builtin.map(|poseidon_builtin| {
-            poseidon_builtin::Component::new(
-                tree_span_provider,
-                poseidon_builtin::Eval {
-                    claim: poseidon_builtin,
-                    cube_252_lookup_elements: interaction_elements.cube_252.clone(),
-                    memory_address_to_id_lookup_elements: interaction_elements
-                        .memory_address_to_id
-                        .clone(),
-                    memory_id_to_big_lookup_elements: interaction_elements
-                        .memory_id_to_value
-                        .clone(),
-                    poseidon_3_partial_rounds_chain_lookup_elements: interaction_elements
-                        .poseidon_3_partial_rounds_chain
-                        .clone(),
-                    poseidon_full_round_chain_lookup_elements: interaction_elements
-                        .poseidon_full_round_chain
-                        .clone(),
-                    range_check_3_3_3_3_3_lookup_elements: interaction_elements
-                        .range_checks
-                        .rc_3_3_3_3_3
-                        .clone(),
-                    range_check_4_4_lookup_elements: interaction_elements
-                        .range_checks
-                        .rc_4_4
-                        .clone(),
-                    range_check_4_4_4_4_lookup_elements: interaction_elements
-                        .range_checks
-                        .rc_4_4_4_4
-                        .clone(),
-                    range_check_felt_252_width_27_lookup_elements: interaction_elements
-                        .range_check_felt_252_width_27
-                        .clone(),
-                },
-                interaction_claim.poseidon_builtin.unwrap().claimed_sum,
-            )
-        });
-        let range_check_96_builtin_component =
-            claim.range_check_96_builtin.map(|range_check_96_builtin| {
-                range_check_builtin_bits_96::Component::new(
-                    tree_span_provider,
-                    range_check_builtin_bits_96::Eval {
-                        claim: range_check_96_builtin,
-                        memory_address_to_id_lookup_elements: interaction_elements
-                            .memory_address_to_id
-                            .clone(),
-                        memory_id_to_big_lookup_elements: interaction_elements
-                            .memory_id_to_value
-                            .clone(),
-                        range_check_6_lookup_elements: interaction_elements
-                            .range_checks
-                            .rc_6
-                            .clone(),
-                    },
-                    interaction_claim
-                        .range_check_96_builtin
-                        .unwrap()
-                        .claimed_sum,
-                )
-            });
-        let range_check_128_builtin_component =
-            claim
-                .range_check_128_builtin
-                .map(|range_check_128_builtin| {
-                    range_check_builtin_bits_128::Component::new(
-                        tree_span_provider,
-                        range_check_builtin_bits_128::Eval {
-                            claim: range_check_128_builtin,
-                            memory_address_to_id_lookup_elements: interaction_elements
-                                .memory_address_to_id
-                                .clone(),
-                            memory_id_to_big_lookup_elements: interaction_elements
-                                .memory_id_to_value
-                                .clone(),
-                        },
-                        interaction_claim
-                            .range_check_128_builtin
-                            .unwrap()
-                            .claimed_sum,
-                    )
-                });
-        Self {
-            add_mod_builtin: add_mod_builtin_component,
-            bitwise_builtin: bitwise_builtin_component,
-            mul_mod_builtin: mul_mod_builtin_component,
-            pedersen_builtin: pedersen_builtin_component,
-            poseidon_builtin: poseidon_builtin_component,
-            range_check_96_builtin: range_check_96_builtin_component,
-            range_check_128_builtin: range_check_128_builtin_component,
-        }
+
+builtin_family! {
+    RangeCheck96BuiltinFamily {
+        id: 1,
+        module: range_check_builtin_bits_96,
+        segment: range_check_96,
+        builtin_segment: range_check_bits_96,
+        segment_start: range_check96_builtin_segment_start,
+        memory_cells: RANGE_CHECK_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+            range_check_6_lookup_elements: interaction_elements.range_checks.rc_6,
+        },
     }
+}
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
-        let mut vec: Vec<&dyn ComponentProver<SimdBackend>> = vec![];
-        if let Some(add_mod_builtin) = &self.add_mod_builtin {
-            vec.push(add_mod_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        if let Some(bitwise_builtin) = &self.bitwise_builtin {
-            vec.push(bitwise_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        if let Some(mul_mod_builtin) = &self.mul_mod_builtin {
-            vec.push(mul_mod_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        if let Some(pedersen_builtin) = &self.pedersen_builtin {
-            vec.push(pedersen_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        if let Some(poseidon_builtin) = &self.poseidon_builtin {
-            vec.push(poseidon_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        if let Some(range_check_96_builtin) = &self.range_check_96_builtin {
-            vec.push(range_check_96_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        if let Some(range_check_128_builtin) = &self.range_check_128_builtin {
-            vec.push(range_check_128_builtin as &dyn ComponentProver<SimdBackend>);
-        }
-        vec
+builtin_family! {
+    RangeCheck128BuiltinFamily {
+        id: 0,
+        module: range_check_builtin_bits_128,
+        segment: range_check_128,
+        builtin_segment: range_check_bits_128,
+        segment_start: range_check_builtin_segment_start,
+        memory_cells: RANGE_CHECK_MEMORY_CELLS,
+        lookup_elements: |interaction_elements| {
+            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id,
+            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value,
+        },
     }
 }
 
-impl std::fmt::Display for BuiltinComponents {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(add_mod_builtin) = &self.add_mod_builtin {
-            writeln!(
-                f,
-                "AddModBuiltin: {}",
-                indented_component_display(add_mod_builtin)
-            )?;
-        }
-        if let Some(bitwise_builtin) = &self.bitwise_builtin {
-            writeln!(
-                f,
-                "BitwiseBuiltin: {}",
-                indented_component_display(bitwise_builtin)
-            )?;
-        }
-        if let Some(mul_mod_builtin) = &self.mul_mod_builtin {
-            writeln!(
-                f,
-                "MulModBuiltin: {}",
-                indented_component_display(mul_mod_builtin)
-            )?;
-        }
-        if let Some(pedersen_builtin) = &self.pedersen_builtin {
-            writeln!(
-                f,
-                "PedersenBuiltin: {}",
-                indented_component_display(pedersen_builtin)
-            )?;
-        }
-        if let Some(poseidon_builtin) = &self.poseidon_builtin {
-            writeln!(
-                f,
-                "PoseidonBuiltin: {}",
-                indented_component_display(poseidon_builtin)
-            )?;
+/// Defines the claims and components of the builtins of the Cairo AIR, see [`builtin_families`].
+macro_rules! define_builtins {
+    ($($field:ident: $module:ident => $family:ident,)*) => {
+        #[derive(Serialize, Deserialize, CairoSerialize)]
+        pub struct BuiltinsClaim {
+            $(pub $field: Option<$module::Claim>,)*
+        }
+        impl BuiltinsClaim {
+            pub fn mix_into(&self, channel: &mut impl Channel) {
+                $(
+                    if let Some(claim) = &self.$field {
+                        claim.mix_into(channel);
+                    }
+                )*
+            }
+
+            pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
+                TreeVec::concat_cols(chain!(
+                    $(self.$field.iter().map(|claim| $family.log_sizes(claim)),)*
+                ))
+            }
+
+            pub fn accumulate_relation_uses(&self, relation_uses: &mut RelationUsesDict) {
+                $(
+                    if let Some(claim) = &self.$field {
+                        $family.accumulate_relation_uses(claim, relation_uses);
+                    }
+                )*
+            }
+
+            /// Verifies the claim of each builtin against its segment.
+            pub fn verify(&self, public_data: &PublicData) -> Result<(), CairoVerificationError> {
+                $(verify_builtin::<$family>(self.$field.as_ref(), public_data)?;)*
+                Ok(())
+            }
         }
-        if let Some(range_check_96_builtin) = &self.range_check_96_builtin {
-            writeln!(
-                f,
-                "RangeCheck96Builtin: {}",
-                indented_component_display(range_check_96_builtin)
-            )?;
+
+        #[derive(Serialize, Deserialize, CairoSerialize)]
+        pub struct BuiltinsInteractionClaim {
+            $(pub $field: Option<$module::InteractionClaim>,)*
+        }
+        impl BuiltinsInteractionClaim {
+            pub fn mix_into(&self, channel: &mut impl Channel) {
+                $(
+                    if let Some(interaction_claim) = &self.$field {
+                        interaction_claim.mix_into(channel);
+                    }
+                )*
+            }
+
+            pub fn sum(&self) -> SecureField {
+                let mut sum = QM31::zero();
+                $(
+                    if let Some(interaction_claim) = &self.$field {
+                        sum += interaction_claim.claimed_sum;
+                    }
+                )*
+                sum
+            }
+
+            /// Returns the interaction claim of the builtins of `claim`, with zero claimed sums.
+            pub(crate) fn zero(claim: &BuiltinsClaim) -> Self {
+                Self {
+                    $(
+                        $field: claim.$field.as_ref().map(|_| $module::InteractionClaim {
+                            claimed_sum: SecureField::zero(),
+                        }),
+                    )*
+                }
+            }
         }
-        if let Some(range_check_128_builtin) = &self.range_check_128_builtin {
-            writeln!(
-                f,
-                "RangeCheck128Builtin: {}",
-                indented_component_display(range_check_128_builtin)
-            )?;
+
+        pub struct BuiltinComponents {
+            $(pub $field: Option<$module::Component>,)*
+        }
+        impl BuiltinComponents {
+            pub fn new(
+                tree_span_provider: &mut TraceLocationAllocator,
+                claim: &BuiltinsClaim,
+                interaction_elements: &CairoInteractionElements,
+                interaction_claim: &BuiltinsInteractionClaim,
+            ) -> Self {
+                Self {
+                    $(
+                        $field: claim.$field.map(|claim| {
+                            $family.component(
+                                tree_span_provider,
+                                &claim,
+                                interaction_elements,
+                                interaction_claim.$field.unwrap().claimed_sum,
+                            )
+                        }),
+                    )*
+                }
+            }
+
+            pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
+                chain!($(self.$field.iter().map(|component| B::component_prover(component)),)*)
+                    .collect()
+            }
+
+            pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+                $(
+                    if let Some(component) = &self.$field {
+                        visitor.visit($family::NAME, component);
+                    }
+                )*
+            }
         }
-        Ok(())
-    }
+
+        impl std::fmt::Display for BuiltinComponents {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $(
+                    if let Some(component) = &self.$field {
+                        writeln!(
+                            f,
+                            "{}: {}",
+                            $family::NAME,
+                            indented_component_display(component)
+                        )?;
+                    }
+                )*
+                Ok(())
+            }
+        }
+    };
 }
+builtin_families!(define_builtins);
@@ -0,0 +1,367 @@
+//! Component families defined outside this crate, e.g. custom builtins, that are proven alongside
+//! the Cairo AIR.
+//!
+//! A family registers with a [`ComponentRegistry`], which both the prover and the verifier must
+//! be given. The claims of registered families are appended to the [`CairoClaim`] and
+//! [`CairoInteractionClaim`] in registration order, and their components follow all the built-in
+//! components in the trace.
+//!
+//! A family may be a custom builtin, whose segment `main` takes a pointer to after the builtin
+//! pointers, see [`ComponentFamily::has_public_segment`]. The builtins of the Cairo AIR are
+//! component families as well, whose claims keep their typed encoding in the [`BuiltinsClaim`].
+//!
+//! [`CairoClaim`]: crate::air::CairoClaim
+//! [`CairoInteractionClaim`]: crate::air::CairoInteractionClaim
+//! [`BuiltinsClaim`]: crate::builtins_air::BuiltinsClaim
+
+use std::sync::Arc;
+
+use itertools::{zip_eq, Itertools};
+use serde::{Deserialize, Serialize};
+use stwo_cairo_serialize::CairoSerialize;
+use stwo_prover::constraint_framework::{
+    FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+};
+use stwo_prover::core::air::ComponentProver;
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::channel::Channel;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::pcs::TreeVec;
+
+use crate::air::{CairoInteractionElements, PublicData, RelationUsesDict, SegmentRange};
+use crate::components::ColumnNames;
+use crate::verifier::{ensure, CairoVerificationError};
+
+/// The claim of a registered component family.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, CairoSerialize)]
+pub struct ExtensionClaim {
+    /// The [`ComponentFamily::id`] of the family the claim belongs to.
+    pub family_id: u32,
+    /// The claim, in a family-specific encoding, e.g. the log sizes of its components.
+    pub values: Vec<u32>,
+}
+impl ExtensionClaim {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.family_id as u64);
+        channel.mix_u64(self.values.len() as u64);
+        channel.mix_u32s(&self.values);
+    }
+}
+
+/// The interaction claim of a registered component family.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, CairoSerialize)]
+pub struct ExtensionInteractionClaim {
+    /// The claimed sums of the family's components, in the order of
+    /// [`ComponentFamily::components`].
+    pub claimed_sums: Vec<SecureField>,
+}
+impl ExtensionInteractionClaim {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&self.claimed_sums);
+    }
+
+    pub fn sum(&self) -> SecureField {
+        self.claimed_sums.iter().sum()
+    }
+}
+
+/// The claim of a [`ComponentFamily`]. The claims of registered families are proven in their
+/// [`ExtensionClaim`] encoding.
+pub trait FamilyClaim: Sized {
+    /// Encodes the claim as [`ExtensionClaim::values`].
+    fn encode(&self) -> Vec<u32>;
+
+    /// Decodes [`ExtensionClaim::values`], failing if they aren't the encoding of a claim.
+    fn decode(values: &[u32]) -> Result<Self, CairoVerificationError>;
+}
+
+/// A family of components proven alongside the Cairo AIR.
+///
+/// The components interact with the Cairo AIR through the relations of
+/// [`CairoInteractionElements`], e.g. a custom builtin reads its segment through the memory
+/// relations. They may only read preprocessed columns of the canonical preprocessed trace, and
+/// with [`PreProcessedTraceVariant::UsageAware`], only those read by the built-in components
+/// present in the claim.
+///
+/// The builtins of the Cairo AIR are component families as well, see [`builtin_families`].
+///
+/// [`PreProcessedTraceVariant::UsageAware`]: crate::PreProcessedTraceVariant::UsageAware
+/// [`builtin_families`]: crate::builtin_families
+pub trait ComponentFamily: Send + Sync {
+    type Claim: FamilyClaim;
+    /// The evaluator of the family's components.
+    type Eval: FrameworkEval + ColumnNames + Sync + 'static;
+
+    /// A unique identifier of the family, mixed into the channel with its claim.
+    fn id(&self) -> u32;
+
+    /// Returns the log sizes of the family's components.
+    /// Does not include the preprocessed trace log sizes.
+    fn log_sizes(&self, claim: &Self::Claim) -> TreeVec<Vec<u32>>;
+
+    /// Accumulates the number of uses of each relation by the family's components.
+    fn accumulate_relation_uses(&self, claim: &Self::Claim, relation_uses: &mut RelationUsesDict);
+
+    /// Whether `main` takes a pointer to a segment of the family after the builtin pointers, and
+    /// returns a pointer to its end. The ranges of these segments are in
+    /// [`PublicSegmentRanges::extensions`], in registration order.
+    ///
+    /// [`PublicSegmentRanges::extensions`]: crate::air::PublicSegmentRanges::extensions
+    fn has_public_segment(&self) -> bool {
+        false
+    }
+
+    /// Verifies the claim against the public data. `segment` is the range of the family's
+    /// segment if it [has one](Self::has_public_segment).
+    fn verify_claim(
+        &self,
+        claim: &Self::Claim,
+        public_data: &PublicData,
+        segment: Option<&SegmentRange>,
+    ) -> Result<(), CairoVerificationError>;
+
+    /// Returns the number of the family's components, i.e. of its claimed sums.
+    fn n_components(&self, claim: &Self::Claim) -> usize;
+
+    /// Returns the evaluators of the family's components, in the order their traces were
+    /// written.
+    fn evals(
+        &self,
+        claim: &Self::Claim,
+        interaction_elements: &CairoInteractionElements,
+    ) -> Vec<Self::Eval>;
+
+    /// Returns the family's components, given their claimed sums.
+    fn components(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        claim: &Self::Claim,
+        interaction_elements: &CairoInteractionElements,
+        claimed_sums: &[SecureField],
+    ) -> Vec<FrameworkComponent<Self::Eval>> {
+        zip_eq(self.evals(claim, interaction_elements), claimed_sums)
+            .map(|(eval, claimed_sum)| {
+                FrameworkComponent::new(tree_span_provider, eval, *claimed_sum)
+            })
+            .collect()
+    }
+}
+
+/// A registered [`ComponentFamily`], whose claims are in their [`ExtensionClaim`] encoding.
+trait RegisteredFamily: Send + Sync {
+    fn id(&self) -> u32;
+
+    fn has_public_segment(&self) -> bool;
+
+    fn verify_claim(
+        &self,
+        claim: &ExtensionClaim,
+        interaction_claim: &ExtensionInteractionClaim,
+        public_data: &PublicData,
+        segment: Option<&SegmentRange>,
+    ) -> Result<(), CairoVerificationError>;
+
+    fn log_sizes(&self, claim: &ExtensionClaim) -> TreeVec<Vec<u32>>;
+
+    fn accumulate_relation_uses(
+        &self,
+        claim: &ExtensionClaim,
+        relation_uses: &mut RelationUsesDict,
+    );
+
+    fn components(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        claim: &ExtensionClaim,
+        interaction_elements: &CairoInteractionElements,
+        interaction_claim: &ExtensionInteractionClaim,
+    ) -> Vec<Box<dyn ComponentProver<SimdBackend>>>;
+}
+
+/// Decodes a claim that was either verified by [`ComponentRegistry::verify_claims`], or encoded
+/// by the prover.
+fn decode_verified<F: ComponentFamily>(family: &F, claim: &ExtensionClaim) -> F::Claim {
+    F::Claim::decode(&claim.values).unwrap_or_else(|error| {
+        panic!(
+            "Unverified claim of component family {}: {error}",
+            ComponentFamily::id(family)
+        )
+    })
+}
+
+impl<F: ComponentFamily> RegisteredFamily for F {
+    fn id(&self) -> u32 {
+        ComponentFamily::id(self)
+    }
+
+    fn has_public_segment(&self) -> bool {
+        ComponentFamily::has_public_segment(self)
+    }
+
+    fn verify_claim(
+        &self,
+        claim: &ExtensionClaim,
+        interaction_claim: &ExtensionInteractionClaim,
+        public_data: &PublicData,
+        segment: Option<&SegmentRange>,
+    ) -> Result<(), CairoVerificationError> {
+        let claim = F::Claim::decode(&claim.values)?;
+        // Otherwise, claimed sums without a component would be added to the lookup sum.
+        ensure!(
+            interaction_claim.claimed_sums.len() == self.n_components(&claim),
+            "Component family {} must have exactly one claimed sum per component",
+            ComponentFamily::id(self)
+        );
+        ComponentFamily::verify_claim(self, &claim, public_data, segment)
+    }
+
+    fn log_sizes(&self, claim: &ExtensionClaim) -> TreeVec<Vec<u32>> {
+        ComponentFamily::log_sizes(self, &decode_verified(self, claim))
+    }
+
+    fn accumulate_relation_uses(
+        &self,
+        claim: &ExtensionClaim,
+        relation_uses: &mut RelationUsesDict,
+    ) {
+        ComponentFamily::accumulate_relation_uses(
+            self,
+            &decode_verified(self, claim),
+            relation_uses,
+        )
+    }
+
+    fn components(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        claim: &ExtensionClaim,
+        interaction_elements: &CairoInteractionElements,
+        interaction_claim: &ExtensionInteractionClaim,
+    ) -> Vec<Box<dyn ComponentProver<SimdBackend>>> {
+        ComponentFamily::components(
+            self,
+            tree_span_provider,
+            &decode_verified(self, claim),
+            interaction_elements,
+            &interaction_claim.claimed_sums,
+        )
+        .into_iter()
+        .map(|component| Box::new(component) as Box<dyn ComponentProver<SimdBackend>>)
+        .collect()
+    }
+}
+
+/// The component families proven alongside the Cairo AIR. Empty by default, in which case proofs
+/// with extension claims are rejected.
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    families: Vec<Arc<dyn RegisteredFamily>>,
+}
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `family`. Its claims are expected after those of previously registered families.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a family with the same id is already registered.
+    pub fn register(&mut self, family: impl ComponentFamily + 'static) -> &mut Self {
+        let id = ComponentFamily::id(&family);
+        assert!(
+            self.family_ids().all(|family_id| family_id != id),
+            "Component family {id} is already registered"
+        );
+        self.families.push(Arc::new(family));
+        self
+    }
+
+    /// Returns the ids of the registered families, in registration order.
+    pub fn family_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.families.iter().map(|family| family.id())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.families.is_empty()
+    }
+
+    /// Verifies that there is exactly one claim and one interaction claim per registered family,
+    /// in registration order, and one public segment per family with a public segment. Verifies
+    /// each claim against the public data.
+    ///
+    /// The other methods of the registry expect claims verified by this method.
+    pub fn verify_claims(
+        &self,
+        claims: &[ExtensionClaim],
+        interaction_claims: &[ExtensionInteractionClaim],
+        public_data: &PublicData,
+    ) -> Result<(), CairoVerificationError> {
+        ensure!(
+            claims.iter().map(|claim| claim.family_id).collect_vec()
+                == self.family_ids().collect_vec(),
+            "Extension claims don't match the registered component families"
+        );
+        ensure!(
+            interaction_claims.len() == claims.len(),
+            "Extension interaction claims don't match the registered component families"
+        );
+        let segments = &public_data.public_memory.public_segments.extensions;
+        ensure!(
+            segments.len()
+                == self
+                    .families
+                    .iter()
+                    .filter(|family| family.has_public_segment())
+                    .count(),
+            "Extension segments don't match the registered component families"
+        );
+        let mut segments = segments.iter();
+        for (family, (claim, interaction_claim)) in
+            zip_eq(&self.families, zip_eq(claims, interaction_claims))
+        {
+            let segment = family
+                .has_public_segment()
+                .then(|| segments.next().unwrap());
+            family.verify_claim(claim, interaction_claim, public_data, segment)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the log sizes of the components of all the families.
+    /// Does not include the preprocessed trace log sizes.
+    pub fn log_sizes(&self, claims: &[ExtensionClaim]) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(
+            zip_eq(&self.families, claims).map(|(family, claim)| family.log_sizes(claim)),
+        )
+    }
+
+    pub fn accumulate_relation_uses(
+        &self,
+        claims: &[ExtensionClaim],
+        relation_uses: &mut RelationUsesDict,
+    ) {
+        for (family, claim) in zip_eq(&self.families, claims) {
+            family.accumulate_relation_uses(claim, relation_uses);
+        }
+    }
+
+    pub fn components(
+        &self,
+        tree_span_provider: &mut TraceLocationAllocator,
+        claims: &[ExtensionClaim],
+        interaction_elements: &CairoInteractionElements,
+        interaction_claims: &[ExtensionInteractionClaim],
+    ) -> Vec<Box<dyn ComponentProver<SimdBackend>>> {
+        zip_eq(&self.families, zip_eq(claims, interaction_claims))
+            .flat_map(|(family, (claim, interaction_claim))| {
+                family.components(
+                    tree_span_provider,
+                    claim,
+                    interaction_elements,
+                    interaction_claim,
+                )
+            })
+            .collect()
+    }
+}
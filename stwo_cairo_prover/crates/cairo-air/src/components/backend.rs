@@ -0,0 +1,362 @@
+//! The backends the Cairo AIR can be proven on.
+//!
+//! [`SimdBackend`] is the production backend. [`CpuBackend`] is a reference backend, producing
+//! the same proofs, used to cross-check the SIMD commitment, constraint evaluation and FRI code
+//! paths. The witness is generated by the SIMD witness generators on both backends; their
+//! vectorized deductions are checked against scalar references by their own tests. Components of
+//! registered families are only proven on the SIMD backend.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::{Deref, Mul};
+
+use itertools::Itertools;
+use num_traits::Zero;
+use stwo_prover::constraint_framework::{
+    Batching, EvalAtRow, FrameworkComponent, FrameworkEval, INTERACTION_TRACE_IDX,
+    PREPROCESSED_TRACE_IDX,
+};
+use stwo_prover::core::air::accumulation::{
+    DomainEvaluationAccumulator, PointEvaluationAccumulator,
+};
+use stwo_prover::core::air::{Component, ComponentProver, Trace};
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::backend::{Backend, CpuBackend};
+use stwo_prover::core::circle::CirclePoint;
+use stwo_prover::core::constraints::coset_vanishing;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::fields::FieldExpOps;
+use stwo_prover::core::lookups::utils::Fraction;
+use stwo_prover::core::pcs::TreeVec;
+use stwo_prover::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+use stwo_prover::core::poly::BitReversedOrder;
+use stwo_prover::core::utils::{bit_reverse, offset_bit_reversed_circle_domain_index};
+use stwo_prover::core::ColumnVec;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("Components of registered families can only be proven on the SIMD backend")]
+pub struct UnsupportedExtensionsError;
+
+/// A backend the components of the Cairo AIR can be proven on.
+pub trait ComponentProverBackend: Backend {
+    /// The prover of a component borrowed for `'a`.
+    type Prover<'a>: Deref<Target = dyn ComponentProver<Self> + 'a>;
+
+    fn component_prover<E: FrameworkEval + Sync>(
+        component: &FrameworkComponent<E>,
+    ) -> Self::Prover<'_>;
+
+    /// Returns the provers of the components of registered families, which are SIMD provers.
+    fn extension_provers(
+        extensions: &[Box<dyn ComponentProver<SimdBackend>>],
+    ) -> Result<Vec<Self::Prover<'_>>, UnsupportedExtensionsError>;
+}
+
+impl ComponentProverBackend for SimdBackend {
+    type Prover<'a> = &'a dyn ComponentProver<Self>;
+
+    fn component_prover<E: FrameworkEval + Sync>(
+        component: &FrameworkComponent<E>,
+    ) -> Self::Prover<'_> {
+        component
+    }
+
+    fn extension_provers(
+        extensions: &[Box<dyn ComponentProver<SimdBackend>>],
+    ) -> Result<Vec<Self::Prover<'_>>, UnsupportedExtensionsError> {
+        Ok(extensions
+            .iter()
+            .map(|component| component.as_ref() as &dyn ComponentProver<Self>)
+            .collect())
+    }
+}
+
+impl ComponentProverBackend for CpuBackend {
+    type Prover<'a> = Box<dyn ComponentProver<Self> + 'a>;
+
+    fn component_prover<E: FrameworkEval + Sync>(
+        component: &FrameworkComponent<E>,
+    ) -> Self::Prover<'_> {
+        Box::new(CpuFrameworkComponent(component))
+    }
+
+    fn extension_provers(
+        extensions: &[Box<dyn ComponentProver<SimdBackend>>],
+    ) -> Result<Vec<Self::Prover<'_>>, UnsupportedExtensionsError> {
+        if !extensions.is_empty() {
+            return Err(UnsupportedExtensionsError);
+        }
+        Ok(vec![])
+    }
+}
+
+/// A [`FrameworkComponent`], proven on the [`CpuBackend`].
+///
+/// The constraints are evaluated row by row on the evaluation domain by [`CpuDomainEvaluator`],
+/// independently of the [`SimdBackend`] domain evaluation.
+pub struct CpuFrameworkComponent<'a, E: FrameworkEval>(pub &'a FrameworkComponent<E>);
+
+impl<E: FrameworkEval> Component for CpuFrameworkComponent<'_, E> {
+    fn n_constraints(&self) -> usize {
+        self.0.n_constraints()
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.0.max_constraint_log_degree_bound()
+    }
+
+    fn trace_log_degree_bounds(&self) -> TreeVec<ColumnVec<u32>> {
+        self.0.trace_log_degree_bounds()
+    }
+
+    fn mask_points(
+        &self,
+        point: CirclePoint<SecureField>,
+    ) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
+        self.0.mask_points(point)
+    }
+
+    fn preproccessed_column_indices(&self) -> ColumnVec<usize> {
+        self.0.preproccessed_column_indices()
+    }
+
+    fn evaluate_constraint_quotients_at_point(
+        &self,
+        point: CirclePoint<SecureField>,
+        mask: &TreeVec<ColumnVec<Vec<SecureField>>>,
+        evaluation_accumulator: &mut PointEvaluationAccumulator,
+    ) {
+        self.0
+            .evaluate_constraint_quotients_at_point(point, mask, evaluation_accumulator)
+    }
+}
+
+impl<E: FrameworkEval> ComponentProver<CpuBackend> for CpuFrameworkComponent<'_, E> {
+    fn evaluate_constraint_quotients_on_domain(
+        &self,
+        trace: &Trace<'_, CpuBackend>,
+        evaluation_accumulator: &mut DomainEvaluationAccumulator<CpuBackend>,
+    ) {
+        let n_constraints = self.n_constraints();
+        if n_constraints == 0 {
+            return;
+        }
+
+        let eval_domain = CanonicCoset::new(self.max_constraint_log_degree_bound()).circle_domain();
+        let trace_domain = CanonicCoset::new(self.0.log_size());
+
+        // The component's columns, evaluated on the evaluation domain.
+        let twiddles = CpuBackend::precompute_twiddles(eval_domain.half_coset);
+        let mut component_polys = trace.polys.sub_tree(self.0.trace_locations());
+        let mut component_evals = trace.evals.sub_tree(self.0.trace_locations());
+        component_polys[PREPROCESSED_TRACE_IDX] = self
+            .preproccessed_column_indices()
+            .iter()
+            .map(|idx| trace.polys[PREPROCESSED_TRACE_IDX][*idx])
+            .collect();
+        component_evals[PREPROCESSED_TRACE_IDX] = self
+            .preproccessed_column_indices()
+            .iter()
+            .map(|idx| trace.evals[PREPROCESSED_TRACE_IDX][*idx])
+            .collect();
+        let component_evals: TreeVec<
+            Vec<Cow<'_, CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>>>,
+        > = TreeVec(
+            component_polys
+                .0
+                .into_iter()
+                .zip_eq(component_evals.0)
+                .map(|(polys, evals)| {
+                    polys
+                        .into_iter()
+                        .zip_eq(evals)
+                        .map(|(poly, eval)| {
+                            if eval.domain == eval_domain {
+                                Cow::Borrowed(eval)
+                            } else {
+                                Cow::Owned(poly.evaluate_with_twiddles(eval_domain, &twiddles))
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+        let columns: TreeVec<Vec<&[BaseField]>> = TreeVec(
+            component_evals
+                .iter()
+                .map(|evals| evals.iter().map(|eval| eval.values.as_slice()).collect())
+                .collect(),
+        );
+
+        // The quotients' denominator, the vanishing polynomial of the trace domain, is constant on
+        // each coset of the trace domain in the evaluation domain.
+        let log_expand = eval_domain.log_size() - trace_domain.log_size();
+        let mut denom_inv = (0..1 << log_expand)
+            .map(|i| coset_vanishing(trace_domain.coset(), eval_domain.at(i)).inverse())
+            .collect_vec();
+        bit_reverse(&mut denom_inv);
+
+        // The accumulator combines the component's constraints with the powers of its random
+        // coefficient, from the last constraint to the first.
+        let [mut accum] = evaluation_accumulator.columns([(eval_domain.log_size(), n_constraints)]);
+        accum.random_coeff_powers.reverse();
+
+        // The claimed sum is spread evenly over the rows, see `LogupAtRow`.
+        let cumsum_shift = self.0.claimed_sum()
+            * BaseField::from_u32_unchecked(1 << trace_domain.log_size()).inverse();
+
+        for row in 0..eval_domain.size() {
+            let evaluator = self.0.deref().evaluate(CpuDomainEvaluator::new(
+                &columns,
+                row,
+                trace_domain.log_size(),
+                eval_domain.log_size(),
+                cumsum_shift,
+                &accum.random_coeff_powers,
+            ));
+            let quotient = evaluator.row_res * denom_inv[row >> trace_domain.log_size()];
+            accum.col.set(row, accum.col.at(row) + quotient);
+        }
+    }
+}
+
+/// Evaluates a component's constraints, including the logup constraints, on a row of its
+/// evaluation domain, combining them with the powers of its random coefficient.
+struct CpuDomainEvaluator<'a> {
+    /// The component's columns, evaluated on the evaluation domain.
+    columns: &'a TreeVec<Vec<&'a [BaseField]>>,
+    column_indices: Vec<usize>,
+    row: usize,
+    trace_log_size: u32,
+    eval_log_size: u32,
+    cumsum_shift: SecureField,
+    /// The powers of the random coefficient, by constraint index.
+    random_coeff_powers: &'a [SecureField],
+    constraint_index: usize,
+    /// The random linear combination of the constraints evaluated so far.
+    row_res: SecureField,
+    fracs: Vec<Fraction<SecureField, SecureField>>,
+    /// The cumulative sum of the logup batches so far.
+    prev_cumsum: Option<SecureField>,
+}
+impl<'a> CpuDomainEvaluator<'a> {
+    fn new(
+        columns: &'a TreeVec<Vec<&'a [BaseField]>>,
+        row: usize,
+        trace_log_size: u32,
+        eval_log_size: u32,
+        cumsum_shift: SecureField,
+        random_coeff_powers: &'a [SecureField],
+    ) -> Self {
+        Self {
+            columns,
+            column_indices: vec![0; columns.len()],
+            row,
+            trace_log_size,
+            eval_log_size,
+            cumsum_shift,
+            random_coeff_powers,
+            constraint_index: 0,
+            row_res: SecureField::zero(),
+            fracs: vec![],
+            prev_cumsum: None,
+        }
+    }
+
+    /// Adds the logup constraint of a batch of fractions. The last batch also accounts for the
+    /// previous row's cumulative sum, and the claimed sum.
+    fn add_batch_constraint(
+        &mut self,
+        fracs: Vec<Fraction<SecureField, SecureField>>,
+        is_last: bool,
+    ) {
+        let Fraction {
+            numerator,
+            denominator,
+        } = fracs
+            .into_iter()
+            .reduce(|a, b| a + b)
+            .expect("Empty logup batch");
+
+        let prev_cumsum = self.prev_cumsum.take().unwrap_or_else(SecureField::zero);
+        let mut diff = match is_last {
+            true => {
+                let [prev_row_cumsum, cur_cumsum] =
+                    self.next_extension_interaction_mask(INTERACTION_TRACE_IDX, [-1, 0]);
+                self.prev_cumsum = Some(cur_cumsum);
+                cur_cumsum - prev_row_cumsum + self.cumsum_shift
+            }
+            false => {
+                let [cur_cumsum] = self.next_extension_interaction_mask(INTERACTION_TRACE_IDX, [0]);
+                self.prev_cumsum = Some(cur_cumsum);
+                cur_cumsum
+            }
+        };
+        diff -= prev_cumsum;
+        self.add_constraint(diff * denominator - numerator);
+    }
+}
+
+impl EvalAtRow for CpuDomainEvaluator<'_> {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        let column = self.columns[interaction][self.column_indices[interaction]];
+        self.column_indices[interaction] += 1;
+        offsets.map(|offset| {
+            column[offset_bit_reversed_circle_domain_index(
+                self.row,
+                self.trace_log_size,
+                self.eval_log_size,
+                offset as i32,
+            )]
+        })
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF>,
+    {
+        self.row_res += self.random_coeff_powers[self.constraint_index] * constraint;
+        self.constraint_index += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.fracs.push(fraction);
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        let fracs = std::mem::take(&mut self.fracs);
+        assert_eq!(batching.len(), fracs.len());
+        let mut batches = BTreeMap::<usize, Vec<_>>::new();
+        for (batch, frac) in batching.iter().zip(fracs) {
+            batches.entry(*batch).or_default().push(frac);
+        }
+        let n_batches = batches.len();
+        for (i, batch) in batches.into_values().enumerate() {
+            self.add_batch_constraint(batch, i == n_batches - 1);
+        }
+    }
+
+    fn finalize_logup(&mut self) {
+        let batching = (0..self.fracs.len()).collect();
+        self.finalize_logup_batched(&batching);
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        let batching = (0..self.fracs.len()).map(|i| i / 2).collect();
+        self.finalize_logup_batched(&batching);
+    }
+}
@@ -0,0 +1,5 @@
+//! The names of the trace columns of the generated components. Generated by `build.rs` from the
+//! `next_trace_mask` calls of each component's `evaluate`, so that they can't go out of sync with
+//! the components, and regenerating the components doesn't drop them.
+
+include!(concat!(env!("OUT_DIR"), "/column_names.rs"));
@@ -9,6 +9,7 @@ use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
 use stwo_prover::core::pcs::TreeVec;
 
+use crate::components::{interaction_column_names, ColumnNames};
 use crate::preprocessed::{PreProcessedColumn, Seq};
 use crate::relations;
 
@@ -76,6 +77,18 @@ impl FrameworkEval for Eval {
     }
 }
 
+impl ColumnNames for Eval {
+    fn trace_column_names(&self) -> Vec<String> {
+        (0..MEMORY_ADDRESS_TO_ID_SPLIT)
+            .flat_map(|i| [format!("id_{i}"), format!("multiplicity_{i}")])
+            .collect()
+    }
+
+    fn interaction_column_names(&self) -> Vec<String> {
+        interaction_column_names(SECURE_EXTENSION_DEGREE * MEMORY_ADDRESS_TO_ID_SPLIT.div_ceil(2))
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, CairoSerialize)]
 pub struct Claim {
     pub log_size: u32,
@@ -16,6 +16,7 @@ use stwo_prover::core::pcs::TreeVec;
 use stwo_prover::relation;
 
 use super::prelude::RelationUse;
+use crate::components::{interaction_column_names, ColumnNames};
 use crate::preprocessed::{PreProcessedColumn, Seq};
 use crate::relations;
 
@@ -106,6 +107,18 @@ impl FrameworkEval for BigEval {
     }
 }
 
+impl ColumnNames for BigEval {
+    fn trace_column_names(&self) -> Vec<String> {
+        value_column_names(N_M31_IN_FELT252)
+    }
+
+    fn interaction_column_names(&self) -> Vec<String> {
+        interaction_column_names(
+            SECURE_EXTENSION_DEGREE * ((N_M31_IN_FELT252.div_ceil(2) + 1).div_ceil(2)),
+        )
+    }
+}
+
 pub fn big_components_from_claim(
     log_sizes: &[u32],
     claimed_sums: &[SecureField],
@@ -187,6 +200,23 @@ impl FrameworkEval for SmallEval {
     }
 }
 
+impl ColumnNames for SmallEval {
+    fn trace_column_names(&self) -> Vec<String> {
+        value_column_names(N_M31_IN_SMALL_FELT252)
+    }
+
+    fn interaction_column_names(&self) -> Vec<String> {
+        interaction_column_names(SECURE_EXTENSION_DEGREE * (N_M31_IN_SMALL_FELT252.div_ceil(2) + 1))
+    }
+}
+
+fn value_column_names(n_limbs: usize) -> Vec<String> {
+    (0..n_limbs)
+        .map(|i| format!("value_limb_{i}"))
+        .chain(["multiplicity".to_string()])
+        .collect()
+}
+
 #[derive(Clone, Serialize, Deserialize, CairoSerialize)]
 pub struct Claim {
     pub big_log_sizes: Vec<u32>,
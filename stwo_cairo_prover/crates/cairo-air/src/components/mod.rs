@@ -46,9 +46,12 @@ pub mod verify_bitwise_xor_8;
 pub mod verify_bitwise_xor_9;
 pub mod verify_instruction;
 
+mod backend;
+pub mod column_names;
 pub(crate) mod prelude;
 pub(crate) mod subroutines;
 
+pub use backend::{ComponentProverBackend, CpuFrameworkComponent, UnsupportedExtensionsError};
 use itertools::Itertools;
 pub use range_check_vector::{
     range_check_11, range_check_12, range_check_18, range_check_19, range_check_3_3_3_3_3,
@@ -56,6 +59,7 @@ pub use range_check_vector::{
     range_check_6, range_check_7_2_5, range_check_8, range_check_9_9,
 };
 use stwo_prover::constraint_framework::{FrameworkComponent, FrameworkEval};
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
 
 #[cfg(test)]
 pub mod constraints_regression_test_values;
@@ -77,3 +81,23 @@ pub(crate) fn display_components<E: FrameworkEval>(components: &[FrameworkCompon
         .map(|component| indented_component_display(component))
         .join("\n")
 }
+
+/// Names of a component's trace columns, in trace order. Used for debugging witness generation.
+pub trait ColumnNames {
+    fn trace_column_names(&self) -> Vec<String>;
+    fn interaction_column_names(&self) -> Vec<String>;
+}
+
+/// Names the columns of a logup interaction trace with `n_columns` columns. Every
+/// `SECURE_EXTENSION_DEGREE` consecutive columns are the coordinates of a single batch's sum.
+pub fn interaction_column_names(n_columns: usize) -> Vec<String> {
+    (0..n_columns)
+        .map(|i| {
+            format!(
+                "logup_{}_{}",
+                i / SECURE_EXTENSION_DEGREE,
+                i % SECURE_EXTENSION_DEGREE
+            )
+        })
+        .collect()
+}
@@ -2,7 +2,7 @@ pub use num_traits::One;
 pub use serde::{Deserialize, Serialize};
 pub use stwo_cairo_serialize::CairoSerialize;
 pub use stwo_prover::constraint_framework::{
-    EvalAtRow, FrameworkComponent, FrameworkEval, RelationEntry,
+    EvalAtRow, FrameworkComponent, FrameworkEval, RelationEntry, INTERACTION_TRACE_IDX,
 };
 pub use stwo_prover::core::channel::Channel;
 pub use stwo_prover::core::fields::m31::M31;
@@ -11,6 +11,7 @@ pub use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
 pub use stwo_prover::core::pcs::TreeVec;
 
 pub use crate::blake::*;
+pub use crate::components::{interaction_column_names, ColumnNames};
 pub use crate::pedersen::const_columns::PedersenPoints;
 pub use crate::poseidon::const_columns::PoseidonRoundKeys;
 pub use crate::preprocessed::*;
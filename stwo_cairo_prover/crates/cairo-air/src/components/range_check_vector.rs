@@ -119,6 +119,16 @@ macro_rules! range_check_eval{
                     }
                 }
 
+                impl $crate::components::ColumnNames for Eval {
+                    fn trace_column_names(&self) -> Vec<String> {
+                        vec!["multiplicity".to_string()]
+                    }
+
+                    fn interaction_column_names(&self) -> Vec<String> {
+                        $crate::components::interaction_column_names(SECURE_EXTENSION_DEGREE)
+                    }
+                }
+
                 #[cfg(test)]
                 mod tests {
                     use num_traits::Zero;
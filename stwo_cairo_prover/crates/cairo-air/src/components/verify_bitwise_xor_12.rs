@@ -74,6 +74,18 @@ impl FrameworkEval for Eval {
     }
 }
 
+impl ColumnNames for Eval {
+    fn trace_column_names(&self) -> Vec<String> {
+        (0..N_MULT_COLUMNS)
+            .map(|i| format!("multiplicity_{i}"))
+            .collect()
+    }
+
+    fn interaction_column_names(&self) -> Vec<String> {
+        interaction_column_names(self.claim.log_sizes()[INTERACTION_TRACE_IDX].len())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use num_traits::Zero;
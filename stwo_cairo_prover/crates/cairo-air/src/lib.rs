@@ -2,11 +2,14 @@
 #![feature(array_chunks)]
 use serde::{Deserialize, Serialize};
 
+use crate::air::CairoClaim;
 use crate::preprocessed::PreProcessedTrace;
 
 pub mod air;
+pub mod air_description;
 pub mod blake;
 pub mod builtins_air;
+pub mod component_registry;
 pub mod components;
 pub mod opcodes_air;
 pub mod pedersen;
@@ -19,24 +22,42 @@ pub mod utils;
 
 // TODO(Ohad): verifier crate.
 pub mod verifier;
+pub mod verifier_codegen;
 
 pub use air::CairoProof;
 
 /// The preprocessed trace used for the prover.
 // TODO(Ohad): move somewhere else.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PreProcessedTraceVariant {
     Canonical,
     CanonicalWithoutPedersen,
+    /// The canonical columns read by the built-in components present in the claim. Not
+    /// supported by the Cairo verifier, whose preprocessed trace is canonical.
+    UsageAware,
 }
 impl PreProcessedTraceVariant {
-    pub fn to_preprocessed_trace(&self) -> PreProcessedTrace {
+    /// Returns the preprocessed trace of the variant, or `None` for [`Self::UsageAware`], whose
+    /// trace depends on the claim, see [`Self::to_preprocessed_trace_for_claim`].
+    pub fn to_preprocessed_trace(&self) -> Option<PreProcessedTrace> {
+        match self {
+            PreProcessedTraceVariant::Canonical => Some(PreProcessedTrace::canonical()),
+            PreProcessedTraceVariant::CanonicalWithoutPedersen => {
+                Some(PreProcessedTrace::canonical_without_pedersen())
+            }
+            PreProcessedTraceVariant::UsageAware => None,
+        }
+    }
+
+    /// Returns the preprocessed trace of the variant for proving or verifying `claim`.
+    pub fn to_preprocessed_trace_for_claim(&self, claim: &CairoClaim) -> PreProcessedTrace {
         match self {
             PreProcessedTraceVariant::Canonical => PreProcessedTrace::canonical(),
             PreProcessedTraceVariant::CanonicalWithoutPedersen => {
                 PreProcessedTrace::canonical_without_pedersen()
             }
+            PreProcessedTraceVariant::UsageAware => PreProcessedTrace::usage_aware(claim),
         }
     }
 }
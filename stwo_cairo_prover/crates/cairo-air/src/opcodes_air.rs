@@ -1,302 +1,131 @@
 use itertools::{chain, Itertools};
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
+use stwo_cairo_adapter::opcode_components;
 use stwo_cairo_serialize::CairoSerialize;
 use stwo_prover::constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::air::ComponentProver;
-use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::channel::Channel;
-use stwo_prover::core::fields::qm31::{SecureField, QM31};
+use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::pcs::TreeVec;
 
 use super::air::CairoInteractionElements;
 use super::components::display_components;
-use crate::air::{accumulate_relation_uses, RelationUsesDict};
+use crate::air::{accumulate_relation_uses, CairoComponentVisitor, RelationUsesDict};
 use crate::components::{
     add_ap_opcode, add_opcode, add_opcode_small, assert_eq_opcode, assert_eq_opcode_double_deref,
     assert_eq_opcode_imm, blake_compress_opcode, call_opcode, call_opcode_rel_imm, generic_opcode,
     jnz_opcode, jnz_opcode_taken, jump_opcode, jump_opcode_double_deref, jump_opcode_rel,
     jump_opcode_rel_imm, mul_opcode, mul_opcode_small, qm_31_add_mul_opcode, ret_opcode,
+    ComponentProverBackend,
 };
 
-#[derive(Serialize, Deserialize, CairoSerialize)]
-pub struct OpcodeClaim {
-    pub add: Vec<add_opcode::Claim>,
-    pub add_small: Vec<add_opcode_small::Claim>,
-    pub add_ap: Vec<add_ap_opcode::Claim>,
-    pub assert_eq: Vec<assert_eq_opcode::Claim>,
-    pub assert_eq_imm: Vec<assert_eq_opcode_imm::Claim>,
-    pub assert_eq_double_deref: Vec<assert_eq_opcode_double_deref::Claim>,
-    pub blake: Vec<blake_compress_opcode::Claim>,
-    pub call: Vec<call_opcode::Claim>,
-    pub call_rel_imm: Vec<call_opcode_rel_imm::Claim>,
-    pub generic: Vec<generic_opcode::Claim>,
-    pub jnz: Vec<jnz_opcode::Claim>,
-    pub jnz_taken: Vec<jnz_opcode_taken::Claim>,
-    pub jump: Vec<jump_opcode::Claim>,
-    pub jump_double_deref: Vec<jump_opcode_double_deref::Claim>,
-    pub jump_rel: Vec<jump_opcode_rel::Claim>,
-    pub jump_rel_imm: Vec<jump_opcode_rel_imm::Claim>,
-    pub mul: Vec<mul_opcode::Claim>,
-    pub mul_small: Vec<mul_opcode_small::Claim>,
-    pub qm31: Vec<qm_31_add_mul_opcode::Claim>,
-    pub ret: Vec<ret_opcode::Claim>,
+/// Declares the opcode claims and components, with a vector of instances per opcode component.
+macro_rules! opcode_structs {
+    ($($field:ident: $module:ident,)*) => {
+        #[derive(Serialize, Deserialize, CairoSerialize)]
+        pub struct OpcodeClaim {
+            $(pub $field: Vec<$module::Claim>,)*
+        }
+
+        #[derive(Serialize, Deserialize, CairoSerialize)]
+        pub struct OpcodeInteractionClaim {
+            $(pub $field: Vec<$module::InteractionClaim>,)*
+        }
+
+        pub struct OpcodeComponents {
+            $(pub $field: Vec<$module::Component>,)*
+        }
+    };
 }
+opcode_components!(opcode_structs);
+
 impl OpcodeClaim {
     /// For each opcode component vector, mixes the length and then the claims.
     pub fn mix_into(&self, channel: &mut impl Channel) {
-        macro_rules! mix_component_vector {
-            ($field:ident) => {
-                channel.mix_u64(self.$field.len() as u64);
-                self.$field.iter().for_each(|c| c.mix_into(channel));
+        macro_rules! mix_component_vectors {
+            ($($field:ident: $module:ident,)*) => {
+                $(
+                    channel.mix_u64(self.$field.len() as u64);
+                    self.$field.iter().for_each(|c| c.mix_into(channel));
+                )*
             };
         }
-
-        mix_component_vector!(add);
-        mix_component_vector!(add_small);
-        mix_component_vector!(add_ap);
-        mix_component_vector!(assert_eq);
-        mix_component_vector!(assert_eq_imm);
-        mix_component_vector!(assert_eq_double_deref);
-        mix_component_vector!(blake);
-        mix_component_vector!(call);
-        mix_component_vector!(call_rel_imm);
-        mix_component_vector!(generic);
-        mix_component_vector!(jnz);
-        mix_component_vector!(jnz_taken);
-        mix_component_vector!(jump);
-        mix_component_vector!(jump_double_deref);
-        mix_component_vector!(jump_rel);
-        mix_component_vector!(jump_rel_imm);
-        mix_component_vector!(mul);
-        mix_component_vector!(mul_small);
-        mix_component_vector!(qm31);
-        mix_component_vector!(ret);
+        opcode_components!(mix_component_vectors);
     }
 
     pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
-        TreeVec::concat_cols(chain!(
-            self.add.iter().map(|c| c.log_sizes()),
-            self.add_small.iter().map(|c| c.log_sizes()),
-            self.add_ap.iter().map(|c| c.log_sizes()),
-            self.assert_eq.iter().map(|c| c.log_sizes()),
-            self.assert_eq_imm.iter().map(|c| c.log_sizes()),
-            self.assert_eq_double_deref.iter().map(|c| c.log_sizes()),
-            self.blake.iter().map(|c| c.log_sizes()),
-            self.call.iter().map(|c| c.log_sizes()),
-            self.call_rel_imm.iter().map(|c| c.log_sizes()),
-            self.generic.iter().map(|c| c.log_sizes()),
-            self.jnz.iter().map(|c| c.log_sizes()),
-            self.jnz_taken.iter().map(|c| c.log_sizes()),
-            self.jump.iter().map(|c| c.log_sizes()),
-            self.jump_double_deref.iter().map(|c| c.log_sizes()),
-            self.jump_rel.iter().map(|c| c.log_sizes()),
-            self.jump_rel_imm.iter().map(|c| c.log_sizes()),
-            self.mul.iter().map(|c| c.log_sizes()),
-            self.mul_small.iter().map(|c| c.log_sizes()),
-            self.qm31.iter().map(|c| c.log_sizes()),
-            self.ret.iter().map(|c| c.log_sizes()),
-        ))
+        macro_rules! log_sizes {
+            ($($field:ident: $module:ident,)*) => {
+                TreeVec::concat_cols(chain!($(self.$field.iter().map(|c| c.log_sizes()),)*))
+            };
+        }
+        opcode_components!(log_sizes)
     }
 
     pub fn accumulate_relation_uses(&self, relation_uses: &mut RelationUsesDict) {
-        let Self {
-            add,
-            add_small,
-            add_ap,
-            assert_eq,
-            assert_eq_imm,
-            assert_eq_double_deref,
-            blake,
-            call,
-            call_rel_imm,
-            generic,
-            jnz,
-            jnz_taken,
-            jump,
-            jump_double_deref,
-            jump_rel,
-            jump_rel_imm,
-            mul,
-            mul_small,
-            qm31,
-            ret,
-        } = self;
-
-        // TODO(alonf): canonicalize the name of field and module.
         macro_rules! relation_uses {
-            ($field:ident, $module:ident) => {
-                $field.iter().for_each(|c| {
-                    accumulate_relation_uses(
-                        relation_uses,
-                        $module::RELATION_USES_PER_ROW,
-                        c.log_size,
-                    )
-                });
+            ($($field:ident: $module:ident,)*) => {
+                $(
+                    self.$field.iter().for_each(|c| {
+                        accumulate_relation_uses(
+                            relation_uses,
+                            $module::RELATION_USES_PER_ROW,
+                            c.log_size,
+                        )
+                    });
+                )*
             };
         }
-        relation_uses!(add, add_opcode);
-        relation_uses!(add_small, add_opcode_small);
-        relation_uses!(add_ap, add_ap_opcode);
-        relation_uses!(assert_eq, assert_eq_opcode);
-        relation_uses!(assert_eq_imm, assert_eq_opcode_imm);
-        relation_uses!(assert_eq_double_deref, assert_eq_opcode_double_deref);
-        relation_uses!(blake, blake_compress_opcode);
-        relation_uses!(call, call_opcode);
-        relation_uses!(call_rel_imm, call_opcode_rel_imm);
-        relation_uses!(generic, generic_opcode);
-        relation_uses!(jnz, jnz_opcode);
-        relation_uses!(jnz_taken, jnz_opcode_taken);
-        relation_uses!(jump, jump_opcode);
-        relation_uses!(jump_double_deref, jump_opcode_double_deref);
-        relation_uses!(jump_rel, jump_opcode_rel);
-        relation_uses!(jump_rel_imm, jump_opcode_rel_imm);
-        relation_uses!(mul, mul_opcode);
-        relation_uses!(mul_small, mul_opcode_small);
-        relation_uses!(qm31, qm_31_add_mul_opcode);
-        relation_uses!(ret, ret_opcode);
+        opcode_components!(relation_uses);
     }
 }
 
-#[derive(Serialize, Deserialize, CairoSerialize)]
-pub struct OpcodeInteractionClaim {
-    pub add: Vec<add_opcode::InteractionClaim>,
-    pub add_small: Vec<add_opcode_small::InteractionClaim>,
-    pub add_ap: Vec<add_ap_opcode::InteractionClaim>,
-    pub assert_eq: Vec<assert_eq_opcode::InteractionClaim>,
-    pub assert_eq_imm: Vec<assert_eq_opcode_imm::InteractionClaim>,
-    pub assert_eq_double_deref: Vec<assert_eq_opcode_double_deref::InteractionClaim>,
-    pub blake: Vec<blake_compress_opcode::InteractionClaim>,
-    pub call: Vec<call_opcode::InteractionClaim>,
-    pub call_rel_imm: Vec<call_opcode_rel_imm::InteractionClaim>,
-    pub generic: Vec<generic_opcode::InteractionClaim>,
-    pub jnz: Vec<jnz_opcode::InteractionClaim>,
-    pub jnz_taken: Vec<jnz_opcode_taken::InteractionClaim>,
-    pub jump: Vec<jump_opcode::InteractionClaim>,
-    pub jump_double_deref: Vec<jump_opcode_double_deref::InteractionClaim>,
-    pub jump_rel: Vec<jump_opcode_rel::InteractionClaim>,
-    pub jump_rel_imm: Vec<jump_opcode_rel_imm::InteractionClaim>,
-    pub mul: Vec<mul_opcode::InteractionClaim>,
-    pub mul_small: Vec<mul_opcode_small::InteractionClaim>,
-    pub qm31: Vec<qm_31_add_mul_opcode::InteractionClaim>,
-    pub ret: Vec<ret_opcode::InteractionClaim>,
-}
 impl OpcodeInteractionClaim {
+    /// Returns the interaction claim of the components of `claim`, with zero claimed sums.
+    pub(crate) fn zero(claim: &OpcodeClaim) -> Self {
+        macro_rules! zero_claimed_sums {
+            ($($field:ident: $module:ident,)*) => {
+                Self {
+                    $(
+                        $field: claim
+                            .$field
+                            .iter()
+                            .map(|_| $module::InteractionClaim {
+                                claimed_sum: SecureField::zero(),
+                            })
+                            .collect(),
+                    )*
+                }
+            };
+        }
+        opcode_components!(zero_claimed_sums)
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
-        self.add.iter().for_each(|c| c.mix_into(channel));
-        self.add_small.iter().for_each(|c| c.mix_into(channel));
-        self.add_ap.iter().for_each(|c| c.mix_into(channel));
-        self.assert_eq.iter().for_each(|c| c.mix_into(channel));
-        self.assert_eq_imm.iter().for_each(|c| c.mix_into(channel));
-        self.assert_eq_double_deref
-            .iter()
-            .for_each(|c| c.mix_into(channel));
-        self.blake.iter().for_each(|c| c.mix_into(channel));
-        self.call.iter().for_each(|c| c.mix_into(channel));
-        self.call_rel_imm.iter().for_each(|c| c.mix_into(channel));
-        self.generic.iter().for_each(|c| c.mix_into(channel));
-        self.jnz.iter().for_each(|c| c.mix_into(channel));
-        self.jnz_taken.iter().for_each(|c| c.mix_into(channel));
-        self.jump.iter().for_each(|c| c.mix_into(channel));
-        self.jump_double_deref
-            .iter()
-            .for_each(|c| c.mix_into(channel));
-        self.jump_rel.iter().for_each(|c| c.mix_into(channel));
-        self.jump_rel_imm.iter().for_each(|c| c.mix_into(channel));
-        self.mul.iter().for_each(|c| c.mix_into(channel));
-        self.mul_small.iter().for_each(|c| c.mix_into(channel));
-        self.qm31.iter().for_each(|c| c.mix_into(channel));
-        self.ret.iter().for_each(|c| c.mix_into(channel));
+        macro_rules! mix_component_vectors {
+            ($($field:ident: $module:ident,)*) => {
+                $(self.$field.iter().for_each(|c| c.mix_into(channel));)*
+            };
+        }
+        opcode_components!(mix_component_vectors);
     }
 
     pub fn sum(&self) -> SecureField {
-        let mut sum = QM31::zero();
-        for interaction_claim in &self.add {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.add_small {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.add_ap {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.assert_eq {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.assert_eq_imm {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.assert_eq_double_deref {
-            sum += interaction_claim.claimed_sum;
+        macro_rules! sum_claimed_sums {
+            ($($field:ident: $module:ident,)*) => {{
+                let mut sum = SecureField::zero();
+                $(
+                    for interaction_claim in &self.$field {
+                        sum += interaction_claim.claimed_sum;
+                    }
+                )*
+                sum
+            }};
         }
-        for interaction_claim in &self.blake {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.call {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.call_rel_imm {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.generic {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.jnz {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.jnz_taken {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.jump {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.jump_double_deref {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.jump_rel {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.jump_rel_imm {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.mul {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.mul_small {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.qm31 {
-            sum += interaction_claim.claimed_sum;
-        }
-        for interaction_claim in &self.ret {
-            sum += interaction_claim.claimed_sum;
-        }
-        sum
+        opcode_components!(sum_claimed_sums)
     }
 }
 
-pub struct OpcodeComponents {
-    pub add: Vec<add_opcode::Component>,
-    pub add_small: Vec<add_opcode_small::Component>,
-    pub add_ap: Vec<add_ap_opcode::Component>,
-    pub assert_eq: Vec<assert_eq_opcode::Component>,
-    pub assert_eq_imm: Vec<assert_eq_opcode_imm::Component>,
-    pub assert_eq_double_deref: Vec<assert_eq_opcode_double_deref::Component>,
-    pub blake: Vec<blake_compress_opcode::Component>,
-    pub call: Vec<call_opcode::Component>,
-    pub call_rel_imm: Vec<call_opcode_rel_imm::Component>,
-    pub generic: Vec<generic_opcode::Component>,
-    pub jnz: Vec<jnz_opcode::Component>,
-    pub jnz_taken: Vec<jnz_opcode_taken::Component>,
-    pub jump: Vec<jump_opcode::Component>,
-    pub jump_double_deref: Vec<jump_opcode_double_deref::Component>,
-    pub jump_rel: Vec<jump_opcode_rel::Component>,
-    pub jump_rel_imm: Vec<jump_opcode_rel_imm::Component>,
-    pub mul: Vec<mul_opcode::Component>,
-    pub mul_small: Vec<mul_opcode_small::Component>,
-    pub qm31: Vec<qm_31_add_mul_opcode::Component>,
-    pub ret: Vec<ret_opcode::Component>,
-}
 impl OpcodeComponents {
     pub fn new(
         tree_span_provider: &mut TraceLocationAllocator,
@@ -839,154 +668,44 @@ impl OpcodeComponents {
         }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
-        let mut vec: Vec<&dyn ComponentProver<SimdBackend>> = vec![];
-        vec.extend(
-            self.add
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.add_small
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.add_ap
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.assert_eq
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.assert_eq_imm
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.assert_eq_double_deref
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.blake
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.call
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.call_rel_imm
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.generic
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.jnz
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.jnz_taken
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.jump
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.jump_double_deref
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.jump_rel
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.jump_rel_imm
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.mul
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.mul_small
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.qm31
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
-        vec.extend(
-            self.ret
-                .iter()
-                .map(|component| component as &dyn ComponentProver<SimdBackend>),
-        );
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
+        let mut vec: Vec<B::Prover<'_>> = vec![];
+        macro_rules! extend_provers {
+            ($($field:ident: $module:ident,)*) => {
+                $(vec.extend(
+                    self.$field
+                        .iter()
+                        .map(|component| B::component_prover(component)),
+                );)*
+            };
+        }
+        opcode_components!(extend_provers);
         vec
     }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        macro_rules! visit_opcodes {
+            ($($field:ident: $module:ident,)*) => {
+                $(for component in &self.$field {
+                    visitor.visit(stringify!($module), component);
+                })*
+            };
+        }
+        opcode_components!(visit_opcodes);
+    }
 }
 
 impl std::fmt::Display for OpcodeComponents {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "add:")?;
-        writeln!(f, "{}", display_components(&self.add))?;
-        writeln!(f, "add_small:")?;
-        writeln!(f, "{}", display_components(&self.add_small))?;
-        writeln!(f, "add_ap:")?;
-        writeln!(f, "{}", display_components(&self.add_ap))?;
-        writeln!(f, "assert_eq:")?;
-        writeln!(f, "{}", display_components(&self.assert_eq))?;
-        writeln!(f, "assert_eq_imm:")?;
-        writeln!(f, "{}", display_components(&self.assert_eq_imm))?;
-        writeln!(f, "assert_eq_double_deref:")?;
-        writeln!(f, "{}", display_components(&self.assert_eq_double_deref))?;
-        writeln!(f, "blake:")?;
-        writeln!(f, "{}", display_components(&self.blake))?;
-        writeln!(f, "call:")?;
-        writeln!(f, "{}", display_components(&self.call))?;
-        writeln!(f, "call_rel_imm:")?;
-        writeln!(f, "{}", display_components(&self.call_rel_imm))?;
-        writeln!(f, "generic:")?;
-        writeln!(f, "{}", display_components(&self.generic))?;
-        writeln!(f, "jnz:")?;
-        writeln!(f, "{}", display_components(&self.jnz))?;
-        writeln!(f, "jnz_taken:")?;
-        writeln!(f, "{}", display_components(&self.jnz_taken))?;
-        writeln!(f, "jump:")?;
-        writeln!(f, "{}", display_components(&self.jump))?;
-        writeln!(f, "jump_double_deref:")?;
-        writeln!(f, "{}", display_components(&self.jump_double_deref))?;
-        writeln!(f, "jump_rel:")?;
-        writeln!(f, "{}", display_components(&self.jump_rel))?;
-        writeln!(f, "jump_rel_imm:")?;
-        writeln!(f, "{}", display_components(&self.jump_rel_imm))?;
-        writeln!(f, "mul:")?;
-        writeln!(f, "{}", display_components(&self.mul))?;
-        writeln!(f, "mul_small:")?;
-        writeln!(f, "{}", display_components(&self.mul_small))?;
-        writeln!(f, "qm31:")?;
-        writeln!(f, "{}", display_components(&self.qm31))?;
-        writeln!(f, "ret:")?;
-        writeln!(f, "{}", display_components(&self.ret))?;
+        macro_rules! display_opcodes {
+            ($($field:ident: $module:ident,)*) => {
+                $(
+                    writeln!(f, "{}:", stringify!($field))?;
+                    writeln!(f, "{}", display_components(&self.$field))?;
+                )*
+            };
+        }
+        opcode_components!(display_opcodes);
         Ok(())
     }
 }
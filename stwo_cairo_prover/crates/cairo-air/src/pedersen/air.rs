@@ -1,12 +1,14 @@
 use num_traits::Zero;
 use stwo_prover::constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::air::ComponentProver;
-use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::fields::qm31::QM31;
 
-use crate::air::{accumulate_relation_uses, CairoInteractionElements, RelationUsesDict};
+use crate::air::{
+    accumulate_relation_uses, CairoComponentVisitor, CairoInteractionElements, RelationUsesDict,
+};
 use crate::components::prelude::*;
-use crate::components::{indented_component_display, partial_ec_mul, pedersen_points_table};
+use crate::components::{
+    indented_component_display, partial_ec_mul, pedersen_points_table, ComponentProverBackend,
+};
 
 #[derive(Serialize, Deserialize, CairoSerialize)]
 pub struct PedersenContextClaim {
@@ -76,6 +78,20 @@ pub struct PedersenContextInteractionClaim {
     pub claim: Option<InteractionClaim>,
 }
 impl PedersenContextInteractionClaim {
+    /// Returns the interaction claim of the components of `claim`, with zero claimed sums.
+    pub(crate) fn zero(claim: &PedersenContextClaim) -> Self {
+        Self {
+            claim: claim.claim.as_ref().map(|_| InteractionClaim {
+                partial_ec_mul: partial_ec_mul::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                pedersen_points_table: pedersen_points_table::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+            }),
+        }
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
         if let Some(claim) = &self.claim {
             claim.mix_into(channel);
@@ -126,12 +142,18 @@ impl PedersenContextComponents {
         Self { components }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
         self.components
             .as_ref()
             .map(|c| c.provers())
             .unwrap_or_default()
     }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        if let Some(components) = &self.components {
+            components.visit(visitor);
+        }
+    }
 }
 
 impl std::fmt::Display for PedersenContextComponents {
@@ -185,8 +207,16 @@ impl Components {
         }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
-        vec![&self.partial_ec_mul, &self.pedersen_points_table]
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
+        vec![
+            B::component_prover(&self.partial_ec_mul),
+            B::component_prover(&self.pedersen_points_table),
+        ]
+    }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        visitor.visit("partial_ec_mul", &self.partial_ec_mul);
+        visitor.visit("pedersen_points_table", &self.pedersen_points_table);
     }
 }
 
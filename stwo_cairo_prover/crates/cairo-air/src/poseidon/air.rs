@@ -1,14 +1,15 @@
 use num_traits::Zero;
 use stwo_prover::constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::air::ComponentProver;
-use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::fields::qm31::QM31;
 
-use crate::air::{accumulate_relation_uses, CairoInteractionElements, RelationUsesDict};
+use crate::air::{
+    accumulate_relation_uses, CairoComponentVisitor, CairoInteractionElements, RelationUsesDict,
+};
 use crate::components::prelude::*;
 use crate::components::{
     cube_252, indented_component_display, poseidon_3_partial_rounds_chain,
     poseidon_full_round_chain, poseidon_round_keys, range_check_felt_252_width_27,
+    ComponentProverBackend,
 };
 
 #[derive(Serialize, Deserialize, CairoSerialize)]
@@ -106,6 +107,30 @@ pub struct PoseidonContextInteractionClaim {
     pub claim: Option<InteractionClaim>,
 }
 impl PoseidonContextInteractionClaim {
+    /// Returns the interaction claim of the components of `claim`, with zero claimed sums.
+    pub(crate) fn zero(claim: &PoseidonContextClaim) -> Self {
+        Self {
+            claim: claim.claim.as_ref().map(|_| InteractionClaim {
+                poseidon_3_partial_rounds_chain:
+                    poseidon_3_partial_rounds_chain::InteractionClaim {
+                        claimed_sum: QM31::zero(),
+                    },
+                poseidon_full_round_chain: poseidon_full_round_chain::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                cube_252: cube_252::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                poseidon_round_keys: poseidon_round_keys::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+                range_check_felt_252_width_27: range_check_felt_252_width_27::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                },
+            }),
+        }
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
         if let Some(claim) = &self.claim {
             claim.mix_into(channel);
@@ -165,12 +190,18 @@ impl PoseidonContextComponents {
         Self { components }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
         self.components
             .as_ref()
             .map(|c| c.provers())
             .unwrap_or_default()
     }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        if let Some(components) = &self.components {
+            components.visit(visitor);
+        }
+    }
 }
 
 impl std::fmt::Display for PoseidonContextComponents {
@@ -287,14 +318,28 @@ impl Components {
         }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
         vec![
+            B::component_prover(&self.poseidon_3_partial_rounds_chain),
+            B::component_prover(&self.poseidon_full_round_chain),
+            B::component_prover(&self.cube_252),
+            B::component_prover(&self.poseidon_round_keys),
+            B::component_prover(&self.range_check_felt_252_width_27),
+        ]
+    }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        visitor.visit(
+            "poseidon_3_partial_rounds_chain",
             &self.poseidon_3_partial_rounds_chain,
-            &self.poseidon_full_round_chain,
-            &self.cube_252,
-            &self.poseidon_round_keys,
+        );
+        visitor.visit("poseidon_full_round_chain", &self.poseidon_full_round_chain);
+        visitor.visit("cube_252", &self.cube_252);
+        visitor.visit("poseidon_round_keys", &self.poseidon_round_keys);
+        visitor.visit(
+            "range_check_felt_252_width_27",
             &self.range_check_felt_252_width_27,
-        ]
+        );
     }
 }
 
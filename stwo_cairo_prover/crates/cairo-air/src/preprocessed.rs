@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::iter::zip;
 use std::simd::{u32x16, Simd};
 
@@ -6,17 +7,26 @@ use stwo_cairo_common::preprocessed_consts::blake::N_BLAKE_SIGMA_COLS;
 use stwo_cairo_common::preprocessed_consts::poseidon::N_WORDS as POSEIDON_N_WORDS;
 use stwo_cairo_common::prover_types::simd::LOG_N_LANES;
 use stwo_prover::constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_prover::constraint_framework::{
+    FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+};
 use stwo_prover::core::backend::simd::column::BaseColumn;
 use stwo_prover::core::backend::simd::m31::{PackedM31, N_LANES};
 use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::backend::Col;
+use stwo_prover::core::channel::Blake2sChannel;
 use stwo_prover::core::fields::m31::{BaseField, M31, MODULUS_BITS};
 use stwo_prover::core::poly::circle::{CanonicCoset, CircleEvaluation};
 use stwo_prover::core::poly::BitReversedOrder;
 
 use super::pedersen::const_columns::{PedersenPoints, PEDERSEN_TABLE_N_COLUMNS};
 use super::poseidon::const_columns::PoseidonRoundKeys;
+use crate::air::{
+    CairoClaim, CairoComponentVisitor, CairoComponents, CairoInteractionClaim,
+    CairoInteractionElements,
+};
 use crate::blake::const_columns::BlakeSigma;
+use crate::components::ColumnNames;
 
 // Size to initialize the preprocessed trace with for `PreprocessedColumn::BitwiseXor`.
 const XOR_N_BITS: [u32; 5] = [4, 7, 8, 9, 10];
@@ -77,6 +87,19 @@ impl PreProcessedTrace {
         Self { columns }
     }
 
+    /// Generates the canonical columns read by the built-in components of `claim`, in canonical
+    /// order. The verifier rebuilds the same trace from the claim in the proof.
+    pub fn usage_aware(claim: &CairoClaim) -> Self {
+        let used_ids = used_column_ids(claim);
+        let columns = Self::canonical()
+            .columns
+            .into_iter()
+            .filter(|c| used_ids.contains(&c.id().id))
+            .collect();
+
+        Self { columns }
+    }
+
     pub fn log_sizes(&self) -> Vec<u32> {
         self.columns.iter().map(|c| c.log_size()).collect()
     }
@@ -90,6 +113,44 @@ impl PreProcessedTrace {
     }
 }
 
+/// Returns the ids of the preprocessed columns read by the built-in components of `claim`.
+fn used_column_ids(claim: &CairoClaim) -> HashSet<String> {
+    struct PreProcessedReads<'a> {
+        canonical_ids: &'a [PreProcessedColumnId],
+        used_ids: HashSet<String>,
+    }
+    impl CairoComponentVisitor for PreProcessedReads<'_> {
+        fn visit<E: FrameworkEval + ColumnNames + Sync>(
+            &mut self,
+            _name: &'static str,
+            component: &FrameworkComponent<E>,
+        ) {
+            self.used_ids.extend(
+                component
+                    .preproccessed_column_indices()
+                    .iter()
+                    .map(|&index| self.canonical_ids[index].id.clone()),
+            );
+        }
+    }
+
+    // The columns a component reads depend on its claim only, so the components are built with
+    // arbitrary lookup elements and claimed sums.
+    let canonical_ids = PreProcessedTrace::canonical().ids();
+    let components = CairoComponents::new_built_in(
+        &mut TraceLocationAllocator::new_with_preproccessed_columns(&canonical_ids),
+        claim,
+        &CairoInteractionElements::draw(&mut Blake2sChannel::default()),
+        &CairoInteractionClaim::zero(claim),
+    );
+    let mut reads = PreProcessedReads {
+        canonical_ids: &canonical_ids,
+        used_ids: HashSet::new(),
+    };
+    components.visit(&mut reads);
+    reads.used_ids
+}
+
 fn gen_range_check_columns() -> Vec<Box<dyn PreProcessedColumn>> {
     // RangeCheck_4_3.
     let range_check_4_3_col_0 = RangeCheck::new([4, 3], 0);
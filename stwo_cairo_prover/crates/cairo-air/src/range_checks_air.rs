@@ -1,130 +1,297 @@
+use itertools::chain;
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 use stwo_cairo_serialize::CairoSerialize;
 use stwo_prover::constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::air::ComponentProver;
-use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::channel::Channel;
 use stwo_prover::core::fields::qm31::{SecureField, QM31};
 use stwo_prover::core::pcs::TreeVec;
 
+use crate::air::{is_looked_up, CairoComponentVisitor, RelationUsesDict};
 use crate::components::{
     indented_component_display, range_check_11, range_check_12, range_check_18, range_check_19,
     range_check_3_3_3_3_3, range_check_3_6_6_3, range_check_4_3, range_check_4_4,
     range_check_4_4_4_4, range_check_5_4, range_check_6, range_check_7_2_5, range_check_8,
-    range_check_9_9,
+    range_check_9_9, ComponentProverBackend,
 };
 use crate::relations;
 
+/// Each range-check table is omitted when none of the components look it up.
 #[derive(Serialize, Deserialize, CairoSerialize)]
 pub struct RangeChecksClaim {
-    pub rc_6: range_check_6::Claim,
-    pub rc_8: range_check_8::Claim,
-    pub rc_11: range_check_11::Claim,
-    pub rc_12: range_check_12::Claim,
-    pub rc_18: range_check_18::Claim,
-    pub rc_19: range_check_19::Claim,
-    pub rc_4_3: range_check_4_3::Claim,
-    pub rc_4_4: range_check_4_4::Claim,
-    pub rc_5_4: range_check_5_4::Claim,
-    pub rc_9_9: range_check_9_9::Claim,
-    pub rc_7_2_5: range_check_7_2_5::Claim,
-    pub rc_3_6_6_3: range_check_3_6_6_3::Claim,
-    pub rc_4_4_4_4: range_check_4_4_4_4::Claim,
-    pub rc_3_3_3_3_3: range_check_3_3_3_3_3::Claim,
+    pub rc_6: Option<range_check_6::Claim>,
+    pub rc_8: Option<range_check_8::Claim>,
+    pub rc_11: Option<range_check_11::Claim>,
+    pub rc_12: Option<range_check_12::Claim>,
+    pub rc_18: Option<range_check_18::Claim>,
+    pub rc_19: Option<range_check_19::Claim>,
+    pub rc_4_3: Option<range_check_4_3::Claim>,
+    pub rc_4_4: Option<range_check_4_4::Claim>,
+    pub rc_5_4: Option<range_check_5_4::Claim>,
+    pub rc_9_9: Option<range_check_9_9::Claim>,
+    pub rc_7_2_5: Option<range_check_7_2_5::Claim>,
+    pub rc_3_6_6_3: Option<range_check_3_6_6_3::Claim>,
+    pub rc_4_4_4_4: Option<range_check_4_4_4_4::Claim>,
+    pub rc_3_3_3_3_3: Option<range_check_3_3_3_3_3::Claim>,
 }
 impl RangeChecksClaim {
+    /// The claim of the range-check tables that are looked up by the components accumulated in
+    /// `relation_uses`, see [`is_looked_up`].
+    pub fn looked_up(relation_uses: &RelationUsesDict) -> Self {
+        let is_used = |relation_id| is_looked_up(relation_uses, relation_id);
+        Self {
+            rc_6: is_used("RangeCheck_6").then_some(range_check_6::Claim {}),
+            rc_8: is_used("RangeCheck_8").then_some(range_check_8::Claim {}),
+            rc_11: is_used("RangeCheck_11").then_some(range_check_11::Claim {}),
+            rc_12: is_used("RangeCheck_12").then_some(range_check_12::Claim {}),
+            rc_18: is_used("RangeCheck_18").then_some(range_check_18::Claim {}),
+            rc_19: is_used("RangeCheck_19").then_some(range_check_19::Claim {}),
+            rc_4_3: is_used("RangeCheck_4_3").then_some(range_check_4_3::Claim {}),
+            rc_4_4: is_used("RangeCheck_4_4").then_some(range_check_4_4::Claim {}),
+            rc_5_4: is_used("RangeCheck_5_4").then_some(range_check_5_4::Claim {}),
+            rc_9_9: is_used("RangeCheck_9_9").then_some(range_check_9_9::Claim {}),
+            rc_7_2_5: is_used("RangeCheck_7_2_5").then_some(range_check_7_2_5::Claim {}),
+            rc_3_6_6_3: is_used("RangeCheck_3_6_6_3").then_some(range_check_3_6_6_3::Claim {}),
+            rc_4_4_4_4: is_used("RangeCheck_4_4_4_4").then_some(range_check_4_4_4_4::Claim {}),
+            rc_3_3_3_3_3: is_used("RangeCheck_3_3_3_3_3")
+                .then_some(range_check_3_3_3_3_3::Claim {}),
+        }
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
-        self.rc_6.mix_into(channel);
-        self.rc_8.mix_into(channel);
-        self.rc_11.mix_into(channel);
-        self.rc_12.mix_into(channel);
-        self.rc_18.mix_into(channel);
-        self.rc_19.mix_into(channel);
-        self.rc_4_3.mix_into(channel);
-        self.rc_4_4.mix_into(channel);
-        self.rc_5_4.mix_into(channel);
-        self.rc_9_9.mix_into(channel);
-        self.rc_7_2_5.mix_into(channel);
-        self.rc_3_6_6_3.mix_into(channel);
-        self.rc_4_4_4_4.mix_into(channel);
-        self.rc_3_3_3_3_3.mix_into(channel);
+        // The range-check claims are empty, so their presence is mixed explicitly.
+        channel.mix_u64(self.rc_6.is_some() as u64);
+        channel.mix_u64(self.rc_8.is_some() as u64);
+        channel.mix_u64(self.rc_11.is_some() as u64);
+        channel.mix_u64(self.rc_12.is_some() as u64);
+        channel.mix_u64(self.rc_18.is_some() as u64);
+        channel.mix_u64(self.rc_19.is_some() as u64);
+        channel.mix_u64(self.rc_4_3.is_some() as u64);
+        channel.mix_u64(self.rc_4_4.is_some() as u64);
+        channel.mix_u64(self.rc_5_4.is_some() as u64);
+        channel.mix_u64(self.rc_9_9.is_some() as u64);
+        channel.mix_u64(self.rc_7_2_5.is_some() as u64);
+        channel.mix_u64(self.rc_3_6_6_3.is_some() as u64);
+        channel.mix_u64(self.rc_4_4_4_4.is_some() as u64);
+        channel.mix_u64(self.rc_3_3_3_3_3.is_some() as u64);
     }
 
     pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
-        TreeVec::concat_cols(
-            vec![
-                self.rc_6.log_sizes(),
-                self.rc_8.log_sizes(),
-                self.rc_11.log_sizes(),
-                self.rc_12.log_sizes(),
-                self.rc_18.log_sizes(),
-                self.rc_19.log_sizes(),
-                self.rc_4_3.log_sizes(),
-                self.rc_4_4.log_sizes(),
-                self.rc_5_4.log_sizes(),
-                self.rc_9_9.log_sizes(),
-                self.rc_7_2_5.log_sizes(),
-                self.rc_3_6_6_3.log_sizes(),
-                self.rc_4_4_4_4.log_sizes(),
-                self.rc_3_3_3_3_3.log_sizes(),
-            ]
-            .into_iter(),
-        )
+        TreeVec::concat_cols(chain!(
+            self.rc_6.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_8.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_11.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_12.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_18.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_19.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_4_3.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_4_4.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_5_4.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_9_9.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_7_2_5.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_3_6_6_3.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_4_4_4_4.as_ref().map(|rc| rc.log_sizes()),
+            self.rc_3_3_3_3_3.as_ref().map(|rc| rc.log_sizes()),
+        ))
     }
 }
 
 #[derive(Serialize, Deserialize, CairoSerialize)]
 pub struct RangeChecksInteractionClaim {
-    pub rc_6: range_check_6::InteractionClaim,
-    pub rc_8: range_check_8::InteractionClaim,
-    pub rc_11: range_check_11::InteractionClaim,
-    pub rc_12: range_check_12::InteractionClaim,
-    pub rc_18: range_check_18::InteractionClaim,
-    pub rc_19: range_check_19::InteractionClaim,
-    pub rc_4_3: range_check_4_3::InteractionClaim,
-    pub rc_4_4: range_check_4_4::InteractionClaim,
-    pub rc_5_4: range_check_5_4::InteractionClaim,
-    pub rc_9_9: range_check_9_9::InteractionClaim,
-    pub rc_7_2_5: range_check_7_2_5::InteractionClaim,
-    pub rc_3_6_6_3: range_check_3_6_6_3::InteractionClaim,
-    pub rc_4_4_4_4: range_check_4_4_4_4::InteractionClaim,
-    pub rc_3_3_3_3_3: range_check_3_3_3_3_3::InteractionClaim,
+    pub rc_6: Option<range_check_6::InteractionClaim>,
+    pub rc_8: Option<range_check_8::InteractionClaim>,
+    pub rc_11: Option<range_check_11::InteractionClaim>,
+    pub rc_12: Option<range_check_12::InteractionClaim>,
+    pub rc_18: Option<range_check_18::InteractionClaim>,
+    pub rc_19: Option<range_check_19::InteractionClaim>,
+    pub rc_4_3: Option<range_check_4_3::InteractionClaim>,
+    pub rc_4_4: Option<range_check_4_4::InteractionClaim>,
+    pub rc_5_4: Option<range_check_5_4::InteractionClaim>,
+    pub rc_9_9: Option<range_check_9_9::InteractionClaim>,
+    pub rc_7_2_5: Option<range_check_7_2_5::InteractionClaim>,
+    pub rc_3_6_6_3: Option<range_check_3_6_6_3::InteractionClaim>,
+    pub rc_4_4_4_4: Option<range_check_4_4_4_4::InteractionClaim>,
+    pub rc_3_3_3_3_3: Option<range_check_3_3_3_3_3::InteractionClaim>,
 }
 impl RangeChecksInteractionClaim {
+    /// Returns the interaction claim of the components of `claim`, with zero claimed sums.
+    pub(crate) fn zero(claim: &RangeChecksClaim) -> Self {
+        Self {
+            rc_6: claim
+                .rc_6
+                .as_ref()
+                .map(|_| range_check_6::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_8: claim
+                .rc_8
+                .as_ref()
+                .map(|_| range_check_8::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_11: claim
+                .rc_11
+                .as_ref()
+                .map(|_| range_check_11::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_12: claim
+                .rc_12
+                .as_ref()
+                .map(|_| range_check_12::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_18: claim
+                .rc_18
+                .as_ref()
+                .map(|_| range_check_18::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_19: claim
+                .rc_19
+                .as_ref()
+                .map(|_| range_check_19::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_4_3: claim
+                .rc_4_3
+                .as_ref()
+                .map(|_| range_check_4_3::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_4_4: claim
+                .rc_4_4
+                .as_ref()
+                .map(|_| range_check_4_4::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_5_4: claim
+                .rc_5_4
+                .as_ref()
+                .map(|_| range_check_5_4::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_9_9: claim
+                .rc_9_9
+                .as_ref()
+                .map(|_| range_check_9_9::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_7_2_5: claim
+                .rc_7_2_5
+                .as_ref()
+                .map(|_| range_check_7_2_5::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_3_6_6_3: claim
+                .rc_3_6_6_3
+                .as_ref()
+                .map(|_| range_check_3_6_6_3::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_4_4_4_4: claim
+                .rc_4_4_4_4
+                .as_ref()
+                .map(|_| range_check_4_4_4_4::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }),
+            rc_3_3_3_3_3: claim.rc_3_3_3_3_3.as_ref().map(|_| {
+                range_check_3_3_3_3_3::InteractionClaim {
+                    claimed_sum: QM31::zero(),
+                }
+            }),
+        }
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
-        self.rc_6.mix_into(channel);
-        self.rc_8.mix_into(channel);
-        self.rc_11.mix_into(channel);
-        self.rc_12.mix_into(channel);
-        self.rc_18.mix_into(channel);
-        self.rc_19.mix_into(channel);
-        self.rc_4_3.mix_into(channel);
-        self.rc_4_4.mix_into(channel);
-        self.rc_5_4.mix_into(channel);
-        self.rc_9_9.mix_into(channel);
-        self.rc_7_2_5.mix_into(channel);
-        self.rc_3_6_6_3.mix_into(channel);
-        self.rc_4_4_4_4.mix_into(channel);
-        self.rc_3_3_3_3_3.mix_into(channel);
+        if let Some(rc_6) = &self.rc_6 {
+            rc_6.mix_into(channel);
+        }
+        if let Some(rc_8) = &self.rc_8 {
+            rc_8.mix_into(channel);
+        }
+        if let Some(rc_11) = &self.rc_11 {
+            rc_11.mix_into(channel);
+        }
+        if let Some(rc_12) = &self.rc_12 {
+            rc_12.mix_into(channel);
+        }
+        if let Some(rc_18) = &self.rc_18 {
+            rc_18.mix_into(channel);
+        }
+        if let Some(rc_19) = &self.rc_19 {
+            rc_19.mix_into(channel);
+        }
+        if let Some(rc_4_3) = &self.rc_4_3 {
+            rc_4_3.mix_into(channel);
+        }
+        if let Some(rc_4_4) = &self.rc_4_4 {
+            rc_4_4.mix_into(channel);
+        }
+        if let Some(rc_5_4) = &self.rc_5_4 {
+            rc_5_4.mix_into(channel);
+        }
+        if let Some(rc_9_9) = &self.rc_9_9 {
+            rc_9_9.mix_into(channel);
+        }
+        if let Some(rc_7_2_5) = &self.rc_7_2_5 {
+            rc_7_2_5.mix_into(channel);
+        }
+        if let Some(rc_3_6_6_3) = &self.rc_3_6_6_3 {
+            rc_3_6_6_3.mix_into(channel);
+        }
+        if let Some(rc_4_4_4_4) = &self.rc_4_4_4_4 {
+            rc_4_4_4_4.mix_into(channel);
+        }
+        if let Some(rc_3_3_3_3_3) = &self.rc_3_3_3_3_3 {
+            rc_3_3_3_3_3.mix_into(channel);
+        }
     }
 
     pub fn sum(&self) -> SecureField {
         let mut sum = QM31::zero();
-        sum += self.rc_6.claimed_sum;
-        sum += self.rc_8.claimed_sum;
-        sum += self.rc_11.claimed_sum;
-        sum += self.rc_12.claimed_sum;
-        sum += self.rc_18.claimed_sum;
-        sum += self.rc_19.claimed_sum;
-        sum += self.rc_4_3.claimed_sum;
-        sum += self.rc_4_4.claimed_sum;
-        sum += self.rc_5_4.claimed_sum;
-        sum += self.rc_9_9.claimed_sum;
-        sum += self.rc_7_2_5.claimed_sum;
-        sum += self.rc_3_6_6_3.claimed_sum;
-        sum += self.rc_4_4_4_4.claimed_sum;
-        sum += self.rc_3_3_3_3_3.claimed_sum;
+        if let Some(rc_6) = &self.rc_6 {
+            sum += rc_6.claimed_sum;
+        }
+        if let Some(rc_8) = &self.rc_8 {
+            sum += rc_8.claimed_sum;
+        }
+        if let Some(rc_11) = &self.rc_11 {
+            sum += rc_11.claimed_sum;
+        }
+        if let Some(rc_12) = &self.rc_12 {
+            sum += rc_12.claimed_sum;
+        }
+        if let Some(rc_18) = &self.rc_18 {
+            sum += rc_18.claimed_sum;
+        }
+        if let Some(rc_19) = &self.rc_19 {
+            sum += rc_19.claimed_sum;
+        }
+        if let Some(rc_4_3) = &self.rc_4_3 {
+            sum += rc_4_3.claimed_sum;
+        }
+        if let Some(rc_4_4) = &self.rc_4_4 {
+            sum += rc_4_4.claimed_sum;
+        }
+        if let Some(rc_5_4) = &self.rc_5_4 {
+            sum += rc_5_4.claimed_sum;
+        }
+        if let Some(rc_9_9) = &self.rc_9_9 {
+            sum += rc_9_9.claimed_sum;
+        }
+        if let Some(rc_7_2_5) = &self.rc_7_2_5 {
+            sum += rc_7_2_5.claimed_sum;
+        }
+        if let Some(rc_3_6_6_3) = &self.rc_3_6_6_3 {
+            sum += rc_3_6_6_3.claimed_sum;
+        }
+        if let Some(rc_4_4_4_4) = &self.rc_4_4_4_4 {
+            sum += rc_4_4_4_4.claimed_sum;
+        }
+        if let Some(rc_3_3_3_3_3) = &self.rc_3_3_3_3_3 {
+            sum += rc_3_3_3_3_3.claimed_sum;
+        }
         sum
     }
 }
@@ -168,97 +335,126 @@ impl RangeChecksInteractionElements {
 }
 
 pub struct RangeChecksComponents {
-    pub rc_6: range_check_6::Component,
-    pub rc_8: range_check_8::Component,
-    pub rc_11: range_check_11::Component,
-    pub rc_12: range_check_12::Component,
-    pub rc_18: range_check_18::Component,
-    pub rc_19: range_check_19::Component,
-    pub rc_4_3: range_check_4_3::Component,
-    pub rc_4_4: range_check_4_4::Component,
-    pub rc_5_4: range_check_5_4::Component,
-    pub rc_9_9: range_check_9_9::Component,
-    pub rc_7_2_5: range_check_7_2_5::Component,
-    pub rc_3_6_6_3: range_check_3_6_6_3::Component,
-    pub rc_4_4_4_4: range_check_4_4_4_4::Component,
-    pub rc_3_3_3_3_3: range_check_3_3_3_3_3::Component,
+    pub rc_6: Option<range_check_6::Component>,
+    pub rc_8: Option<range_check_8::Component>,
+    pub rc_11: Option<range_check_11::Component>,
+    pub rc_12: Option<range_check_12::Component>,
+    pub rc_18: Option<range_check_18::Component>,
+    pub rc_19: Option<range_check_19::Component>,
+    pub rc_4_3: Option<range_check_4_3::Component>,
+    pub rc_4_4: Option<range_check_4_4::Component>,
+    pub rc_5_4: Option<range_check_5_4::Component>,
+    pub rc_9_9: Option<range_check_9_9::Component>,
+    pub rc_7_2_5: Option<range_check_7_2_5::Component>,
+    pub rc_3_6_6_3: Option<range_check_3_6_6_3::Component>,
+    pub rc_4_4_4_4: Option<range_check_4_4_4_4::Component>,
+    pub rc_3_3_3_3_3: Option<range_check_3_3_3_3_3::Component>,
 }
 impl RangeChecksComponents {
     pub fn new(
         tree_span_provider: &mut TraceLocationAllocator,
+        claim: &RangeChecksClaim,
         interaction_elements: &RangeChecksInteractionElements,
         interaction_claim: &RangeChecksInteractionClaim,
     ) -> Self {
-        let rc_6_component = range_check_6::Component::new(
-            tree_span_provider,
-            range_check_6::Eval::new(interaction_elements.rc_6.clone()),
-            interaction_claim.rc_6.claimed_sum,
-        );
-        let rc_8_component = range_check_8::Component::new(
-            tree_span_provider,
-            range_check_8::Eval::new(interaction_elements.rc_8.clone()),
-            interaction_claim.rc_8.claimed_sum,
-        );
-        let rc_11_component = range_check_11::Component::new(
-            tree_span_provider,
-            range_check_11::Eval::new(interaction_elements.rc_11.clone()),
-            interaction_claim.rc_11.claimed_sum,
-        );
-        let rc_12_component = range_check_12::Component::new(
-            tree_span_provider,
-            range_check_12::Eval::new(interaction_elements.rc_12.clone()),
-            interaction_claim.rc_12.claimed_sum,
-        );
-        let rc_18_component = range_check_18::Component::new(
-            tree_span_provider,
-            range_check_18::Eval::new(interaction_elements.rc_18.clone()),
-            interaction_claim.rc_18.claimed_sum,
-        );
-        let rc_19_component = range_check_19::Component::new(
-            tree_span_provider,
-            range_check_19::Eval::new(interaction_elements.rc_19.clone()),
-            interaction_claim.rc_19.claimed_sum,
-        );
-        let rc_4_3_component = range_check_4_3::Component::new(
-            tree_span_provider,
-            range_check_4_3::Eval::new(interaction_elements.rc_4_3.clone()),
-            interaction_claim.rc_4_3.claimed_sum,
-        );
-        let rc_4_4_component = range_check_4_4::Component::new(
-            tree_span_provider,
-            range_check_4_4::Eval::new(interaction_elements.rc_4_4.clone()),
-            interaction_claim.rc_4_4.claimed_sum,
-        );
-        let rc_5_4_component = range_check_5_4::Component::new(
-            tree_span_provider,
-            range_check_5_4::Eval::new(interaction_elements.rc_5_4.clone()),
-            interaction_claim.rc_5_4.claimed_sum,
-        );
-        let rc_9_9_component = range_check_9_9::Component::new(
-            tree_span_provider,
-            range_check_9_9::Eval::new(interaction_elements.rc_9_9.clone()),
-            interaction_claim.rc_9_9.claimed_sum,
-        );
-        let rc_7_2_5_component = range_check_7_2_5::Component::new(
-            tree_span_provider,
-            range_check_7_2_5::Eval::new(interaction_elements.rc_7_2_5.clone()),
-            interaction_claim.rc_7_2_5.claimed_sum,
-        );
-        let rc_3_6_6_3_component = range_check_3_6_6_3::Component::new(
-            tree_span_provider,
-            range_check_3_6_6_3::Eval::new(interaction_elements.rc_3_6_6_3.clone()),
-            interaction_claim.rc_3_6_6_3.claimed_sum,
-        );
-        let rc_4_4_4_4_component = range_check_4_4_4_4::Component::new(
-            tree_span_provider,
-            range_check_4_4_4_4::Eval::new(interaction_elements.rc_4_4_4_4.clone()),
-            interaction_claim.rc_4_4_4_4.claimed_sum,
-        );
-        let rc_3_3_3_3_3_component = range_check_3_3_3_3_3::Component::new(
-            tree_span_provider,
-            range_check_3_3_3_3_3::Eval::new(interaction_elements.rc_3_3_3_3_3.clone()),
-            interaction_claim.rc_3_3_3_3_3.claimed_sum,
-        );
+        let rc_6_component = claim.rc_6.as_ref().map(|_| {
+            range_check_6::Component::new(
+                tree_span_provider,
+                range_check_6::Eval::new(interaction_elements.rc_6.clone()),
+                interaction_claim.rc_6.unwrap().claimed_sum,
+            )
+        });
+        let rc_8_component = claim.rc_8.as_ref().map(|_| {
+            range_check_8::Component::new(
+                tree_span_provider,
+                range_check_8::Eval::new(interaction_elements.rc_8.clone()),
+                interaction_claim.rc_8.unwrap().claimed_sum,
+            )
+        });
+        let rc_11_component = claim.rc_11.as_ref().map(|_| {
+            range_check_11::Component::new(
+                tree_span_provider,
+                range_check_11::Eval::new(interaction_elements.rc_11.clone()),
+                interaction_claim.rc_11.unwrap().claimed_sum,
+            )
+        });
+        let rc_12_component = claim.rc_12.as_ref().map(|_| {
+            range_check_12::Component::new(
+                tree_span_provider,
+                range_check_12::Eval::new(interaction_elements.rc_12.clone()),
+                interaction_claim.rc_12.unwrap().claimed_sum,
+            )
+        });
+        let rc_18_component = claim.rc_18.as_ref().map(|_| {
+            range_check_18::Component::new(
+                tree_span_provider,
+                range_check_18::Eval::new(interaction_elements.rc_18.clone()),
+                interaction_claim.rc_18.unwrap().claimed_sum,
+            )
+        });
+        let rc_19_component = claim.rc_19.as_ref().map(|_| {
+            range_check_19::Component::new(
+                tree_span_provider,
+                range_check_19::Eval::new(interaction_elements.rc_19.clone()),
+                interaction_claim.rc_19.unwrap().claimed_sum,
+            )
+        });
+        let rc_4_3_component = claim.rc_4_3.as_ref().map(|_| {
+            range_check_4_3::Component::new(
+                tree_span_provider,
+                range_check_4_3::Eval::new(interaction_elements.rc_4_3.clone()),
+                interaction_claim.rc_4_3.unwrap().claimed_sum,
+            )
+        });
+        let rc_4_4_component = claim.rc_4_4.as_ref().map(|_| {
+            range_check_4_4::Component::new(
+                tree_span_provider,
+                range_check_4_4::Eval::new(interaction_elements.rc_4_4.clone()),
+                interaction_claim.rc_4_4.unwrap().claimed_sum,
+            )
+        });
+        let rc_5_4_component = claim.rc_5_4.as_ref().map(|_| {
+            range_check_5_4::Component::new(
+                tree_span_provider,
+                range_check_5_4::Eval::new(interaction_elements.rc_5_4.clone()),
+                interaction_claim.rc_5_4.unwrap().claimed_sum,
+            )
+        });
+        let rc_9_9_component = claim.rc_9_9.as_ref().map(|_| {
+            range_check_9_9::Component::new(
+                tree_span_provider,
+                range_check_9_9::Eval::new(interaction_elements.rc_9_9.clone()),
+                interaction_claim.rc_9_9.unwrap().claimed_sum,
+            )
+        });
+        let rc_7_2_5_component = claim.rc_7_2_5.as_ref().map(|_| {
+            range_check_7_2_5::Component::new(
+                tree_span_provider,
+                range_check_7_2_5::Eval::new(interaction_elements.rc_7_2_5.clone()),
+                interaction_claim.rc_7_2_5.unwrap().claimed_sum,
+            )
+        });
+        let rc_3_6_6_3_component = claim.rc_3_6_6_3.as_ref().map(|_| {
+            range_check_3_6_6_3::Component::new(
+                tree_span_provider,
+                range_check_3_6_6_3::Eval::new(interaction_elements.rc_3_6_6_3.clone()),
+                interaction_claim.rc_3_6_6_3.unwrap().claimed_sum,
+            )
+        });
+        let rc_4_4_4_4_component = claim.rc_4_4_4_4.as_ref().map(|_| {
+            range_check_4_4_4_4::Component::new(
+                tree_span_provider,
+                range_check_4_4_4_4::Eval::new(interaction_elements.rc_4_4_4_4.clone()),
+                interaction_claim.rc_4_4_4_4.unwrap().claimed_sum,
+            )
+        });
+        let rc_3_3_3_3_3_component = claim.rc_3_3_3_3_3.as_ref().map(|_| {
+            range_check_3_3_3_3_3::Component::new(
+                tree_span_provider,
+                range_check_3_3_3_3_3::Eval::new(interaction_elements.rc_3_3_3_3_3.clone()),
+                interaction_claim.rc_3_3_3_3_3.unwrap().claimed_sum,
+            )
+        });
         Self {
             rc_6: rc_6_component,
             rc_8: rc_8_component,
@@ -277,90 +473,159 @@ impl RangeChecksComponents {
         }
     }
 
-    pub fn provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
-        vec![
-            &self.rc_6 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_8 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_11 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_12 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_18 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_19 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_4_3 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_4_4 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_5_4 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_9_9 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_7_2_5 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_3_6_6_3 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_4_4_4_4 as &dyn ComponentProver<SimdBackend>,
-            &self.rc_3_3_3_3_3 as &dyn ComponentProver<SimdBackend>,
-        ]
+    pub fn provers<B: ComponentProverBackend>(&self) -> Vec<B::Prover<'_>> {
+        let mut vec: Vec<B::Prover<'_>> = vec![];
+        if let Some(rc_6) = &self.rc_6 {
+            vec.push(B::component_prover(rc_6));
+        }
+        if let Some(rc_8) = &self.rc_8 {
+            vec.push(B::component_prover(rc_8));
+        }
+        if let Some(rc_11) = &self.rc_11 {
+            vec.push(B::component_prover(rc_11));
+        }
+        if let Some(rc_12) = &self.rc_12 {
+            vec.push(B::component_prover(rc_12));
+        }
+        if let Some(rc_18) = &self.rc_18 {
+            vec.push(B::component_prover(rc_18));
+        }
+        if let Some(rc_19) = &self.rc_19 {
+            vec.push(B::component_prover(rc_19));
+        }
+        if let Some(rc_4_3) = &self.rc_4_3 {
+            vec.push(B::component_prover(rc_4_3));
+        }
+        if let Some(rc_4_4) = &self.rc_4_4 {
+            vec.push(B::component_prover(rc_4_4));
+        }
+        if let Some(rc_5_4) = &self.rc_5_4 {
+            vec.push(B::component_prover(rc_5_4));
+        }
+        if let Some(rc_9_9) = &self.rc_9_9 {
+            vec.push(B::component_prover(rc_9_9));
+        }
+        if let Some(rc_7_2_5) = &self.rc_7_2_5 {
+            vec.push(B::component_prover(rc_7_2_5));
+        }
+        if let Some(rc_3_6_6_3) = &self.rc_3_6_6_3 {
+            vec.push(B::component_prover(rc_3_6_6_3));
+        }
+        if let Some(rc_4_4_4_4) = &self.rc_4_4_4_4 {
+            vec.push(B::component_prover(rc_4_4_4_4));
+        }
+        if let Some(rc_3_3_3_3_3) = &self.rc_3_3_3_3_3 {
+            vec.push(B::component_prover(rc_3_3_3_3_3));
+        }
+        vec
+    }
+
+    pub fn visit(&self, visitor: &mut impl CairoComponentVisitor) {
+        if let Some(rc_6) = &self.rc_6 {
+            visitor.visit("range_check_6", rc_6);
+        }
+        if let Some(rc_8) = &self.rc_8 {
+            visitor.visit("range_check_8", rc_8);
+        }
+        if let Some(rc_11) = &self.rc_11 {
+            visitor.visit("range_check_11", rc_11);
+        }
+        if let Some(rc_12) = &self.rc_12 {
+            visitor.visit("range_check_12", rc_12);
+        }
+        if let Some(rc_18) = &self.rc_18 {
+            visitor.visit("range_check_18", rc_18);
+        }
+        if let Some(rc_19) = &self.rc_19 {
+            visitor.visit("range_check_19", rc_19);
+        }
+        if let Some(rc_4_3) = &self.rc_4_3 {
+            visitor.visit("range_check_4_3", rc_4_3);
+        }
+        if let Some(rc_4_4) = &self.rc_4_4 {
+            visitor.visit("range_check_4_4", rc_4_4);
+        }
+        if let Some(rc_5_4) = &self.rc_5_4 {
+            visitor.visit("range_check_5_4", rc_5_4);
+        }
+        if let Some(rc_9_9) = &self.rc_9_9 {
+            visitor.visit("range_check_9_9", rc_9_9);
+        }
+        if let Some(rc_7_2_5) = &self.rc_7_2_5 {
+            visitor.visit("range_check_7_2_5", rc_7_2_5);
+        }
+        if let Some(rc_3_6_6_3) = &self.rc_3_6_6_3 {
+            visitor.visit("range_check_3_6_6_3", rc_3_6_6_3);
+        }
+        if let Some(rc_4_4_4_4) = &self.rc_4_4_4_4 {
+            visitor.visit("range_check_4_4_4_4", rc_4_4_4_4);
+        }
+        if let Some(rc_3_3_3_3_3) = &self.rc_3_3_3_3_3 {
+            visitor.visit("range_check_3_3_3_3_3", rc_3_3_3_3_3);
+        }
     }
 }
 
 impl std::fmt::Display for RangeChecksComponents {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "RangeCheck6: {}", indented_component_display(&self.rc_6))?;
-        writeln!(f, "RangeCheck8: {}", indented_component_display(&self.rc_8))?;
-        writeln!(
-            f,
-            "RangeCheck11: {}",
-            indented_component_display(&self.rc_11)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck12: {}",
-            indented_component_display(&self.rc_12)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck18: {}",
-            indented_component_display(&self.rc_18)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck19: {}",
-            indented_component_display(&self.rc_19)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck4_3: {}",
-            indented_component_display(&self.rc_4_3)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck4_4: {}",
-            indented_component_display(&self.rc_4_4)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck5_4: {}",
-            indented_component_display(&self.rc_5_4)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck9_9: {}",
-            indented_component_display(&self.rc_9_9)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck7_2_5: {}",
-            indented_component_display(&self.rc_7_2_5)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck3_6_6_3: {}",
-            indented_component_display(&self.rc_3_6_6_3)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck4_4_4_4: {}",
-            indented_component_display(&self.rc_4_4_4_4)
-        )?;
-        writeln!(
-            f,
-            "RangeCheck3_3_3_3_3: {}",
-            indented_component_display(&self.rc_3_3_3_3_3)
-        )?;
+        if let Some(rc_6) = &self.rc_6 {
+            writeln!(f, "RangeCheck6: {}", indented_component_display(rc_6))?;
+        }
+        if let Some(rc_8) = &self.rc_8 {
+            writeln!(f, "RangeCheck8: {}", indented_component_display(rc_8))?;
+        }
+        if let Some(rc_11) = &self.rc_11 {
+            writeln!(f, "RangeCheck11: {}", indented_component_display(rc_11))?;
+        }
+        if let Some(rc_12) = &self.rc_12 {
+            writeln!(f, "RangeCheck12: {}", indented_component_display(rc_12))?;
+        }
+        if let Some(rc_18) = &self.rc_18 {
+            writeln!(f, "RangeCheck18: {}", indented_component_display(rc_18))?;
+        }
+        if let Some(rc_19) = &self.rc_19 {
+            writeln!(f, "RangeCheck19: {}", indented_component_display(rc_19))?;
+        }
+        if let Some(rc_4_3) = &self.rc_4_3 {
+            writeln!(f, "RangeCheck4_3: {}", indented_component_display(rc_4_3))?;
+        }
+        if let Some(rc_4_4) = &self.rc_4_4 {
+            writeln!(f, "RangeCheck4_4: {}", indented_component_display(rc_4_4))?;
+        }
+        if let Some(rc_5_4) = &self.rc_5_4 {
+            writeln!(f, "RangeCheck5_4: {}", indented_component_display(rc_5_4))?;
+        }
+        if let Some(rc_9_9) = &self.rc_9_9 {
+            writeln!(f, "RangeCheck9_9: {}", indented_component_display(rc_9_9))?;
+        }
+        if let Some(rc_7_2_5) = &self.rc_7_2_5 {
+            writeln!(
+                f,
+                "RangeCheck7_2_5: {}",
+                indented_component_display(rc_7_2_5)
+            )?;
+        }
+        if let Some(rc_3_6_6_3) = &self.rc_3_6_6_3 {
+            writeln!(
+                f,
+                "RangeCheck3_6_6_3: {}",
+                indented_component_display(rc_3_6_6_3)
+            )?;
+        }
+        if let Some(rc_4_4_4_4) = &self.rc_4_4_4_4 {
+            writeln!(
+                f,
+                "RangeCheck4_4_4_4: {}",
+                indented_component_display(rc_4_4_4_4)
+            )?;
+        }
+        if let Some(rc_3_3_3_3_3) = &self.rc_3_3_3_3_3 {
+            writeln!(
+                f,
+                "RangeCheck3_3_3_3_3: {}",
+                indented_component_display(rc_3_3_3_3_3)
+            )?;
+        }
         Ok(())
     }
 }
@@ -1,38 +1,50 @@
 #![allow(non_camel_case_types)]
 use stwo_prover::relation;
 
-relation!(BlakeG, 20);
-relation!(BlakeRound, 35);
-relation!(BlakeRoundSigma, 17);
-relation!(Cube252, 20);
-relation!(MemoryAddressToId, 2);
-relation!(MemoryIdToBig, 29);
-relation!(Opcodes, 3);
-relation!(PartialEcMul, 73);
-relation!(PedersenPointsTable, 57);
-relation!(Poseidon3PartialRoundsChain, 42);
-relation!(PoseidonFullRoundChain, 32);
-relation!(PoseidonRoundKeys, 31);
-relation!(RangeCheck_11, 1);
-relation!(RangeCheck_12, 1);
-relation!(RangeCheck_18, 1);
-relation!(RangeCheck_19, 1);
-relation!(RangeCheck_3_3_3_3_3, 5);
-relation!(RangeCheck_3_6_6_3, 4);
-relation!(RangeCheck_3_6, 2);
-relation!(RangeCheck_4_3, 2);
-relation!(RangeCheck_4_4_4_4, 4);
-relation!(RangeCheck_4_4, 2);
-relation!(RangeCheck_5_4, 2);
-relation!(RangeCheck_6, 1);
-relation!(RangeCheck_7_2_5, 3);
-relation!(RangeCheck_8, 1);
-relation!(RangeCheck_9_9, 2);
-relation!(RangeCheckFelt252Width27, 10);
-relation!(TripleXor32, 8);
-relation!(VerifyBitwiseXor_12, 3);
-relation!(VerifyBitwiseXor_4, 3);
-relation!(VerifyBitwiseXor_7, 3);
-relation!(VerifyBitwiseXor_8, 3);
-relation!(VerifyBitwiseXor_9, 3);
-relation!(VerifyInstruction, 7);
+/// Defines the Cairo AIR relations, and [`RELATION_SIZES`] listing them.
+macro_rules! cairo_relations {
+    ($($name:ident: $size:expr),+ $(,)?) => {
+        $(relation!($name, $size);)+
+
+        /// The name and size (maximal number of values in a tuple) of every relation.
+        pub const RELATION_SIZES: &[(&str, usize)] = &[$((stringify!($name), $size)),+];
+    };
+}
+
+cairo_relations! {
+    BlakeG: 20,
+    BlakeRound: 35,
+    BlakeRoundSigma: 17,
+    Cube252: 20,
+    MemoryAddressToId: 2,
+    MemoryIdToBig: 29,
+    Opcodes: 3,
+    PartialEcMul: 73,
+    PedersenPointsTable: 57,
+    Poseidon3PartialRoundsChain: 42,
+    PoseidonFullRoundChain: 32,
+    PoseidonRoundKeys: 31,
+    RangeCheck_11: 1,
+    RangeCheck_12: 1,
+    RangeCheck_18: 1,
+    RangeCheck_19: 1,
+    RangeCheck_3_3_3_3_3: 5,
+    RangeCheck_3_6_6_3: 4,
+    RangeCheck_3_6: 2,
+    RangeCheck_4_3: 2,
+    RangeCheck_4_4_4_4: 4,
+    RangeCheck_4_4: 2,
+    RangeCheck_5_4: 2,
+    RangeCheck_6: 1,
+    RangeCheck_7_2_5: 3,
+    RangeCheck_8: 1,
+    RangeCheck_9_9: 2,
+    RangeCheckFelt252Width27: 10,
+    TripleXor32: 8,
+    VerifyBitwiseXor_12: 3,
+    VerifyBitwiseXor_4: 3,
+    VerifyBitwiseXor_7: 3,
+    VerifyBitwiseXor_8: 3,
+    VerifyBitwiseXor_9: 3,
+    VerifyInstruction: 7,
+}
@@ -8,6 +8,7 @@ use stwo_prover::core::channel::MerkleChannel;
 use stwo_prover::core::vcs::ops::MerkleHasher;
 use tracing::{span, Level};
 
+use crate::air::USAGE_AWARE_NOT_CAIRO_SERIALIZABLE;
 use crate::CairoProof;
 
 /// Cairo proof format
@@ -21,6 +22,9 @@ pub enum ProofFormat {
 }
 
 /// Serializes Cairo proof given the desired format and writes it to a file.
+///
+/// Fails without writing the file if the format is [`ProofFormat::CairoSerde`] and the proof uses
+/// a preprocessed trace the Cairo verifier doesn't support.
 pub fn serialize_proof_to_file<MC: MerkleChannel>(
     proof: &CairoProof<MC::H>,
     proof_path: PathBuf,
@@ -30,6 +34,13 @@ where
     MC::H: Serialize,
     <MC::H as MerkleHasher>::Hash: CairoSerialize,
 {
+    if matches!(proof_format, ProofFormat::CairoSerde) && !proof.is_cairo_verifiable() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            USAGE_AWARE_NOT_CAIRO_SERIALIZABLE,
+        ));
+    }
+
     let span = span!(Level::INFO, "Serialize proof").entered();
 
     let mut proof_file = File::create(proof_path)?;
@@ -1,14 +1,13 @@
+use std::sync::{Arc, Mutex};
+
 use num_traits::{One, Zero};
-use paste::paste;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde_json::to_string_pretty;
-use stwo_cairo_adapter::builtins::{
-    ADD_MOD_MEMORY_CELLS, BITWISE_MEMORY_CELLS, MUL_MOD_MEMORY_CELLS, PEDERSEN_MEMORY_CELLS,
-    POSEIDON_MEMORY_CELLS, RANGE_CHECK_MEMORY_CELLS,
-};
 use stwo_cairo_adapter::memory::LARGE_MEMORY_VALUE_ID_BASE;
 use stwo_cairo_adapter::HashMap;
 use stwo_cairo_common::memory::LOG_MEMORY_ADDRESS_BOUND;
 use stwo_cairo_common::prover_types::cpu::{CasmState, PRIME};
+use stwo_prover::constraint_framework::preprocessed_columns::PreProcessedColumnId;
 use stwo_prover::constraint_framework::PREPROCESSED_TRACE_IDX;
 use stwo_prover::core::channel::{Channel, MerkleChannel};
 use stwo_prover::core::fields::m31::BaseField;
@@ -18,14 +17,29 @@ use stwo_prover::core::prover::{verify, VerificationError};
 use thiserror::Error;
 
 use crate::air::{
-    lookup_sum, CairoClaim, CairoComponents, CairoInteractionElements, MemorySection, PublicData,
-    PublicMemory, PublicSegmentRanges, SegmentRange,
+    lookup_sum, CairoClaim, CairoComponents, CairoInteractionClaim, CairoInteractionElements,
+    MemorySection, PublicData, PublicMemory, PublicSegmentRanges, SegmentRange,
 };
 use crate::builtins_air::BuiltinsClaim;
+use crate::component_registry::ComponentRegistry;
 use crate::components::memory_address_to_id::MEMORY_ADDRESS_TO_ID_SPLIT;
 use crate::{CairoProof, PreProcessedTraceVariant};
 
-fn verify_claim(claim: &CairoClaim) {
+/// Returns a [`CairoVerificationError::Malformed`] error with the given message from the enclosing
+/// function, unless `cond` holds.
+macro_rules! ensure {
+    ($cond:expr, $($message:tt)+) => {
+        if !$cond {
+            return Err($crate::verifier::CairoVerificationError::Malformed(format!($($message)+)));
+        }
+    };
+}
+pub(crate) use ensure;
+
+fn verify_claim(
+    claim: &CairoClaim,
+    registry: &ComponentRegistry,
+) -> Result<(), CairoVerificationError> {
     let PublicData {
         public_memory:
             PublicMemory {
@@ -48,26 +62,42 @@ fn verify_claim(claim: &CairoClaim) {
             },
     } = &claim.public_data;
 
-    verify_builtins(&claim.builtins, public_segments);
+    verify_builtins(&claim.builtins, &claim.public_data)?;
 
-    verify_program(program, public_segments);
+    verify_program(program, public_segments)?;
 
-    assert_eq!(*initial_pc, BaseField::one());
-    assert!(
+    ensure!(
+        *initial_pc == BaseField::one(),
+        "Initial pc must be 1, but got {initial_pc}"
+    );
+    ensure!(
         *initial_pc + BaseField::from(2) < *initial_ap,
         "Initial pc + 2 must be less than initial ap, but got initial_pc: {}, initial_ap: {}",
         initial_pc,
         initial_ap
     );
-    assert_eq!(initial_fp, final_fp);
-    assert_eq!(initial_fp, initial_ap);
-    assert_eq!(*final_pc, BaseField::from(5));
-    assert!(initial_ap <= final_ap);
+    ensure!(
+        initial_fp == final_fp,
+        "Initial fp {initial_fp} differs from final fp {final_fp}"
+    );
+    ensure!(
+        initial_fp == initial_ap,
+        "Initial fp {initial_fp} differs from initial ap {initial_ap}"
+    );
+    ensure!(
+        *final_pc == BaseField::from(5),
+        "Final pc must be 5, but got {final_pc}"
+    );
+    ensure!(
+        initial_ap <= final_ap,
+        "Initial ap {initial_ap} exceeds final ap {final_ap}"
+    );
 
-    // Assert that each relation has strictly less than P uses.
+    // Check that each relation has strictly less than P uses.
     let mut relation_uses = HashMap::<&'static str, u64>::new();
     claim.accumulate_relation_uses(&mut relation_uses);
-    check_relation_uses(&relation_uses);
+    registry.accumulate_relation_uses(&claim.extensions, &mut relation_uses);
+    check_relation_uses(&relation_uses)?;
 
     // Large value IDs reside in [LARGE_MEMORY_VALUE_ID_BASE..P).
     // Check that IDs in (ID -> Value) do not overflow P.
@@ -79,10 +109,16 @@ fn verify_claim(claim: &CairoClaim) {
         .sum::<u32>()
         - 1
         + LARGE_MEMORY_VALUE_ID_BASE;
-    assert!(largest_id < PRIME);
+    ensure!(
+        largest_id < PRIME,
+        "Memory ids overflow P, the largest id is {largest_id}"
+    );
+    Ok(())
 }
 
-fn check_relation_uses(relation_uses: &HashMap<&'static str, u64>) {
+fn check_relation_uses(
+    relation_uses: &HashMap<&'static str, u64>,
+) -> Result<(), CairoVerificationError> {
     let all_relation_uses_pretty = to_string_pretty(&relation_uses).unwrap();
     log::info!("All relation uses:\n{}", all_relation_uses_pretty);
 
@@ -91,14 +127,130 @@ fn check_relation_uses(relation_uses: &HashMap<&'static str, u64>) {
         .filter(|(_, &uses)| uses >= PRIME.into())
         .collect::<Vec<_>>();
 
-    if !outstanding_relations.is_empty() {
-        let outstanding_relations_pretty = to_string_pretty(&outstanding_relations).unwrap();
-        panic!(
-            "Found {} outstanding relations:\n{}",
-            outstanding_relations.len(),
-            outstanding_relations_pretty
-        );
-    }
+    ensure!(
+        outstanding_relations.is_empty(),
+        "Found {} outstanding relations:\n{}",
+        outstanding_relations.len(),
+        to_string_pretty(&outstanding_relations).unwrap()
+    );
+    Ok(())
+}
+
+/// Lookup tables may be omitted from the claim, in which case they must also be omitted from the
+/// interaction claim, as their claimed sums would otherwise be added to the lookup sum without a
+/// component backing them.
+fn verify_lookup_tables(
+    claim: &CairoClaim,
+    interaction_claim: &CairoInteractionClaim,
+) -> Result<(), CairoVerificationError> {
+    check_same_presence(
+        "verify_instruction",
+        &claim.verify_instruction,
+        &interaction_claim.verify_instruction,
+    )?;
+    check_same_presence(
+        "range_check_6",
+        &claim.range_checks.rc_6,
+        &interaction_claim.range_checks.rc_6,
+    )?;
+    check_same_presence(
+        "range_check_8",
+        &claim.range_checks.rc_8,
+        &interaction_claim.range_checks.rc_8,
+    )?;
+    check_same_presence(
+        "range_check_11",
+        &claim.range_checks.rc_11,
+        &interaction_claim.range_checks.rc_11,
+    )?;
+    check_same_presence(
+        "range_check_12",
+        &claim.range_checks.rc_12,
+        &interaction_claim.range_checks.rc_12,
+    )?;
+    check_same_presence(
+        "range_check_18",
+        &claim.range_checks.rc_18,
+        &interaction_claim.range_checks.rc_18,
+    )?;
+    check_same_presence(
+        "range_check_19",
+        &claim.range_checks.rc_19,
+        &interaction_claim.range_checks.rc_19,
+    )?;
+    check_same_presence(
+        "range_check_4_3",
+        &claim.range_checks.rc_4_3,
+        &interaction_claim.range_checks.rc_4_3,
+    )?;
+    check_same_presence(
+        "range_check_4_4",
+        &claim.range_checks.rc_4_4,
+        &interaction_claim.range_checks.rc_4_4,
+    )?;
+    check_same_presence(
+        "range_check_5_4",
+        &claim.range_checks.rc_5_4,
+        &interaction_claim.range_checks.rc_5_4,
+    )?;
+    check_same_presence(
+        "range_check_9_9",
+        &claim.range_checks.rc_9_9,
+        &interaction_claim.range_checks.rc_9_9,
+    )?;
+    check_same_presence(
+        "range_check_7_2_5",
+        &claim.range_checks.rc_7_2_5,
+        &interaction_claim.range_checks.rc_7_2_5,
+    )?;
+    check_same_presence(
+        "range_check_3_6_6_3",
+        &claim.range_checks.rc_3_6_6_3,
+        &interaction_claim.range_checks.rc_3_6_6_3,
+    )?;
+    check_same_presence(
+        "range_check_4_4_4_4",
+        &claim.range_checks.rc_4_4_4_4,
+        &interaction_claim.range_checks.rc_4_4_4_4,
+    )?;
+    check_same_presence(
+        "range_check_3_3_3_3_3",
+        &claim.range_checks.rc_3_3_3_3_3,
+        &interaction_claim.range_checks.rc_3_3_3_3_3,
+    )?;
+    check_same_presence(
+        "verify_bitwise_xor_4",
+        &claim.verify_bitwise_xor_4,
+        &interaction_claim.verify_bitwise_xor_4,
+    )?;
+    check_same_presence(
+        "verify_bitwise_xor_7",
+        &claim.verify_bitwise_xor_7,
+        &interaction_claim.verify_bitwise_xor_7,
+    )?;
+    check_same_presence(
+        "verify_bitwise_xor_8",
+        &claim.verify_bitwise_xor_8,
+        &interaction_claim.verify_bitwise_xor_8,
+    )?;
+    check_same_presence(
+        "verify_bitwise_xor_9",
+        &claim.verify_bitwise_xor_9,
+        &interaction_claim.verify_bitwise_xor_9,
+    )?;
+    Ok(())
+}
+
+fn check_same_presence<C, I>(
+    name: &str,
+    claim: &Option<C>,
+    interaction_claim: &Option<I>,
+) -> Result<(), CairoVerificationError> {
+    ensure!(
+        claim.is_some() == interaction_claim.is_some(),
+        "Presence of {name} differs between the claim and the interaction claim"
+    );
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -107,168 +259,149 @@ pub struct RelationUse {
     pub uses: u64,
 }
 
-struct BuiltinClaim {
-    segment_start: u32,
-    log_size: u32,
+pub(crate) struct BuiltinClaim {
+    pub segment_start: u32,
+    pub log_size: u32,
 }
 
-fn verify_builtins(builtins_claim: &BuiltinsClaim, segment_ranges: &PublicSegmentRanges) {
+fn verify_builtins(
+    builtins_claim: &BuiltinsClaim,
+    public_data: &PublicData,
+) -> Result<(), CairoVerificationError> {
     let PublicSegmentRanges {
         output,
-        pedersen,
-        range_check_128,
         ecdsa,
-        bitwise,
         ec_op,
         keccak,
-        poseidon,
-        range_check_96,
-        add_mod,
-        mul_mod,
-    } = *segment_ranges;
+        gas,
+        ..
+    } = &public_data.public_memory.public_segments;
     // Check that non-supported builtins aren't used.
     if let Some(ecdsa) = ecdsa {
-        assert_eq!(
-            ecdsa.start_ptr.value, ecdsa.stop_ptr.value,
-            "ECDSA segment is not empty"
-        );
+        ensure!(ecdsa.is_empty(), "ECDSA segment is not empty");
     }
     if let Some(keccak) = keccak {
-        assert_eq!(
-            keccak.start_ptr.value, keccak.stop_ptr.value,
-            "Keccak segment is not empty"
-        );
+        ensure!(keccak.is_empty(), "Keccak segment is not empty");
     }
     if let Some(ec_op) = ec_op {
-        assert_eq!(
-            ec_op.start_ptr.value, ec_op.stop_ptr.value,
-            "EC_OP segment is not empty"
-        );
+        ensure!(ec_op.is_empty(), "EC_OP segment is not empty");
     }
 
-    // Output builtin.
-    assert!(output.stop_ptr.value < 1 << 31);
-    assert!(output.start_ptr.value <= output.stop_ptr.value);
-
-    // Macro for calling `check_builtin` on all builtins except both range_check builtins.
-    macro_rules! check_builtin_generic {
-        ($name:ident) => {
-            paste! {
-                check_builtin(
-                    builtins_claim.[<$name _builtin>]
-                        .map(|claim| BuiltinClaim {
-                            segment_start: claim.[<$name _builtin_segment_start>],
-                            log_size: claim.log_size,
-                        }),
-                    $name,
-                    stringify!($name),
-                    [<$name:upper _MEMORY_CELLS>]
-                );
-            }
-        };
+    // A program can't gain gas.
+    if let Some(gas) = gas {
+        ensure!(
+            gas.stop_ptr.value <= gas.start_ptr.value,
+            "Remaining gas exceeds the initial gas"
+        );
     }
 
-    // All other supported builtins.
-    check_builtin(
-        builtins_claim
-            .range_check_128_builtin
-            .map(|claim| BuiltinClaim {
-                segment_start: claim.range_check_builtin_segment_start,
-                log_size: claim.log_size,
-            }),
-        range_check_128,
-        "range_check_128",
-        RANGE_CHECK_MEMORY_CELLS,
+    // Output builtin.
+    ensure!(
+        output.stop_ptr.value < 1 << 31,
+        "Output stop pointer must be less than 2^31, but got {}",
+        output.stop_ptr.value
     );
-    check_builtin(
-        builtins_claim
-            .range_check_96_builtin
-            .map(|claim| BuiltinClaim {
-                segment_start: claim.range_check96_builtin_segment_start,
-                log_size: claim.log_size,
-            }),
-        range_check_96,
-        "range_check_96",
-        RANGE_CHECK_MEMORY_CELLS,
+    ensure!(
+        output.start_ptr.value <= output.stop_ptr.value,
+        "Output start pointer exceeds the output stop pointer"
     );
-    check_builtin_generic!(bitwise);
-    check_builtin_generic!(add_mod);
-    check_builtin_generic!(mul_mod);
-    check_builtin_generic!(pedersen);
-    check_builtin_generic!(poseidon);
+
+    // All other supported builtins.
+    builtins_claim.verify(public_data)
 }
 
-fn verify_program(program: &MemorySection, public_segments: &PublicSegmentRanges) {
+fn verify_program(
+    program: &MemorySection,
+    public_segments: &PublicSegmentRanges,
+) -> Result<(), CairoVerificationError> {
     // For information about how the compiler adds this code, see:
     // https://github.com/starkware-libs/cairo/blob/3babe0518abc8e4fc72f519fb515d6c752138f78/crates/cairo-lang-executable/src/executable.rs#L21-L25
 
-    // First instruction: add_app_immediate (n_builtins).
-    let n_builtins = public_segments.present_segments().len() as u32;
-    assert_eq!(program[0].1, [0x7fff7fff, 0x4078001, 0, 0, 0, 0, 0, 0]); // add_ap_imm.
-    assert_eq!(program[1].1, [n_builtins, 0, 0, 0, 0, 0, 0, 0]); // Imm.
-
-    // Safe call.
-    assert_eq!(program[2].1, [0x80018000, 0x11048001, 0, 0, 0, 0, 0, 0]); // Instruction: call rel ?
-    assert_eq!(program[4].1, [0x7fff7fff, 0x1078001, 0, 0, 0, 0, 0, 0]); // Instruction: jmp rel 0.
-    assert_eq!(program[5].1, [0, 0, 0, 0, 0, 0, 0, 0]); // Imm of last instruction (jmp rel 0).
+    // First instruction: add_app_immediate (n_builtins), also allocating the extension segment
+    // pointers and the gas counter if present.
+    let n_builtins =
+        public_segments.present_segments().len() as u32 + public_segments.gas.is_some() as u32;
+    ensure!(
+        program.len() >= 6,
+        "The program is too short for its entry code: {} words",
+        program.len()
+    );
+    let expected_entry_code = [
+        (0, [0x7fff7fff, 0x4078001, 0, 0, 0, 0, 0, 0]), // add_ap_imm.
+        (1, [n_builtins, 0, 0, 0, 0, 0, 0, 0]),         // Imm.
+        // Safe call.
+        (2, [0x80018000, 0x11048001, 0, 0, 0, 0, 0, 0]), // Instruction: call rel ?
+        (4, [0x7fff7fff, 0x1078001, 0, 0, 0, 0, 0, 0]),  // Instruction: jmp rel 0.
+        (5, [0, 0, 0, 0, 0, 0, 0, 0]),                   // Imm of last instruction (jmp rel 0).
+    ];
+    for (index, expected) in expected_entry_code {
+        ensure!(
+            program[index].1 == expected,
+            "Unexpected entry code at program index {index}: expected {expected:?}, got {:?}",
+            program[index].1
+        );
+    }
+    Ok(())
 }
 
-fn check_builtin(
+pub(crate) fn check_builtin(
     builtin_claim: Option<BuiltinClaim>,
     segment_range: Option<SegmentRange>,
     name: &str,
     n_cells: usize,
-) {
+) -> Result<(), CairoVerificationError> {
     let segment_range = match segment_range {
-        None => return,
+        None => return Ok(()),
         Some(segment_range) => {
             if segment_range.is_empty() {
-                return;
+                return Ok(());
             }
             segment_range
         }
     };
 
     // If segment range is non-empty, claim must be Some.
-    let BuiltinClaim {
+    let Some(BuiltinClaim {
         segment_start,
         log_size,
-    } = builtin_claim.unwrap_or_else(|| {
-        panic!(
+    }) = builtin_claim
+    else {
+        return Err(CairoVerificationError::Malformed(format!(
             "Missing {} builtin claim despite non-empty segment range {:?}",
             name, segment_range
-        )
-    });
+        )));
+    };
 
-    let segment_end = segment_start + (1 << log_size) * n_cells as u32;
     let start_ptr = segment_range.start_ptr.value;
     let stop_ptr = segment_range.stop_ptr.value;
-    assert!(
-        (stop_ptr - start_ptr) % n_cells as u32 == 0,
-        "Builtin segment range must divisible by {} cells, but got start_ptr: {}, stop_ptr: {}",
-        n_cells,
-        start_ptr,
-        stop_ptr
-    );
-
     // Check that segment_start == start_ptr <= stop_ptr <= segment_end < 2**31.
-    assert_eq!(
-        start_ptr, segment_start,
+    ensure!(
+        start_ptr == segment_start,
         "Builtin segment start doesn't match claim"
     );
-    assert!(
+    ensure!(
         start_ptr <= stop_ptr,
         "Range start should be less than or equal to range stop"
     );
-    assert!(
-        stop_ptr <= segment_end,
+    ensure!(
+        (stop_ptr - start_ptr) % n_cells as u32 == 0,
+        "Builtin segment range must divisible by {} cells, but got start_ptr: {}, stop_ptr: {}",
+        n_cells,
+        start_ptr,
+        stop_ptr
+    );
+    // Computed in u64, as a malformed log size may overflow u32.
+    let segment_end = segment_start as u64 + ((n_cells as u64) << log_size.min(32));
+    ensure!(
+        stop_ptr as u64 <= segment_end,
         "Builtin stop pointer must be within the builtin segment"
     );
-    assert!(
+    ensure!(
         segment_end < 1 << 31,
         "segment_end must be less than 2^31, but got {}",
         segment_end
     );
+    Ok(())
 }
 
 /// Logup security is defined by the `QM31` space (~124 bits) + `INTERACTION_POW_BITS` -
@@ -278,30 +411,171 @@ fn check_builtin(
 pub const INTERACTION_POW_BITS: u32 = 24;
 
 pub fn verify_cairo<MC: MerkleChannel>(
+    proof: CairoProof<MC::H>,
+    pcs_config: PcsConfig,
+    preprocessed_trace: PreProcessedTraceVariant,
+) -> Result<(), CairoVerificationError> {
+    verify_cairo_with_registry::<MC>(
+        proof,
+        pcs_config,
+        preprocessed_trace,
+        &ComponentRegistry::default(),
+    )
+}
+
+/// Same as [`verify_cairo`], for proofs of the Cairo AIR extended with the component families of
+/// `registry`.
+pub fn verify_cairo_with_registry<MC: MerkleChannel>(
+    proof: CairoProof<MC::H>,
+    pcs_config: PcsConfig,
+    preprocessed_trace: PreProcessedTraceVariant,
+    registry: &ComponentRegistry,
+) -> Result<(), CairoVerificationError> {
+    let preprocessed_trace = PreProcessedTraceMetadata::new(preprocessed_trace, &proof.claim);
+    verify_cairo_inner::<MC>(proof, pcs_config, &preprocessed_trace, registry)
+}
+
+/// The parts of a preprocessed trace needed for verification.
+struct PreProcessedTraceMetadata {
+    variant: PreProcessedTraceVariant,
+    log_sizes: Vec<u32>,
+    ids: Vec<PreProcessedColumnId>,
+}
+impl PreProcessedTraceMetadata {
+    fn new(variant: PreProcessedTraceVariant, claim: &CairoClaim) -> Self {
+        let preprocessed_trace = variant.to_preprocessed_trace_for_claim(claim);
+        Self {
+            variant,
+            log_sizes: preprocessed_trace.log_sizes(),
+            ids: preprocessed_trace.ids(),
+        }
+    }
+}
+
+/// Verifies Cairo proofs, computing the preprocessed trace metadata of each
+/// [`PreProcessedTraceVariant`] only once. The metadata of
+/// [`PreProcessedTraceVariant::UsageAware`] depends on the claim, and is computed per proof.
+pub struct CairoVerifier {
+    pcs_config: PcsConfig,
+    registry: ComponentRegistry,
+    preprocessed_traces: Mutex<HashMap<PreProcessedTraceVariant, Arc<PreProcessedTraceMetadata>>>,
+}
+impl CairoVerifier {
+    pub fn new(pcs_config: PcsConfig) -> Self {
+        Self {
+            pcs_config,
+            registry: ComponentRegistry::default(),
+            preprocessed_traces: Mutex::new(HashMap::default()),
+        }
+    }
+
+    /// Verifies proofs of the Cairo AIR extended with the component families of `registry`.
+    pub fn with_registry(mut self, registry: ComponentRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Same as [`verify_cairo`], with the verifier's `PcsConfig`.
+    pub fn verify<MC: MerkleChannel>(
+        &self,
+        proof: CairoProof<MC::H>,
+        preprocessed_trace: PreProcessedTraceVariant,
+    ) -> Result<(), CairoVerificationError> {
+        let preprocessed_trace = self.preprocessed_trace(preprocessed_trace, &proof.claim);
+        verify_cairo_inner::<MC>(proof, self.pcs_config, &preprocessed_trace, &self.registry)
+    }
+
+    /// Verifies the given proofs in parallel, returning a result per proof, in order.
+    /// Proofs with an invalid claim result in [`CairoVerificationError::Malformed`].
+    pub fn verify_many<MC: MerkleChannel>(
+        &self,
+        proofs: Vec<(CairoProof<MC::H>, PreProcessedTraceVariant)>,
+    ) -> Vec<Result<(), CairoVerificationError>>
+    where
+        MC::H: Send,
+    {
+        proofs
+            .into_par_iter()
+            .map(|(proof, preprocessed_trace)| self.verify::<MC>(proof, preprocessed_trace))
+            .collect()
+    }
+
+    fn preprocessed_trace(
+        &self,
+        variant: PreProcessedTraceVariant,
+        claim: &CairoClaim,
+    ) -> Arc<PreProcessedTraceMetadata> {
+        if variant == PreProcessedTraceVariant::UsageAware {
+            return Arc::new(PreProcessedTraceMetadata::new(variant, claim));
+        }
+        if let Some(metadata) = self.preprocessed_traces.lock().unwrap().get(&variant) {
+            return metadata.clone();
+        }
+        // Computed without holding the lock, so that other variants aren't blocked. Concurrent
+        // verifications of the same variant may compute it more than once.
+        let metadata = Arc::new(PreProcessedTraceMetadata::new(variant, claim));
+        self.preprocessed_traces
+            .lock()
+            .unwrap()
+            .entry(variant)
+            .or_insert(metadata)
+            .clone()
+    }
+}
+
+/// Runs the auxiliary verifications of a Cairo proof, i.e. everything but the STARK: the public
+/// data, the builtin segments, the program, the registered extensions and the presence of lookup
+/// tables.
+pub fn verify_cairo_claims(
+    claim: &CairoClaim,
+    interaction_claim: &CairoInteractionClaim,
+    registry: &ComponentRegistry,
+) -> Result<(), CairoVerificationError> {
+    // Check that ADDRESS->ID component does not overflow.
+    ensure!(
+        claim.memory_address_to_id.log_size <= LOG_MEMORY_ADDRESS_BOUND
+            && (1 << claim.memory_address_to_id.log_size) * MEMORY_ADDRESS_TO_ID_SPLIT
+                <= (1 << LOG_MEMORY_ADDRESS_BOUND),
+        "The memory address to id component overflows, its log size is {}",
+        claim.memory_address_to_id.log_size
+    );
+
+    registry.verify_claims(
+        &claim.extensions,
+        &interaction_claim.extensions,
+        &claim.public_data,
+    )?;
+    verify_claim(claim, registry)?;
+    verify_lookup_tables(claim, interaction_claim)
+}
+
+fn verify_cairo_inner<MC: MerkleChannel>(
     CairoProof {
         claim,
         interaction_pow,
         interaction_claim,
         stark_proof,
+        preprocessed_trace: proof_preprocessed_trace,
     }: CairoProof<MC::H>,
     pcs_config: PcsConfig,
-    preprocessed_trace: PreProcessedTraceVariant,
+    preprocessed_trace: &PreProcessedTraceMetadata,
+    registry: &ComponentRegistry,
 ) -> Result<(), CairoVerificationError> {
-    // Auxiliary verifications.
-    // Assert that ADDRESS->ID component does not overflow.
-    assert!(
-        (1 << claim.memory_address_to_id.log_size) * MEMORY_ADDRESS_TO_ID_SPLIT
-            <= (1 << LOG_MEMORY_ADDRESS_BOUND)
-    );
+    if proof_preprocessed_trace != preprocessed_trace.variant {
+        return Err(CairoVerificationError::PreProcessedTraceMismatch {
+            expected: preprocessed_trace.variant,
+            actual: proof_preprocessed_trace,
+        });
+    }
 
-    verify_claim(&claim);
+    verify_cairo_claims(&claim, &interaction_claim, registry)?;
 
     let channel = &mut MC::C::default();
     pcs_config.mix_into(channel);
     let commitment_scheme_verifier = &mut CommitmentSchemeVerifier::<MC>::new(pcs_config);
 
-    let mut log_sizes = claim.log_sizes();
-    log_sizes[PREPROCESSED_TRACE_IDX] = preprocessed_trace.to_preprocessed_trace().log_sizes();
+    let mut log_sizes = claim.log_sizes_with_extensions(registry);
+    log_sizes[PREPROCESSED_TRACE_IDX] = preprocessed_trace.log_sizes.clone();
 
     // Preproccessed trace.
     commitment_scheme_verifier.commit(stark_proof.commitments[0], &log_sizes[0], channel);
@@ -324,11 +598,12 @@ pub fn verify_cairo<MC: MerkleChannel>(
     interaction_claim.mix_into(channel);
     commitment_scheme_verifier.commit(stark_proof.commitments[2], &log_sizes[2], channel);
 
-    let component_generator = CairoComponents::new(
+    let component_generator = CairoComponents::new_with_registry(
         &claim,
         &interaction_elements,
         &interaction_claim,
-        &preprocessed_trace.to_preprocessed_trace().ids(),
+        &preprocessed_trace.ids,
+        registry,
     );
     let components = component_generator.components();
 
@@ -350,4 +625,11 @@ pub enum CairoVerificationError {
     Stark(#[from] VerificationError),
     #[error("Proof of work verification failed.")]
     ProofOfWork,
+    #[error("Malformed proof: {0}")]
+    Malformed(String),
+    #[error("Preprocessed trace mismatch: expected {expected:?}, got {actual:?}")]
+    PreProcessedTraceMismatch {
+        expected: PreProcessedTraceVariant,
+        actual: PreProcessedTraceVariant,
+    },
 }
@@ -0,0 +1,996 @@
+//! Generates the constraint evaluation code of the Cairo verifier's components
+//! (`stwo_cairo_verifier/crates/cairo_air/src/components`) from the Rust AIR.
+//!
+//! Every component is evaluated with [`CodegenEvaluator`], a symbolic [`EvalAtRow`] whose values
+//! are [`Expr`]s, and the resulting constraints are emitted as a Cairo module with:
+//! - The component's `Claim` struct.
+//! - `mask_points`, registering the component's preprocessed columns and mask points.
+//! - `ConstraintParams`, the lookup elements, preprocessed columns and claims the constraints
+//!   depend on.
+//! - `evaluate_constraints_at_point`, accumulating the constraint quotients in order of evaluation,
+//!   and a function per intermediate.
+
+use std::any::type_name;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use itertools::Itertools;
+use num_traits::{One, Zero};
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use stwo_prover::constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_prover::constraint_framework::{
+    Batching, EvalAtRow, FrameworkEval, Relation, RelationEntry, INTERACTION_TRACE_IDX,
+    ORIGINAL_TRACE_IDX,
+};
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::fields::FieldExpOps;
+use stwo_prover::core::lookups::utils::Fraction;
+
+use crate::air_description::{visit_cairo_components, ComponentVisitor};
+use crate::components::ColumnNames;
+use crate::verifier::RelationUse;
+
+/// Returns the generated constraints module of every component, as pairs of the module's path
+/// relative to the verifier's `components` directory, and its code.
+pub fn cairo_verifier_constraints() -> Vec<(String, String)> {
+    let mut generator = CodeGenerator::default();
+    visit_cairo_components(&mut generator);
+    generator.modules
+}
+
+#[derive(Default)]
+struct CodeGenerator {
+    modules: Vec<(String, String)>,
+}
+impl ComponentVisitor for CodeGenerator {
+    fn visit<C: Serialize, E: FrameworkEval + ColumnNames>(
+        &mut self,
+        name: &'static str,
+        claim: &C,
+        eval: &E,
+        _relation_uses_per_row: &[RelationUse],
+    ) {
+        let path = match name {
+            "memory_id_to_big" => "memory_id_to_big/constraints_big.cairo".to_string(),
+            "memory_id_to_small" => "memory_id_to_big/constraints_small.cairo".to_string(),
+            _ => format!("{name}/constraints.cairo"),
+        };
+        let evaluator = eval.evaluate(CodegenEvaluator::default());
+        self.modules
+            .push((path, CairoModule::new(claim, evaluator).to_string()));
+    }
+}
+
+/// A symbolic value of the AIR. Base field values are extension field values with a zero
+/// imaginary part, as in the Cairo verifier.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Col {
+        interaction: usize,
+        column: usize,
+        offset: isize,
+    },
+    /// A lookup element, preprocessed column or claim value.
+    Param(String),
+    Intermediate(usize),
+    /// The inverse of the trace's size.
+    ColumnSizeInverse,
+    Const(SecureField),
+    /// An extension field element from its coordinates.
+    SecureCol(Box<[Expr; SECURE_EXTENSION_DEGREE]>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+    Inv(Box<Expr>),
+}
+
+impl Expr {
+    /// Folds constants, and removes additions of zero and multiplications by one.
+    fn simplify(self) -> Self {
+        match self {
+            Expr::Add(a, b) => match (a.simplify(), b.simplify()) {
+                (Expr::Const(a), Expr::Const(b)) => Expr::Const(a + b),
+                (Expr::Const(zero), e) | (e, Expr::Const(zero)) if zero.is_zero() => e,
+                (a, b) => Expr::Add(Box::new(a), Box::new(b)),
+            },
+            Expr::Sub(a, b) => match (a.simplify(), b.simplify()) {
+                (Expr::Const(a), Expr::Const(b)) => Expr::Const(a - b),
+                (e, Expr::Const(zero)) if zero.is_zero() => e,
+                (Expr::Const(zero), e) if zero.is_zero() => Expr::Neg(Box::new(e)),
+                (a, b) => Expr::Sub(Box::new(a), Box::new(b)),
+            },
+            Expr::Mul(a, b) => match (a.simplify(), b.simplify()) {
+                (Expr::Const(a), Expr::Const(b)) => Expr::Const(a * b),
+                (Expr::Const(zero), _) | (_, Expr::Const(zero)) if zero.is_zero() => {
+                    Expr::Const(SecureField::zero())
+                }
+                (Expr::Const(one), e) | (e, Expr::Const(one)) if one.is_one() => e,
+                (a, b) => Expr::Mul(Box::new(a), Box::new(b)),
+            },
+            Expr::Neg(a) => match a.simplify() {
+                Expr::Const(a) => Expr::Const(-a),
+                a => Expr::Neg(Box::new(a)),
+            },
+            Expr::Inv(a) => match a.simplify() {
+                Expr::Const(a) => Expr::Const(a.inverse()),
+                a => Expr::Inv(Box::new(a)),
+            },
+            Expr::SecureCol(values) => Expr::SecureCol(Box::new((*values).map(Expr::simplify))),
+            e => e,
+        }
+    }
+
+    /// Collects the names of the variables the expression depends on.
+    fn collect_variables(&self, variables: &mut BTreeSet<String>) {
+        match self {
+            Expr::Col { .. } | Expr::Param(_) | Expr::Intermediate(_) => {
+                variables.insert(self.to_string());
+            }
+            Expr::ColumnSizeInverse => {
+                variables.insert("column_size".to_string());
+            }
+            Expr::Const(_) => {}
+            Expr::SecureCol(values) => values.iter().for_each(|v| v.collect_variables(variables)),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => {
+                a.collect_variables(variables);
+                b.collect_variables(variables);
+            }
+            Expr::Neg(a) | Expr::Inv(a) => a.collect_variables(variables),
+        }
+    }
+
+    fn is_atomic(&self) -> bool {
+        matches!(
+            self,
+            Expr::Col { .. }
+                | Expr::Param(_)
+                | Expr::Intermediate(_)
+                | Expr::Const(_)
+                | Expr::SecureCol(_)
+        )
+    }
+
+    /// Formats the expression as an operand, in parentheses unless it's atomic or `bare`.
+    fn operand(&self, bare: bool) -> String {
+        match bare || self.is_atomic() {
+            true => self.to_string(),
+            false => format!("({self})"),
+        }
+    }
+}
+
+/// Formats the expression as Cairo code.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Col {
+                interaction,
+                column,
+                offset,
+            } => {
+                let offset = match offset {
+                    offset if *offset < 0 => format!("neg_{}", -offset),
+                    offset => offset.to_string(),
+                };
+                write!(f, "trace_{interaction}_column_{column}_offset_{offset}")
+            }
+            Expr::Param(name) => write!(f, "{name}"),
+            Expr::Intermediate(i) => write!(f, "intermediate{i}"),
+            Expr::ColumnSizeInverse => write!(f, "column_size.inverse().into()"),
+            Expr::Const(value) => {
+                let [a, b, c, d] = value.to_m31_array();
+                write!(f, "qm31_const::<{a}, {b}, {c}, {d}>()")
+            }
+            Expr::SecureCol(values) => write!(
+                f,
+                "QM31Trait::from_partial_evals([{}])",
+                values.iter().join(", ")
+            ),
+            Expr::Add(a, b) | Expr::Sub(a, b) => {
+                let op = match self {
+                    Expr::Add(..) => "+",
+                    _ => "-",
+                };
+                let a_is_bare = matches!(**a, Expr::Add(..) | Expr::Sub(..) | Expr::Mul(..));
+                let b_is_bare = matches!(**b, Expr::Mul(..));
+                write!(f, "{} {op} {}", a.operand(a_is_bare), b.operand(b_is_bare))
+            }
+            Expr::Mul(a, b) => {
+                let a_is_bare = matches!(**a, Expr::Mul(..));
+                write!(f, "{} * {}", a.operand(a_is_bare), b.operand(false))
+            }
+            Expr::Neg(a) => write!(f, "-{}", a.operand(false)),
+            Expr::Inv(a) => write!(f, "{}.inverse()", a.operand(false)),
+        }
+    }
+}
+
+impl Zero for Expr {
+    fn zero() -> Self {
+        Expr::Const(SecureField::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, Expr::Const(value) if value.is_zero())
+    }
+}
+
+impl One for Expr {
+    fn one() -> Self {
+        Expr::Const(SecureField::one())
+    }
+}
+
+impl FieldExpOps for Expr {
+    fn inverse(&self) -> Self {
+        Expr::Inv(Box::new(self.clone()))
+    }
+}
+
+impl Neg for Expr {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+impl From<BaseField> for Expr {
+    fn from(value: BaseField) -> Self {
+        Expr::Const(value.into())
+    }
+}
+
+impl From<SecureField> for Expr {
+    fn from(value: SecureField) -> Self {
+        Expr::Const(value)
+    }
+}
+
+/// Implements the arithmetic operators of [`Expr`] with a right hand side of type `$rhs`.
+macro_rules! impl_expr_ops {
+    ($($rhs:ty),*) => {
+        $(
+            impl Add<$rhs> for Expr {
+                type Output = Self;
+
+                fn add(self, rhs: $rhs) -> Self {
+                    Expr::Add(Box::new(self), Box::new(rhs.into()))
+                }
+            }
+
+            impl Sub<$rhs> for Expr {
+                type Output = Self;
+
+                fn sub(self, rhs: $rhs) -> Self {
+                    Expr::Sub(Box::new(self), Box::new(rhs.into()))
+                }
+            }
+
+            impl Mul<$rhs> for Expr {
+                type Output = Self;
+
+                fn mul(self, rhs: $rhs) -> Self {
+                    Expr::Mul(Box::new(self), Box::new(rhs.into()))
+                }
+            }
+
+            impl AddAssign<$rhs> for Expr {
+                fn add_assign(&mut self, rhs: $rhs) {
+                    *self = self.clone() + rhs;
+                }
+            }
+
+            impl SubAssign<$rhs> for Expr {
+                fn sub_assign(&mut self, rhs: $rhs) {
+                    *self = self.clone() - rhs;
+                }
+            }
+
+            impl MulAssign<$rhs> for Expr {
+                fn mul_assign(&mut self, rhs: $rhs) {
+                    *self = self.clone() * rhs;
+                }
+            }
+        )*
+    };
+}
+impl_expr_ops!(Expr, BaseField, SecureField);
+
+/// A symbolic [`EvalAtRow`], recording a component's columns, intermediates and constraints,
+/// including the logup constraints.
+#[derive(Default)]
+struct CodegenEvaluator {
+    /// The number of mask columns, over all the interactions.
+    n_columns: usize,
+    /// The offsets of the mask columns, by interaction and column index.
+    mask_offsets: BTreeMap<usize, Vec<(usize, Vec<isize>)>>,
+    preprocessed_columns: Vec<String>,
+    intermediates: Vec<Expr>,
+    constraints: Vec<Expr>,
+    fracs: Vec<Fraction<Expr, Expr>>,
+    /// The cumulative sum of the logup batches so far.
+    prev_cumsum: Option<Expr>,
+}
+
+impl CodegenEvaluator {
+    fn next_cumsum(&mut self, offsets: &[isize]) -> Vec<Expr> {
+        let coordinates: [Vec<Expr>; SECURE_EXTENSION_DEGREE] =
+            std::array::from_fn(|_| self.next_column(INTERACTION_TRACE_IDX, offsets.to_vec()));
+        (0..offsets.len())
+            .map(|i| {
+                Expr::SecureCol(Box::new(std::array::from_fn(|coordinate| {
+                    coordinates[coordinate][i].clone()
+                })))
+            })
+            .collect()
+    }
+
+    fn next_column(&mut self, interaction: usize, offsets: Vec<isize>) -> Vec<Expr> {
+        let column = self.n_columns;
+        self.n_columns += 1;
+        let values = offsets
+            .iter()
+            .map(|&offset| Expr::Col {
+                interaction,
+                column,
+                offset,
+            })
+            .collect();
+        self.mask_offsets
+            .entry(interaction)
+            .or_default()
+            .push((column, offsets));
+        values
+    }
+
+    /// Adds the logup constraint of a batch of fractions. The last batch also accounts for the
+    /// previous row's cumulative sum, and the claimed sum.
+    fn add_batch_constraint(&mut self, fracs: Vec<Fraction<Expr, Expr>>, is_last: bool) {
+        let Fraction {
+            numerator,
+            denominator,
+        } = fracs
+            .into_iter()
+            .reduce(|a, b| Fraction {
+                numerator: a.numerator * b.denominator.clone()
+                    + b.numerator * a.denominator.clone(),
+                denominator: a.denominator * b.denominator,
+            })
+            .expect("Empty logup batch");
+
+        let prev_cumsum = self.prev_cumsum.take();
+        let cumsums = self.next_cumsum(if is_last { &[-1, 0] } else { &[0] });
+        let cur_cumsum = cumsums.last().unwrap().clone();
+        let mut diff = cur_cumsum.clone();
+        if let Some(prev_cumsum) = prev_cumsum {
+            diff = diff - prev_cumsum;
+        }
+        if is_last {
+            let cumsum_shift = Expr::Param("claimed_sum".to_string()) * Expr::ColumnSizeInverse;
+            diff = diff - cumsums[0].clone() + cumsum_shift;
+        }
+        self.prev_cumsum = Some(cur_cumsum);
+        self.constraints
+            .push((diff * denominator - numerator).simplify());
+    }
+}
+
+impl EvalAtRow for CodegenEvaluator {
+    type F = Expr;
+    type EF = Expr;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        assert_eq!(
+            interaction, ORIGINAL_TRACE_IDX,
+            "Preprocessed and interaction columns are masked by the evaluator"
+        );
+        self.next_column(interaction, offsets.to_vec())
+            .try_into()
+            .unwrap()
+    }
+
+    fn get_preprocessed_column(&mut self, column: PreProcessedColumnId) -> Self::F {
+        let param = preprocessed_column_param(&column.id);
+        self.preprocessed_columns.push(column.id);
+        Expr::Param(param)
+    }
+
+    fn add_intermediate(&mut self, expr: Self::F) -> Self::F {
+        self.intermediates.push(expr.simplify());
+        Expr::Intermediate(self.intermediates.len() - 1)
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF>,
+    {
+        self.constraints.push((Expr::one() * constraint).simplify());
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        Expr::SecureCol(Box::new(values))
+    }
+
+    /// Replaces the relation's combination by the verifier's lookup elements, named after the
+    /// relation, and evaluates it in an intermediate.
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        entry: RelationEntry<'_, Self::F, Self::EF, R>,
+    ) {
+        // The relation types are named after their relations.
+        let relation = type_name::<R>().rsplit("::").next().unwrap();
+        let mut recorder = FractionRecorder::default();
+        recorder.add_to_relation(entry);
+        let Fraction {
+            numerator,
+            denominator,
+        } = recorder.fraction.unwrap();
+
+        let combined = relation_values(denominator)
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| Expr::Param(format!("{relation}_alpha{i}")) * value)
+            .fold(Expr::zero(), |acc, term| acc + term)
+            - Expr::Param(format!("{relation}_z"));
+        let denominator = self.add_intermediate(combined);
+        self.write_logup_frac(Fraction::new(numerator, denominator));
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.fracs.push(fraction);
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        let fracs = std::mem::take(&mut self.fracs);
+        assert_eq!(batching.len(), fracs.len());
+        let mut batches = BTreeMap::<usize, Vec<_>>::new();
+        for (batch, frac) in batching.iter().zip(fracs) {
+            batches.entry(*batch).or_default().push(frac);
+        }
+        let n_batches = batches.len();
+        for (i, batch) in batches.into_values().enumerate() {
+            self.add_batch_constraint(batch, i == n_batches - 1);
+        }
+    }
+
+    fn finalize_logup(&mut self) {
+        let batching = (0..self.fracs.len()).collect();
+        self.finalize_logup_batched(&batching);
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        let batching = (0..self.fracs.len()).map(|i| i / 2).collect();
+        self.finalize_logup_batched(&batching);
+    }
+}
+
+/// Records the fraction of a single relation entry.
+#[derive(Default)]
+struct FractionRecorder {
+    fraction: Option<Fraction<Expr, Expr>>,
+}
+impl EvalAtRow for FractionRecorder {
+    type F = Expr;
+    type EF = Expr;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        _interaction: usize,
+        _offsets: [isize; N],
+    ) -> [Self::F; N] {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn add_constraint<G>(&mut self, _constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF>,
+    {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        Expr::SecureCol(Box::new(values))
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.fraction = Some(fraction);
+    }
+
+    fn finalize_logup_batched(&mut self, _batching: &Batching) {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn finalize_logup(&mut self) {
+        unreachable!("Only relation entries are recorded")
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        unreachable!("Only relation entries are recorded")
+    }
+}
+
+/// Extracts the values of a relation entry from its combination `sum(alpha^i * value_i) - z`,
+/// evaluated symbolically with constant lookup elements.
+fn relation_values(combination: Expr) -> Vec<Expr> {
+    fn collect_terms(sum: Expr, values: &mut Vec<Expr>) {
+        match sum {
+            Expr::Add(acc, term) => {
+                collect_terms(*acc, values);
+                values.push(match *term {
+                    Expr::Mul(power, value) if matches!(*power, Expr::Const(_)) => *value,
+                    Expr::Mul(value, power) if matches!(*power, Expr::Const(_)) => *value,
+                    term => panic!("Unexpected relation term: {term}"),
+                });
+            }
+            zero if zero.is_zero() => {}
+            sum => panic!("Unexpected relation combination: {sum}"),
+        }
+    }
+
+    let Expr::Sub(sum, _z) = combination else {
+        panic!("Unexpected relation combination: {combination}");
+    };
+    let mut values = vec![];
+    collect_terms(*sum, &mut values);
+    values.into_iter().map(Expr::simplify).collect()
+}
+
+/// The name of a preprocessed column's parameter. Sequence columns are named `seq`, as their
+/// size depends on the claim.
+fn preprocessed_column_param(id: &str) -> String {
+    match id.starts_with("seq_") {
+        true => "seq".to_string(),
+        false => id.to_string(),
+    }
+}
+
+/// The verifier's `PreprocessedColumn` of a preprocessed column id.
+fn cairo_preprocessed_column(id: &str) -> String {
+    let indices = |s: &str| {
+        s.split('_')
+            .map(|n| n.parse::<u32>().unwrap())
+            .collect_vec()
+    };
+    if id.starts_with("seq_") {
+        "PreprocessedColumn::Seq(log_size)".to_string()
+    } else if let Some(rest) = id.strip_prefix("bitwise_xor_") {
+        let [n_bits, column] = indices(rest).try_into().unwrap();
+        format!("PreprocessedColumn::BitwiseXor(({n_bits}, {column}))")
+    } else if let Some(rest) = id.strip_prefix("range_check_") {
+        let (ranges, column) = rest.split_once("_column_").unwrap();
+        let ranges = indices(ranges);
+        format!(
+            "PreprocessedColumn::RangeCheck{}(([{}], {column}))",
+            ranges.len(),
+            ranges.iter().join(", ")
+        )
+    } else if let Some(column) = id.strip_prefix("poseidon_round_keys_") {
+        format!("PreprocessedColumn::PoseidonRoundKeys({column})")
+    } else if let Some(column) = id.strip_prefix("blake_sigma_") {
+        format!("PreprocessedColumn::BlakeSigma({column})")
+    } else if let Some(column) = id.strip_prefix("pedersen_points_") {
+        format!("PreprocessedColumn::PedersenPoints({column})")
+    } else {
+        panic!("Unsupported preprocessed column: {id}")
+    }
+}
+
+/// The fields of a struct, in order of declaration.
+struct StructFields(Vec<(String, serde_json::Value)>);
+impl<'de> Deserialize<'de> for StructFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = StructFields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a struct")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<StructFields, A::Error> {
+                let mut fields = vec![];
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(StructFields(fields))
+            }
+        }
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+/// The generated Cairo module of a component.
+struct CairoModule {
+    /// The claim's fields and their Cairo types.
+    claim_fields: Vec<(String, &'static str)>,
+    evaluator: CodegenEvaluator,
+}
+impl CairoModule {
+    fn new(claim: &impl Serialize, evaluator: CodegenEvaluator) -> Self {
+        let claim = serde_json::to_string(claim).unwrap();
+        let StructFields(fields) = serde_json::from_str(&claim).unwrap();
+        let claim_fields = fields
+            .into_iter()
+            .map(|(name, value)| {
+                let ty = match value {
+                    serde_json::Value::Number(_) => "u32",
+                    serde_json::Value::Array(_) => "Array<u32>",
+                    value => panic!("Unsupported claim field {name}: {value}"),
+                };
+                (name, ty)
+            })
+            .collect();
+        Self {
+            claim_fields,
+            evaluator,
+        }
+    }
+
+    fn n_columns(&self, interaction: usize) -> usize {
+        self.evaluator
+            .mask_offsets
+            .get(&interaction)
+            .map_or(0, |columns| columns.len())
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut variables = BTreeSet::new();
+        for expr in self
+            .evaluator
+            .intermediates
+            .iter()
+            .chain(&self.evaluator.constraints)
+        {
+            expr.collect_variables(&mut variables);
+        }
+        let mut params = variables
+            .into_iter()
+            .filter(|variable| {
+                !variable.starts_with("trace_")
+                    && !variable.starts_with("intermediate")
+                    && variable != "column_size"
+            })
+            .collect_vec();
+        params.push("column_size".to_string());
+        params
+    }
+
+    fn write_claim(&self, f: &mut impl Write) -> fmt::Result {
+        let is_copy = self.claim_fields.iter().all(|(_, ty)| *ty == "u32");
+        match is_copy {
+            true => writeln!(f, "#[derive(Drop, Serde, Copy)]")?,
+            false => writeln!(f, "#[derive(Drop, Serde)]")?,
+        }
+        if self.claim_fields.is_empty() {
+            return writeln!(f, "pub struct Claim {{}}");
+        }
+        writeln!(f, "pub struct Claim {{")?;
+        for (name, ty) in &self.claim_fields {
+            writeln!(f, "    pub {name}: {ty},")?;
+        }
+        writeln!(f, "}}")
+    }
+
+    fn write_mask_points(&self, f: &mut impl Write) -> fmt::Result {
+        writeln!(f, "pub fn mask_points(")?;
+        writeln!(f, "    ref preprocessed_column_set: PreprocessedColumnSet,")?;
+        writeln!(
+            f,
+            "    ref trace_mask_points: ColumnArray<Array<CirclePoint<QM31>>>,"
+        )?;
+        writeln!(
+            f,
+            "    ref interaction_trace_mask_points: ColumnArray<Array<CirclePoint<QM31>>>,"
+        )?;
+        writeln!(f, "    point: CirclePoint<QM31>,")?;
+        writeln!(f, "    trace_gen: CirclePointIndex,")?;
+        writeln!(f, "    log_size: u32,")?;
+        writeln!(f, ") {{")?;
+        for id in self.evaluator.preprocessed_columns.iter().unique() {
+            writeln!(
+                f,
+                "    preprocessed_column_set.insert({});",
+                cairo_preprocessed_column(id)
+            )?;
+        }
+        let offsets = self
+            .evaluator
+            .mask_offsets
+            .values()
+            .flatten()
+            .flat_map(|(_, offsets)| offsets)
+            .filter(|offset| **offset != 0)
+            .unique()
+            .sorted();
+        for offset in offsets {
+            let (name, sign) = match *offset < 0 {
+                true => (format!("neg_{}", -offset), "-"),
+                false => (offset.to_string(), ""),
+            };
+            writeln!(
+                f,
+                "    let point_offset_{name} = point.add_circle_point_m31({sign}trace_gen.mul({}).to_point());",
+                offset.abs()
+            )?;
+        }
+        for (interaction, mask_points) in [
+            (ORIGINAL_TRACE_IDX, "trace_mask_points"),
+            (INTERACTION_TRACE_IDX, "interaction_trace_mask_points"),
+        ] {
+            let columns = self.evaluator.mask_offsets.get(&interaction);
+            for (_, offsets) in columns.into_iter().flatten() {
+                let points = offsets.iter().map(|offset| match offset {
+                    0 => "point".to_string(),
+                    offset if *offset < 0 => format!("point_offset_neg_{}", -offset),
+                    offset => format!("point_offset_{offset}"),
+                });
+                writeln!(
+                    f,
+                    "    {mask_points}.append(array![{}]);",
+                    points.join(", ")
+                )?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+
+    fn write_mask_values(
+        &self,
+        f: &mut impl Write,
+        interaction: usize,
+        mask_values: &str,
+    ) -> fmt::Result {
+        let columns = self
+            .evaluator
+            .mask_offsets
+            .get(&interaction)
+            .cloned()
+            .unwrap_or_default();
+        let column_name = |column: usize| format!("trace_{interaction}_column_{column}");
+        writeln!(f, "    let [")?;
+        for (column, _) in &columns {
+            writeln!(f, "        {},", column_name(*column))?;
+        }
+        writeln!(
+            f,
+            "    ]: [Span<QM31>; {}] = (*{mask_values}.multi_pop_front().unwrap()).unbox();",
+            columns.len()
+        )?;
+        for (column, offsets) in columns {
+            let values = offsets.iter().map(|&offset| {
+                Expr::Col {
+                    interaction,
+                    column,
+                    offset,
+                }
+                .to_string()
+            });
+            writeln!(
+                f,
+                "    let [{}]: [QM31; {}] = (*{}.try_into().unwrap()).unbox();",
+                values.join(", "),
+                offsets.len(),
+                column_name(column)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_evaluate(&self, f: &mut impl Write, params: &[String]) -> fmt::Result {
+        writeln!(f, "pub fn evaluate_constraints_at_point(")?;
+        writeln!(f, "    ref sum: QM31,")?;
+        writeln!(f, "    ref trace_mask_values: ColumnSpan<Span<QM31>>,")?;
+        writeln!(
+            f,
+            "    ref interaction_mask_values: ColumnSpan<Span<QM31>>,"
+        )?;
+        writeln!(f, "    params: ConstraintParams,")?;
+        writeln!(f, "    random_coeff: QM31,")?;
+        writeln!(f, "    domain_vanish_at_point_inv: QM31,")?;
+        writeln!(f, ") {{")?;
+        writeln!(
+            f,
+            "    let ConstraintParams {{ {} }} = params;",
+            params.join(", ")
+        )?;
+        self.write_mask_values(f, ORIGINAL_TRACE_IDX, "trace_mask_values")?;
+        self.write_mask_values(f, INTERACTION_TRACE_IDX, "interaction_mask_values")?;
+        writeln!(f, "    core::internal::revoke_ap_tracking();")?;
+        for (i, intermediate) in self.evaluator.intermediates.iter().enumerate() {
+            writeln!(
+                f,
+                "    let intermediate{i} = intermediate{i}({});",
+                variables(intermediate).join(", ")
+            )?;
+        }
+        for (i, constraint) in self.evaluator.constraints.iter().enumerate() {
+            writeln!(f)?;
+            writeln!(f, "    // Constraint {i}")?;
+            writeln!(
+                f,
+                "    let constraint_quotient = ({constraint}) * domain_vanish_at_point_inv;"
+            )?;
+            writeln!(f, "    sum = sum * random_coeff + constraint_quotient;")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// The variables an expression depends on, in sorted order.
+fn variables(expr: &Expr) -> Vec<String> {
+    let mut variables = BTreeSet::new();
+    expr.collect_variables(&mut variables);
+    variables.into_iter().collect()
+}
+
+impl fmt::Display for CairoModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "// This file is generated from the Rust AIR by `cairo_air::verifier_codegen`. Do not edit.\n\
+             // To regenerate, run `FIX=1 cargo test -p cairo-air test_verifier_constraints`.\n"
+        )?;
+        writeln!(
+            f,
+            "use stwo_constraint_framework::{{PreprocessedColumn, PreprocessedColumnSet, PreprocessedColumnSetImpl}};"
+        )?;
+        writeln!(
+            f,
+            "use stwo_verifier_core::circle::{{CirclePoint, CirclePointIndex, CirclePointIndexImpl, CirclePointQM31AddCirclePointM31Impl}};"
+        )?;
+        writeln!(f, "use stwo_verifier_core::fields::Invertible;")?;
+        writeln!(f, "use stwo_verifier_core::fields::m31::{{M31, m31}};")?;
+        writeln!(
+            f,
+            "use stwo_verifier_core::fields::qm31::{{QM31, QM31Trait, qm31_const}};"
+        )?;
+        writeln!(f, "use stwo_verifier_core::{{ColumnArray, ColumnSpan}};\n")?;
+
+        writeln!(
+            f,
+            "pub const N_TRACE_COLUMNS: usize = {};",
+            self.n_columns(ORIGINAL_TRACE_IDX)
+        )?;
+        writeln!(
+            f,
+            "pub const N_INTERACTION_COLUMNS: usize = {};\n",
+            self.n_columns(INTERACTION_TRACE_IDX)
+        )?;
+        self.write_claim(f)?;
+        writeln!(f)?;
+        self.write_mask_points(f)?;
+        writeln!(f)?;
+
+        let params = self.params();
+        writeln!(f, "#[derive(Drop)]")?;
+        writeln!(f, "pub struct ConstraintParams {{")?;
+        for param in &params {
+            let ty = match param.as_str() {
+                "column_size" => "M31",
+                _ => "QM31",
+            };
+            writeln!(f, "    pub {param}: {ty},")?;
+        }
+        writeln!(f, "}}\n")?;
+        self.write_evaluate(f, &params)?;
+
+        for (i, intermediate) in self.evaluator.intermediates.iter().enumerate() {
+            writeln!(f)?;
+            let args = variables(intermediate)
+                .into_iter()
+                .map(|variable| format!("{variable}: QM31"));
+            writeln!(
+                f,
+                "pub fn intermediate{i}({}) -> QM31 {{",
+                args.collect_vec().join(", ")
+            )?;
+            writeln!(f, "    {intermediate}")?;
+            writeln!(f, "}}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalizes Cairo code for comparison, ignoring whitespace and trailing commas, so the
+/// generated code may be formatted with `scarb fmt`.
+pub fn normalize_cairo_code(code: &str) -> String {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    code.replace(",)", ")")
+        .replace(",]", "]")
+        .replace(",}", "}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use stwo_prover::core::fields::m31::M31;
+
+    use super::*;
+
+    #[test]
+    fn test_simplify_and_format() {
+        let col = || Expr::Col {
+            interaction: ORIGINAL_TRACE_IDX,
+            column: 3,
+            offset: -1,
+        };
+        let expr = (Expr::one() * col() + Expr::zero())
+            * (Expr::from(M31::from_u32_unchecked(2)) - Expr::Param("seq".to_string()))
+            - -col();
+
+        assert_eq!(
+            expr.simplify().to_string(),
+            "trace_1_column_3_offset_neg_1 * (qm31_const::<2, 0, 0, 0>() - seq) - (-trace_1_column_3_offset_neg_1)"
+        );
+    }
+
+    #[test]
+    fn test_relation_values() {
+        let values = [
+            Expr::Param("a".to_string()),
+            Expr::Param("b".to_string()) + Expr::one(),
+        ];
+        let combination = values.iter().fold(Expr::zero(), |acc, value| {
+            acc + Expr::from(SecureField::one()) * value.clone()
+        }) - Expr::from(SecureField::one());
+
+        assert_eq!(relation_values(combination), values);
+    }
+
+    #[test]
+    fn test_cairo_preprocessed_column() {
+        assert_eq!(
+            cairo_preprocessed_column("range_check_4_4_column_1"),
+            "PreprocessedColumn::RangeCheck2(([4, 4], 1))"
+        );
+        assert_eq!(
+            cairo_preprocessed_column("bitwise_xor_10_2"),
+            "PreprocessedColumn::BitwiseXor((10, 2))"
+        );
+        assert_eq!(
+            cairo_preprocessed_column("seq_17"),
+            "PreprocessedColumn::Seq(log_size)"
+        );
+    }
+
+    /// Fails if the verifier's generated constraints are missing or differ from the Rust AIR. To
+    /// update them, run the test with FIX=1.
+    #[test]
+    fn test_verifier_constraints() {
+        let components_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../stwo_cairo_verifier/crates/cairo_air/src/components");
+        let is_fix_mode = std::env::var("FIX") == Ok("1".to_string());
+
+        let mut outdated = vec![];
+        for (path, code) in cairo_verifier_constraints() {
+            let path = components_dir.join(path);
+            if is_fix_mode {
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, &code).unwrap();
+            }
+            let is_outdated = std::fs::read_to_string(&path).map_or(true, |expected| {
+                normalize_cairo_code(&code) != normalize_cairo_code(&expected)
+            });
+            if is_outdated {
+                outdated.push(path.display().to_string());
+            }
+        }
+
+        assert!(
+            outdated.is_empty(),
+            "The verifier's constraints are missing or differ from the Rust AIR: {outdated:?}. \
+             To update them, run the test with FIX=1."
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use cairo_air::air_description::air_description;
+use clap::Parser;
+use dev_utils::utils::Error;
+
+// Command line arguments for 'air_description'.
+/// Prints a JSON description of the Cairo AIR: the relations, and every component's columns,
+/// relation uses and constraint degrees.
+///
+/// Example command line:
+///     ```
+///     cargo run --bin air_description -- --output path/to/air_description.json
+///     ```
+#[derive(Parser, Debug)]
+struct Args {
+    /// The path to write the description to. Printed to stdout if not given.
+    #[structopt(long = "output")]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::try_parse_from(std::env::args())?;
+
+    let description = sonic_rs::to_string_pretty(&air_description())?;
+    match args.output {
+        Some(path) => std::fs::write(path, description)?,
+        None => println!("{description}"),
+    }
+    Ok(())
+}
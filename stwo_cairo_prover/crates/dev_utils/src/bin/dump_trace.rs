@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use dev_utils::utils::{dump_trace, Error};
+use stwo_cairo_adapter::test_utils::{read_compiled_cairo_program, run_program_and_adapter};
+use tracing::{span, Level};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+// Command line arguments for 'dump_trace'.
+/// Runs witness generation without proving, and writes the traces of the selected components as
+/// CSV files, with a header of the column names.
+///
+/// Example command line:
+///     ```
+///     cargo run --bin dump_trace -- --compiled_program absolute/path/to/compiled.json
+///     --output_dir path/to/traces --components mul_opcode,partial_ec_mul --pc_range 10 20
+///     ```
+#[derive(Parser, Debug)]
+struct Args {
+    #[structopt(long = "compiled_program")]
+    compiled_program: PathBuf,
+    /// The path to the JSON file containing the prover parameters (optional). See
+    /// `prove_from_compiled_program` for the expected format. Only the preprocessed trace is used.
+    #[structopt(long = "params_json")]
+    params_json: Option<PathBuf>,
+    /// The directory to write the CSV files to, one file per component.
+    #[structopt(long = "output_dir")]
+    output_dir: PathBuf,
+    /// Comma separated component names, ex. `mul_opcode,partial_ec_mul`. All components are
+    /// dumped if not given.
+    #[arg(long, value_delimiter = ',')]
+    components: Vec<String>,
+    /// Only dump the opcode rows that executed a pc in `[START, END)`. Doesn't filter non-opcode
+    /// components.
+    #[arg(long, num_args = 2, value_names = ["START", "END"])]
+    pc_range: Option<Vec<u32>>,
+}
+
+fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_span_events(FmtSpan::ENTER | FmtSpan::CLOSE)
+        .init();
+
+    let _span = span!(Level::INFO, "run").entered();
+    let args = Args::try_parse_from(std::env::args())?;
+
+    let compiled_program = read_compiled_cairo_program(&args.compiled_program);
+    let input = run_program_and_adapter(&compiled_program);
+
+    dump_trace(
+        input,
+        args.params_json,
+        &args.components,
+        args.pc_range.map(|range| range[0]..range[1]),
+        &args.output_dir,
+    )
+}
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use cairo_air::utils::ProofFormat;
 use clap::Parser;
-use dev_utils::utils::{create_and_serialize_proof, Error};
+use dev_utils::utils::{create_and_serialize_proof, diagnose_relations, Error};
 use stwo_cairo_adapter::test_utils::{read_compiled_cairo_program, run_program_and_adapter};
 use tracing::{span, Level};
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -46,8 +46,8 @@ struct Args {
     #[structopt(long = "params_json")]
     params_json: Option<PathBuf>,
     /// The output file path for the proof.
-    #[structopt(long = "proof_path")]
-    proof_path: PathBuf,
+    #[structopt(long = "proof_path", required_unless_present = "diagnose_relations")]
+    proof_path: Option<PathBuf>,
     /// The format of the proof output.
     /// - json: Standard JSON format (default)
     /// - cairo_serde: Array of field elements serialized as hex strings, ex. `["0x1", "0x2"]`
@@ -56,6 +56,10 @@ struct Args {
     /// Verify the generated proof.
     #[structopt(long = "verify")]
     verify: bool,
+    /// Generate the trace without proving, and report the relation tuples that aren't balanced,
+    /// along with the components and rows using them. Fails if any relation is unbalanced.
+    #[structopt(long = "diagnose_relations")]
+    diagnose_relations: bool,
 }
 
 fn main() -> Result<(), Error> {
@@ -69,10 +73,15 @@ fn main() -> Result<(), Error> {
     let compiled_program = read_compiled_cairo_program(&args.compiled_program);
     let input = run_program_and_adapter(&compiled_program);
 
+    if args.diagnose_relations {
+        return diagnose_relations(input, args.params_json);
+    }
+
     create_and_serialize_proof(
         input,
         args.verify,
-        args.proof_path,
+        args.proof_path
+            .expect("The proof path is required unless diagnosing relations"),
         args.proof_format,
         args.params_json,
     )
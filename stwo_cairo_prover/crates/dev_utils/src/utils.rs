@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 use cairo_air::utils::{serialize_proof_to_file, ProofFormat};
 use cairo_air::verifier::{verify_cairo, CairoVerificationError};
@@ -6,6 +10,9 @@ use cairo_air::PreProcessedTraceVariant;
 use serde::Serialize;
 use stwo_cairo_adapter::vm_import::VmImportError;
 use stwo_cairo_adapter::ProverInput;
+use stwo_cairo_prover::debug_tools::relation_diagnostics::diagnose_cairo_relations;
+use stwo_cairo_prover::debug_tools::trace_dump::cairo_component_traces;
+use stwo_cairo_prover::hooks::CairoProvingError;
 use stwo_cairo_prover::prover::{
     default_prod_prover_parameters, prove_cairo, ChannelHash, ProverParameters,
 };
@@ -14,7 +21,6 @@ use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::backend::BackendForChannel;
 use stwo_prover::core::channel::MerkleChannel;
 use stwo_prover::core::pcs::PcsConfig;
-use stwo_prover::core::prover::ProvingError;
 use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
 use stwo_prover::core::vcs::ops::MerkleHasher;
 use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleChannel;
@@ -27,13 +33,17 @@ pub enum Error {
     #[error("IO failed: {0}")]
     IO(#[from] std::io::Error),
     #[error("Proving failed: {0}")]
-    Proving(#[from] ProvingError),
+    Proving(#[from] CairoProvingError),
     #[error("Serialization failed: {0}")]
     Serializing(#[from] sonic_rs::error::Error),
     #[error("Verification failed: {0}")]
     Verification(#[from] CairoVerificationError),
     #[error("VM import failed: {0}")]
     VmImport(#[from] VmImportError),
+    #[error("Unbalanced relations")]
+    UnbalancedRelations,
+    #[error("Unknown component: {0}")]
+    UnknownComponent(String),
 }
 
 /// Generates proof given the Cairo VM output and prover config/parameters.
@@ -75,10 +85,7 @@ pub fn create_and_serialize_proof(
         channel_hash,
         pcs_config,
         preprocessed_trace,
-    } = match proof_params_json {
-        Some(path) => sonic_rs::from_str(&std::fs::read_to_string(&path)?)?,
-        None => default_prod_prover_parameters(),
-    };
+    } = read_prover_parameters(proof_params_json)?;
 
     let create_and_serialize_generic_proof: fn(
         ProverInput,
@@ -103,3 +110,71 @@ pub fn create_and_serialize_proof(
 
     Ok(())
 }
+
+/// Generates the trace without proving, and logs the relation tuples that aren't balanced.
+/// Fails if any relation is unbalanced.
+pub fn diagnose_relations(
+    input: ProverInput,
+    proof_params_json: Option<PathBuf>,
+) -> Result<(), Error> {
+    let ProverParameters {
+        preprocessed_trace, ..
+    } = read_prover_parameters(proof_params_json)?;
+
+    let diagnostics = diagnose_cairo_relations(input, preprocessed_trace);
+    log::info!("Relation diagnostics:\n{diagnostics}");
+    match diagnostics.is_balanced() {
+        true => Ok(()),
+        false => Err(Error::UnbalancedRelations),
+    }
+}
+
+/// Generates the trace without proving, and writes the traces of the given components (or all
+/// of them, if empty) as CSV files to `output_dir`, one file per component. Opcode components'
+/// rows are filtered by `pc_range`, if given.
+pub fn dump_trace(
+    input: ProverInput,
+    proof_params_json: Option<PathBuf>,
+    components: &[String],
+    pc_range: Option<Range<u32>>,
+    output_dir: &Path,
+) -> Result<(), Error> {
+    let ProverParameters {
+        preprocessed_trace, ..
+    } = read_prover_parameters(proof_params_json)?;
+
+    let traces = cairo_component_traces(input, preprocessed_trace);
+    if let Some(unknown) = components
+        .iter()
+        .find(|&name| !traces.iter().any(|trace| trace.component == name))
+    {
+        return Err(Error::UnknownComponent(unknown.clone()));
+    }
+
+    std::fs::create_dir_all(output_dir)?;
+    let mut index_by_component = HashMap::<&str, usize>::new();
+    for trace in &traces {
+        // Components split to several instances (e.g. `memory_id_to_big`) are suffixed by index.
+        let index = index_by_component.entry(trace.component).or_default();
+        let file_name = match *index {
+            0 => format!("{}.csv", trace.component),
+            i => format!("{}_{i}.csv", trace.component),
+        };
+        *index += 1;
+        if !components.is_empty() && !components.iter().any(|name| name == trace.component) {
+            continue;
+        }
+
+        let path = output_dir.join(file_name);
+        trace.write_csv(BufWriter::new(File::create(&path)?), pc_range.clone())?;
+        log::info!("Wrote {} to {}", trace.component, path.display());
+    }
+    Ok(())
+}
+
+fn read_prover_parameters(proof_params_json: Option<PathBuf>) -> Result<ProverParameters, Error> {
+    Ok(match proof_params_json {
+        Some(path) => sonic_rs::from_str(&std::fs::read_to_string(&path)?)?,
+        None => default_prod_prover_parameters(),
+    })
+}
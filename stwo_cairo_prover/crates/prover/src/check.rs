@@ -0,0 +1,108 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use cairo_air::component_registry::ComponentRegistry;
+use cairo_air::verifier::verify_cairo_claims;
+use num_traits::Zero;
+use stwo_cairo_adapter::ProverInput;
+use stwo_prover::core::fields::qm31::SecureField;
+use tracing::{span, Level};
+
+use crate::debug_tools::constraint_checker::{check_cairo_trace, ConstraintReport};
+use crate::debug_tools::mock_tree_builder::MockPreProcessedTrace;
+
+/// The result of [`check_cairo`].
+#[derive(Debug, Clone)]
+pub struct CairoCheckReport {
+    /// The error of the verifier's claim checks, if the claim is invalid (e.g. uses an
+    /// unsupported builtin).
+    pub claim_error: Option<String>,
+    pub constraints: ConstraintReport,
+    pub lookup_sum: SecureField,
+    pub duration: Duration,
+}
+impl CairoCheckReport {
+    /// Whether the input is provable.
+    pub fn is_ok(&self) -> bool {
+        self.claim_error.is_none() && self.constraints.is_ok() && self.lookup_sum.is_zero()
+    }
+}
+
+impl fmt::Display for CairoCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_ok() {
+            true => writeln!(f, "Check passed in {:.2?}.", self.duration)?,
+            false => writeln!(f, "Check failed in {:.2?}.", self.duration)?,
+        }
+        if let Some(error) = &self.claim_error {
+            writeln!(f, "The claim is invalid: {error}")?;
+        }
+        write!(f, "{}", self.constraints)?;
+        match self.lookup_sum.is_zero() {
+            true => writeln!(f, "The lookup sum is zero."),
+            false => writeln!(f, "The lookup sum is not zero: {}", self.lookup_sum),
+        }
+    }
+}
+
+/// Checks that the input is provable without producing a STARK: generates the traces without
+/// committing to them, then runs the verifier's claim checks, and checks all constraints and that
+/// the lookup sum is zero.
+///
+/// This is much faster than [`crate::prover::prove_cairo`], and catches the same failures, but
+/// with a report of the failing components and rows.
+pub fn check_cairo<'a>(
+    input: ProverInput,
+    preprocessed_trace: impl Into<MockPreProcessedTrace<'a>>,
+) -> CairoCheckReport {
+    let _span = span!(Level::INFO, "check_cairo").entered();
+    let start = Instant::now();
+    let (constraints, traces) = check_cairo_trace(input, preprocessed_trace);
+    let claim_error = verify_cairo_claims(
+        &traces.claim,
+        &traces.interaction_claim,
+        &ComponentRegistry::new(),
+    )
+    .err()
+    .map(|error| error.to_string());
+
+    CairoCheckReport {
+        claim_error,
+        constraints,
+        lookup_sum: traces.lookup_sum(),
+        duration: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use num_traits::One;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+    use stwo_prover::core::fields::m31::M31;
+
+    use super::*;
+
+    #[test]
+    fn test_check_cairo() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+
+        let report = check_cairo(input, &testing_preprocessed_tree(20));
+
+        assert!(report.is_ok(), "{report}");
+    }
+
+    #[test]
+    fn test_check_cairo_unbalanced() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let mut input = run_program_and_adapter(&compiled_program);
+        input.state_transitions.final_state.fp += M31::one();
+
+        let report = check_cairo(input, &testing_preprocessed_tree(20));
+
+        assert!(!report.is_ok());
+        assert!(report.claim_error.is_none());
+        assert!(!report.lookup_sum.is_zero());
+    }
+}
@@ -0,0 +1,554 @@
+//! Estimates the cost of proving a Cairo execution without running the prover.
+//!
+//! The component sizes are derived from [`ExecutionResources`] exactly as the witness generators
+//! derive them, so [`claim_log_sizes`] matches [`CairoClaim::log_sizes`] of the proof that would
+//! be generated. Proof size and prove time are estimates.
+//!
+//! [`CairoClaim::log_sizes`]: cairo_air::air::CairoClaim::log_sizes
+
+use std::time::Duration;
+
+use cairo_air::air::{accumulate_relation_uses, is_looked_up, RelationUsesDict};
+use cairo_air::blake::air::{BlakeContextClaim, Claim as BlakeClaim};
+use cairo_air::builtins_air::BuiltinsClaim;
+use cairo_air::components::memory_address_to_id::MEMORY_ADDRESS_TO_ID_SPLIT;
+use cairo_air::components::{
+    add_ap_opcode, add_mod_builtin, add_opcode, add_opcode_small, assert_eq_opcode,
+    assert_eq_opcode_double_deref, assert_eq_opcode_imm, bitwise_builtin, blake_compress_opcode,
+    blake_g, blake_round, blake_round_sigma, call_opcode, call_opcode_rel_imm, cube_252,
+    generic_opcode, jnz_opcode, jnz_opcode_taken, jump_opcode, jump_opcode_double_deref,
+    jump_opcode_rel, jump_opcode_rel_imm, memory_address_to_id, memory_id_to_big, mul_mod_builtin,
+    mul_opcode, mul_opcode_small, partial_ec_mul, pedersen_builtin, pedersen_points_table,
+    poseidon_3_partial_rounds_chain, poseidon_builtin, poseidon_full_round_chain,
+    poseidon_round_keys, qm_31_add_mul_opcode, range_check_builtin_bits_128,
+    range_check_builtin_bits_96, range_check_felt_252_width_27, ret_opcode, triple_xor_32,
+    verify_bitwise_xor_12, verify_bitwise_xor_4, verify_bitwise_xor_7, verify_bitwise_xor_8,
+    verify_bitwise_xor_9, verify_instruction,
+};
+use cairo_air::opcodes_air::OpcodeClaim;
+use cairo_air::pedersen::air::{Claim as PedersenClaim, PedersenContextClaim};
+use cairo_air::poseidon::air::{Claim as PoseidonClaim, PoseidonContextClaim};
+use cairo_air::preprocessed::{PreProcessedTrace, MAX_SEQUENCE_LOG_SIZE};
+use cairo_air::range_checks_air::RangeChecksClaim;
+use cairo_vm::types::builtin_name::BuiltinName;
+use itertools::{chain, Itertools};
+use serde::{Deserialize, Serialize};
+use stwo_cairo_adapter::{opcode_components, ExecutionResources, ProverInput};
+use stwo_prover::constraint_framework::PREPROCESSED_TRACE_IDX;
+use stwo_prover::core::backend::simd::m31::N_LANES;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::pcs::{PcsConfig, TreeVec};
+
+use crate::report::ProvingReport;
+use crate::witness::utils::tree_trace_cells;
+
+/// Size in bytes of a base field element.
+const M31_BYTES: usize = 4;
+/// Size in bytes of a secure field element.
+const QM31_BYTES: usize = SECURE_EXTENSION_DEGREE * M31_BYTES;
+/// Size in bytes of a Merkle hash.
+const HASH_BYTES: usize = 32;
+/// Number of columns of the composition polynomial.
+const N_COMPOSITION_COLUMNS: usize = 2 * SECURE_EXTENSION_DEGREE;
+
+/// Blake rounds fed by every blake compression.
+pub(crate) const BLAKE_ROUNDS_PER_COMPRESS: usize = 10;
+/// G functions fed by every blake round.
+pub(crate) const BLAKE_G_PER_ROUND: usize = 8;
+/// Triple xors fed by every blake compression.
+pub(crate) const TRIPLE_XORS_PER_COMPRESS: usize = 8;
+/// Partial EC multiplications fed by every pedersen instance.
+pub(crate) const PARTIAL_EC_MULS_PER_PEDERSEN: usize = 30;
+/// Partial round chains fed by every poseidon instance.
+pub(crate) const PARTIAL_ROUND_CHAINS_PER_POSEIDON: usize = 27;
+/// Full round chains fed by every poseidon instance.
+pub(crate) const FULL_ROUND_CHAINS_PER_POSEIDON: usize = 8;
+
+/// Log size of a component with `n_rows` instances, padded as the witness generators pad.
+fn padded_log_size(n_rows: usize) -> u32 {
+    std::cmp::max(n_rows.next_power_of_two(), N_LANES).ilog2()
+}
+
+/// Log size of a component that receives `uses_per_row` inputs from every (padded) row of a
+/// component of size `1 << parent_log_size`.
+fn fed_log_size(parent_log_size: u32, uses_per_row: usize) -> u32 {
+    parent_log_size + uses_per_row.next_power_of_two().ilog2()
+}
+
+fn opcode_claim(resources: &ExecutionResources) -> OpcodeClaim {
+    let log_size = |name: &str| {
+        resources
+            .opcode_instance_counts
+            .get(name)
+            .copied()
+            .filter(|&n_instances| n_instances > 0)
+            .map(padded_log_size)
+    };
+    macro_rules! opcode_claim {
+        ($($field:ident: $module:ident,)*) => {
+            OpcodeClaim {
+                $(
+                    $field: log_size(stringify!($module))
+                        .map(|log_size| $module::Claim { log_size })
+                        .into_iter()
+                        .collect_vec(),
+                )*
+            }
+        };
+    }
+    opcode_components!(opcode_claim)
+}
+
+/// Log size of a builtin component. Builtin segments are padded to a power of two by the
+/// adapter, hence the number of instances is already the number of rows.
+fn builtin_log_size(resources: &ExecutionResources, builtin: BuiltinName) -> Option<u32> {
+    resources
+        .builtin_instance_counts
+        .get(&builtin)
+        .copied()
+        .filter(|&n_instances| n_instances > 0)
+        .map(|n_instances| n_instances.ilog2())
+}
+
+fn builtins_claim(resources: &ExecutionResources) -> BuiltinsClaim {
+    let log_size = |builtin| builtin_log_size(resources, builtin);
+    BuiltinsClaim {
+        add_mod_builtin: log_size(BuiltinName::add_mod).map(|log_size| add_mod_builtin::Claim {
+            log_size,
+            add_mod_builtin_segment_start: 0,
+        }),
+        bitwise_builtin: log_size(BuiltinName::bitwise).map(|log_size| bitwise_builtin::Claim {
+            log_size,
+            bitwise_builtin_segment_start: 0,
+        }),
+        mul_mod_builtin: log_size(BuiltinName::mul_mod).map(|log_size| mul_mod_builtin::Claim {
+            log_size,
+            mul_mod_builtin_segment_start: 0,
+        }),
+        pedersen_builtin: log_size(BuiltinName::pedersen).map(|log_size| pedersen_builtin::Claim {
+            log_size,
+            pedersen_builtin_segment_start: 0,
+        }),
+        poseidon_builtin: log_size(BuiltinName::poseidon).map(|log_size| poseidon_builtin::Claim {
+            log_size,
+            poseidon_builtin_segment_start: 0,
+        }),
+        range_check_96_builtin: log_size(BuiltinName::range_check96).map(|log_size| {
+            range_check_builtin_bits_96::Claim {
+                log_size,
+                range_check96_builtin_segment_start: 0,
+            }
+        }),
+        range_check_128_builtin: log_size(BuiltinName::range_check).map(|log_size| {
+            range_check_builtin_bits_128::Claim {
+                log_size,
+                range_check_builtin_segment_start: 0,
+            }
+        }),
+    }
+}
+
+fn blake_context_claim(opcodes: &OpcodeClaim) -> BlakeContextClaim {
+    let claim = opcodes.blake.first().map(|blake| {
+        let blake_round_log_size = fed_log_size(blake.log_size, BLAKE_ROUNDS_PER_COMPRESS);
+        BlakeClaim {
+            blake_round: blake_round::Claim {
+                log_size: blake_round_log_size,
+            },
+            blake_g: blake_g::Claim {
+                log_size: fed_log_size(blake_round_log_size, BLAKE_G_PER_ROUND),
+            },
+            blake_sigma: blake_round_sigma::Claim {},
+            triple_xor_32: triple_xor_32::Claim {
+                log_size: fed_log_size(blake.log_size, TRIPLE_XORS_PER_COMPRESS),
+            },
+            verify_bitwise_xor_12: verify_bitwise_xor_12::Claim {},
+        }
+    });
+    BlakeContextClaim { claim }
+}
+
+fn pedersen_context_claim(builtins: &BuiltinsClaim) -> PedersenContextClaim {
+    let claim = builtins.pedersen_builtin.map(|pedersen| PedersenClaim {
+        partial_ec_mul: partial_ec_mul::Claim {
+            log_size: fed_log_size(pedersen.log_size, PARTIAL_EC_MULS_PER_PEDERSEN),
+        },
+        pedersen_points_table: pedersen_points_table::Claim {},
+    });
+    PedersenContextClaim { claim }
+}
+
+fn poseidon_context_claim(builtins: &BuiltinsClaim) -> PoseidonContextClaim {
+    let claim = builtins.poseidon_builtin.map(|poseidon| {
+        let n_builtin_rows = 1 << poseidon.log_size;
+        let partial_rounds_log_size =
+            fed_log_size(poseidon.log_size, PARTIAL_ROUND_CHAINS_PER_POSEIDON);
+        let full_rounds_log_size = fed_log_size(poseidon.log_size, FULL_ROUND_CHAINS_PER_POSEIDON);
+        // `cube_252` is fed by the builtin and by both round chains.
+        let n_cube_252_rows = 2 * n_builtin_rows
+            + 3 * (1 << partial_rounds_log_size)
+            + 3 * (1 << full_rounds_log_size);
+        // `range_check_felt_252_width_27` is fed by the builtin and the partial round chain.
+        let n_range_check_felt_252_rows = 2 * n_builtin_rows + 3 * (1 << partial_rounds_log_size);
+        PoseidonClaim {
+            poseidon_3_partial_rounds_chain: poseidon_3_partial_rounds_chain::Claim {
+                log_size: partial_rounds_log_size,
+            },
+            poseidon_full_round_chain: poseidon_full_round_chain::Claim {
+                log_size: full_rounds_log_size,
+            },
+            cube_252: cube_252::Claim {
+                log_size: padded_log_size(n_cube_252_rows),
+            },
+            poseidon_round_keys: poseidon_round_keys::Claim {},
+            range_check_felt_252_width_27: range_check_felt_252_width_27::Claim {
+                log_size: padded_log_size(n_range_check_felt_252_rows),
+            },
+        }
+    });
+    PoseidonContextClaim { claim }
+}
+
+fn memory_claims(
+    resources: &ExecutionResources,
+) -> (memory_address_to_id::Claim, memory_id_to_big::Claim) {
+    let sizes = &resources.memory_tables_sizes;
+
+    // Address 0 is not part of the table.
+    let n_addresses = sizes.address_to_id.saturating_sub(1);
+    let memory_address_to_id = memory_address_to_id::Claim {
+        log_size: padded_log_size(n_addresses.div_ceil(MEMORY_ADDRESS_TO_ID_SPLIT)),
+    };
+
+    // The big values table is split to components of at most `1 << MAX_SEQUENCE_LOG_SIZE` rows.
+    let big_log_size = padded_log_size(sizes.id_to_big);
+    let n_big_components = 1 << big_log_size.saturating_sub(MAX_SEQUENCE_LOG_SIZE);
+    let memory_id_to_value = memory_id_to_big::Claim {
+        big_log_sizes: vec![std::cmp::min(big_log_size, MAX_SEQUENCE_LOG_SIZE); n_big_components],
+        small_log_size: padded_log_size(sizes.id_to_small),
+    };
+
+    (memory_address_to_id, memory_id_to_value)
+}
+
+/// Returns the log sizes the [`cairo_air::air::CairoClaim`] of an execution with the given
+/// resources would have. The order of the components matches
+/// [`cairo_air::air::CairoClaim::log_sizes`].
+pub fn claim_log_sizes(resources: &ExecutionResources) -> TreeVec<Vec<u32>> {
+    let opcodes = opcode_claim(resources);
+    let blake_context = blake_context_claim(&opcodes);
+    let builtins = builtins_claim(resources);
+    let pedersen_context = pedersen_context_claim(&builtins);
+    let poseidon_context = poseidon_context_claim(&builtins);
+    let (memory_address_to_id, memory_id_to_value) = memory_claims(resources);
+
+    let mut relation_uses = RelationUsesDict::default();
+    opcodes.accumulate_relation_uses(&mut relation_uses);
+    blake_context.accumulate_relation_uses(&mut relation_uses);
+    builtins.accumulate_relation_uses(&mut relation_uses);
+    pedersen_context.accumulate_relation_uses(&mut relation_uses);
+    poseidon_context.accumulate_relation_uses(&mut relation_uses);
+    for &log_size in &memory_id_to_value.big_log_sizes {
+        accumulate_relation_uses(
+            &mut relation_uses,
+            memory_id_to_big::RELATION_USES_PER_ROW_BIG,
+            log_size,
+        );
+    }
+    accumulate_relation_uses(
+        &mut relation_uses,
+        memory_id_to_big::RELATION_USES_PER_ROW_SMALL,
+        memory_id_to_value.small_log_size,
+    );
+    let verify_instruction =
+        is_looked_up(&relation_uses, "VerifyInstruction").then(|| verify_instruction::Claim {
+            log_size: padded_log_size(resources.verify_instructions_count),
+        });
+    if let Some(verify_instruction) = verify_instruction {
+        accumulate_relation_uses(
+            &mut relation_uses,
+            verify_instruction::RELATION_USES_PER_ROW,
+            verify_instruction.log_size,
+        );
+    }
+    let xor_log_sizes = |relation_id: &str, log_sizes: TreeVec<Vec<u32>>| {
+        is_looked_up(&relation_uses, relation_id).then_some(log_sizes)
+    };
+
+    TreeVec::concat_cols(chain!(
+        [opcodes.log_sizes()],
+        verify_instruction.map(|verify_instruction| verify_instruction.log_sizes()),
+        [
+            blake_context.log_sizes(),
+            builtins.log_sizes(),
+            pedersen_context.log_sizes(),
+            poseidon_context.log_sizes(),
+            memory_address_to_id.log_sizes(),
+            memory_id_to_value.log_sizes(),
+            RangeChecksClaim::looked_up(&relation_uses).log_sizes(),
+        ],
+        xor_log_sizes(
+            "VerifyBitwiseXor_4",
+            verify_bitwise_xor_4::Claim {}.log_sizes()
+        ),
+        xor_log_sizes(
+            "VerifyBitwiseXor_7",
+            verify_bitwise_xor_7::Claim {}.log_sizes()
+        ),
+        xor_log_sizes(
+            "VerifyBitwiseXor_8",
+            verify_bitwise_xor_8::Claim {}.log_sizes()
+        ),
+        xor_log_sizes(
+            "VerifyBitwiseXor_9",
+            verify_bitwise_xor_9::Claim {}.log_sizes()
+        ),
+    ))
+}
+
+/// Per-cell prove time constants, in nanoseconds.
+///
+/// The defaults are rough figures for a multi-core x86 machine. For accurate estimates, calibrate
+/// on the target machine with [`ProveTimeModel::calibrate`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ProveTimeModel {
+    /// Time to generate a single base trace cell.
+    pub base_trace_ns_per_cell: f64,
+    /// Time to generate a single interaction trace cell.
+    pub interaction_trace_ns_per_cell: f64,
+    /// Time to commit and prove a single trace cell, before blowup.
+    pub stark_ns_per_cell: f64,
+    /// Time that does not depend on the trace size (e.g. twiddles, preprocessed trace, PoW).
+    pub fixed: Duration,
+}
+impl Default for ProveTimeModel {
+    fn default() -> Self {
+        Self {
+            base_trace_ns_per_cell: 1.0,
+            interaction_trace_ns_per_cell: 4.0,
+            stark_ns_per_cell: 12.0,
+            fixed: Duration::from_secs(2),
+        }
+    }
+}
+impl ProveTimeModel {
+    /// Fits the per-cell constants to a report of a real `prove_cairo` run.
+    pub fn calibrate(report: &ProvingReport) -> Self {
+        let base_cells: u64 = report
+            .components
+            .iter()
+            .map(|c| c.n_rows() * c.n_trace_columns as u64)
+            .sum();
+        let interaction_cells: u64 = report
+            .components
+            .iter()
+            .map(|c| c.n_rows() * c.n_interaction_columns as u64)
+            .sum();
+        let ns_per_cell = |duration: Duration, n_cells: u64| {
+            duration.as_nanos() as f64 / std::cmp::max(n_cells, 1) as f64
+        };
+        let timings = &report.timings;
+        let fixed = timings
+            .total
+            .saturating_sub(timings.base_trace + timings.interaction_trace + timings.prove_stark);
+
+        Self {
+            base_trace_ns_per_cell: ns_per_cell(timings.base_trace, base_cells),
+            interaction_trace_ns_per_cell: ns_per_cell(
+                timings.interaction_trace,
+                interaction_cells,
+            ),
+            stark_ns_per_cell: ns_per_cell(timings.prove_stark, base_cells + interaction_cells),
+            fixed,
+        }
+    }
+
+    /// Estimates the prove time of a trace with the given number of cells per tree.
+    pub fn estimate(&self, trace_cells: &[u64]) -> Duration {
+        let base_cells = trace_cells[1] as f64;
+        let interaction_cells = trace_cells[2] as f64;
+        let ns = base_cells * self.base_trace_ns_per_cell
+            + interaction_cells * self.interaction_trace_ns_per_cell
+            + (base_cells + interaction_cells) * self.stark_ns_per_cell;
+        self.fixed + Duration::from_nanos(ns as u64)
+    }
+}
+
+/// Estimated proving cost of a Cairo execution.
+#[derive(Debug, Clone)]
+pub struct CostEstimate {
+    /// Exact log sizes of the claim, including the preprocessed trace.
+    pub log_sizes: TreeVec<Vec<u32>>,
+    /// Exact number of cells in each trace.
+    pub trace_cells: Vec<u64>,
+    /// Estimated proof size in bytes, ignoring Merkle decommitment deduplication.
+    pub proof_size_bytes: usize,
+    /// Estimated prove time.
+    pub prove_time: Duration,
+}
+impl CostEstimate {
+    pub fn total_trace_cells(&self) -> u64 {
+        self.trace_cells.iter().sum()
+    }
+}
+
+/// Estimates the cost of proving an execution with the given resources.
+pub fn estimate_cost(
+    resources: &ExecutionResources,
+    pcs_config: PcsConfig,
+    preprocessed_trace: &PreProcessedTrace,
+    time_model: &ProveTimeModel,
+) -> CostEstimate {
+    let mut log_sizes = claim_log_sizes(resources);
+    log_sizes[PREPROCESSED_TRACE_IDX] = preprocessed_trace.log_sizes();
+    let trace_cells = tree_trace_cells(log_sizes.clone());
+
+    CostEstimate {
+        proof_size_bytes: estimate_proof_size(&log_sizes, pcs_config),
+        prove_time: time_model.estimate(&trace_cells),
+        log_sizes,
+        trace_cells,
+    }
+}
+
+/// Estimates the cost of proving `input`, without generating any trace.
+pub fn estimate_cost_from_input(
+    input: &ProverInput,
+    pcs_config: PcsConfig,
+    preprocessed_trace: &PreProcessedTrace,
+    time_model: &ProveTimeModel,
+) -> CostEstimate {
+    estimate_cost(
+        &ExecutionResources::from_prover_input(input),
+        pcs_config,
+        preprocessed_trace,
+        time_model,
+    )
+}
+
+/// Estimates the size of a STARK proof over trees with the given column log sizes.
+/// Merkle authentication paths are counted in full for every query, so this is an upper bound
+/// on the decommitment size.
+fn estimate_proof_size(log_sizes: &TreeVec<Vec<u32>>, pcs_config: PcsConfig) -> usize {
+    let fri_config = pcs_config.fri_config;
+    let n_queries = fri_config.n_queries;
+    let log_blowup_factor = fri_config.log_blowup_factor;
+    let max_log_size = log_sizes
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or_default();
+    let composition_log_size = max_log_size + 1;
+
+    let trees = log_sizes
+        .iter()
+        .map(|tree| (tree.len(), tree.iter().copied().max().unwrap_or_default()))
+        .chain([(N_COMPOSITION_COLUMNS, composition_log_size)])
+        .collect_vec();
+    let n_columns: usize = trees.iter().map(|(n_columns, _)| n_columns).sum();
+
+    // Commitments, sampled values at the OODS point and queried values.
+    let commitments = trees.len() * HASH_BYTES;
+    let sampled_values = n_columns * QM31_BYTES;
+    let queried_values = n_queries * n_columns * M31_BYTES;
+    let decommitments: usize = trees
+        .iter()
+        .map(|&(_, log_size)| n_queries * (log_size + log_blowup_factor) as usize * HASH_BYTES)
+        .sum();
+
+    // FRI: a commitment, a decommitment and a sibling value per query for every folded layer.
+    let first_layer_log_size = composition_log_size + log_blowup_factor;
+    let last_layer_log_size = fri_config.log_last_layer_degree_bound + log_blowup_factor;
+    let fri_layers: usize = (last_layer_log_size + 1..=first_layer_log_size)
+        .map(|log_size| HASH_BYTES + n_queries * (log_size as usize * HASH_BYTES + QM31_BYTES))
+        .sum();
+    let last_layer = (1 << fri_config.log_last_layer_degree_bound) * QM31_BYTES;
+
+    // Proof of work nonces (interaction and FRI).
+    let pow = 2 * std::mem::size_of::<u64>();
+
+    commitments + sampled_values + queried_values + decommitments + fri_layers + last_layer + pow
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+    use stwo_prover::core::backend::simd::m31::LOG_N_LANES;
+
+    use super::*;
+    use crate::debug_tools::mock_tree_builder::mock_cairo_traces;
+
+    #[test]
+    fn test_fed_log_size() {
+        assert_eq!(fed_log_size(4, 1), 4);
+        assert_eq!(fed_log_size(4, 8), 7);
+        assert_eq!(fed_log_size(4, 10), 8);
+        assert_eq!(fed_log_size(5, 27), 10);
+    }
+
+    #[test]
+    fn test_padded_log_size() {
+        assert_eq!(padded_log_size(0), LOG_N_LANES);
+        assert_eq!(padded_log_size(1), LOG_N_LANES);
+        assert_eq!(padded_log_size(17), 5);
+        assert_eq!(padded_log_size(32), 5);
+    }
+
+    fn assert_claim_log_sizes_match_witness(test_name: &str) {
+        let input = run_program_and_adapter(&get_test_program(test_name));
+        let resources = ExecutionResources::from_prover_input(&input);
+
+        let claim = mock_cairo_traces(input, &testing_preprocessed_tree(20)).claim;
+
+        assert_eq!(
+            claim_log_sizes(&resources).0,
+            claim.log_sizes().0,
+            "{test_name}"
+        );
+    }
+
+    #[test]
+    fn test_claim_log_sizes_match_witness() {
+        assert_claim_log_sizes_match_witness("test_prove_verify_all_opcode_components");
+    }
+
+    #[cfg(feature = "slow-tests")]
+    #[test]
+    fn test_claim_log_sizes_match_witness_on_builtins() {
+        for test_name in [
+            "test_prove_verify_add_mod_builtin",
+            "test_prove_verify_bitwise_builtin",
+            "test_prove_verify_mul_mod_builtin",
+            "test_prove_verify_pedersen_builtin",
+            "test_prove_verify_poseidon_builtin",
+            "test_prove_verify_range_check_bits_96_builtin",
+            "test_prove_verify_range_check_bits_128_builtin",
+            "test_prove_verify_all_builtins",
+        ] {
+            assert_claim_log_sizes_match_witness(test_name);
+        }
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let preprocessed_trace = testing_preprocessed_tree(20);
+
+        let estimate = estimate_cost_from_input(
+            &input,
+            PcsConfig::default(),
+            &preprocessed_trace,
+            &ProveTimeModel::default(),
+        );
+
+        assert_eq!(
+            estimate.log_sizes[PREPROCESSED_TRACE_IDX],
+            preprocessed_trace.log_sizes()
+        );
+        assert!(estimate.total_trace_cells() > 0);
+        assert!(estimate.proof_size_bytes > 0);
+    }
+}
@@ -0,0 +1,987 @@
+//! A fuzzer of random CASM programs, checking the adapter and the Cairo AIR against the VM: every
+//! program the VM runs should be adapted, and its traces should satisfy the AIR's constraints and
+//! relations.
+//!
+//! Programs are sequences of [`Gadget`]s, short instruction sequences that are valid given the
+//! cells written so far. Together they cover every opcode shape of
+//! `CasmStatesByOpcode::push_instr`, including edge offsets, small/big operand boundaries and
+//! shapes only the generic opcode supports. Failing programs are shrunk to a
+//! minimal sequence of gadgets.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use cairo_air::preprocessed::PreProcessedTrace;
+use cairo_vm::Felt252;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use stwo_cairo_adapter::decode::{Instruction, OpcodeExtension};
+use stwo_cairo_adapter::test_utils::{
+    program_from_encoded, run_program_until_end, RunProgramError,
+};
+
+use crate::debug_tools::constraint_checker::check_cairo_constraints;
+use crate::debug_tools::relation_diagnostics::diagnose_cairo_relations;
+use crate::panic_utils::panic_message;
+
+/// The number of gadgets in a generated program.
+pub const N_GADGETS: usize = 24;
+
+/// Integers in `(-SMALL_BOUND, SMALL_BOUND)` are tracked by value.
+const SMALL_BOUND: i128 = 1 << 100;
+
+/// Values at the boundaries of the small add and mul opcodes, and of the instruction offsets.
+const BOUNDARY_VALUES: [i128; 14] = [
+    0,
+    1,
+    2,
+    -1,
+    (1 << 27) - 1,
+    1 << 27,
+    -(1 << 27),
+    -(1 << 27) - 1,
+    (1 << 36) - 1,
+    1 << 36,
+    (1 << 15) - 1,
+    1 << 63,
+    (1 << 72) - 1,
+    1 << 72,
+];
+
+/// The encoding of `ret`.
+const RET: u128 = 0x208b7fff7fff7ffe;
+
+/// The modulus of the coordinates of QM31 values.
+const M31_PRIME: u32 = 0x7fffffff;
+/// The number of bits of each coordinate in a packed QM31 value.
+const QM31_COORDINATE_BITS: u32 = 36;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GadgetKind {
+    /// `[ap] = imm, ap++`, through ap or fp.
+    AssertImm,
+    /// `[ap + offset] = imm`, far from ap.
+    FarAssertImm,
+    /// `[ap] = [ap/fp + offset], ap++`.
+    Copy,
+    /// `[ap] = [[ap/fp + offset] + offset], ap++`.
+    DoubleDeref,
+    /// `[ap] = [ap/fp + offset] + [ap/fp + offset]/imm, ap++`.
+    Add,
+    /// `[ap] = [ap/fp + offset] * [ap/fp + offset]/imm, ap++`.
+    Mul,
+    /// `ap += imm` or `ap += [ap/fp + offset]`.
+    AddAp,
+    /// `jmp rel 2 if [ap/fp + offset] != 0`.
+    Jnz,
+    /// `jmp rel 2`.
+    JumpRelImm,
+    /// `jmp rel [ap/fp + offset]`.
+    JumpRel,
+    /// `jmp abs [ap/fp + offset]`.
+    JumpAbs,
+    /// `jmp abs [[ap/fp + offset] + offset]`.
+    JumpAbsDoubleDeref,
+    /// `call rel 4; jmp rel 3; ret`.
+    CallRelImm,
+    /// `call abs [ap/fp + offset]; jmp rel 3; ret`.
+    CallAbs,
+    /// `[ap] = [ap/fp + offset] +/* [ap/fp + offset]/imm, ap++` over QM31.
+    Qm31AddMul,
+    /// `jmp rel [ap/fp + offset] if [ap/fp + offset] != 0`, in the generic opcode.
+    GenericJnz,
+    /// `jmp rel [ap - 2] + [ap - 1]`, in the generic opcode.
+    GenericJumpRelAdd,
+    /// `ap += [ap - 2] * [ap - 1]`, in the generic opcode.
+    GenericAddApMul,
+    /// A blake compression of a state and a message, written to `[ap]`, ..., `[ap + 7]`.
+    Blake,
+    /// A blake compression of the last block of a message.
+    BlakeFinalize,
+}
+impl GadgetKind {
+    const ALL: [GadgetKind; 20] = [
+        GadgetKind::AssertImm,
+        GadgetKind::FarAssertImm,
+        GadgetKind::Copy,
+        GadgetKind::DoubleDeref,
+        GadgetKind::Add,
+        GadgetKind::Mul,
+        GadgetKind::AddAp,
+        GadgetKind::Jnz,
+        GadgetKind::JumpRelImm,
+        GadgetKind::JumpRel,
+        GadgetKind::JumpAbs,
+        GadgetKind::JumpAbsDoubleDeref,
+        GadgetKind::CallRelImm,
+        GadgetKind::CallAbs,
+        GadgetKind::Qm31AddMul,
+        GadgetKind::GenericJnz,
+        GadgetKind::GenericJumpRelAdd,
+        GadgetKind::GenericAddApMul,
+        GadgetKind::Blake,
+        GadgetKind::BlakeFinalize,
+    ];
+}
+
+/// A short instruction sequence. Its operands and values are drawn from `seed` when the program
+/// is built, so gadgets stay valid when other gadgets are removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gadget {
+    pub kind: GadgetKind,
+    pub seed: u64,
+}
+impl Gadget {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            kind: *GadgetKind::ALL.choose(rng).unwrap(),
+            seed: rng.gen(),
+        }
+    }
+}
+
+/// Builds the encoded program of a sequence of gadgets.
+pub fn build_program(gadgets: &[Gadget]) -> Vec<Felt252> {
+    let mut builder = ProgramBuilder::new();
+    for gadget in gadgets {
+        builder.apply(*gadget);
+    }
+    builder.code
+}
+
+/// The value of a memory cell, as known when building the program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    /// An integer in `(-SMALL_BOUND, SMALL_BOUND)`.
+    Small(i128),
+    /// Any other field element.
+    Felt,
+    /// A pointer to the program, at the given offset from its start.
+    ProgramPtr(usize),
+    /// A pointer to the execution segment, at the given position relative to the initial ap.
+    ExecPtr(i64),
+    /// A packed QM31 value, whose coordinates are reduced.
+    Qm31,
+}
+impl Value {
+    fn small(value: i128) -> Self {
+        match value.abs() < SMALL_BOUND {
+            true => Value::Small(value),
+            false => Value::Felt,
+        }
+    }
+
+    fn is_integer(&self) -> bool {
+        matches!(self, Value::Small(_) | Value::Felt | Value::Qm31)
+    }
+
+    fn is_qm31(&self) -> bool {
+        matches!(self, Value::Small(0..=0x7ffffffe) | Value::Qm31)
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Value::Small(a), Value::Small(b)) => Value::small(a + b),
+            _ => Value::Felt,
+        }
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Value::Small(0), _) | (_, Value::Small(0)) => Value::Small(0),
+            (Value::Small(a), Value::Small(b)) => {
+                a.checked_mul(b).map_or(Value::Felt, Value::small)
+            }
+            _ => Value::Felt,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Register {
+    Ap,
+    Fp,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CellRef {
+    register: Register,
+    offset: i16,
+}
+
+/// `[fp - 1]`, for unused operands.
+const FP_MINUS_1: CellRef = CellRef {
+    register: Register::Fp,
+    offset: -1,
+};
+/// `[ap + 0]`.
+const AP: CellRef = CellRef {
+    register: Register::Ap,
+    offset: 0,
+};
+
+enum Op1 {
+    Imm,
+    Cell(CellRef),
+    /// `[op0 + offset]`.
+    Deref(i16),
+}
+
+#[derive(PartialEq, Eq)]
+enum Res {
+    Op1,
+    Add,
+    Mul,
+}
+
+fn instruction(dst: CellRef, op0: CellRef, op1: Op1, res: Res) -> Instruction {
+    let (offset2, op_1_imm, op_1_base_fp, op_1_base_ap) = match op1 {
+        Op1::Imm => (1, true, false, false),
+        Op1::Cell(cell) => (
+            cell.offset,
+            false,
+            cell.register == Register::Fp,
+            cell.register == Register::Ap,
+        ),
+        Op1::Deref(offset) => (offset, false, false, false),
+    };
+    Instruction {
+        offset0: dst.offset,
+        offset1: op0.offset,
+        offset2,
+        dst_base_fp: dst.register == Register::Fp,
+        op0_base_fp: op0.register == Register::Fp,
+        op_1_imm,
+        op_1_base_fp,
+        op_1_base_ap,
+        res_add: res == Res::Add,
+        res_mul: res == Res::Mul,
+        // All offsets are zero, and all flags are off.
+        ..Instruction::decode(0x800080008000)
+    }
+}
+
+/// Draws an immediate, and its known value.
+fn random_felt(rng: &mut impl Rng) -> (Felt252, Value) {
+    let value = match rng.gen_range(0..4) {
+        0 => *BOUNDARY_VALUES.choose(rng).unwrap(),
+        1 => rng.gen_range(-(1 << 30)..1 << 30),
+        2 => rng.gen::<u64>() as i128,
+        _ => {
+            let shift = Felt252::from(1u128 << 64);
+            let felt =
+                Felt252::from(rng.gen::<u128>()) * shift * shift + Felt252::from(rng.gen::<u128>());
+            return (felt, Value::Felt);
+        }
+    };
+    (Felt252::from(value), Value::Small(value))
+}
+
+/// Packs the coordinates of a QM31 value.
+fn pack_qm31(coordinates: [u32; 4]) -> Felt252 {
+    coordinates
+        .iter()
+        .rev()
+        .fold(Felt252::from(0), |packed, coordinate| {
+            packed * Felt252::from(1u64 << QM31_COORDINATE_BITS) + Felt252::from(*coordinate)
+        })
+}
+
+/// Draws a packed QM31 value.
+fn random_qm31(rng: &mut impl Rng) -> Felt252 {
+    pack_qm31(std::array::from_fn(|_| rng.gen_range(0..M31_PRIME)))
+}
+
+/// Builds a program gadget by gadget, tracking the registers and the written cells.
+struct ProgramBuilder {
+    code: Vec<Felt252>,
+    /// The written cells, by their position relative to the initial ap.
+    cells: BTreeMap<i64, Value>,
+    ap: i64,
+    fp: i64,
+}
+impl ProgramBuilder {
+    fn new() -> Self {
+        Self {
+            code: vec![],
+            // In proof mode, the execution starts with `[fp - 2] = fp` and `[fp - 1] = 0`.
+            cells: BTreeMap::from([(-2, Value::ExecPtr(0)), (-1, Value::Small(0))]),
+            ap: 0,
+            fp: 0,
+        }
+    }
+
+    fn pc(&self) -> usize {
+        self.code.len()
+    }
+
+    fn push(&mut self, instruction: Instruction, imm: Option<Felt252>) {
+        assert_eq!(instruction.op_1_imm, imm.is_some());
+        self.code.push(Felt252::from(instruction.encode()));
+        self.code.extend(imm);
+    }
+
+    /// Writes `value` to `[ap]`, and increments ap.
+    fn push_value(&mut self, value: Value) {
+        self.cells.insert(self.ap, value);
+        self.ap += 1;
+    }
+
+    /// References the cell at `position` through fp or ap, preferring fp if `prefer_fp`.
+    fn cell_ref(&self, position: i64, prefer_fp: bool) -> Option<CellRef> {
+        let by_register = |register, base: i64| {
+            let offset = i16::try_from(position - base).ok()?;
+            Some(CellRef { register, offset })
+        };
+        let ap = by_register(Register::Ap, self.ap);
+        let fp = by_register(Register::Fp, self.fp);
+        match prefer_fp {
+            true => fp.or(ap),
+            false => ap.or(fp),
+        }
+    }
+
+    /// Picks a reachable written cell whose value satisfies `predicate`.
+    fn pick(
+        &self,
+        rng: &mut impl Rng,
+        predicate: impl Fn(&Value) -> bool,
+    ) -> Option<(i64, CellRef, Value)> {
+        let candidates = self
+            .cells
+            .iter()
+            .filter(|(_, value)| predicate(value))
+            .filter_map(|(&position, &value)| {
+                Some((position, self.cell_ref(position, rng.gen())?, value))
+            })
+            .collect_vec();
+        candidates.choose(rng).copied()
+    }
+
+    /// `[ap] = imm, ap++`.
+    fn assert_imm(&mut self, rng: &mut impl Rng, imm: Felt252, value: Value) {
+        let dst = self.cell_ref(self.ap, rng.gen()).unwrap();
+        let instruction = Instruction {
+            opcode_assert_eq: true,
+            ap_update_add_1: true,
+            ..instruction(dst, FP_MINUS_1, Op1::Imm, Res::Op1)
+        };
+        self.push(instruction, Some(imm));
+        self.push_value(value);
+    }
+
+    /// Writes `n_words` random u32 words from `[ap]`, incrementing ap. Returns the position of the
+    /// first word.
+    fn write_u32_words(&mut self, rng: &mut impl Rng, n_words: usize) -> i64 {
+        let start = self.ap;
+        for _ in 0..n_words {
+            let word = rng.gen::<u32>();
+            self.assert_imm(rng, Felt252::from(word), Value::Small(word as i128));
+        }
+        start
+    }
+
+    /// `jmp rel imm`.
+    fn jump_rel_imm(&mut self, imm: i64, ap_plus_one: bool) {
+        let instruction = Instruction {
+            pc_update_jump_rel: true,
+            ap_update_add_1: ap_plus_one,
+            ..instruction(FP_MINUS_1, FP_MINUS_1, Op1::Imm, Res::Op1)
+        };
+        self.push(instruction, Some(Felt252::from(imm)));
+        if ap_plus_one {
+            self.ap += 1;
+        }
+    }
+
+    /// Writes a pointer to `target`, given as an offset from the current pc, to `[ap]`, and
+    /// increments ap. Returns the pointer's position. Takes 2 words of code.
+    fn write_program_ptr(&mut self, rng: &mut impl Rng, target: usize) -> i64 {
+        if self
+            .pick(rng, |v| matches!(v, Value::ProgramPtr(_)))
+            .is_none()
+        {
+            self.call_rel_imm();
+        }
+        let target = self.pc() + target;
+        let (_, src, value) = self
+            .pick(rng, |v| matches!(v, Value::ProgramPtr(_)))
+            .unwrap();
+        let Value::ProgramPtr(ptr) = value else {
+            unreachable!()
+        };
+        let instruction = Instruction {
+            opcode_assert_eq: true,
+            ap_update_add_1: true,
+            ..instruction(AP, src, Op1::Imm, Res::Add)
+        };
+        self.push(instruction, Some(Felt252::from(target - ptr)));
+        self.push_value(Value::ProgramPtr(target));
+        self.ap - 1
+    }
+
+    /// Writes a pointer to the cell at `target` to `[ap]`, and increments ap. Returns the
+    /// pointer's position.
+    fn write_exec_ptr(&mut self, rng: &mut impl Rng, target: i64) -> i64 {
+        let (_, src, value) = self.pick(rng, |v| matches!(v, Value::ExecPtr(_))).unwrap();
+        let Value::ExecPtr(ptr) = value else {
+            unreachable!()
+        };
+        let instruction = Instruction {
+            opcode_assert_eq: true,
+            ap_update_add_1: true,
+            ..instruction(AP, src, Op1::Imm, Res::Add)
+        };
+        self.push(instruction, Some(Felt252::from(target - ptr)));
+        self.push_value(Value::ExecPtr(target));
+        self.ap - 1
+    }
+
+    /// `call rel 4; jmp rel 3; ret`.
+    fn call_rel_imm(&mut self) {
+        let call_pc = self.pc();
+        let call = Instruction {
+            pc_update_jump_rel: true,
+            opcode_call: true,
+            ..instruction(
+                AP,
+                CellRef {
+                    register: Register::Ap,
+                    offset: 1,
+                },
+                Op1::Imm,
+                Res::Op1,
+            )
+        };
+        self.push(call, Some(Felt252::from(4)));
+        self.jump_rel_imm(3, false);
+        self.push(Instruction::decode(RET), None);
+        self.push_value(Value::ExecPtr(self.fp));
+        self.push_value(Value::ProgramPtr(call_pc + 2));
+    }
+
+    fn apply(&mut self, gadget: Gadget) {
+        let rng = &mut StdRng::seed_from_u64(gadget.seed);
+        match gadget.kind {
+            GadgetKind::AssertImm => {
+                let (imm, value) = random_felt(rng);
+                self.assert_imm(rng, imm, value);
+            }
+            GadgetKind::FarAssertImm => {
+                let offset = *[100, 1000, i16::MAX].choose(rng).unwrap();
+                if self.cells.contains_key(&(self.ap + offset as i64)) {
+                    return;
+                }
+                let (imm, value) = random_felt(rng);
+                let dst = CellRef {
+                    register: Register::Ap,
+                    offset,
+                };
+                let instruction = Instruction {
+                    opcode_assert_eq: true,
+                    ..instruction(dst, FP_MINUS_1, Op1::Imm, Res::Op1)
+                };
+                self.push(instruction, Some(imm));
+                self.cells.insert(self.ap + offset as i64, value);
+            }
+            GadgetKind::Copy => {
+                let (_, src, value) = self.pick(rng, |_| true).unwrap();
+                let instruction = Instruction {
+                    opcode_assert_eq: true,
+                    ap_update_add_1: true,
+                    ..instruction(AP, FP_MINUS_1, Op1::Cell(src), Res::Op1)
+                };
+                self.push(instruction, None);
+                self.push_value(value);
+            }
+            GadgetKind::DoubleDeref => {
+                let (_, ptr_cell, ptr) =
+                    self.pick(rng, |v| matches!(v, Value::ExecPtr(_))).unwrap();
+                let Value::ExecPtr(ptr) = ptr else {
+                    unreachable!()
+                };
+                let targets = self
+                    .cells
+                    .iter()
+                    .filter_map(|(&position, &value)| {
+                        Some((i16::try_from(position - ptr).ok()?, value))
+                    })
+                    .collect_vec();
+                let Some(&(offset, value)) = targets.choose(rng) else {
+                    return;
+                };
+                let instruction = Instruction {
+                    opcode_assert_eq: true,
+                    ap_update_add_1: true,
+                    ..instruction(AP, ptr_cell, Op1::Deref(offset), Res::Op1)
+                };
+                self.push(instruction, None);
+                self.push_value(value);
+            }
+            GadgetKind::Add | GadgetKind::Mul => {
+                let Some((_, op0, a)) = self.pick(rng, Value::is_integer) else {
+                    return;
+                };
+                let (op1, imm, b) = match rng.gen_bool(0.5) {
+                    true => {
+                        let (imm, b) = random_felt(rng);
+                        (Op1::Imm, Some(imm), b)
+                    }
+                    false => {
+                        let Some((_, op1, b)) = self.pick(rng, Value::is_integer) else {
+                            return;
+                        };
+                        (Op1::Cell(op1), None, b)
+                    }
+                };
+                let (res, value) = match gadget.kind {
+                    GadgetKind::Add => (Res::Add, a.add(b)),
+                    _ => (Res::Mul, a.mul(b)),
+                };
+                let dst = self.cell_ref(self.ap, rng.gen()).unwrap();
+                let instruction = Instruction {
+                    opcode_assert_eq: true,
+                    ap_update_add_1: true,
+                    ..instruction(dst, op0, op1, res)
+                };
+                self.push(instruction, imm);
+                self.push_value(value);
+            }
+            GadgetKind::AddAp => {
+                let is_small = |v: &Value| matches!(v, Value::Small(0..=1000));
+                let (op1, imm, increment) = match self.pick(rng, is_small) {
+                    Some((_, src, Value::Small(increment))) if rng.gen_bool(0.5) => {
+                        (Op1::Cell(src), None, increment as i64)
+                    }
+                    _ => {
+                        let increment = *[0, 1, 2, 5, 1000, i16::MAX as i64].choose(rng).unwrap();
+                        (Op1::Imm, Some(Felt252::from(increment)), increment)
+                    }
+                };
+                let instruction = Instruction {
+                    ap_update_add: true,
+                    ..instruction(FP_MINUS_1, FP_MINUS_1, op1, Res::Op1)
+                };
+                self.push(instruction, imm);
+                self.ap += increment;
+            }
+            GadgetKind::Jnz => {
+                let Some((_, condition, _)) = self.pick(rng, |v| matches!(v, Value::Small(_)))
+                else {
+                    return;
+                };
+                let ap_plus_one = rng.gen_bool(0.5);
+                let instruction = Instruction {
+                    pc_update_jnz: true,
+                    ap_update_add_1: ap_plus_one,
+                    ..instruction(condition, FP_MINUS_1, Op1::Imm, Res::Op1)
+                };
+                // Both branches continue to the next instruction.
+                self.push(instruction, Some(Felt252::from(2)));
+                if ap_plus_one {
+                    self.ap += 1;
+                }
+            }
+            GadgetKind::JumpRelImm => self.jump_rel_imm(2, rng.gen_bool(0.5)),
+            GadgetKind::JumpRel => {
+                self.assert_imm(rng, Felt252::from(1), Value::Small(1));
+                let src = self.cell_ref(self.ap - 1, rng.gen()).unwrap();
+                let instruction = Instruction {
+                    pc_update_jump_rel: true,
+                    ..instruction(FP_MINUS_1, FP_MINUS_1, Op1::Cell(src), Res::Op1)
+                };
+                self.push(instruction, None);
+            }
+            GadgetKind::JumpAbs => {
+                // The jump follows the pointer's 2 words, and takes 1 word.
+                let target = self.write_program_ptr(rng, 2 + 1);
+                let src = self.cell_ref(target, rng.gen()).unwrap();
+                let instruction = Instruction {
+                    pc_update_jump: true,
+                    ..instruction(FP_MINUS_1, FP_MINUS_1, Op1::Cell(src), Res::Op1)
+                };
+                self.push(instruction, None);
+            }
+            GadgetKind::JumpAbsDoubleDeref => {
+                let target = self.write_program_ptr(rng, 2 + 1);
+                let Some((_, ptr_cell, Value::ExecPtr(ptr))) = self.pick(
+                    rng,
+                    |v| matches!(v, Value::ExecPtr(ptr) if i16::try_from(target - ptr).is_ok()),
+                ) else {
+                    return;
+                };
+                let instruction = Instruction {
+                    pc_update_jump: true,
+                    ..instruction(
+                        FP_MINUS_1,
+                        ptr_cell,
+                        Op1::Deref((target - ptr) as i16),
+                        Res::Op1,
+                    )
+                };
+                self.push(instruction, None);
+            }
+            GadgetKind::CallRelImm => self.call_rel_imm(),
+            GadgetKind::CallAbs => {
+                // The callee follows the pointer's 2 words, the call's word and the jump's 2
+                // words.
+                let target = self.write_program_ptr(rng, 2 + 3);
+                let src = self.cell_ref(target, rng.gen()).unwrap();
+                let call_pc = self.pc();
+                let call = Instruction {
+                    pc_update_jump: true,
+                    opcode_call: true,
+                    ..instruction(
+                        AP,
+                        CellRef {
+                            register: Register::Ap,
+                            offset: 1,
+                        },
+                        Op1::Cell(src),
+                        Res::Op1,
+                    )
+                };
+                self.push(call, None);
+                self.jump_rel_imm(3, false);
+                self.push(Instruction::decode(RET), None);
+                self.push_value(Value::ExecPtr(self.fp));
+                self.push_value(Value::ProgramPtr(call_pc + 1));
+            }
+            GadgetKind::Qm31AddMul => {
+                let Some((_, op0, _)) = self.pick(rng, Value::is_qm31) else {
+                    return;
+                };
+                let (op1, imm) = match self.pick(rng, Value::is_qm31) {
+                    Some((_, op1, _)) if rng.gen_bool(0.5) => (Op1::Cell(op1), None),
+                    _ => (Op1::Imm, Some(random_qm31(rng))),
+                };
+                let res = match rng.gen_bool(0.5) {
+                    true => Res::Add,
+                    false => Res::Mul,
+                };
+                let instruction = Instruction {
+                    opcode_assert_eq: true,
+                    ap_update_add_1: true,
+                    opcode_extension: OpcodeExtension::QM31Operation,
+                    ..instruction(AP, op0, op1, res)
+                };
+                self.push(instruction, imm);
+                self.push_value(Value::Qm31);
+            }
+            GadgetKind::GenericJnz => {
+                let Some((_, condition, _)) = self.pick(rng, |v| matches!(v, Value::Small(_)))
+                else {
+                    return;
+                };
+                self.assert_imm(rng, Felt252::from(1), Value::Small(1));
+                let Some(condition) = condition_after_push(condition) else {
+                    return;
+                };
+                let src = self.cell_ref(self.ap - 1, rng.gen()).unwrap();
+                let instruction = Instruction {
+                    pc_update_jnz: true,
+                    ..instruction(condition, FP_MINUS_1, Op1::Cell(src), Res::Op1)
+                };
+                self.push(instruction, None);
+            }
+            GadgetKind::GenericJumpRelAdd => {
+                // The operands sum to the jump's size, 1.
+                let (imm, value) = random_felt(rng);
+                self.assert_imm(rng, imm, value);
+                self.assert_imm(rng, Felt252::from(1) - imm, Value::Felt);
+                let instruction = Instruction {
+                    pc_update_jump_rel: true,
+                    ..instruction(
+                        FP_MINUS_1,
+                        CellRef {
+                            register: Register::Ap,
+                            offset: -2,
+                        },
+                        Op1::Cell(CellRef {
+                            register: Register::Ap,
+                            offset: -1,
+                        }),
+                        Res::Add,
+                    )
+                };
+                self.push(instruction, None);
+            }
+            GadgetKind::GenericAddApMul => {
+                let (a, b) = (rng.gen_range(0..8), rng.gen_range(0..8));
+                self.assert_imm(rng, Felt252::from(a), Value::Small(a));
+                self.assert_imm(rng, Felt252::from(b), Value::Small(b));
+                let instruction = Instruction {
+                    ap_update_add: true,
+                    ..instruction(
+                        FP_MINUS_1,
+                        CellRef {
+                            register: Register::Ap,
+                            offset: -2,
+                        },
+                        Op1::Cell(CellRef {
+                            register: Register::Ap,
+                            offset: -1,
+                        }),
+                        Res::Mul,
+                    )
+                };
+                self.push(instruction, None);
+                self.ap += (a * b) as i64;
+            }
+            GadgetKind::Blake | GadgetKind::BlakeFinalize => {
+                let state = self.write_u32_words(rng, 8);
+                let message = self.write_u32_words(rng, 16);
+                let counter = self.write_u32_words(rng, 1);
+                let state_ptr = self.write_exec_ptr(rng, state);
+                let message_ptr = self.write_exec_ptr(rng, message);
+                // The VM writes the new state to `[ap]`, ..., `[ap + 7]`.
+                if (self.ap..self.ap + 8).any(|position| self.cells.contains_key(&position)) {
+                    return;
+                }
+                let dst = self.cell_ref(counter, rng.gen()).unwrap();
+                let op0 = self.cell_ref(state_ptr, rng.gen()).unwrap();
+                let op1 = self.cell_ref(message_ptr, rng.gen()).unwrap();
+                let ap_plus_one = rng.gen_bool(0.5);
+                let opcode_extension = match gadget.kind {
+                    GadgetKind::Blake => OpcodeExtension::Blake,
+                    _ => OpcodeExtension::BlakeFinalize,
+                };
+                let instruction = Instruction {
+                    ap_update_add_1: ap_plus_one,
+                    opcode_extension,
+                    ..instruction(dst, op0, Op1::Cell(op1), Res::Op1)
+                };
+                self.push(instruction, None);
+                for position in self.ap..self.ap + 8 {
+                    self.cells.insert(position, Value::Felt);
+                }
+                // Skips the new state.
+                let skip = 8 - ap_plus_one as i64;
+                let instruction = Instruction {
+                    ap_update_add: true,
+                    ..instruction(FP_MINUS_1, FP_MINUS_1, Op1::Imm, Res::Op1)
+                };
+                self.push(instruction, Some(Felt252::from(skip)));
+                self.ap += 8;
+            }
+        }
+    }
+}
+
+/// Adjusts an ap based reference to a cell after ap was incremented.
+fn condition_after_push(cell: CellRef) -> Option<CellRef> {
+    match cell.register {
+        Register::Ap => Some(CellRef {
+            register: Register::Ap,
+            offset: cell.offset.checked_sub(1)?,
+        }),
+        Register::Fp => Some(cell),
+    }
+}
+
+/// The result of running a generated program.
+#[derive(Debug)]
+pub enum CaseOutcome {
+    /// The VM failed to run the program, so it's not a valid test case.
+    Rejected(String),
+    Passed,
+    Failed(String),
+}
+
+/// Runs a program through the VM and the adapter, and checks its traces against the AIR's
+/// constraints and relations.
+pub fn run_case(gadgets: &[Gadget]) -> CaseOutcome {
+    let (program, program_len) = program_from_encoded(build_program(gadgets));
+    let input = match panic::catch_unwind(AssertUnwindSafe(|| {
+        run_program_until_end(&program, program_len)
+    })) {
+        Ok(Ok(input)) => input,
+        Ok(Err(RunProgramError::Vm(error))) => return CaseOutcome::Rejected(error),
+        Ok(Err(error)) => return CaseOutcome::Failed(error.to_string()),
+        Err(panic) => {
+            return CaseOutcome::Failed(format!("Run panicked: {}", panic_message(&*panic)))
+        }
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let preprocessed_trace = PreProcessedTrace::canonical_without_pedersen();
+        let report = check_cairo_constraints(
+            input.clone(),
+            PreProcessedTrace::canonical_without_pedersen(),
+        );
+        if !report.is_ok() {
+            return CaseOutcome::Failed(report.to_string());
+        }
+        let diagnostics = diagnose_cairo_relations(input, &preprocessed_trace);
+        if !diagnostics.is_balanced() {
+            return CaseOutcome::Failed(diagnostics.to_string());
+        }
+        CaseOutcome::Passed
+    }));
+    result.unwrap_or_else(|panic| {
+        CaseOutcome::Failed(format!(
+            "Witness generation panicked: {}",
+            panic_message(&*panic)
+        ))
+    })
+}
+
+/// Shrinks a failing sequence of gadgets by removing chunks of gadgets while it still fails.
+pub fn shrink(
+    mut gadgets: Vec<Gadget>,
+    mut is_failing: impl FnMut(&[Gadget]) -> bool,
+) -> Vec<Gadget> {
+    let mut chunk_size = (gadgets.len() / 2).max(1);
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+        while start < gadgets.len() {
+            let end = (start + chunk_size).min(gadgets.len());
+            let candidate = [&gadgets[..start], &gadgets[end..]].concat();
+            if is_failing(&candidate) {
+                gadgets = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if !removed {
+            if chunk_size == 1 {
+                return gadgets;
+            }
+            chunk_size /= 2;
+        }
+    }
+}
+
+/// A shrunk failing case.
+#[derive(Debug)]
+pub struct FuzzFailure {
+    /// The seed of the generated case.
+    pub seed: u64,
+    pub gadgets: Vec<Gadget>,
+    pub failure: String,
+}
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Case with seed {} failed: {}", self.seed, self.failure)?;
+        writeln!(f, "Minimal gadgets: {:?}", self.gadgets)?;
+        let code = build_program(&self.gadgets);
+        writeln!(
+            f,
+            "Encoded program: [{}]",
+            code.iter().map(|w| w.to_hex_string()).join(", ")
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FuzzStats {
+    pub n_passed: usize,
+    pub n_rejected: usize,
+}
+
+/// Runs `n_cases` random programs, the i-th generated from `seed + i`. Returns the first failing
+/// case, shrunk.
+pub fn fuzz_casm_programs(
+    seed: u64,
+    n_cases: usize,
+    n_gadgets: usize,
+) -> Result<FuzzStats, FuzzFailure> {
+    let mut stats = FuzzStats::default();
+    for case_seed in (0..n_cases as u64).map(|i| seed.wrapping_add(i)) {
+        let rng = &mut StdRng::seed_from_u64(case_seed);
+        let gadgets = (0..n_gadgets).map(|_| Gadget::random(rng)).collect_vec();
+        match run_case(&gadgets) {
+            CaseOutcome::Passed => stats.n_passed += 1,
+            CaseOutcome::Rejected(_) => stats.n_rejected += 1,
+            CaseOutcome::Failed(_) => {
+                let gadgets = shrink(gadgets, |gadgets| {
+                    matches!(run_case(gadgets), CaseOutcome::Failed(_))
+                });
+                let CaseOutcome::Failed(failure) = run_case(&gadgets) else {
+                    unreachable!("Shrinking keeps the case failing")
+                };
+                return Err(FuzzFailure {
+                    seed: case_seed,
+                    gadgets,
+                    failure,
+                });
+            }
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `CASM_FUZZ_CASES` (default 2) programs from `CASM_FUZZ_SEED` (default 0).
+    #[test]
+    fn test_fuzz_casm_programs() {
+        let env_var = |name: &str, default: u64| {
+            std::env::var(name).map_or(default, |value| value.parse().unwrap())
+        };
+        let seed = env_var("CASM_FUZZ_SEED", 0);
+        let n_cases = env_var("CASM_FUZZ_CASES", 2) as usize;
+
+        match fuzz_casm_programs(seed, n_cases, N_GADGETS) {
+            Ok(stats) => assert!(stats.n_passed > 0, "All cases were rejected: {stats:?}"),
+            Err(failure) => panic!("{failure}"),
+        }
+    }
+
+    #[test]
+    fn test_every_gadget_runs() {
+        for kind in GadgetKind::ALL {
+            let gadgets = [Gadget { kind, seed: 0 }, Gadget { kind, seed: 1 }];
+            let (program, program_len) = program_from_encoded(build_program(&gadgets));
+
+            let result = run_program_until_end(&program, program_len);
+
+            assert!(result.is_ok(), "{kind:?}: {}", result.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn test_blake_is_proven_by_blake_component() {
+        for kind in [GadgetKind::Blake, GadgetKind::BlakeFinalize] {
+            for seed in 0..2 {
+                let gadgets = [Gadget { kind, seed }];
+                let (program, program_len) = program_from_encoded(build_program(&gadgets));
+
+                let input = run_program_until_end(&program, program_len).unwrap();
+
+                let states = &input.state_transitions.casm_states_by_opcode;
+                assert_eq!(
+                    states.blake_compress_opcode.len(),
+                    1,
+                    "{kind:?}, seed {seed}"
+                );
+                match run_case(&gadgets) {
+                    CaseOutcome::Passed => {}
+                    outcome => panic!("{kind:?}, seed {seed}: {outcome:?}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shrink() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let mut gadgets = (0..20).map(|_| Gadget::random(rng)).collect_vec();
+        let culprit = Gadget {
+            kind: GadgetKind::Mul,
+            seed: 7,
+        };
+        gadgets.insert(13, culprit);
+
+        let shrunk = shrink(gadgets, |gadgets| gadgets.contains(&culprit));
+
+        assert_eq!(shrunk, [culprit]);
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Deref, Mul};
+
+use cairo_air::air::{CairoComponentVisitor, CairoComponents};
+use cairo_air::components::ColumnNames;
+use cairo_air::preprocessed::PreProcessedTrace;
+use itertools::Itertools;
+use num_traits::{One, Zero};
+use serde::Serialize;
+use stwo_cairo_adapter::decode::Instruction;
+use stwo_cairo_adapter::opcodes::CasmStatesByOpcode;
+use stwo_cairo_adapter::{HashMap, ProverInput};
+use stwo_cairo_common::prover_types::cpu::CasmState;
+use stwo_prover::constraint_framework::{
+    Batching, EvalAtRow, FrameworkComponent, FrameworkEval, INTERACTION_TRACE_IDX,
+    PREPROCESSED_TRACE_IDX,
+};
+use stwo_prover::core::fields::m31::{BaseField, M31};
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::fields::FieldExpOps;
+use stwo_prover::core::lookups::utils::Fraction;
+use stwo_prover::core::pcs::TreeVec;
+
+use crate::debug_tools::mock_tree_builder::{
+    mock_cairo_traces, MockCairoTraces, MockPreProcessedTrace,
+};
+
+/// Checking a component stops after this many failing rows.
+pub const MAX_FAILED_ROWS_PER_COMPONENT: usize = 64;
+
+/// A constraint that doesn't hold on a row of a component's trace.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstraintFailure {
+    pub component: &'static str,
+    pub row: usize,
+    /// The index of the constraint, in order of evaluation. `None` for the logup constraints.
+    pub constraint: Option<usize>,
+    /// For opcode components, the state of the executed instruction. `None` for padding rows.
+    pub casm_state: Option<CasmState>,
+    /// For opcode components, the executed instruction. `None` for padding rows.
+    pub instruction: Option<Instruction>,
+}
+
+/// The result of [`check_cairo_constraints`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConstraintReport {
+    pub failures: Vec<ConstraintFailure>,
+    /// Components whose check stopped after [`MAX_FAILED_ROWS_PER_COMPONENT`] failing rows.
+    pub truncated_components: Vec<&'static str>,
+}
+impl ConstraintReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return writeln!(f, "All constraints are satisfied.");
+        }
+        writeln!(f, "Constraint failures ({}):", self.failures.len())?;
+        for failure in &self.failures {
+            let constraint = match failure.constraint {
+                Some(index) => format!("constraint {index}"),
+                None => "logup constraints".to_string(),
+            };
+            write!(
+                f,
+                "  {} row {}: {}",
+                failure.component, failure.row, constraint
+            )?;
+            if let Some(CasmState { pc, ap, fp }) = failure.casm_state {
+                write!(f, ", pc: {}, ap: {}, fp: {}", pc.0, ap.0, fp.0)?;
+            }
+            if let Some(instruction) = &failure.instruction {
+                write!(f, ", instruction: {instruction:?}")?;
+            }
+            writeln!(f)?;
+        }
+        if !self.truncated_components.is_empty() {
+            writeln!(
+                f,
+                "Stopped checking after {} failing rows: {}",
+                MAX_FAILED_ROWS_PER_COMPONENT,
+                self.truncated_components.iter().join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the traces of the given input without proving, and checks all Cairo AIR constraints
+/// on them, collecting the failures.
+pub fn check_cairo_constraints(
+    input: ProverInput,
+    preprocessed_trace: PreProcessedTrace,
+) -> ConstraintReport {
+    check_cairo_trace(input, &preprocessed_trace).0
+}
+
+/// Same as [`check_cairo_constraints`], but also returns the checked traces.
+pub(crate) fn check_cairo_trace<'a>(
+    input: ProverInput,
+    preprocessed_trace: impl Into<MockPreProcessedTrace<'a>>,
+) -> (ConstraintReport, MockCairoTraces) {
+    let casm_states = input.state_transitions.casm_states_by_opcode.clone();
+    let instructions: HashMap<u32, u128> = input.inst_cache.iter().copied().collect();
+    let traces = mock_cairo_traces(input, preprocessed_trace);
+    let report = check_mock_cairo_traces(&traces, &casm_states, &instructions);
+    (report, traces)
+}
+
+/// Checks all Cairo AIR constraints on the given traces, attributing failures of opcode
+/// components to the executed instructions.
+fn check_mock_cairo_traces(
+    traces: &MockCairoTraces,
+    casm_states: &CasmStatesByOpcode,
+    instructions: &HashMap<u32, u128>,
+) -> ConstraintReport {
+    let mut report = ConstraintReport::default();
+    let trace = traces.commitment_scheme.trace_domain_evaluations();
+    for (component, failures) in check_cairo_components(&trace, &traces.components()) {
+        let n_failed_rows = failures.iter().map(|&(row, _)| row).dedup().count();
+        if n_failed_rows >= MAX_FAILED_ROWS_PER_COMPONENT {
+            report.truncated_components.push(component);
+        }
+        let component_states = casm_states.states(component);
+        report
+            .failures
+            .extend(failures.into_iter().map(|(row, constraint)| {
+                // Rows beyond the executed instructions are padding.
+                let casm_state = component_states.and_then(|states| states.get(row).copied());
+                ConstraintFailure {
+                    component,
+                    row,
+                    constraint,
+                    casm_state,
+                    instruction: casm_state
+                        .and_then(|state| instructions.get(&state.pc.0))
+                        .map(|&encoded| Instruction::decode(encoded)),
+                }
+            }));
+    }
+    report
+}
+
+type ComponentFailures = (&'static str, Vec<(usize, Option<usize>)>);
+
+// Checks all Cairo AIR constraints on the given trace (as evaluations on the trace domains).
+// Extension components are type-erased, and are not covered.
+fn check_cairo_components(
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+    cairo_components: &CairoComponents,
+) -> Vec<ComponentFailures> {
+    struct CheckVisitor<'a, 'b> {
+        trace: &'a TreeVec<Vec<&'b Vec<M31>>>,
+        failures: Vec<ComponentFailures>,
+    }
+    impl CairoComponentVisitor for CheckVisitor<'_, '_> {
+        fn visit<E: FrameworkEval + ColumnNames + Sync>(
+            &mut self,
+            name: &'static str,
+            component: &FrameworkComponent<E>,
+        ) {
+            let component_failures = check_component(component, self.trace);
+            if !component_failures.is_empty() {
+                self.failures.push((name, component_failures));
+            }
+        }
+    }
+
+    let mut visitor = CheckVisitor {
+        trace,
+        failures: vec![],
+    };
+    cairo_components.visit(&mut visitor);
+    visitor.failures
+}
+
+/// Checks the constraints of a component on every row of its trace, returning the failing
+/// `(row, constraint)` pairs, where `constraint` is `None` for the logup constraints.
+/// Stops after [`MAX_FAILED_ROWS_PER_COMPONENT`] failing rows.
+pub fn check_component<E: FrameworkEval + Sync>(
+    component: &FrameworkComponent<E>,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+) -> Vec<(usize, Option<usize>)> {
+    check_component_rows(component, trace, 0..1 << component.log_size())
+}
+
+/// Like [`check_component`], but only checks the given rows.
+pub fn check_component_rows<E: FrameworkEval + Sync>(
+    component: &FrameworkComponent<E>,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+    rows: impl IntoIterator<Item = usize>,
+) -> Vec<(usize, Option<usize>)> {
+    let mut component_trace = trace
+        .sub_tree(component.trace_locations())
+        .map(|tree| tree.into_iter().cloned().collect_vec());
+    component_trace[PREPROCESSED_TRACE_IDX] = component
+        .preproccessed_column_indices()
+        .iter()
+        .map(|idx| trace[PREPROCESSED_TRACE_IDX][*idx])
+        .collect();
+
+    // The claimed sum is spread evenly over the rows, see `LogupAtRow`.
+    let cumsum_shift = component.claimed_sum()
+        * BaseField::from_u32_unchecked(1 << component.log_size()).inverse();
+    let component_eval = component.deref();
+
+    let mut failures = vec![];
+    let mut n_failed_rows = 0;
+    for row in rows {
+        let evaluator =
+            component_eval.evaluate(CheckingEvaluator::new(&component_trace, row, cumsum_shift));
+        if evaluator.failed_constraints.is_empty() && !evaluator.logup_failed {
+            continue;
+        }
+
+        failures.extend(
+            evaluator
+                .failed_constraints
+                .into_iter()
+                .map(|constraint| (row, Some(constraint))),
+        );
+        if evaluator.logup_failed {
+            failures.push((row, None));
+        }
+        n_failed_rows += 1;
+        if n_failed_rows == MAX_FAILED_ROWS_PER_COMPONENT {
+            break;
+        }
+    }
+    failures
+}
+
+/// Evaluates a component's constraints, including the logup constraints, on a single row of its
+/// trace, recording the failing constraints instead of panicking.
+struct CheckingEvaluator<'a> {
+    trace: &'a TreeVec<Vec<Vec<M31>>>,
+    column_indices: Vec<usize>,
+    row: usize,
+    cumsum_shift: SecureField,
+    n_constraints: usize,
+    /// The indices of the failing constraints, in order of evaluation.
+    failed_constraints: Vec<usize>,
+    /// Whether any of the logup constraints fails.
+    logup_failed: bool,
+    fracs: Vec<Fraction<SecureField, SecureField>>,
+    /// The cumulative sum of the logup batches so far.
+    prev_cumsum: Option<SecureField>,
+}
+impl<'a> CheckingEvaluator<'a> {
+    fn new(trace: &'a TreeVec<Vec<Vec<M31>>>, row: usize, cumsum_shift: SecureField) -> Self {
+        Self {
+            trace,
+            column_indices: vec![0; trace.len()],
+            row,
+            cumsum_shift,
+            n_constraints: 0,
+            failed_constraints: vec![],
+            logup_failed: false,
+            fracs: vec![],
+            prev_cumsum: None,
+        }
+    }
+
+    /// Checks the logup constraint of a batch of fractions. The last batch also accounts for the
+    /// previous row's cumulative sum, and the claimed sum.
+    fn check_batch_constraint(
+        &mut self,
+        fracs: Vec<Fraction<SecureField, SecureField>>,
+        is_last: bool,
+    ) {
+        let Fraction {
+            numerator,
+            denominator,
+        } = fracs
+            .into_iter()
+            .reduce(|a, b| a + b)
+            .expect("Empty logup batch");
+
+        let prev_cumsum = self.prev_cumsum.take().unwrap_or_else(SecureField::zero);
+        let mut diff = match is_last {
+            true => {
+                let [prev_row_cumsum, cur_cumsum] =
+                    self.next_extension_interaction_mask(INTERACTION_TRACE_IDX, [-1, 0]);
+                self.prev_cumsum = Some(cur_cumsum);
+                cur_cumsum - prev_row_cumsum + self.cumsum_shift
+            }
+            false => {
+                let [cur_cumsum] = self.next_extension_interaction_mask(INTERACTION_TRACE_IDX, [0]);
+                self.prev_cumsum = Some(cur_cumsum);
+                cur_cumsum
+            }
+        };
+        diff -= prev_cumsum;
+        if diff * denominator - numerator != SecureField::zero() {
+            self.logup_failed = true;
+        }
+    }
+}
+
+impl EvalAtRow for CheckingEvaluator<'_> {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        let column = &self.trace[interaction][self.column_indices[interaction]];
+        self.column_indices[interaction] += 1;
+        // The mask row may wrap around the column.
+        offsets.map(|offset| {
+            column[(self.row as isize + offset).rem_euclid(column.len() as isize) as usize]
+        })
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF>,
+    {
+        if SecureField::one() * constraint != SecureField::zero() {
+            self.failed_constraints.push(self.n_constraints);
+        }
+        self.n_constraints += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.fracs.push(fraction);
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        let fracs = std::mem::take(&mut self.fracs);
+        assert_eq!(batching.len(), fracs.len());
+        let mut batches = BTreeMap::<usize, Vec<_>>::new();
+        for (batch, frac) in batching.iter().zip(fracs) {
+            batches.entry(*batch).or_default().push(frac);
+        }
+        let n_batches = batches.len();
+        for (i, batch) in batches.into_values().enumerate() {
+            self.check_batch_constraint(batch, i == n_batches - 1);
+        }
+    }
+
+    fn finalize_logup(&mut self) {
+        let batching = (0..self.fracs.len()).collect();
+        self.finalize_logup_batched(&batching);
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        let batching = (0..self.fracs.len()).map(|i| i / 2).collect();
+        self.finalize_logup_batched(&batching);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+    use stwo_prover::constraint_framework::ORIGINAL_TRACE_IDX;
+
+    use super::*;
+
+    #[test]
+    fn test_check_valid_constraints() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+
+        let report = check_cairo_constraints(input, testing_preprocessed_tree(20));
+
+        assert!(report.is_ok(), "{report}");
+        assert!(report.truncated_components.is_empty());
+    }
+
+    #[test]
+    fn test_corrupted_cell_reports_failing_step() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let casm_states = input.state_transitions.casm_states_by_opcode.clone();
+        let instructions: HashMap<u32, u128> = input.inst_cache.iter().copied().collect();
+        let mut traces = mock_cairo_traces(input, &testing_preprocessed_tree(20));
+        let states = &casm_states.add_opcode;
+        let row = states.len() / 2;
+        let column = {
+            let components = traces.components();
+            let span = components.opcodes.add[0]
+                .trace_locations()
+                .iter()
+                .find(|span| span.tree_index == ORIGINAL_TRACE_IDX)
+                .unwrap();
+            span.col_start
+        };
+
+        // Corrupt the pc of a real step.
+        traces.commitment_scheme.trees[ORIGINAL_TRACE_IDX][column][row] += M31::one();
+        let report = check_mock_cairo_traces(&traces, &casm_states, &instructions);
+
+        assert!(!report.is_ok());
+        let expected_state = states[row];
+        for failure in &report.failures {
+            assert_eq!(failure.component, "add_opcode");
+            assert_eq!(failure.row, row);
+            assert_eq!(failure.casm_state, Some(expected_state));
+            let instruction = failure.instruction.as_ref().unwrap();
+            assert_eq!(instruction.encode(), instructions[&expected_state.pc.0]);
+        }
+    }
+
+    #[test]
+    fn test_constraint_report_to_json() {
+        let casm_state = CasmState {
+            pc: M31::from_u32_unchecked(1),
+            ap: M31::from_u32_unchecked(100),
+            fp: M31::from_u32_unchecked(100),
+        };
+        // `[ap + 0] = 5, ap++`.
+        let instruction = Instruction::decode(0x480680017fff8000);
+        let report = ConstraintReport {
+            failures: vec![ConstraintFailure {
+                component: "assert_eq_opcode_imm",
+                row: 3,
+                constraint: Some(2),
+                casm_state: Some(casm_state),
+                instruction: Some(instruction),
+            }],
+            truncated_components: vec![],
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert!(!report.is_ok());
+        let failure = &json["failures"][0];
+        assert_eq!(failure["component"], "assert_eq_opcode_imm");
+        assert_eq!(failure["row"], 3);
+        assert_eq!(failure["constraint"], 2);
+        assert_eq!(failure["instruction"]["offset0"], 0);
+        assert!(report
+            .to_string()
+            .contains("assert_eq_opcode_imm row 3: constraint 2, pc: 1, ap: 100, fp: 100"));
+    }
+}
@@ -1,14 +1,25 @@
+use cairo_air::air::{
+    lookup_sum, CairoClaim, CairoComponents, CairoInteractionClaim, CairoInteractionElements,
+};
+use cairo_air::preprocessed::PreProcessedTrace;
+use cairo_air::PreProcessedTraceVariant;
 use itertools::Itertools;
+use stwo_cairo_adapter::ProverInput;
+use stwo_prover::constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_prover::constraint_framework::ORIGINAL_TRACE_IDX;
 use stwo_prover::core::backend::{Backend, Column};
+use stwo_prover::core::channel::Blake2sChannel;
 use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::pcs::{TreeSubspan, TreeVec};
 use stwo_prover::core::poly::circle::CircleEvaluation;
 use stwo_prover::core::poly::BitReversedOrder;
 use stwo_prover::core::ColumnVec;
 
-use crate::witness::utils::TreeBuilder;
+use crate::witness::cairo::CairoClaimGenerator;
+use crate::witness::utils::{TraceBuffer, TreeBuilder};
 
-/// A mock commitment scheme implementation used for testing purposes.
+/// A mock commitment scheme implementation used for testing and debugging purposes.
 #[derive(Default)]
 pub struct MockCommitmentScheme {
     pub trees: TreeVec<ColumnVec<Vec<M31>>>,
@@ -72,6 +83,100 @@ impl<B: Backend> TreeBuilder<B> for MockTreeBuilder<'_> {
     }
 }
 
+/// The traces of a Cairo input, generated into a [`MockCommitmentScheme`] with interaction
+/// elements drawn from a dummy channel.
+pub struct MockCairoTraces {
+    pub claim: CairoClaim,
+    pub interaction_elements: CairoInteractionElements,
+    pub interaction_claim: CairoInteractionClaim,
+    pub commitment_scheme: MockCommitmentScheme,
+    preprocessed_column_ids: Vec<PreProcessedColumnId>,
+}
+impl MockCairoTraces {
+    pub fn components(&self) -> CairoComponents {
+        CairoComponents::new(
+            &self.claim,
+            &self.interaction_elements,
+            &self.interaction_claim,
+            &self.preprocessed_column_ids,
+        )
+    }
+
+    pub fn lookup_sum(&self) -> SecureField {
+        lookup_sum(
+            &self.claim,
+            &self.interaction_elements,
+            &self.interaction_claim,
+        )
+    }
+}
+
+/// The preprocessed trace of [`mock_cairo_traces`]: either a given trace, or the trace of a
+/// variant for the generated claim.
+#[derive(Clone, Copy)]
+pub enum MockPreProcessedTrace<'a> {
+    Trace(&'a PreProcessedTrace),
+    Variant(PreProcessedTraceVariant),
+}
+impl<'a> From<&'a PreProcessedTrace> for MockPreProcessedTrace<'a> {
+    fn from(trace: &'a PreProcessedTrace) -> Self {
+        Self::Trace(trace)
+    }
+}
+impl From<PreProcessedTraceVariant> for MockPreProcessedTrace<'_> {
+    fn from(variant: PreProcessedTraceVariant) -> Self {
+        Self::Variant(variant)
+    }
+}
+
+/// Generates the preprocessed, base and interaction traces of `input`, without committing to
+/// them.
+pub fn mock_cairo_traces<'a>(
+    input: ProverInput,
+    preprocessed_trace: impl Into<MockPreProcessedTrace<'a>>,
+) -> MockCairoTraces {
+    let mut commitment_scheme = MockCommitmentScheme::default();
+
+    // Base trace. Buffered until the preprocessed trace, which may depend on the claim, is
+    // written.
+    let cairo_claim_generator = CairoClaimGenerator::new(input);
+    let mut base_trace = TraceBuffer::new(ORIGINAL_TRACE_IDX);
+    let (claim, interaction_generator) = cairo_claim_generator.write_trace(&mut base_trace);
+
+    // Preprocessed trace.
+    let variant_trace;
+    let preprocessed_trace = match preprocessed_trace.into() {
+        MockPreProcessedTrace::Trace(trace) => trace,
+        MockPreProcessedTrace::Variant(variant) => {
+            variant_trace = variant.to_preprocessed_trace_for_claim(&claim);
+            &variant_trace
+        }
+    };
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(preprocessed_trace.gen_trace());
+    tree_builder.finalize_interaction();
+
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(base_trace.into_evals());
+    tree_builder.finalize_interaction();
+
+    // Interaction trace.
+    let mut dummy_channel = Blake2sChannel::default();
+    let interaction_elements = CairoInteractionElements::draw(&mut dummy_channel);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    let interaction_claim =
+        interaction_generator.write_interaction_trace(&mut tree_builder, &interaction_elements);
+    tree_builder.finalize_interaction();
+
+    MockCairoTraces {
+        claim,
+        interaction_elements,
+        interaction_claim,
+        commitment_scheme,
+        preprocessed_column_ids: preprocessed_trace.ids(),
+    }
+}
+
 mod tests {
     use std::array;
     use std::simd::u32x16;
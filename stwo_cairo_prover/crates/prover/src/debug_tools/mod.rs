@@ -1,8 +1,11 @@
 #[cfg(test)]
 pub mod assert_constraints;
-
 #[cfg(test)]
+pub mod casm_fuzzer;
+pub mod constraint_checker;
 pub mod mock_tree_builder;
-
-#[cfg(feature = "relation-tracker")]
+pub mod relation_diagnostics;
 pub mod relation_tracker;
+pub mod trace_dump;
+#[cfg(test)]
+pub mod trace_mutation;
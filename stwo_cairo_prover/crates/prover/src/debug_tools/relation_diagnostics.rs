@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use num_traits::Zero;
+use stwo_cairo_adapter::ProverInput;
+use stwo_prover::core::fields::m31::{M31, P};
+use stwo_prover::core::fields::qm31::SecureField;
+
+use crate::debug_tools::mock_tree_builder::{mock_cairo_traces, MockPreProcessedTrace};
+use crate::debug_tools::relation_tracker::{cairo_relation_entries, PUBLIC_DATA};
+
+/// A single contribution to a relation tuple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationUse {
+    /// The emitting component, or [`PUBLIC_DATA`].
+    pub component: &'static str,
+    /// The row in the component's trace. `None` for the public data.
+    pub row: Option<usize>,
+    /// Positive for consumers, negative for producers.
+    pub multiplicity: i64,
+}
+
+/// A relation tuple whose multiplicities don't sum to zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnbalancedTuple {
+    pub relation: String,
+    pub values: Vec<M31>,
+    pub multiplicity: i64,
+    pub uses: Vec<RelationUse>,
+}
+
+/// The public data's contribution to a relation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicDataEntry {
+    pub relation: String,
+    pub values: Vec<M31>,
+    pub multiplicity: i64,
+}
+
+/// The result of [`diagnose_cairo_relations`].
+#[derive(Debug, Clone)]
+pub struct RelationDiagnostics {
+    pub lookup_sum: SecureField,
+    /// Empty if `lookup_sum` is zero.
+    pub unbalanced: Vec<UnbalancedTuple>,
+    /// Empty if `lookup_sum` is zero.
+    pub public_data: Vec<PublicDataEntry>,
+}
+impl RelationDiagnostics {
+    pub fn is_balanced(&self) -> bool {
+        self.lookup_sum.is_zero()
+    }
+}
+
+impl fmt::Display for RelationDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_balanced() {
+            return writeln!(f, "All relations are balanced.");
+        }
+        writeln!(f, "Lookup sum: {}", self.lookup_sum)?;
+        writeln!(f, "Unbalanced tuples ({}):", self.unbalanced.len())?;
+        for tuple in &self.unbalanced {
+            writeln!(
+                f,
+                "  {}({}) multiplicity: {}",
+                tuple.relation,
+                tuple.values.iter().join(", "),
+                tuple.multiplicity
+            )?;
+            for relation_use in &tuple.uses {
+                match relation_use.row {
+                    Some(row) => writeln!(
+                        f,
+                        "    {} row {}: {}",
+                        relation_use.component, row, relation_use.multiplicity
+                    )?,
+                    None => writeln!(
+                        f,
+                        "    {}: {}",
+                        relation_use.component, relation_use.multiplicity
+                    )?,
+                }
+            }
+        }
+        writeln!(f, "Public data contributions ({}):", self.public_data.len())?;
+        for entry in &self.public_data {
+            writeln!(
+                f,
+                "  {}({}): {}",
+                entry.relation,
+                entry.values.iter().join(", "),
+                entry.multiplicity
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the traces of the given input without proving, and checks that the lookup sum is
+/// zero. If it isn't, reports every unbalanced relation tuple along with the components and rows
+/// that use it.
+/// NOTE: evaluating the relations is slow, and should only be used for debugging.
+pub fn diagnose_cairo_relations<'a>(
+    input: ProverInput,
+    preprocessed_trace: impl Into<MockPreProcessedTrace<'a>>,
+) -> RelationDiagnostics {
+    let traces = mock_cairo_traces(input, preprocessed_trace);
+    let lookup_sum = traces.lookup_sum();
+    if lookup_sum.is_zero() {
+        return RelationDiagnostics {
+            lookup_sum,
+            unbalanced: vec![],
+            public_data: vec![],
+        };
+    }
+
+    let entries = cairo_relation_entries(
+        &traces.components(),
+        &traces.commitment_scheme.trace_domain_evaluations(),
+        &traces.claim.public_data,
+    );
+
+    let mut public_data = vec![];
+    let mut tuples: HashMap<(String, Vec<M31>), (M31, Vec<RelationUse>)> = HashMap::new();
+    for located_entry in entries {
+        let entry = located_entry.entry;
+        if entry.mult.is_zero() {
+            continue;
+        }
+        if located_entry.component == PUBLIC_DATA {
+            public_data.push(PublicDataEntry {
+                relation: entry.relation.clone(),
+                values: entry.values.clone(),
+                multiplicity: to_signed(entry.mult),
+            });
+        }
+        let (multiplicity, uses) = tuples
+            .entry((entry.relation, entry.values))
+            .or_insert_with(|| (M31::zero(), vec![]));
+        *multiplicity += entry.mult;
+        uses.push(RelationUse {
+            component: located_entry.component,
+            row: located_entry.row,
+            multiplicity: to_signed(entry.mult),
+        });
+    }
+
+    let unbalanced = tuples
+        .into_iter()
+        .filter(|(_, (multiplicity, _))| !multiplicity.is_zero())
+        .map(
+            |((relation, values), (multiplicity, uses))| UnbalancedTuple {
+                relation,
+                values,
+                multiplicity: to_signed(multiplicity),
+                uses,
+            },
+        )
+        .sorted_by(|a, b| (&a.relation, &a.values).cmp(&(&b.relation, &b.values)))
+        .collect();
+
+    RelationDiagnostics {
+        lookup_sum,
+        unbalanced,
+        public_data,
+    }
+}
+
+/// Interprets a multiplicity as a signed integer in `(-P/2, P/2]`.
+fn to_signed(value: M31) -> i64 {
+    if value.0 > P / 2 {
+        value.0 as i64 - P as i64
+    } else {
+        value.0 as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use num_traits::One;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+
+    use super::*;
+
+    #[test]
+    fn test_to_signed() {
+        assert_eq!(to_signed(M31::zero()), 0);
+        assert_eq!(to_signed(M31::one()), 1);
+        assert_eq!(to_signed(-M31::one()), -1);
+        assert_eq!(to_signed(-M31::from_u32_unchecked(5)), -5);
+    }
+
+    #[test]
+    fn test_diagnose_balanced_relations() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+
+        let diagnostics = diagnose_cairo_relations(input, &testing_preprocessed_tree(20));
+
+        assert!(diagnostics.is_balanced());
+        assert!(diagnostics.unbalanced.is_empty());
+    }
+
+    #[test]
+    fn test_diagnose_unbalanced_final_state() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let mut input = run_program_and_adapter(&compiled_program);
+        let final_state = input.state_transitions.final_state;
+        input.state_transitions.final_state.fp += M31::one();
+
+        let diagnostics = diagnose_cairo_relations(input, &testing_preprocessed_tree(20));
+
+        assert!(!diagnostics.is_balanced());
+        let [produced, public] = diagnostics.unbalanced.try_into().unwrap();
+        // The real final state is produced by the last instruction, but isn't consumed.
+        assert_eq!(produced.relation, "Opcodes");
+        assert_eq!(produced.values, final_state.values());
+        assert_eq!(produced.multiplicity, -1);
+        assert!(produced.uses.iter().all(|u| u.component != PUBLIC_DATA));
+        // The public data consumes a final state that isn't produced.
+        assert_eq!(public.multiplicity, 1);
+        assert_eq!(
+            public.uses,
+            [RelationUse {
+                component: PUBLIC_DATA,
+                row: None,
+                multiplicity: 1
+            }]
+        );
+        assert!(!diagnostics.public_data.is_empty());
+    }
+}
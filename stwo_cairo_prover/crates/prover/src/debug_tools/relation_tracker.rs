@@ -1,38 +1,38 @@
-use cairo_air::air::{CairoComponents, PublicData};
-use cairo_air::opcodes_air::OpcodeComponents;
-use itertools::{chain, Itertools};
+use cairo_air::air::{CairoComponentVisitor, CairoComponents, PublicData};
+use cairo_air::components::ColumnNames;
+use itertools::Itertools;
 use num_traits::One;
 use stwo_cairo_common::prover_types::felt::split_f252;
 use stwo_prover::constraint_framework::relation_tracker::{
     add_to_relation_entries, RelationSummary, RelationTrackerEntry,
 };
 use stwo_prover::constraint_framework::{FrameworkComponent, FrameworkEval};
-use stwo_prover::core::backend::simd::SimdBackend;
-use stwo_prover::core::backend::{BackendForChannel, Column};
+use stwo_prover::core::backend::simd::m31::{LOG_N_LANES, N_LANES};
+use stwo_prover::core::backend::{Backend, BackendForChannel, Column};
 use stwo_prover::core::channel::MerkleChannel;
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::pcs::{CommitmentSchemeProver, TreeVec};
 use stwo_prover::core::poly::circle::CanonicCoset;
 
-pub fn track_and_summarize_cairo_relations<MC: MerkleChannel>(
-    commitment_scheme: &CommitmentSchemeProver<'_, SimdBackend, MC>,
+pub fn track_and_summarize_cairo_relations<B: Backend, MC: MerkleChannel>(
+    commitment_scheme: &CommitmentSchemeProver<'_, B, MC>,
     components: &CairoComponents,
     public_data: &PublicData,
 ) -> RelationSummary
 where
-    SimdBackend: BackendForChannel<MC>,
+    B: BackendForChannel<MC>,
 {
     let entries = track_cairo_relations(commitment_scheme, components, public_data);
     RelationSummary::summarize_relations(&entries).cleaned()
 }
 
-pub fn track_cairo_relations<MC: MerkleChannel>(
-    commitment_scheme: &CommitmentSchemeProver<'_, SimdBackend, MC>,
+pub fn track_cairo_relations<B: Backend, MC: MerkleChannel>(
+    commitment_scheme: &CommitmentSchemeProver<'_, B, MC>,
     components: &CairoComponents,
     public_data: &PublicData,
 ) -> Vec<RelationTrackerEntry>
 where
-    SimdBackend: BackendForChannel<MC>,
+    B: BackendForChannel<MC>,
 {
     // Cairo air aggregates interpolated polynomials. Evaluate to get the original trace.
     // NOTE: this process is slow, and should be only used for debugging.
@@ -50,9 +50,46 @@ where
     let evals = &evals.as_ref();
     let trace = &evals.into();
 
-    let mut entries = cairo_relation_entries(components, trace);
+    cairo_relation_entries(components, trace, public_data)
+        .into_iter()
+        .map(|located_entry| located_entry.entry)
+        .collect()
+}
+
+/// A relation entry, along with where it was emitted.
+pub struct LocatedRelationEntry {
+    /// The name of the emitting component, or [`PUBLIC_DATA`].
+    pub component: &'static str,
+    /// The row in the component's trace. `None` for entries of the public data.
+    pub row: Option<usize>,
+    pub entry: RelationTrackerEntry,
+}
 
-    // Public data.
+/// The pseudo component name of relation entries contributed by the public data.
+pub const PUBLIC_DATA: &str = "public_data";
+
+/// Returns the relation entries of all the components, evaluated on the given trace (as
+/// evaluations on the trace domains), followed by the entries of the public data.
+pub fn cairo_relation_entries(
+    components: &CairoComponents,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+    public_data: &PublicData,
+) -> Vec<LocatedRelationEntry> {
+    let mut entries = cairo_component_relation_entries(components, trace);
+    entries.extend(
+        public_data_relation_entries(public_data)
+            .into_iter()
+            .map(|entry| LocatedRelationEntry {
+                component: PUBLIC_DATA,
+                row: None,
+                entry,
+            }),
+    );
+    entries
+}
+
+fn public_data_relation_entries(public_data: &PublicData) -> Vec<RelationTrackerEntry> {
+    let mut entries = vec![];
     let initial_pc = public_data.initial_state.pc.0;
     let initial_ap = public_data.initial_state.ap.0;
     let final_ap = public_data.final_state.ap.0;
@@ -89,153 +126,51 @@ where
     entries
 }
 
-fn cairo_relation_entries(
+fn cairo_component_relation_entries(
     cairo_components: &CairoComponents,
     trace: &TreeVec<Vec<&Vec<M31>>>,
-) -> Vec<RelationTrackerEntry> {
-    let CairoComponents {
-        opcodes,
-        verify_instruction,
-        blake_context,
-        builtins,
-        memory_address_to_id,
-        memory_id_to_value,
-        range_checks,
-        verify_bitwise_xor_4,
-        verify_bitwise_xor_7,
-        verify_bitwise_xor_8,
-        verify_bitwise_xor_9,
-        pedersen_context,
-        poseidon_context,
-    } = cairo_components;
-    let OpcodeComponents {
-        add,
-        add_small,
-        add_ap,
-        assert_eq,
-        assert_eq_imm,
-        assert_eq_double_deref,
-        blake,
-        call,
-        call_rel_imm,
-        generic,
-        jnz,
-        jnz_taken,
-        jump,
-        jump_double_deref,
-        jump_rel,
-        jump_rel_imm,
-        mul,
-        mul_small,
-        qm31,
-        ret,
-    } = opcodes;
-
-    let mut entries = chain!(
-        add_to_relation_entries_many(add, trace),
-        add_to_relation_entries_many(add_small, trace),
-        add_to_relation_entries_many(add_ap, trace),
-        add_to_relation_entries_many(assert_eq, trace),
-        add_to_relation_entries_many(assert_eq_imm, trace),
-        add_to_relation_entries_many(assert_eq_double_deref, trace),
-        add_to_relation_entries_many(blake, trace),
-        add_to_relation_entries_many(call, trace),
-        add_to_relation_entries_many(call_rel_imm, trace),
-        add_to_relation_entries_many(generic, trace),
-        add_to_relation_entries_many(jnz, trace),
-        add_to_relation_entries_many(jnz_taken, trace),
-        add_to_relation_entries_many(jump, trace),
-        add_to_relation_entries_many(jump_double_deref, trace),
-        add_to_relation_entries_many(jump_rel, trace),
-        add_to_relation_entries_many(jump_rel_imm, trace),
-        add_to_relation_entries_many(mul, trace),
-        add_to_relation_entries_many(mul_small, trace),
-        add_to_relation_entries_many(qm31, trace),
-        add_to_relation_entries_many(ret, trace),
-        add_to_relation_entries(verify_instruction, trace),
-        add_to_relation_entries(&range_checks.rc_6, trace),
-        add_to_relation_entries(&range_checks.rc_8, trace),
-        add_to_relation_entries(&range_checks.rc_11, trace),
-        add_to_relation_entries(&range_checks.rc_12, trace),
-        add_to_relation_entries(&range_checks.rc_18, trace),
-        add_to_relation_entries(&range_checks.rc_19, trace),
-        add_to_relation_entries(&range_checks.rc_4_3, trace),
-        add_to_relation_entries(&range_checks.rc_4_4, trace),
-        add_to_relation_entries(&range_checks.rc_5_4, trace),
-        add_to_relation_entries(&range_checks.rc_9_9, trace),
-        add_to_relation_entries(&range_checks.rc_7_2_5, trace),
-        add_to_relation_entries(&range_checks.rc_3_6_6_3, trace),
-        add_to_relation_entries(&range_checks.rc_4_4_4_4, trace),
-        add_to_relation_entries(&range_checks.rc_3_3_3_3_3, trace),
-        add_to_relation_entries(verify_bitwise_xor_4, trace),
-        add_to_relation_entries(verify_bitwise_xor_7, trace),
-        add_to_relation_entries(verify_bitwise_xor_8, trace),
-        add_to_relation_entries(verify_bitwise_xor_9, trace),
-        add_to_relation_entries(memory_address_to_id, trace),
-        add_to_relation_entries_many(&memory_id_to_value.0, trace),
-        add_to_relation_entries(&memory_id_to_value.1, trace),
-    )
-    .collect_vec();
-
-    if let Some(components) = &blake_context.components {
-        entries.extend(chain!(
-            add_to_relation_entries(&components.blake_round, trace),
-            add_to_relation_entries(&components.blake_g, trace),
-            add_to_relation_entries(&components.blake_sigma, trace),
-            add_to_relation_entries(&components.triple_xor_32, trace),
-            add_to_relation_entries(&components.verify_bitwise_xor_12, trace),
-        ));
+) -> Vec<LocatedRelationEntry> {
+    struct RelationEntriesVisitor<'a, 'b> {
+        trace: &'a TreeVec<Vec<&'b Vec<M31>>>,
+        entries: Vec<LocatedRelationEntry>,
     }
-
-    // Builtins
-    if let Some(add_mod) = &builtins.add_mod_builtin {
-        entries.extend(add_to_relation_entries(add_mod, trace));
-    }
-    if let Some(bitwise) = &builtins.bitwise_builtin {
-        entries.extend(add_to_relation_entries(bitwise, trace));
-    }
-    if let Some(pederson) = &builtins.pedersen_builtin {
-        entries.extend(add_to_relation_entries(pederson, trace));
-    }
-    if let Some(poseidon) = &builtins.poseidon_builtin {
-        entries.extend(add_to_relation_entries(poseidon, trace));
-    }
-    if let Some(mul_mod) = &builtins.mul_mod_builtin {
-        entries.extend(add_to_relation_entries(mul_mod, trace));
-    }
-    if let Some(rc_96) = &builtins.range_check_96_builtin {
-        entries.extend(add_to_relation_entries(rc_96, trace));
-    }
-    if let Some(rc_128) = &builtins.range_check_128_builtin {
-        entries.extend(add_to_relation_entries(rc_128, trace));
+    impl CairoComponentVisitor for RelationEntriesVisitor<'_, '_> {
+        fn visit<E: FrameworkEval + ColumnNames + Sync>(
+            &mut self,
+            name: &'static str,
+            component: &FrameworkComponent<E>,
+        ) {
+            self.entries
+                .extend(located_relation_entries(name, component, self.trace));
+        }
     }
 
-    if let Some(components) = &poseidon_context.components {
-        entries.extend(chain!(
-            add_to_relation_entries(&components.poseidon_3_partial_rounds_chain, trace),
-            add_to_relation_entries(&components.poseidon_full_round_chain, trace),
-            add_to_relation_entries(&components.cube_252, trace),
-            add_to_relation_entries(&components.poseidon_round_keys, trace),
-            add_to_relation_entries(&components.range_check_felt_252_width_27, trace),
-        ));
-    }
-
-    if let Some(components) = &pedersen_context.components {
-        entries.extend(chain!(
-            add_to_relation_entries(&components.pedersen_points_table, trace),
-            add_to_relation_entries(&components.partial_ec_mul, trace),
-        ));
-    }
-
-    entries
+    let mut visitor = RelationEntriesVisitor {
+        trace,
+        entries: vec![],
+    };
+    cairo_components.visit(&mut visitor);
+    visitor.entries
 }
 
-fn add_to_relation_entries_many<E: FrameworkEval>(
-    components: &[FrameworkComponent<E>],
+pub(crate) fn located_relation_entries<E: FrameworkEval>(
+    component_name: &'static str,
+    component: &FrameworkComponent<E>,
     trace: &TreeVec<Vec<&Vec<M31>>>,
-) -> Vec<RelationTrackerEntry> {
-    components
-        .iter()
-        .flat_map(|x| add_to_relation_entries(x, trace))
+) -> Vec<LocatedRelationEntry> {
+    let entries = add_to_relation_entries(component, trace);
+    // The tracker evaluates `N_LANES` rows at a time, emitting each relation use for all the
+    // lanes, in order.
+    let n_vec_rows = 1 << (component.log_size() - LOG_N_LANES);
+    assert_eq!(entries.len() % (n_vec_rows * N_LANES), 0);
+    let n_entries_per_vec_row = entries.len() / n_vec_rows;
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| LocatedRelationEntry {
+            component: component_name,
+            row: Some((i / n_entries_per_vec_row) * N_LANES + i % N_LANES),
+            entry,
+        })
         .collect()
 }
@@ -0,0 +1,179 @@
+use std::io::{self, Write};
+use std::ops::Range;
+
+use cairo_air::air::{CairoComponentVisitor, CairoComponents};
+use cairo_air::components::ColumnNames;
+use itertools::{chain, Itertools};
+use stwo_cairo_adapter::ProverInput;
+use stwo_cairo_common::prover_types::cpu::CasmState;
+use stwo_prover::constraint_framework::{
+    FrameworkComponent, FrameworkEval, INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX,
+};
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::pcs::TreeVec;
+
+use crate::debug_tools::mock_tree_builder::{mock_cairo_traces, MockPreProcessedTrace};
+
+/// The base and interaction trace of a single component, along with the columns' names.
+pub struct ComponentTrace {
+    pub component: &'static str,
+    /// The names of the base trace columns followed by the interaction trace columns.
+    pub column_names: Vec<String>,
+    /// The columns, in the order of `column_names`.
+    pub columns: Vec<Vec<M31>>,
+    /// For opcode components, the state executed at each row. Rows beyond it are padding.
+    pub casm_states: Option<Vec<CasmState>>,
+}
+impl ComponentTrace {
+    pub fn n_rows(&self) -> usize {
+        self.columns.first().map_or(0, |column| column.len())
+    }
+
+    /// Writes the trace as CSV, with a header row of the column names.
+    ///
+    /// If `pc_range` is given, only the opcode rows that executed a pc in the range are written.
+    /// The filter doesn't apply to non-opcode components, whose rows are all written.
+    pub fn write_csv(
+        &self,
+        mut writer: impl Write,
+        pc_range: Option<Range<u32>>,
+    ) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{}",
+            chain!(["row"], self.column_names.iter().map(String::as_str)).join(",")
+        )?;
+        for row in 0..self.n_rows() {
+            if let (Some(pc_range), Some(casm_states)) = (&pc_range, &self.casm_states) {
+                let Some(state) = casm_states.get(row) else {
+                    break;
+                };
+                if !pc_range.contains(&state.pc.0) {
+                    continue;
+                }
+            }
+            writeln!(
+                writer,
+                "{row},{}",
+                self.columns.iter().map(|column| column[row].0).join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the traces of the given input without proving, and returns the trace of every
+/// component.
+pub fn cairo_component_traces<'a>(
+    input: ProverInput,
+    preprocessed_trace: impl Into<MockPreProcessedTrace<'a>>,
+) -> Vec<ComponentTrace> {
+    let casm_states = input.state_transitions.casm_states_by_opcode.clone();
+    let mock_traces = mock_cairo_traces(input, preprocessed_trace);
+
+    let mut traces = cairo_traces(
+        &mock_traces.components(),
+        &mock_traces.commitment_scheme.trace_domain_evaluations(),
+    );
+    for trace in &mut traces {
+        trace.casm_states = casm_states.states(trace.component).map(<[_]>::to_vec);
+    }
+    traces
+}
+
+fn cairo_traces(
+    cairo_components: &CairoComponents,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+) -> Vec<ComponentTrace> {
+    struct TraceVisitor<'a, 'b> {
+        trace: &'a TreeVec<Vec<&'b Vec<M31>>>,
+        traces: Vec<ComponentTrace>,
+    }
+    impl CairoComponentVisitor for TraceVisitor<'_, '_> {
+        fn visit<E: FrameworkEval + ColumnNames + Sync>(
+            &mut self,
+            name: &'static str,
+            component: &FrameworkComponent<E>,
+        ) {
+            self.traces
+                .push(component_trace(name, component, self.trace));
+        }
+    }
+
+    let mut visitor = TraceVisitor {
+        trace,
+        traces: vec![],
+    };
+    cairo_components.visit(&mut visitor);
+    visitor.traces
+}
+
+fn component_trace<E: FrameworkEval + ColumnNames>(
+    name: &'static str,
+    component: &FrameworkComponent<E>,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+) -> ComponentTrace {
+    let component_trace = trace.sub_tree(component.trace_locations());
+    let columns = chain!(
+        &component_trace[ORIGINAL_TRACE_IDX],
+        &component_trace[INTERACTION_TRACE_IDX]
+    )
+    .map(|column| (**column).clone())
+    .collect_vec();
+    let column_names = chain!(
+        component.trace_column_names(),
+        component.interaction_column_names()
+    )
+    .collect_vec();
+    assert_eq!(
+        column_names.len(),
+        columns.len(),
+        "{name}: the number of column names doesn't match the trace"
+    );
+
+    ComponentTrace {
+        component: name,
+        column_names,
+        columns,
+        casm_states: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+
+    use super::*;
+
+    #[test]
+    fn test_cairo_component_traces() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+
+        let traces = cairo_component_traces(input, &testing_preprocessed_tree(20));
+
+        let ret = traces.iter().find(|t| t.component == "ret_opcode").unwrap();
+        assert_eq!(ret.column_names[0], "input_pc_col0");
+        assert_eq!(ret.column_names.last().unwrap(), "logup_3_3");
+        let casm_states = ret.casm_states.as_ref().unwrap();
+        assert_eq!(ret.columns[0][0], casm_states[0].pc);
+    }
+
+    #[test]
+    fn test_write_csv_filters_pc_range() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let traces = cairo_component_traces(input, &testing_preprocessed_tree(20));
+        let ret = traces.iter().find(|t| t.component == "ret_opcode").unwrap();
+        let pc = ret.casm_states.as_ref().unwrap()[0].pc.0;
+
+        let mut csv = vec![];
+        ret.write_csv(&mut csv, Some(pc..pc + 1)).unwrap();
+
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("row,input_pc_col0,"));
+        assert!(lines.all(|line| line.split(',').nth(1) == Some(&pc.to_string())));
+    }
+}
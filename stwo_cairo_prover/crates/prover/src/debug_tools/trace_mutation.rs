@@ -0,0 +1,554 @@
+//! Soundness checks by trace mutation: generates a valid trace, then mutates single cells of each
+//! component's base trace, and checks that every mutation is caught either by the component's
+//! constraints or by the lookup sum.
+//!
+//! The interaction trace isn't regenerated after a mutation. Instead, a mutation is considered
+//! caught by the lookup sum if it changes the component's relation entries, as a prover
+//! recomputing the interaction trace would change the total lookup sum. Consequently, only the
+//! non-logup constraints are checked on the mutated rows.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::ops::Range;
+
+use cairo_air::air::CairoComponentVisitor;
+use cairo_air::components::ColumnNames;
+use itertools::Itertools;
+use num_traits::Zero;
+use rand::rngs::StdRng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use stwo_cairo_adapter::ProverInput;
+use stwo_prover::constraint_framework::{FrameworkComponent, FrameworkEval, ORIGINAL_TRACE_IDX};
+use stwo_prover::core::fields::m31::{M31, P};
+use stwo_prover::core::pcs::TreeVec;
+
+use crate::debug_tools::constraint_checker::check_component_rows;
+use crate::debug_tools::mock_tree_builder::{
+    mock_cairo_traces, MockCommitmentScheme, MockPreProcessedTrace,
+};
+use crate::debug_tools::relation_tracker::{located_relation_entries, LocatedRelationEntry};
+
+/// The test programs mutated by default.
+pub const DEFAULT_CORPUS: [&str; 3] = [
+    "test_prove_verify_all_opcode_components",
+    "test_prove_verify_bitwise_builtin",
+    "test_prove_verify_range_check_bits_128_builtin",
+];
+
+/// Swapping cells gives up after this many attempts to find two rows with different values.
+const MAX_SWAP_ATTEMPTS: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct MutationConfig {
+    pub seed: u64,
+    pub mutations_per_component: usize,
+}
+impl Default for MutationConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            mutations_per_component: 16,
+        }
+    }
+}
+
+/// A mutation of a component's base trace. Columns are indexed within the component's base
+/// trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation {
+    FlipBit {
+        column: usize,
+        row: usize,
+        bit: u32,
+    },
+    SwapCells {
+        column: usize,
+        rows: [usize; 2],
+    },
+    /// Adds `delta` to a multiplicity or enabler column.
+    AlterMultiplicity {
+        column: usize,
+        row: usize,
+        delta: i32,
+    },
+}
+impl Mutation {
+    fn column(&self) -> usize {
+        match *self {
+            Mutation::FlipBit { column, .. }
+            | Mutation::SwapCells { column, .. }
+            | Mutation::AlterMultiplicity { column, .. } => column,
+        }
+    }
+
+    fn rows(&self) -> Vec<usize> {
+        match *self {
+            Mutation::FlipBit { row, .. } | Mutation::AlterMultiplicity { row, .. } => vec![row],
+            Mutation::SwapCells { rows, .. } => rows.to_vec(),
+        }
+    }
+
+    /// Applies the mutation to the component's base trace columns.
+    fn apply(&self, columns: &mut [Vec<M31>]) {
+        match *self {
+            Mutation::FlipBit { column, row, bit } => {
+                let value = columns[column][row].0 ^ (1 << bit);
+                columns[column][row] = M31::from_u32_unchecked(value % P);
+            }
+            Mutation::SwapCells { column, rows } => columns[column].swap(rows[0], rows[1]),
+            Mutation::AlterMultiplicity { column, row, delta } => {
+                let magnitude = M31::from_u32_unchecked(delta.unsigned_abs());
+                let cell = &mut columns[column][row];
+                *cell = match delta < 0 {
+                    true => *cell - magnitude,
+                    false => *cell + magnitude,
+                };
+            }
+        }
+    }
+}
+
+/// How a mutation was caught.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detection {
+    /// The constraint with the given index failed on a mutated row.
+    Constraint(usize),
+    /// The component's relation entries changed, changing the lookup sum.
+    Relations,
+}
+
+#[derive(Debug, Clone)]
+pub struct MutationOutcome {
+    pub component: &'static str,
+    pub column_name: String,
+    pub mutation: Mutation,
+    /// `None` if the mutation went unnoticed.
+    pub detection: Option<Detection>,
+}
+
+/// The result of [`mutate_cairo_components`].
+#[derive(Debug, Clone, Default)]
+pub struct MutationReport {
+    pub outcomes: Vec<MutationOutcome>,
+}
+impl MutationReport {
+    pub fn unnoticed(&self) -> impl Iterator<Item = &MutationOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.detection.is_none())
+    }
+
+    pub fn is_sound(&self) -> bool {
+        self.unnoticed().next().is_none()
+    }
+}
+
+impl fmt::Display for MutationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Mutations caught per component:")?;
+        for (component, outcomes) in &self.outcomes.iter().group_by(|outcome| outcome.component) {
+            let outcomes = outcomes.collect_vec();
+            let n_caught = outcomes.iter().filter(|o| o.detection.is_some()).count();
+            writeln!(f, "  {component}: {n_caught}/{}", outcomes.len())?;
+        }
+        if self.is_sound() {
+            return writeln!(f, "All mutations were caught.");
+        }
+        writeln!(f, "Unnoticed mutations ({}):", self.unnoticed().count())?;
+        for outcome in self.unnoticed() {
+            writeln!(
+                f,
+                "  {} column {}: {:?}",
+                outcome.component, outcome.column_name, outcome.mutation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the traces of the given input without proving, and mutates each component's base
+/// trace in turn, checking that every mutation is caught.
+///
+/// Rows that contribute to no relation (e.g. padding rows, whose enabler is off) are only mutated
+/// in their multiplicity and enabler columns, as their other cells are allowed to be
+/// unconstrained.
+pub fn mutate_cairo_components<'a>(
+    input: ProverInput,
+    preprocessed_trace: impl Into<MockPreProcessedTrace<'a>>,
+    config: &MutationConfig,
+) -> MutationReport {
+    let traces = mock_cairo_traces(input, preprocessed_trace);
+    let components = traces.components();
+    let mut commitment_scheme = traces.commitment_scheme;
+    let mut visitor = MutationVisitor {
+        commitment_scheme: &mut commitment_scheme,
+        rng: &mut StdRng::seed_from_u64(config.seed),
+        config,
+        report: MutationReport::default(),
+    };
+    components.visit(&mut visitor);
+    visitor.report
+}
+
+struct MutationVisitor<'a, R: Rng> {
+    commitment_scheme: &'a mut MockCommitmentScheme,
+    rng: &'a mut R,
+    config: &'a MutationConfig,
+    report: MutationReport,
+}
+impl<R: Rng> CairoComponentVisitor for MutationVisitor<'_, R> {
+    fn visit<E: FrameworkEval + ColumnNames + Sync>(
+        &mut self,
+        name: &'static str,
+        component: &FrameworkComponent<E>,
+    ) {
+        let target = Target { name, component };
+        let original_entries = entries_by_row(
+            target.relation_entries(&self.commitment_scheme.trace_domain_evaluations()),
+        );
+        let active_rows = original_entries.keys().copied().sorted().collect_vec();
+        let column_names = target.column_names();
+        let base_columns = target.base_columns();
+
+        for _ in 0..self.config.mutations_per_component {
+            let columns = &self.commitment_scheme.trees[ORIGINAL_TRACE_IDX][base_columns.clone()];
+            let Some(mutation) = random_mutation(self.rng, columns, &column_names, &active_rows)
+            else {
+                continue;
+            };
+            let detection =
+                detect_mutation(&target, self.commitment_scheme, &original_entries, mutation);
+            self.report.outcomes.push(MutationOutcome {
+                component: name,
+                column_name: column_names[mutation.column()].clone(),
+                mutation,
+                detection,
+            });
+        }
+    }
+}
+
+/// Applies the mutation to the target's base trace, checks whether it's caught, and restores the
+/// trace.
+fn detect_mutation(
+    target: &dyn MutationTarget,
+    commitment_scheme: &mut MockCommitmentScheme,
+    original_entries: &HashMap<usize, Vec<LocatedRelationEntry>>,
+    mutation: Mutation,
+) -> Option<Detection> {
+    let base_columns = target.base_columns();
+    let columns = &mut commitment_scheme.trees[ORIGINAL_TRACE_IDX][base_columns.clone()];
+    let original_columns = columns.to_vec();
+    mutation.apply(columns);
+
+    let trace = commitment_scheme.trace_domain_evaluations();
+    let rows = mutation.rows();
+    let detection = target
+        .failed_constraints(&trace, &rows)
+        .first()
+        .map(|&constraint| Detection::Constraint(constraint))
+        .or_else(|| {
+            let entries = entries_by_row(target.relation_entries(&trace));
+            // Compare the mutated rows together, as swapping cells may only move entries
+            // between them.
+            let changed =
+                relation_multiset(&entries, &rows) != relation_multiset(original_entries, &rows);
+            changed.then_some(Detection::Relations)
+        });
+
+    commitment_scheme.trees[ORIGINAL_TRACE_IDX][base_columns].clone_from_slice(&original_columns);
+    detection
+}
+
+/// Returns a random mutation of the component's base trace. Half the mutations of a component with
+/// inactive rows turn on a multiplicity or enabler cell of an inactive row.
+fn random_mutation(
+    rng: &mut impl Rng,
+    columns: &[Vec<M31>],
+    column_names: &[String],
+    active_rows: &[usize],
+) -> Option<Mutation> {
+    let n_rows = columns.first()?.len();
+    let multiplicity_columns = multiplicity_columns(column_names);
+    let has_inactive_rows = active_rows.len() < n_rows;
+    if has_inactive_rows
+        && !multiplicity_columns.is_empty()
+        && (active_rows.is_empty() || rng.gen_bool(0.5))
+    {
+        let row = (0..n_rows)
+            .filter(|row| active_rows.binary_search(row).is_err())
+            .choose(rng)?;
+        return Some(Mutation::AlterMultiplicity {
+            column: *multiplicity_columns.choose(rng)?,
+            row,
+            delta: 1,
+        });
+    }
+
+    let row = *active_rows.choose(rng)?;
+    let column = rng.gen_range(0..columns.len());
+    // Multiplicities can only be altered in components that have multiplicity columns.
+    let n_kinds = if multiplicity_columns.is_empty() {
+        2
+    } else {
+        3
+    };
+    match rng.gen_range(0..n_kinds) {
+        0 => Some(Mutation::FlipBit {
+            column,
+            row,
+            bit: rng.gen_range(0..31),
+        }),
+        1 => (0..MAX_SWAP_ATTEMPTS).find_map(|_| {
+            let other_row = *active_rows.choose(rng)?;
+            (columns[column][row] != columns[column][other_row]).then_some(Mutation::SwapCells {
+                column,
+                rows: [row, other_row],
+            })
+        }),
+        _ => Some(Mutation::AlterMultiplicity {
+            column: *multiplicity_columns.choose(rng)?,
+            row,
+            delta: *[1, -1].choose(rng).unwrap(),
+        }),
+    }
+}
+
+fn multiplicity_columns(column_names: &[String]) -> Vec<usize> {
+    column_names
+        .iter()
+        .positions(|name| name.contains("multiplicity") || name.contains("enabler"))
+        .collect()
+}
+
+fn entries_by_row(entries: Vec<LocatedRelationEntry>) -> HashMap<usize, Vec<LocatedRelationEntry>> {
+    entries
+        .into_iter()
+        .filter(|located_entry| !located_entry.entry.mult.is_zero())
+        .into_group_map_by(|located_entry| located_entry.row.unwrap())
+}
+
+/// The sum of the multiplicities of each relation tuple on the given rows, omitting tuples
+/// summing to zero.
+fn relation_multiset(
+    entries: &HashMap<usize, Vec<LocatedRelationEntry>>,
+    rows: &[usize],
+) -> BTreeSet<(String, Vec<M31>, M31)> {
+    rows.iter()
+        .filter_map(|row| entries.get(row))
+        .flatten()
+        .map(|located_entry| &located_entry.entry)
+        .into_grouping_map_by(|entry| (entry.relation.clone(), entry.values.clone()))
+        .fold(M31::zero(), |sum, _, entry| sum + entry.mult)
+        .into_iter()
+        .filter(|(_, multiplicity)| !multiplicity.is_zero())
+        .map(|((relation, values), multiplicity)| (relation, values, multiplicity))
+        .collect()
+}
+
+/// A component whose base trace is mutated.
+trait MutationTarget {
+    fn name(&self) -> &'static str;
+
+    /// The component's columns in the base trace tree.
+    fn base_columns(&self) -> Range<usize>;
+
+    fn column_names(&self) -> Vec<String>;
+
+    /// The non-logup constraints failing on the given rows.
+    fn failed_constraints(&self, trace: &TreeVec<Vec<&Vec<M31>>>, rows: &[usize]) -> Vec<usize>;
+
+    fn relation_entries(&self, trace: &TreeVec<Vec<&Vec<M31>>>) -> Vec<LocatedRelationEntry>;
+}
+
+struct Target<'a, E: FrameworkEval> {
+    name: &'static str,
+    component: &'a FrameworkComponent<E>,
+}
+impl<E: FrameworkEval + ColumnNames + Sync> MutationTarget for Target<'_, E> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn base_columns(&self) -> Range<usize> {
+        let span = self
+            .component
+            .trace_locations()
+            .iter()
+            .find(|span| span.tree_index == ORIGINAL_TRACE_IDX)
+            .unwrap();
+        span.col_start..span.col_end
+    }
+
+    fn column_names(&self) -> Vec<String> {
+        self.component.trace_column_names()
+    }
+
+    fn failed_constraints(&self, trace: &TreeVec<Vec<&Vec<M31>>>, rows: &[usize]) -> Vec<usize> {
+        check_component_rows(self.component, trace, rows.iter().copied())
+            .into_iter()
+            .filter_map(|(_, constraint)| constraint)
+            .collect()
+    }
+
+    fn relation_entries(&self, trace: &TreeVec<Vec<&Vec<M31>>>) -> Vec<LocatedRelationEntry> {
+        located_relation_entries(self.name, self.component, trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+
+    use super::*;
+
+    #[test]
+    fn test_mutation_apply() {
+        let mut columns = vec![(0..4).map(M31::from_u32_unchecked).collect_vec()];
+
+        Mutation::FlipBit {
+            column: 0,
+            row: 1,
+            bit: 3,
+        }
+        .apply(&mut columns);
+        Mutation::SwapCells {
+            column: 0,
+            rows: [2, 3],
+        }
+        .apply(&mut columns);
+        Mutation::AlterMultiplicity {
+            column: 0,
+            row: 0,
+            delta: -1,
+        }
+        .apply(&mut columns);
+
+        assert_eq!(columns[0], [P - 1, 9, 3, 2].map(M31::from_u32_unchecked));
+    }
+
+    #[test]
+    fn test_random_mutation_alters_only_multiplicity_columns() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let columns = vec![(0..8).map(M31::from_u32_unchecked).collect_vec(); 3];
+        let active_rows = (0..8).collect_vec();
+
+        let without_multiplicities = ["a_col0", "b_col1", "c_col2"].map(String::from);
+        let with_multiplicities = ["a_col0", "multiplicity", "enabler"].map(String::from);
+
+        for _ in 0..64 {
+            let mutation =
+                random_mutation(&mut rng, &columns, &without_multiplicities, &active_rows);
+            assert!(!matches!(
+                mutation,
+                Some(Mutation::AlterMultiplicity { .. })
+            ));
+
+            let mutation = random_mutation(&mut rng, &columns, &with_multiplicities, &active_rows);
+            if let Some(Mutation::AlterMultiplicity { column, .. }) = mutation {
+                assert!(column > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mutations_are_caught() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let config = MutationConfig {
+            seed: 0,
+            mutations_per_component: 2,
+        };
+
+        let report = mutate_cairo_components(input, &testing_preprocessed_tree(20), &config);
+
+        assert!(!report.outcomes.is_empty());
+        assert!(report.is_sound(), "{report}");
+    }
+
+    #[test]
+    fn test_enabling_padding_rows_is_caught() {
+        struct EnablePaddingVisitor<'a> {
+            commitment_scheme: &'a mut MockCommitmentScheme,
+            n_checked: usize,
+        }
+        impl CairoComponentVisitor for EnablePaddingVisitor<'_> {
+            fn visit<E: FrameworkEval + ColumnNames + Sync>(
+                &mut self,
+                name: &'static str,
+                component: &FrameworkComponent<E>,
+            ) {
+                let target = Target { name, component };
+                let Some(enabler) = target.column_names().iter().position(|n| n == "enabler")
+                else {
+                    return;
+                };
+                let original_entries = entries_by_row(
+                    target.relation_entries(&self.commitment_scheme.trace_domain_evaluations()),
+                );
+                let Some(row) =
+                    (0..1 << component.log_size()).find(|row| !original_entries.contains_key(row))
+                else {
+                    return;
+                };
+                let mutation = Mutation::AlterMultiplicity {
+                    column: enabler,
+                    row,
+                    delta: 1,
+                };
+
+                let detection =
+                    detect_mutation(&target, self.commitment_scheme, &original_entries, mutation);
+
+                assert!(detection.is_some(), "{name}: {mutation:?} went unnoticed");
+                self.n_checked += 1;
+            }
+        }
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let traces = mock_cairo_traces(input, &testing_preprocessed_tree(20));
+        let components = traces.components();
+        let mut commitment_scheme = traces.commitment_scheme;
+        let mut visitor = EnablePaddingVisitor {
+            commitment_scheme: &mut commitment_scheme,
+            n_checked: 0,
+        };
+
+        components.visit(&mut visitor);
+
+        assert!(visitor.n_checked > 0);
+    }
+
+    #[test]
+    fn test_mutations_are_caught_on_small_corpus() {
+        let config = MutationConfig {
+            seed: 1,
+            mutations_per_component: 1,
+        };
+        for test_name in DEFAULT_CORPUS {
+            let input = run_program_and_adapter(&get_test_program(test_name));
+
+            let report = mutate_cairo_components(input, &testing_preprocessed_tree(20), &config);
+
+            assert!(report.is_sound(), "{test_name}: {report}");
+        }
+    }
+
+    #[cfg(feature = "slow-tests")]
+    #[test]
+    fn test_mutations_are_caught_on_corpus() {
+        for test_name in DEFAULT_CORPUS {
+            let input = run_program_and_adapter(&get_test_program(test_name));
+
+            let report = mutate_cairo_components(
+                input,
+                &testing_preprocessed_tree(20),
+                &MutationConfig::default(),
+            );
+
+            assert!(report.is_sound(), "{test_name}: {report}");
+        }
+    }
+}
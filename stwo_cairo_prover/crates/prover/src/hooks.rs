@@ -0,0 +1,206 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use cairo_air::components::UnsupportedExtensionsError;
+use stwo_prover::core::prover::ProvingError;
+use thiserror::Error;
+
+/// The phases of `prove_cairo`, in the order they are entered.
+///
+/// Note that the base trace is written before the preprocessed trace is committed, as the
+/// [`PreProcessedTraceVariant::UsageAware`] trace depends on the claim. The composition, FRI and
+/// proof of work steps all run within stwo's `prove`, and are reported as a single
+/// [`ProvingPhase::ProveStark`] phase.
+///
+/// [`PreProcessedTraceVariant::UsageAware`]: cairo_air::PreProcessedTraceVariant::UsageAware
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvingPhase {
+    /// Writing the base trace of a single component, named after its module (e.g.
+    /// "add_opcode"). Components that are omitted from the trace are not reported.
+    BaseTrace { component: &'static str },
+    /// Generating and committing to the preprocessed trace.
+    PreprocessedCommit,
+    /// Committing to the base trace.
+    BaseTraceCommit,
+    /// Grinding the proof of work that precedes drawing the interaction elements.
+    InteractionPow,
+    /// Writing and committing to the interaction trace.
+    InteractionTrace,
+    /// Proving the committed traces: computing and committing to the composition polynomial,
+    /// committing to the FRI layers, grinding the proof of work and decommitting.
+    ProveStark,
+}
+
+/// A cloneable flag used to request the cancellation of a running `prove_cairo`.
+/// Cancellation is cooperative: it is observed only between phases and between component trace
+/// writes.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type ProgressCallback = Box<dyn Fn(ProvingPhase) + Send + Sync>;
+
+/// Optional observers of a `prove_cairo` run.
+#[derive(Default)]
+pub struct ProvingHooks {
+    /// Called whenever a phase is entered.
+    pub on_progress: Option<ProgressCallback>,
+    /// Checked before every phase is entered.
+    pub cancellation: Option<CancellationToken>,
+}
+impl ProvingHooks {
+    pub fn with_progress(
+        mut self,
+        on_progress: impl Fn(ProvingPhase) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Checks for cancellation and reports `phase` as entered.
+    pub fn enter(&self, phase: ProvingPhase) -> Result<(), CairoProvingError> {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            return Err(CairoProvingError::Cancelled);
+        }
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(phase);
+        }
+        Ok(())
+    }
+
+    /// Reports the base trace of `component` as entered, unless it is omitted from the trace.
+    pub(crate) fn enter_base_trace(
+        &self,
+        component: &'static str,
+        is_written: bool,
+    ) -> Result<(), CairoProvingError> {
+        if !is_written {
+            return Ok(());
+        }
+        self.enter(ProvingPhase::BaseTrace { component })
+    }
+
+    /// Writes the base trace of `component` with `write_trace`, reporting it as entered, unless
+    /// it is omitted from the trace, in which case `None` is returned.
+    pub(crate) fn write_base_trace<T>(
+        &self,
+        component: &'static str,
+        is_written: bool,
+        write_trace: impl FnOnce() -> T,
+    ) -> Result<Option<T>, CairoProvingError> {
+        if !is_written {
+            return Ok(None);
+        }
+        self.enter(ProvingPhase::BaseTrace { component })?;
+        Ok(Some(write_trace()))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CairoProvingError {
+    #[error(transparent)]
+    Proving(#[from] ProvingError),
+    #[error("Proving was cancelled")]
+    Cancelled,
+    #[error(transparent)]
+    UnsupportedExtensions(#[from] UnsupportedExtensionsError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+
+    use super::*;
+    use crate::debug_tools::mock_tree_builder::MockCommitmentScheme;
+    use crate::witness::cairo::CairoClaimGenerator;
+
+    #[test]
+    fn test_hooks_report_progress() {
+        let phases = Arc::new(Mutex::new(vec![]));
+        let hooks = ProvingHooks::default().with_progress({
+            let phases = phases.clone();
+            move |phase| phases.lock().unwrap().push(phase)
+        });
+
+        hooks.enter(ProvingPhase::PreprocessedCommit).unwrap();
+        hooks
+            .enter(ProvingPhase::BaseTrace {
+                component: "ret_opcode",
+            })
+            .unwrap();
+
+        assert_eq!(
+            *phases.lock().unwrap(),
+            [
+                ProvingPhase::PreprocessedCommit,
+                ProvingPhase::BaseTrace {
+                    component: "ret_opcode"
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hooks_cancellation() {
+        let token = CancellationToken::new();
+        let hooks = ProvingHooks::default().with_cancellation(token.clone());
+
+        assert!(hooks.enter(ProvingPhase::PreprocessedCommit).is_ok());
+        token.cancel();
+
+        assert!(matches!(
+            hooks.enter(ProvingPhase::BaseTraceCommit),
+            Err(CairoProvingError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_write_trace_cancelled_between_components() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let token = CancellationToken::new();
+        let components = Arc::new(Mutex::new(vec![]));
+        let hooks = ProvingHooks::default()
+            .with_cancellation(token.clone())
+            .with_progress({
+                let components = components.clone();
+                move |phase| {
+                    if let ProvingPhase::BaseTrace { component } = phase {
+                        components.lock().unwrap().push(component);
+                        token.cancel();
+                    }
+                }
+            });
+
+        let mut commitment_scheme = MockCommitmentScheme::default();
+        let mut tree_builder = commitment_scheme.tree_builder();
+        let result =
+            CairoClaimGenerator::new(input).write_trace_with_hooks(&mut tree_builder, &hooks);
+
+        assert!(matches!(result, Err(CairoProvingError::Cancelled)));
+        assert_eq!(components.lock().unwrap().len(), 1);
+    }
+}
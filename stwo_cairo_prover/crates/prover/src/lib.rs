@@ -2,8 +2,14 @@
 // TODO(Ohad): remove.
 #![allow(clippy::too_many_arguments, clippy::module_inception)]
 
+pub mod check;
+pub mod cost_model;
 pub mod debug_tools;
+pub mod hooks;
+pub mod panic_utils;
+pub mod profiler;
 pub mod prover;
+pub mod report;
 
 pub use stwo_prover;
 pub mod witness;
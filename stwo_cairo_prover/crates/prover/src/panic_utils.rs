@@ -0,0 +1,11 @@
+use std::any::Any;
+
+/// Extracts the message of a caught panic payload, e.g. from [`std::panic::catch_unwind`] or a
+/// panicked thread. Payloads that aren't strings yield an empty message.
+pub fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
@@ -0,0 +1,586 @@
+//! Attributes the proving cost of a Cairo execution to the call stacks that caused it, and through
+//! a [`FunctionMap`] to Cairo functions.
+//!
+//! The call stack of a step is read from the frames the `call` instructions wrote to memory: every
+//! frame above the entry frame holds the caller's fp at `[fp - 2]` and the return pc at `[fp - 1]`.
+//!
+//! Every step is charged the trace cells of its opcode component row, and of the rows it feeds
+//! (e.g. the rounds of a blake compression). Every accessed memory cell is charged to one of the
+//! steps accessing it as an operand, along with the row of its value if the value wasn't charged
+//! yet. Every builtin instance is charged to the step charged for its first accessed cell, along
+//! with the rows it feeds. Fixed size tables (range checks, xor tables) and padding rows aren't
+//! charged to any step.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+
+use cairo_air::components::memory_address_to_id::MEMORY_ADDRESS_TO_ID_SPLIT;
+use cairo_air::components::{
+    add_ap_opcode, add_mod_builtin, add_opcode, add_opcode_small, assert_eq_opcode,
+    assert_eq_opcode_double_deref, assert_eq_opcode_imm, bitwise_builtin, blake_compress_opcode,
+    blake_g, blake_round, call_opcode, call_opcode_rel_imm, cube_252, generic_opcode, jnz_opcode,
+    jnz_opcode_taken, jump_opcode, jump_opcode_double_deref, jump_opcode_rel, jump_opcode_rel_imm,
+    memory_address_to_id, memory_id_to_big, mul_mod_builtin, mul_opcode, mul_opcode_small,
+    partial_ec_mul, pedersen_builtin, poseidon_3_partial_rounds_chain, poseidon_builtin,
+    poseidon_full_round_chain, qm_31_add_mul_opcode, range_check_builtin_bits_128,
+    range_check_builtin_bits_96, range_check_felt_252_width_27, ret_opcode, triple_xor_32,
+    verify_instruction,
+};
+use itertools::Itertools;
+use serde::Serialize;
+use stwo_cairo_adapter::builtins::{
+    MemorySegmentAddresses, ADD_MOD_MEMORY_CELLS, BITWISE_MEMORY_CELLS, MUL_MOD_MEMORY_CELLS,
+    PEDERSEN_MEMORY_CELLS, POSEIDON_MEMORY_CELLS, RANGE_CHECK_MEMORY_CELLS,
+};
+use stwo_cairo_adapter::decode::Instruction;
+use stwo_cairo_adapter::memory::{Memory, MemoryValue, MemoryValueId};
+use stwo_cairo_adapter::{opcode_components, ProverInput};
+use stwo_cairo_common::prover_types::cpu::CasmState;
+use stwo_prover::constraint_framework::{INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX};
+use stwo_prover::core::backend::simd::m31::LOG_N_LANES;
+use stwo_prover::core::pcs::TreeVec;
+
+use crate::cost_model::{
+    BLAKE_G_PER_ROUND, BLAKE_ROUNDS_PER_COMPRESS, FULL_ROUND_CHAINS_PER_POSEIDON,
+    PARTIAL_EC_MULS_PER_PEDERSEN, PARTIAL_ROUND_CHAINS_PER_POSEIDON, TRIPLE_XORS_PER_COMPRESS,
+};
+
+/// The cost charged to a single pc or call stack.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PcCost {
+    pub steps: u64,
+    pub memory_cells: u64,
+    pub builtin_instances: u64,
+    /// Base and interaction trace cells, by component.
+    pub trace_cells: BTreeMap<&'static str, u64>,
+}
+impl PcCost {
+    pub fn total_trace_cells(&self) -> u64 {
+        self.trace_cells.values().sum()
+    }
+
+    fn charge(&mut self, component: &'static str, trace_cells: u64) {
+        *self.trace_cells.entry(component).or_default() += trace_cells;
+    }
+
+    fn merge(&mut self, other: &PcCost) {
+        self.steps += other.steps;
+        self.memory_cells += other.memory_cells;
+        self.builtin_instances += other.builtin_instances;
+        for (&component, &trace_cells) in &other.trace_cells {
+            self.charge(component, trace_cells);
+        }
+    }
+}
+
+/// Maps pcs to the (possibly inlined) Cairo functions containing them.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionMap {
+    /// The first pc of every code block, sorted, with the functions it's inlined in, outermost
+    /// first.
+    starts: Vec<(u32, Vec<String>)>,
+}
+impl FunctionMap {
+    /// Creates a map from the offset of the first instruction of every code block in a program
+    /// loaded at `program_base`, and the functions it's inlined in, outermost first.
+    pub fn new(program_base: u32, blocks: impl IntoIterator<Item = (u32, Vec<String>)>) -> Self {
+        let starts = blocks
+            .into_iter()
+            .map(|(offset, functions)| (program_base + offset, functions))
+            .sorted()
+            .collect();
+        Self { starts }
+    }
+
+    /// The functions containing `pc`, outermost first, which are those of the last block starting
+    /// at or before it.
+    pub fn functions_at(&self, pc: u32) -> &[String] {
+        let index = self.starts.partition_point(|(start, _)| *start <= pc);
+        match index.checked_sub(1) {
+            Some(index) => &self.starts[index].1,
+            None => &[],
+        }
+    }
+
+    /// The innermost function containing `pc`.
+    pub fn function_at(&self, pc: u32) -> Option<&str> {
+        self.functions_at(pc).last().map(String::as_str)
+    }
+
+    /// The frames of `pc` in a profile, outermost first. Pcs outside of any known function are
+    /// named by their value.
+    fn frames(&self, pc: u32) -> Vec<String> {
+        match self.functions_at(pc) {
+            [] => vec![format!("pc_{pc}")],
+            // Folded stacks are separated by semicolons, and followed by a space.
+            functions => functions
+                .iter()
+                .map(|function| function.replace([';', ' '], "_"))
+                .collect(),
+        }
+    }
+
+    /// The frames of a call stack in a profile, outermost first.
+    fn stack_frames(&self, stack: &CallStack) -> Vec<String> {
+        let (&pc, return_pcs) = stack.split_last().unwrap();
+        // The instruction before a return pc is (the immediate of) the call.
+        return_pcs
+            .iter()
+            .flat_map(|return_pc| self.frames(return_pc - 1))
+            .chain(self.frames(pc))
+            .collect()
+    }
+}
+
+/// The return pcs of the calls leading to a frame, outermost first, followed by the executing pc.
+pub type CallStack = Vec<u32>;
+
+/// The cost of an execution, by call stack.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionProfile {
+    pub stacks: BTreeMap<CallStack, PcCost>,
+}
+impl ExecutionProfile {
+    pub fn total_trace_cells(&self) -> u64 {
+        self.stacks.values().map(PcCost::total_trace_cells).sum()
+    }
+
+    /// The cost of every pc, summing the costs of its call stacks.
+    pub fn by_pc(&self) -> BTreeMap<u32, PcCost> {
+        let mut costs = BTreeMap::<u32, PcCost>::new();
+        for (stack, cost) in &self.stacks {
+            costs.entry(*stack.last().unwrap()).or_default().merge(cost);
+        }
+        costs
+    }
+
+    /// The cost of every function, summing the costs of the pcs it directly contains.
+    pub fn by_function(&self, functions: &FunctionMap) -> BTreeMap<String, PcCost> {
+        let mut costs = BTreeMap::<String, PcCost>::new();
+        for (pc, cost) in self.by_pc() {
+            let function = functions.frames(pc).pop().unwrap();
+            costs.entry(function).or_default().merge(&cost);
+        }
+        costs
+    }
+
+    /// Writes the trace cells of every call stack, split by component, in the folded stacks
+    /// format of flamegraph tools (`outer;...;inner;component trace_cells` per line).
+    pub fn write_folded(&self, functions: &FunctionMap, mut writer: impl Write) -> io::Result<()> {
+        let mut costs = BTreeMap::<String, PcCost>::new();
+        for (stack, cost) in &self.stacks {
+            let frames = functions.stack_frames(stack).join(";");
+            costs.entry(frames).or_default().merge(cost);
+        }
+        for (frames, cost) in costs {
+            for (component, trace_cells) in cost.trace_cells {
+                writeln!(writer, "{frames};{component} {trace_cells}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Base and interaction trace cells in a row of a component with the given log sizes.
+fn cells_per_row(log_sizes: TreeVec<Vec<u32>>) -> u64 {
+    (log_sizes[ORIGINAL_TRACE_IDX].len() + log_sizes[INTERACTION_TRACE_IDX].len()) as u64
+}
+
+/// Rows of other components fed by every row of an opcode component, with the cells per row.
+fn fed_rows(component: &str) -> Vec<(&'static str, u64, u64)> {
+    let log_size = LOG_N_LANES;
+    match component {
+        "blake_compress_opcode" => vec![
+            (
+                "blake_round",
+                BLAKE_ROUNDS_PER_COMPRESS as u64,
+                cells_per_row(blake_round::Claim { log_size }.log_sizes()),
+            ),
+            (
+                "blake_g",
+                (BLAKE_ROUNDS_PER_COMPRESS * BLAKE_G_PER_ROUND) as u64,
+                cells_per_row(blake_g::Claim { log_size }.log_sizes()),
+            ),
+            (
+                "triple_xor_32",
+                TRIPLE_XORS_PER_COMPRESS as u64,
+                cells_per_row(triple_xor_32::Claim { log_size }.log_sizes()),
+            ),
+        ],
+        "pedersen_builtin" => vec![(
+            "partial_ec_mul",
+            PARTIAL_EC_MULS_PER_PEDERSEN as u64,
+            cells_per_row(partial_ec_mul::Claim { log_size }.log_sizes()),
+        )],
+        "poseidon_builtin" => {
+            let partial_rounds = PARTIAL_ROUND_CHAINS_PER_POSEIDON as u64;
+            let full_rounds = FULL_ROUND_CHAINS_PER_POSEIDON as u64;
+            vec![
+                (
+                    "poseidon_3_partial_rounds_chain",
+                    partial_rounds,
+                    cells_per_row(poseidon_3_partial_rounds_chain::Claim { log_size }.log_sizes()),
+                ),
+                (
+                    "poseidon_full_round_chain",
+                    full_rounds,
+                    cells_per_row(poseidon_full_round_chain::Claim { log_size }.log_sizes()),
+                ),
+                // `cube_252` is fed by the builtin and by both round chains.
+                (
+                    "cube_252",
+                    2 + 3 * partial_rounds + 3 * full_rounds,
+                    cells_per_row(cube_252::Claim { log_size }.log_sizes()),
+                ),
+                // `range_check_felt_252_width_27` is fed by the builtin and the partial round
+                // chain.
+                (
+                    "range_check_felt_252_width_27",
+                    2 + 3 * partial_rounds,
+                    cells_per_row(range_check_felt_252_width_27::Claim { log_size }.log_sizes()),
+                ),
+            ]
+        }
+        _ => vec![],
+    }
+}
+
+/// Charges a row of `component` to `cost`, along with the rows it feeds.
+fn charge_row(cost: &mut PcCost, component: &'static str, cells_per_row: u64) {
+    cost.charge(component, cells_per_row);
+    for (fed_component, n_rows, cells_per_row) in fed_rows(component) {
+        cost.charge(fed_component, n_rows * cells_per_row);
+    }
+}
+
+/// The addresses an instruction executed at `state` accesses: the instruction itself, and its
+/// operands.
+fn accessed_addresses(state: &CasmState, instruction: &Instruction, memory: &Memory) -> Vec<u32> {
+    let (pc, ap, fp) = (state.pc.0, state.ap.0, state.fp.0);
+    let base = |base_fp: bool| if base_fp { fp } else { ap };
+    let offset = |address: u32, offset: i16| address.checked_add_signed(offset as i32);
+    let dst = offset(base(instruction.dst_base_fp), instruction.offset0);
+    let op0 = offset(base(instruction.op0_base_fp), instruction.offset1);
+    let op1 = if instruction.op_1_imm {
+        Some(pc + 1)
+    } else if instruction.op_1_base_fp {
+        offset(fp, instruction.offset2)
+    } else if instruction.op_1_base_ap {
+        offset(ap, instruction.offset2)
+    } else {
+        // `[[op0] + offset2]`.
+        op0.and_then(|op0| match memory_value(memory, op0)? {
+            MemoryValue::Small(pointer) => {
+                offset(u32::try_from(pointer).ok()?, instruction.offset2)
+            }
+            MemoryValue::F252(_) => None,
+        })
+    };
+    [Some(pc), dst, op0, op1].into_iter().flatten().collect()
+}
+
+fn memory_value(memory: &Memory, address: u32) -> Option<MemoryValue> {
+    let id = memory.address_to_id.get(address as usize)?;
+    (!matches!(id.decode(), MemoryValueId::Empty)).then(|| memory.get(address))
+}
+
+fn memory_pointer(memory: &Memory, address: u32) -> Option<u32> {
+    match memory_value(memory, address)? {
+        MemoryValue::Small(value) => u32::try_from(value).ok(),
+        MemoryValue::F252(_) => None,
+    }
+}
+
+/// Reads the return pcs of the calls leading to the frame at `fp`, outermost first, stopping at
+/// `entry_fp`.
+struct CallSites<'a> {
+    memory: &'a Memory,
+    entry_fp: u32,
+    /// The call sites of every frame seen so far.
+    frames: HashMap<u32, Vec<u32>>,
+}
+impl CallSites<'_> {
+    fn get(&mut self, fp: u32) -> &[u32] {
+        if !self.frames.contains_key(&fp) {
+            let mut return_pcs = vec![];
+            let mut frame_fp = fp;
+            while frame_fp > self.entry_fp {
+                let (Some(caller_fp), Some(return_pc)) = (
+                    memory_pointer(self.memory, frame_fp - 2),
+                    memory_pointer(self.memory, frame_fp - 1),
+                ) else {
+                    break;
+                };
+                // Callers' frames are below their callees', which also rules out cycles.
+                if caller_fp >= frame_fp {
+                    break;
+                }
+                return_pcs.push(return_pc);
+                frame_fp = caller_fp;
+            }
+            return_pcs.reverse();
+            self.frames.insert(fp, return_pcs);
+        }
+        &self.frames[&fp]
+    }
+}
+
+/// Profiles the proving cost of `input` by call stack.
+pub fn profile_execution(input: &ProverInput) -> ExecutionProfile {
+    let mut profile = ExecutionProfile::default();
+    let instructions: HashMap<u32, u128> = input.inst_cache.iter().copied().collect();
+    let memory = &input.memory;
+    let mut call_sites = CallSites {
+        memory,
+        entry_fp: input.state_transitions.initial_state.fp.0,
+        frames: HashMap::new(),
+    };
+    // The call stack charged for every accessed address.
+    let mut address_stacks = HashMap::<u32, CallStack>::new();
+
+    // Steps.
+    let states = &input.state_transitions.casm_states_by_opcode;
+    let log_size = LOG_N_LANES;
+    macro_rules! opcodes {
+        ($($field:ident: $module:ident,)*) => {
+            [$((
+                stringify!($module),
+                &states.$module,
+                cells_per_row($module::Claim { log_size }.log_sizes()),
+            )),*]
+        };
+    }
+    let opcodes = opcode_components!(opcodes);
+    for (component, states, cells_per_row) in opcodes {
+        for state in states {
+            let pc = state.pc.0;
+            let mut stack = call_sites.get(state.fp.0).to_vec();
+            stack.push(pc);
+            let cost = profile.stacks.entry(stack.clone()).or_default();
+            cost.steps += 1;
+            charge_row(cost, component, cells_per_row);
+
+            let instruction = Instruction::decode(instructions[&pc]);
+            for address in accessed_addresses(state, &instruction, memory) {
+                address_stacks
+                    .entry(address)
+                    .or_insert_with(|| stack.clone());
+            }
+        }
+    }
+
+    // Every executed pc is verified once, which is charged to its first call stack.
+    let verify_instruction_cells =
+        cells_per_row(verify_instruction::Claim { log_size }.log_sizes());
+    let mut verified_pcs = HashSet::new();
+    for (stack, cost) in profile.stacks.iter_mut() {
+        if verified_pcs.insert(*stack.last().unwrap()) {
+            cost.charge("verify_instruction", verify_instruction_cells);
+        }
+    }
+
+    // Memory.
+    let address_cells = cells_per_row(memory_address_to_id::Claim { log_size }.log_sizes())
+        .div_ceil(MEMORY_ADDRESS_TO_ID_SPLIT as u64);
+    let small_value_cells = cells_per_row(
+        memory_id_to_big::Claim {
+            big_log_sizes: vec![],
+            small_log_size: log_size,
+        }
+        .log_sizes(),
+    );
+    let big_value_cells = cells_per_row(
+        memory_id_to_big::Claim {
+            big_log_sizes: vec![log_size],
+            small_log_size: log_size,
+        }
+        .log_sizes(),
+    ) - small_value_cells;
+    let mut charged_values = HashSet::new();
+    for (&address, stack) in address_stacks.iter().sorted() {
+        let Some(&id) = memory.address_to_id.get(address as usize) else {
+            continue;
+        };
+        let value_cells = match id.decode() {
+            MemoryValueId::Small(_) => small_value_cells,
+            MemoryValueId::F252(_) => big_value_cells,
+            MemoryValueId::Empty => continue,
+        };
+        let cost = profile.stacks.get_mut(stack).unwrap();
+        cost.memory_cells += 1;
+        cost.charge("memory_address_to_id", address_cells);
+        if charged_values.insert(id.0) {
+            cost.charge("memory_id_to_big", value_cells);
+        }
+    }
+
+    // Builtins.
+    let segments = &input.builtins_segments;
+    let builtins = [
+        (
+            "add_mod_builtin",
+            &segments.add_mod,
+            ADD_MOD_MEMORY_CELLS,
+            cells_per_row(
+                add_mod_builtin::Claim {
+                    log_size,
+                    add_mod_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+        (
+            "bitwise_builtin",
+            &segments.bitwise,
+            BITWISE_MEMORY_CELLS,
+            cells_per_row(
+                bitwise_builtin::Claim {
+                    log_size,
+                    bitwise_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+        (
+            "mul_mod_builtin",
+            &segments.mul_mod,
+            MUL_MOD_MEMORY_CELLS,
+            cells_per_row(
+                mul_mod_builtin::Claim {
+                    log_size,
+                    mul_mod_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+        (
+            "pedersen_builtin",
+            &segments.pedersen,
+            PEDERSEN_MEMORY_CELLS,
+            cells_per_row(
+                pedersen_builtin::Claim {
+                    log_size,
+                    pedersen_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+        (
+            "poseidon_builtin",
+            &segments.poseidon,
+            POSEIDON_MEMORY_CELLS,
+            cells_per_row(
+                poseidon_builtin::Claim {
+                    log_size,
+                    poseidon_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+        (
+            "range_check_builtin_bits_96",
+            &segments.range_check_bits_96,
+            RANGE_CHECK_MEMORY_CELLS,
+            cells_per_row(
+                range_check_builtin_bits_96::Claim {
+                    log_size,
+                    range_check96_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+        (
+            "range_check_builtin_bits_128",
+            &segments.range_check_bits_128,
+            RANGE_CHECK_MEMORY_CELLS,
+            cells_per_row(
+                range_check_builtin_bits_128::Claim {
+                    log_size,
+                    range_check_builtin_segment_start: 0,
+                }
+                .log_sizes(),
+            ),
+        ),
+    ];
+    for (component, segment, n_memory_cells, cells_per_row) in builtins {
+        let Some(MemorySegmentAddresses {
+            begin_addr,
+            stop_ptr,
+        }) = *segment
+        else {
+            continue;
+        };
+        for instance_start in (begin_addr..stop_ptr).step_by(n_memory_cells) {
+            // Instances nobody accessed, e.g. padding instances, aren't charged.
+            let Some(stack) = (instance_start..instance_start + n_memory_cells)
+                .find_map(|address| address_stacks.get(&(address as u32)))
+            else {
+                continue;
+            };
+            let cost = profile.stacks.get_mut(stack).unwrap();
+            cost.builtin_instances += 1;
+            charge_row(cost, component, cells_per_row);
+        }
+    }
+
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+
+    use super::*;
+
+    #[test]
+    fn test_function_map() {
+        let functions = FunctionMap::new(
+            1,
+            [
+                (10, vec!["b".to_string()]),
+                (0, vec!["a".to_string()]),
+                (20, vec!["b".to_string(), "c d".to_string()]),
+            ],
+        );
+
+        assert_eq!(functions.function_at(0), None);
+        assert_eq!(functions.function_at(1), Some("a"));
+        assert_eq!(functions.function_at(10), Some("a"));
+        assert_eq!(functions.function_at(11), Some("b"));
+        assert_eq!(functions.function_at(100), Some("c d"));
+        assert_eq!(functions.frames(100), ["b", "c_d"]);
+        assert_eq!(functions.frames(0), ["pc_0"]);
+        assert_eq!(functions.stack_frames(&vec![12, 5]), ["b", "a"]);
+    }
+
+    #[test]
+    fn test_profile_execution() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let n_steps: usize = input
+            .state_transitions
+            .casm_states_by_opcode
+            .counts()
+            .iter()
+            .map(|(_, count)| count)
+            .sum();
+
+        let profile = profile_execution(&input);
+
+        let total_steps: u64 = profile.stacks.values().map(|cost| cost.steps).sum();
+        assert_eq!(total_steps, n_steps as u64);
+        assert!(profile.by_pc().values().all(|cost| cost.memory_cells > 0));
+        // The program calls functions, so some steps run in called frames.
+        assert!(profile.stacks.keys().any(|stack| stack.len() > 1));
+        let mut folded = vec![];
+        profile
+            .write_folded(&FunctionMap::default(), &mut folded)
+            .unwrap();
+        let folded = String::from_utf8(folded).unwrap();
+        let folded_cells: u64 = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+            .sum();
+        assert_eq!(folded_cells, profile.total_trace_cells());
+    }
+}
@@ -1,37 +1,185 @@
+use std::time::Instant;
+
 use cairo_air::air::{lookup_sum, CairoComponents, CairoInteractionElements};
+use cairo_air::components::ComponentProverBackend;
 use cairo_air::verifier::INTERACTION_POW_BITS;
 use cairo_air::{CairoProof, PreProcessedTraceVariant};
+use itertools::Itertools;
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
-use stwo_cairo_adapter::ProverInput;
+use stwo_cairo_adapter::{ExecutionResources, ProverInput};
+use stwo_prover::constraint_framework::{INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX};
 use stwo_prover::core::backend::simd::SimdBackend;
-use stwo_prover::core::backend::BackendForChannel;
+use stwo_prover::core::backend::{BackendForChannel, Column, CpuBackend};
 use stwo_prover::core::channel::{Channel, MerkleChannel};
+use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::fri::FriConfig;
 use stwo_prover::core::pcs::{CommitmentSchemeProver, PcsConfig};
-use stwo_prover::core::poly::circle::{CanonicCoset, PolyOps};
+use stwo_prover::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+use stwo_prover::core::poly::BitReversedOrder;
 use stwo_prover::core::proof_of_work::GrindOps;
-use stwo_prover::core::prover::{prove, ProvingError};
+use stwo_prover::core::prover::prove;
 use tracing::{event, span, Level};
 
+use crate::hooks::{CairoProvingError, ProvingHooks, ProvingPhase};
+use crate::report::{component_reports, PeakRssSampler, PhaseTimings, ProvingReport};
 use crate::witness::cairo::CairoClaimGenerator;
-use crate::witness::utils::witness_trace_cells;
+use crate::witness::component_registry::WitnessRegistry;
+use crate::witness::utils::{witness_trace_cells, TraceBuffer};
 
 pub(crate) const LOG_MAX_ROWS: u32 = 26;
 
+/// A backend Cairo programs can be proven on. The witness is always generated by the SIMD witness
+/// generators, and transferred to the backend before it is committed.
+pub trait ProverBackend: ComponentProverBackend {
+    fn from_simd_evals(
+        evals: Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) -> Vec<CircleEvaluation<Self, M31, BitReversedOrder>>;
+}
+impl ProverBackend for SimdBackend {
+    fn from_simd_evals(
+        evals: Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) -> Vec<CircleEvaluation<Self, M31, BitReversedOrder>> {
+        evals
+    }
+}
+impl ProverBackend for CpuBackend {
+    fn from_simd_evals(
+        evals: Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>,
+    ) -> Vec<CircleEvaluation<Self, M31, BitReversedOrder>> {
+        evals
+            .into_iter()
+            .map(|eval| CircleEvaluation::new(eval.domain, eval.values.to_cpu()))
+            .collect()
+    }
+}
+
 pub fn prove_cairo<MC: MerkleChannel>(
     input: ProverInput,
     pcs_config: PcsConfig,
     preprocessed_trace: PreProcessedTraceVariant,
-) -> Result<CairoProof<MC::H>, ProvingError>
+) -> Result<CairoProof<MC::H>, CairoProvingError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    prove_cairo_inner::<SimdBackend, MC>(
+        input,
+        pcs_config,
+        preprocessed_trace,
+        &ProvingHooks::default(),
+        WitnessRegistry::default(),
+        None,
+    )
+}
+
+/// Same as [`prove_cairo`], proving on the backend `B`. Proofs are identical on all backends, so
+/// [`CpuBackend`] serves as a reference for the [`SimdBackend`] prover.
+pub fn prove_cairo_on_backend<B: ProverBackend, MC: MerkleChannel>(
+    input: ProverInput,
+    pcs_config: PcsConfig,
+    preprocessed_trace: PreProcessedTraceVariant,
+) -> Result<CairoProof<MC::H>, CairoProvingError>
+where
+    B: BackendForChannel<MC>,
+{
+    prove_cairo_inner::<B, MC>(
+        input,
+        pcs_config,
+        preprocessed_trace,
+        &ProvingHooks::default(),
+        WitnessRegistry::default(),
+        None,
+    )
+}
+
+/// Same as [`prove_cairo`], extending the Cairo AIR with the component families of `registry`.
+/// The proof must be verified with the same families, e.g. with
+/// [`cairo_air::verifier::verify_cairo_with_registry`].
+pub fn prove_cairo_with_registry<MC: MerkleChannel>(
+    input: ProverInput,
+    pcs_config: PcsConfig,
+    preprocessed_trace: PreProcessedTraceVariant,
+    registry: WitnessRegistry,
+) -> Result<CairoProof<MC::H>, CairoProvingError>
 where
     SimdBackend: BackendForChannel<MC>,
+{
+    prove_cairo_inner::<SimdBackend, MC>(
+        input,
+        pcs_config,
+        preprocessed_trace,
+        &ProvingHooks::default(),
+        registry,
+        None,
+    )
+}
+
+/// Same as [`prove_cairo`], but reports progress to `hooks` and stops with
+/// [`CairoProvingError::Cancelled`] once its cancellation token is cancelled.
+pub fn prove_cairo_with_hooks<MC: MerkleChannel>(
+    input: ProverInput,
+    pcs_config: PcsConfig,
+    preprocessed_trace: PreProcessedTraceVariant,
+    hooks: &ProvingHooks,
+) -> Result<CairoProof<MC::H>, CairoProvingError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    prove_cairo_inner::<SimdBackend, MC>(
+        input,
+        pcs_config,
+        preprocessed_trace,
+        hooks,
+        WitnessRegistry::default(),
+        None,
+    )
+}
+
+/// Same as [`prove_cairo`], but also returns a [`ProvingReport`] with per-component trace
+/// statistics, phase timings and the peak RSS observed while proving.
+pub fn prove_cairo_with_report<MC: MerkleChannel>(
+    input: ProverInput,
+    pcs_config: PcsConfig,
+    preprocessed_trace: PreProcessedTraceVariant,
+) -> Result<(CairoProof<MC::H>, ProvingReport), CairoProvingError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let rss_sampler = PeakRssSampler::start();
+    let mut report = ProvingReport::default();
+    let proof = prove_cairo_inner::<SimdBackend, MC>(
+        input,
+        pcs_config,
+        preprocessed_trace,
+        &ProvingHooks::default(),
+        WitnessRegistry::default(),
+        Some(&mut report),
+    )?;
+    report.peak_rss_bytes = rss_sampler.finish();
+    Ok((proof, report))
+}
+
+fn prove_cairo_inner<B: ProverBackend, MC: MerkleChannel>(
+    input: ProverInput,
+    pcs_config: PcsConfig,
+    preprocessed_trace_variant: PreProcessedTraceVariant,
+    hooks: &ProvingHooks,
+    registry: WitnessRegistry,
+    mut report: Option<&mut ProvingReport>,
+) -> Result<CairoProof<MC::H>, CairoProvingError>
+where
+    B: BackendForChannel<MC>,
 {
     let _span = span!(Level::INFO, "prove_cairo").entered();
+    let start = Instant::now();
+    // Only needed for the real row counts in the report.
+    let execution_resources = report
+        .as_ref()
+        .map(|_| ExecutionResources::from_prover_input(&input));
     // Composition polynomial domain log size is LOG_MAX_ROWS + 1, double it
     // because we compute on a half-coset, and account for blowup factor.
-    let twiddles = SimdBackend::precompute_twiddles(
+    let twiddles = B::precompute_twiddles(
         CanonicCoset::new(LOG_MAX_ROWS + pcs_config.fri_config.log_blowup_factor + 2)
             .circle_domain()
             .half_coset,
@@ -40,36 +188,51 @@ where
     // Setup protocol.
     let channel = &mut MC::C::default();
     pcs_config.mix_into(channel);
-    let mut commitment_scheme =
-        CommitmentSchemeProver::<SimdBackend, MC>::new(pcs_config, &twiddles);
+    let mut commitment_scheme = CommitmentSchemeProver::<B, MC>::new(pcs_config, &twiddles);
+
+    // Run Cairo.
+    let (registry, extensions) = registry.into_parts();
+    let cairo_claim_generator =
+        CairoClaimGenerator::new(input).with_extensions(registry.clone(), extensions);
+    // Base trace. Buffered until the preprocessed trace, which may depend on the claim, is
+    // committed.
+    let mut base_trace = TraceBuffer::new(ORIGINAL_TRACE_IDX);
+    let span = span!(Level::INFO, "Base trace").entered();
+    let phase_start = Instant::now();
+    let (claim, interaction_generator) =
+        cairo_claim_generator.write_trace_with_hooks(&mut base_trace, hooks)?;
+    let base_trace_duration = phase_start.elapsed();
+    span.exit();
 
     // Preprocessed trace.
-    let preprocessed_trace = preprocessed_trace.to_preprocessed_trace();
+    hooks.enter(ProvingPhase::PreprocessedCommit)?;
+    let phase_start = Instant::now();
+    let preprocessed_trace = preprocessed_trace_variant.to_preprocessed_trace_for_claim(&claim);
     let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(preprocessed_trace.gen_trace());
+    tree_builder.extend_evals(B::from_simd_evals(preprocessed_trace.gen_trace()));
     tree_builder.commit(channel);
+    let preprocessed_trace_duration = phase_start.elapsed();
 
-    // Run Cairo.
-    let cairo_claim_generator = CairoClaimGenerator::new(input);
-    // Base trace.
+    hooks.enter(ProvingPhase::BaseTraceCommit)?;
     let mut tree_builder = commitment_scheme.tree_builder();
-    let span = span!(Level::INFO, "Base trace").entered();
-    let (claim, interaction_generator) = cairo_claim_generator.write_trace(&mut tree_builder);
-    span.exit();
-
+    tree_builder.extend_evals(B::from_simd_evals(base_trace.into_evals()));
     claim.mix_into(channel);
     tree_builder.commit(channel);
 
     // Draw interaction elements.
-    let interaction_pow = SimdBackend::grind(channel, INTERACTION_POW_BITS);
+    hooks.enter(ProvingPhase::InteractionPow)?;
+    let interaction_pow = B::grind(channel, INTERACTION_POW_BITS);
     channel.mix_u64(interaction_pow);
     let interaction_elements = CairoInteractionElements::draw(channel);
 
     // Interaction trace.
+    hooks.enter(ProvingPhase::InteractionTrace)?;
     let span = span!(Level::INFO, "Interaction trace").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    let interaction_claim =
-        interaction_generator.write_interaction_trace(&mut tree_builder, &interaction_elements);
+    let phase_start = Instant::now();
+    let mut interaction_trace = TraceBuffer::new(INTERACTION_TRACE_IDX);
+    let interaction_claim = interaction_generator
+        .write_interaction_trace(&mut interaction_trace, &interaction_elements);
+    let interaction_trace_duration = phase_start.elapsed();
     span.exit();
 
     tracing::info!(
@@ -82,15 +245,18 @@ where
         SecureField::zero()
     );
 
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(B::from_simd_evals(interaction_trace.into_evals()));
     interaction_claim.mix_into(channel);
     tree_builder.commit(channel);
 
     // Component provers.
-    let component_builder = CairoComponents::new(
+    let component_builder = CairoComponents::new_with_registry(
         &claim,
         &interaction_elements,
         &interaction_claim,
         &preprocessed_trace.ids(),
+        &registry,
     );
 
     // TODO(Ohad): move to a testing routine.
@@ -105,20 +271,39 @@ where
         tracing::info!("Relations summary: {:?}", summary);
     }
 
-    let components = component_builder.provers();
+    let component_provers = component_builder.provers::<B>()?;
+    let components = component_provers
+        .iter()
+        .map(|prover| &**prover)
+        .collect_vec();
 
     // Prove stark.
+    hooks.enter(ProvingPhase::ProveStark)?;
     let span = span!(Level::INFO, "Prove STARKs").entered();
-    let proof = prove::<SimdBackend, _>(&components, channel, commitment_scheme)?;
+    let phase_start = Instant::now();
+    let proof = prove::<B, MC>(&components, channel, commitment_scheme)?;
+    let prove_stark_duration = phase_start.elapsed();
     span.exit();
 
     event!(name: "component_info", Level::DEBUG, "Components: {}", component_builder);
 
+    if let (Some(report), Some(execution_resources)) = (report.as_mut(), &execution_resources) {
+        report.components = component_reports(&component_builder, execution_resources);
+        report.timings = PhaseTimings {
+            preprocessed_trace: preprocessed_trace_duration,
+            base_trace: base_trace_duration,
+            interaction_trace: interaction_trace_duration,
+            prove_stark: prove_stark_duration,
+            total: start.elapsed(),
+        };
+    }
+
     Ok(CairoProof {
         claim,
         interaction_pow,
         interaction_claim,
         stark_proof: proof,
+        preprocessed_trace: preprocessed_trace_variant,
     })
 }
 
@@ -236,22 +421,49 @@ pub mod tests {
 
         use std::io::Write;
         use std::process::Command;
+        use std::sync::{Arc, Mutex};
 
+        use cairo_air::air::{
+            CairoInteractionElements, PublicData, RelationUsesDict, SegmentRange,
+        };
+        use cairo_air::builtins_air::RangeCheck128BuiltinFamily;
+        use cairo_air::component_registry::ComponentFamily;
+        use cairo_air::components::range_check_builtin_bits_128;
         use cairo_air::preprocessed::PreProcessedTrace;
-        use cairo_air::verifier::verify_cairo;
+        use cairo_air::utils::{serialize_proof_to_file, ProofFormat};
+        use cairo_air::verifier::{
+            verify_cairo, verify_cairo_with_registry, CairoVerificationError, CairoVerifier,
+        };
+        use cairo_air::CairoProof;
         use itertools::Itertools;
         use stwo_cairo_adapter::adapter::read_and_adapt_prover_input_info_file;
         use stwo_cairo_adapter::test_utils::{get_prover_input_info_path, get_test_program};
         use stwo_cairo_serialize::CairoSerialize;
+        use stwo_prover::core::air::ComponentProvers;
+        use stwo_prover::core::backend::simd::SimdBackend;
+        use stwo_prover::core::backend::CpuBackend;
         use stwo_prover::core::fri::FriConfig;
-        use stwo_prover::core::pcs::PcsConfig;
+        use stwo_prover::core::pcs::{PcsConfig, TreeVec};
         use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
         use tempfile::NamedTempFile;
         use test_log::test;
 
         use super::*;
         use crate::debug_tools::assert_constraints::assert_cairo_constraints;
-        use crate::prover::{prove_cairo, PreProcessedTraceVariant, ProverInput};
+        use crate::hooks::{ProvingHooks, ProvingPhase};
+        use crate::prover::{
+            prove_cairo, prove_cairo_on_backend, prove_cairo_with_hooks, prove_cairo_with_registry,
+            PreProcessedTraceVariant, ProverInput,
+        };
+        use crate::witness::builtins::BuiltinWitness;
+        use crate::witness::component_registry::{
+            ComponentFamilyWitness, SharedLookupTables, WitnessRegistry,
+        };
+        use crate::witness::components::range_check_builtin_bits_128::{
+            ClaimGenerator as RangeCheck128BuiltinClaimGenerator,
+            InteractionClaimGenerator as RangeCheck128BuiltinInteractionClaimGenerator,
+        };
+        use crate::witness::utils::TreeBuilder;
 
         // TODO(Ohad): fine-grained constraints tests.
         #[test]
@@ -287,6 +499,51 @@ pub mod tests {
             .unwrap();
         }
 
+        #[test]
+        fn test_prove_verify_with_hooks_reports_phases() {
+            let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+            let input = run_program_and_adapter(&compiled_program);
+            let phases = Arc::new(Mutex::new(vec![]));
+            let hooks = ProvingHooks::default().with_progress({
+                let phases = phases.clone();
+                move |phase| phases.lock().unwrap().push(phase)
+            });
+            let preprocessed_trace = PreProcessedTraceVariant::CanonicalWithoutPedersen;
+
+            let cairo_proof = prove_cairo_with_hooks::<Blake2sMerkleChannel>(
+                input,
+                PcsConfig::default(),
+                preprocessed_trace,
+                &hooks,
+            )
+            .unwrap();
+
+            verify_cairo::<Blake2sMerkleChannel>(
+                cairo_proof,
+                PcsConfig::default(),
+                preprocessed_trace,
+            )
+            .unwrap();
+            let phases = phases.lock().unwrap();
+            let (base_trace_phases, other_phases): (Vec<_>, Vec<_>) = phases
+                .iter()
+                .partition(|phase| matches!(phase, ProvingPhase::BaseTrace { .. }));
+            assert_eq!(
+                other_phases,
+                [
+                    &ProvingPhase::PreprocessedCommit,
+                    &ProvingPhase::BaseTraceCommit,
+                    &ProvingPhase::InteractionPow,
+                    &ProvingPhase::InteractionTrace,
+                    &ProvingPhase::ProveStark,
+                ]
+            );
+            for component in ["add_opcode", "memory_id_to_big", "range_check_4_3"] {
+                assert!(base_trace_phases.contains(&&ProvingPhase::BaseTrace { component }));
+            }
+            assert!(base_trace_phases.iter().all_unique());
+        }
+
         #[test]
         fn test_e2e_prove_cairo_verify_all_opcode_components() {
             let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
@@ -357,6 +614,278 @@ pub mod tests {
             .unwrap();
         }
 
+        #[test]
+        fn test_prove_verify_usage_aware_preprocessed_trace() {
+            let compiled_program = get_test_program("test_prove_verify_bitwise_builtin");
+            let input = run_program_and_adapter(&compiled_program);
+            let preprocessed_trace = PreProcessedTraceVariant::UsageAware;
+            let cairo_proof = prove_cairo::<Blake2sMerkleChannel>(
+                input,
+                PcsConfig::default(),
+                preprocessed_trace,
+            )
+            .unwrap();
+            let usage_aware_trace =
+                preprocessed_trace.to_preprocessed_trace_for_claim(&cairo_proof.claim);
+            assert!(
+                usage_aware_trace.ids().len()
+                    < PreProcessedTrace::canonical_without_pedersen().ids().len()
+            );
+
+            let serialized_proof = serde_json::to_string(&cairo_proof).unwrap();
+            assert!(matches!(
+                verify_cairo::<Blake2sMerkleChannel>(
+                    serde_json::from_str(&serialized_proof).unwrap(),
+                    PcsConfig::default(),
+                    PreProcessedTraceVariant::Canonical,
+                ),
+                Err(CairoVerificationError::PreProcessedTraceMismatch { .. })
+            ));
+            // The Cairo verifier only has the canonical preprocessed trace.
+            let proof_file = NamedTempFile::new().unwrap();
+            let error = serialize_proof_to_file::<Blake2sMerkleChannel>(
+                &cairo_proof,
+                proof_file.path().into(),
+                ProofFormat::CairoSerde,
+            )
+            .unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+            verify_cairo::<Blake2sMerkleChannel>(
+                cairo_proof,
+                PcsConfig::default(),
+                preprocessed_trace,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_prove_verify_omits_unused_lookup_tables() {
+            let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+            let input = run_program_and_adapter(&compiled_program);
+            let preprocessed_trace = PreProcessedTraceVariant::Canonical;
+            let cairo_proof = prove_cairo::<Blake2sMerkleChannel>(
+                input,
+                PcsConfig::default(),
+                preprocessed_trace,
+            )
+            .unwrap();
+
+            // `range_check_3_6_6_3` is only looked up by the `mul_mod` builtin.
+            assert!(cairo_proof.claim.range_checks.rc_3_6_6_3.is_none());
+            assert!(cairo_proof
+                .interaction_claim
+                .range_checks
+                .rc_3_6_6_3
+                .is_none());
+            assert!(cairo_proof.claim.range_checks.rc_9_9.is_some());
+
+            verify_cairo::<Blake2sMerkleChannel>(
+                cairo_proof,
+                PcsConfig::default(),
+                preprocessed_trace,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_verify_many() {
+            let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+            let input = run_program_and_adapter(&compiled_program);
+            let preprocessed_trace = PreProcessedTraceVariant::CanonicalWithoutPedersen;
+            let cairo_proof = prove_cairo::<Blake2sMerkleChannel>(
+                input,
+                PcsConfig::default(),
+                preprocessed_trace,
+            )
+            .unwrap();
+            let serialized_proof = serde_json::to_string(&cairo_proof).unwrap();
+            let proof_copy = || serde_json::from_str(&serialized_proof).unwrap();
+            let mut wrong_pow: CairoProof<_> = proof_copy();
+            wrong_pow.interaction_pow += 1;
+            let mut invalid_claim: CairoProof<_> = proof_copy();
+            invalid_claim.claim.memory_address_to_id.log_size = 31;
+
+            let verifier = CairoVerifier::new(PcsConfig::default());
+            let results = verifier.verify_many::<Blake2sMerkleChannel>(vec![
+                (cairo_proof, preprocessed_trace),
+                (wrong_pow, preprocessed_trace),
+                (invalid_claim, preprocessed_trace),
+                (proof_copy(), preprocessed_trace),
+            ]);
+
+            assert!(results[0].is_ok());
+            assert!(matches!(
+                results[1],
+                Err(CairoVerificationError::ProofOfWork)
+            ));
+            assert!(matches!(
+                results[2],
+                Err(CairoVerificationError::Malformed(_))
+            ));
+            assert!(results[3].is_ok());
+        }
+
+        /// A second range check 128 builtin, over the segment of the builtin of the Cairo AIR. It
+        /// looks up the same memory cells again, so the lookup sum only balances if its lookups
+        /// are counted in the memory tables.
+        struct DuplicateRangeCheck128Family;
+        impl ComponentFamily for DuplicateRangeCheck128Family {
+            type Claim = range_check_builtin_bits_128::Claim;
+            type Eval = range_check_builtin_bits_128::Eval;
+
+            fn id(&self) -> u32 {
+                RangeCheck128BuiltinFamily.id()
+            }
+
+            fn log_sizes(&self, claim: &Self::Claim) -> TreeVec<Vec<u32>> {
+                RangeCheck128BuiltinFamily.log_sizes(claim)
+            }
+
+            fn accumulate_relation_uses(
+                &self,
+                claim: &Self::Claim,
+                relation_uses: &mut RelationUsesDict,
+            ) {
+                RangeCheck128BuiltinFamily.accumulate_relation_uses(claim, relation_uses)
+            }
+
+            fn verify_claim(
+                &self,
+                claim: &Self::Claim,
+                public_data: &PublicData,
+                segment: Option<&SegmentRange>,
+            ) -> Result<(), CairoVerificationError> {
+                assert!(segment.is_none());
+                RangeCheck128BuiltinFamily.verify_claim(
+                    claim,
+                    public_data,
+                    public_data
+                        .public_memory
+                        .public_segments
+                        .range_check_128
+                        .as_ref(),
+                )
+            }
+
+            fn n_components(&self, claim: &Self::Claim) -> usize {
+                RangeCheck128BuiltinFamily.n_components(claim)
+            }
+
+            fn evals(
+                &self,
+                claim: &Self::Claim,
+                interaction_elements: &CairoInteractionElements,
+            ) -> Vec<Self::Eval> {
+                RangeCheck128BuiltinFamily.evals(claim, interaction_elements)
+            }
+        }
+
+        struct DuplicateRangeCheck128Witness(RangeCheck128BuiltinClaimGenerator);
+        impl ComponentFamilyWitness for DuplicateRangeCheck128Witness {
+            type Family = DuplicateRangeCheck128Family;
+            type InteractionWitness = RangeCheck128BuiltinInteractionClaimGenerator;
+
+            fn write_trace(
+                self,
+                tree_builder: &mut impl TreeBuilder<SimdBackend>,
+                lookup_tables: &mut SharedLookupTables<'_>,
+            ) -> (
+                range_check_builtin_bits_128::Claim,
+                RangeCheck128BuiltinInteractionClaimGenerator,
+            ) {
+                ComponentFamilyWitness::write_trace(self.0, tree_builder, lookup_tables)
+            }
+        }
+
+        #[test]
+        fn test_prove_verify_with_registry() {
+            let compiled_program = get_test_program("test_prove_verify_all_builtins");
+            let input = run_program_and_adapter(&compiled_program);
+            let preprocessed_trace = PreProcessedTraceVariant::CanonicalWithoutPedersen;
+            let segment = input.builtins_segments.range_check_bits_128.unwrap();
+            let mut registry = WitnessRegistry::new();
+            registry.register(
+                DuplicateRangeCheck128Family,
+                DuplicateRangeCheck128Witness(BuiltinWitness::from_segment(segment)),
+            );
+            let component_registry = registry.registry().clone();
+            let cairo_proof = prove_cairo_with_registry::<Blake2sMerkleChannel>(
+                input,
+                PcsConfig::default(),
+                preprocessed_trace,
+                registry,
+            )
+            .unwrap();
+            let [extension_claim] = &cairo_proof.claim.extensions[..] else {
+                panic!("Expected a single extension claim");
+            };
+            // The family's component is a copy of the builtin's, so are its claims.
+            let builtin_claim = cairo_proof.claim.builtins.range_check_128_builtin.unwrap();
+            assert_eq!(
+                extension_claim.values,
+                [
+                    builtin_claim.log_size,
+                    builtin_claim.range_check_builtin_segment_start
+                ]
+            );
+            assert_eq!(
+                cairo_proof.interaction_claim.extensions[0].claimed_sums,
+                [cairo_proof
+                    .interaction_claim
+                    .builtins
+                    .range_check_128_builtin
+                    .unwrap()
+                    .claimed_sum]
+            );
+
+            // Verifiers without the family reject the proof.
+            let serialized_proof = serde_json::to_string(&cairo_proof).unwrap();
+            let results = CairoVerifier::new(PcsConfig::default())
+                .verify_many::<Blake2sMerkleChannel>(vec![(
+                    serde_json::from_str(&serialized_proof).unwrap(),
+                    preprocessed_trace,
+                )]);
+            assert!(matches!(
+                results[0],
+                Err(CairoVerificationError::Malformed(_))
+            ));
+
+            verify_cairo_with_registry::<Blake2sMerkleChannel>(
+                cairo_proof,
+                PcsConfig::default(),
+                preprocessed_trace,
+                &component_registry,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_cpu_backend_proof_matches_simd() {
+            for program in [
+                "test_prove_verify_all_opcode_components",
+                "test_prove_verify_all_builtins",
+            ] {
+                let input = run_program_and_adapter(&get_test_program(program));
+                let preprocessed_trace = PreProcessedTraceVariant::UsageAware;
+                let serialize = |proof: CairoProof<_>| serde_json::to_string(&proof).unwrap();
+
+                let simd_proof = prove_cairo_on_backend::<SimdBackend, Blake2sMerkleChannel>(
+                    input.clone(),
+                    PcsConfig::default(),
+                    preprocessed_trace,
+                )
+                .unwrap();
+                let cpu_proof = prove_cairo_on_backend::<CpuBackend, Blake2sMerkleChannel>(
+                    input,
+                    PcsConfig::default(),
+                    preprocessed_trace,
+                )
+                .unwrap();
+
+                assert_eq!(serialize(cpu_proof), serialize(simd_proof), "{program}");
+            }
+        }
+
         fn test_proof_stability(path: &str, n_proofs_to_compare: usize) {
             let prover_input_file_path = get_prover_input_info_path(path);
             let input = read_and_adapt_prover_input_info_file(&prover_input_file_path).unwrap();
@@ -0,0 +1,284 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use cairo_air::air::{CairoComponentVisitor, CairoComponents};
+use cairo_air::components::memory_address_to_id::MEMORY_ADDRESS_TO_ID_SPLIT;
+use cairo_air::components::ColumnNames;
+use cairo_vm::types::builtin_name::BuiltinName;
+use serde::{Deserialize, Serialize};
+use stwo_cairo_adapter::ExecutionResources;
+use stwo_prover::constraint_framework::{
+    FrameworkComponent, FrameworkEval, INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX,
+};
+use stwo_prover::core::air::Component;
+
+/// Interval between two consecutive RSS samples.
+const RSS_SAMPLING_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A structured summary of a single `prove_cairo` run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProvingReport {
+    /// Per-component trace statistics, in commitment order.
+    pub components: Vec<ComponentReport>,
+    /// Wall-clock time spent in each proving phase.
+    pub timings: PhaseTimings,
+    /// Peak resident set size observed while proving, in bytes.
+    /// `None` if RSS can not be sampled on this platform.
+    pub peak_rss_bytes: Option<u64>,
+}
+impl ProvingReport {
+    /// Total number of base and interaction trace cells, over all components.
+    pub fn total_trace_cells(&self) -> u64 {
+        self.components.iter().map(|c| c.trace_cells()).sum()
+    }
+
+    /// Total number of padding rows, over all components with a known number of real rows.
+    pub fn total_padding_rows(&self) -> u64 {
+        self.components
+            .iter()
+            .filter_map(|c| c.padding_rows())
+            .sum()
+    }
+}
+
+/// Trace statistics of a single component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentReport {
+    pub name: String,
+    pub log_size: u32,
+    pub n_trace_columns: usize,
+    pub n_interaction_columns: usize,
+    /// Number of rows holding real instances, before padding to a power of two.
+    /// `None` for components whose size does not depend on the execution (e.g. lookup tables)
+    /// or that are only fed by other components.
+    pub n_real_rows: Option<u64>,
+}
+impl ComponentReport {
+    pub fn n_rows(&self) -> u64 {
+        1 << self.log_size
+    }
+
+    pub fn trace_cells(&self) -> u64 {
+        self.n_rows() * (self.n_trace_columns + self.n_interaction_columns) as u64
+    }
+
+    /// Number of rows added only to pad the component to a power of two.
+    pub fn padding_rows(&self) -> Option<u64> {
+        self.n_real_rows
+            .map(|n_real_rows| self.n_rows().saturating_sub(n_real_rows))
+    }
+}
+
+/// Wall-clock durations of the proving phases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhaseTimings {
+    pub preprocessed_trace: Duration,
+    pub base_trace: Duration,
+    pub interaction_trace: Duration,
+    pub prove_stark: Duration,
+    pub total: Duration,
+}
+
+/// Builds a [`ComponentReport`] for every component, named as in the other debug tools.
+/// Real row counts are taken from `resources` where they are known.
+pub fn component_reports(
+    components: &CairoComponents,
+    resources: &ExecutionResources,
+) -> Vec<ComponentReport> {
+    let mut visitor = ReportVisitor {
+        resources,
+        // The big values table is split into several components when it exceeds the maximal
+        // sequence size, in which case the per-component real size is not known here.
+        single_big_component: components.memory_id_to_value.0.len() == 1,
+        reports: vec![],
+    };
+    components.visit(&mut visitor);
+    visitor.reports
+}
+
+struct ReportVisitor<'a> {
+    resources: &'a ExecutionResources,
+    single_big_component: bool,
+    reports: Vec<ComponentReport>,
+}
+impl ReportVisitor<'_> {
+    fn n_real_rows(&self, name: &str) -> Option<usize> {
+        let resources = self.resources;
+        let builtin_count =
+            |builtin: BuiltinName| resources.builtin_instance_counts.get(&builtin).copied();
+        let memory_sizes = &resources.memory_tables_sizes;
+        match name {
+            "verify_instruction" => Some(resources.verify_instructions_count),
+            "add_mod_builtin" => builtin_count(BuiltinName::add_mod),
+            "bitwise_builtin" => builtin_count(BuiltinName::bitwise),
+            "mul_mod_builtin" => builtin_count(BuiltinName::mul_mod),
+            "pedersen_builtin" => builtin_count(BuiltinName::pedersen),
+            "poseidon_builtin" => builtin_count(BuiltinName::poseidon),
+            "range_check_builtin_bits_96" => builtin_count(BuiltinName::range_check96),
+            "range_check_builtin_bits_128" => builtin_count(BuiltinName::range_check),
+            "memory_address_to_id" => Some(
+                memory_sizes
+                    .address_to_id
+                    .div_ceil(MEMORY_ADDRESS_TO_ID_SPLIT),
+            ),
+            "memory_id_to_big" => self.single_big_component.then_some(memory_sizes.id_to_big),
+            "memory_id_to_small" => Some(memory_sizes.id_to_small),
+            // Opcode components are counted by name. The sizes of the other components don't
+            // follow directly from the execution.
+            _ => resources.opcode_instance_counts.get(name).copied(),
+        }
+    }
+}
+impl CairoComponentVisitor for ReportVisitor<'_> {
+    fn visit<E: FrameworkEval + ColumnNames + Sync>(
+        &mut self,
+        name: &'static str,
+        component: &FrameworkComponent<E>,
+    ) {
+        let log_degree_bounds = component.trace_log_degree_bounds();
+        self.reports.push(ComponentReport {
+            name: name.to_string(),
+            log_size: component.log_size(),
+            n_trace_columns: log_degree_bounds[ORIGINAL_TRACE_IDX].len(),
+            n_interaction_columns: log_degree_bounds[INTERACTION_TRACE_IDX].len(),
+            n_real_rows: self.n_real_rows(name).map(|n| n as u64),
+        });
+    }
+}
+
+/// Samples the resident set size of the current process on a background thread, keeping the
+/// maximal value seen until [`PeakRssSampler::finish`] is called.
+pub struct PeakRssSampler {
+    stop: Arc<AtomicBool>,
+    peak: Arc<AtomicU64>,
+    handle: Option<JoinHandle<()>>,
+}
+impl PeakRssSampler {
+    pub fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let peak = Arc::new(AtomicU64::new(0));
+        // Don't spawn a thread if RSS is not available on this platform.
+        let handle = current_rss_bytes().map(|rss| {
+            peak.store(rss, Ordering::Relaxed);
+            let stop = stop.clone();
+            let peak = peak.clone();
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Some(rss) = current_rss_bytes() {
+                        peak.fetch_max(rss, Ordering::Relaxed);
+                    }
+                    std::thread::sleep(RSS_SAMPLING_INTERVAL);
+                }
+            })
+        });
+        Self { stop, peak, handle }
+    }
+
+    /// Stops sampling and returns the peak RSS in bytes, if it could be sampled.
+    pub fn finish(mut self) -> Option<u64> {
+        let handle = self.handle.take()?;
+        self.stop.store(true, Ordering::Relaxed);
+        handle.join().expect("RSS sampler thread panicked");
+        // Take a last sample, in case the peak was reached right before finishing.
+        if let Some(rss) = current_rss_bytes() {
+            self.peak.fetch_max(rss, Ordering::Relaxed);
+        }
+        Some(self.peak.load(Ordering::Relaxed))
+    }
+}
+impl Drop for PeakRssSampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Reads the current resident set size from `/proc/self/status`.
+/// Returns `None` on platforms without procfs.
+fn current_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_air::preprocessed::testing_preprocessed_tree;
+    use itertools::Itertools;
+    use stwo_cairo_adapter::test_utils::{get_test_program, run_program_and_adapter};
+
+    use super::*;
+    use crate::debug_tools::mock_tree_builder::mock_cairo_traces;
+    use crate::debug_tools::trace_dump::cairo_component_traces;
+
+    #[test]
+    fn test_component_report_padding() {
+        let report = ComponentReport {
+            name: "add_opcode".to_string(),
+            log_size: 5,
+            n_trace_columns: 10,
+            n_interaction_columns: 8,
+            n_real_rows: Some(20),
+        };
+
+        assert_eq!(report.n_rows(), 32);
+        assert_eq!(report.padding_rows(), Some(12));
+        assert_eq!(report.trace_cells(), 32 * 18);
+    }
+
+    #[test]
+    fn test_proving_report_serializes_to_json() {
+        let report = ProvingReport {
+            components: vec![ComponentReport {
+                name: "ret_opcode".to_string(),
+                log_size: 4,
+                n_trace_columns: 12,
+                n_interaction_columns: 16,
+                n_real_rows: None,
+            }],
+            timings: PhaseTimings::default(),
+            peak_rss_bytes: Some(1 << 20),
+        };
+
+        let json = serde_json::to_string(&report).unwrap();
+        let deserialized: ProvingReport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.components[0].name, "ret_opcode");
+        assert_eq!(deserialized.total_trace_cells(), report.total_trace_cells());
+        assert_eq!(deserialized.peak_rss_bytes, Some(1 << 20));
+    }
+
+    #[test]
+    fn test_component_reports_match_trace_dump() {
+        let compiled_program = get_test_program("test_prove_verify_all_opcode_components");
+        let input = run_program_and_adapter(&compiled_program);
+        let resources = ExecutionResources::from_prover_input(&input);
+        let preprocessed_trace = testing_preprocessed_tree(20);
+        let traces = mock_cairo_traces(input.clone(), &preprocessed_trace);
+
+        let reports = component_reports(&traces.components(), &resources);
+
+        let dumps = cairo_component_traces(input, &preprocessed_trace);
+        assert_eq!(
+            reports.iter().map(|r| r.name.as_str()).collect_vec(),
+            dumps.iter().map(|d| d.component).collect_vec()
+        );
+        for (report, dump) in reports.iter().zip(&dumps) {
+            assert_eq!(report.n_rows() as usize, dump.n_rows());
+            assert_eq!(
+                report.n_trace_columns + report.n_interaction_columns,
+                dump.columns.len()
+            );
+        }
+        let ret = reports.iter().find(|r| r.name == "ret_opcode").unwrap();
+        assert_eq!(
+            ret.n_real_rows,
+            resources
+                .opcode_instance_counts
+                .get("ret_opcode")
+                .map(|&n| n as u64)
+        );
+    }
+}
@@ -6,6 +6,7 @@ use stwo_cairo_adapter::memory::Memory;
 use stwo_prover::core::backend::simd::SimdBackend;
 use tracing::{span, Level};
 
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::{
     blake_g, blake_round, blake_round_sigma, memory_address_to_id, memory_id_to_big, triple_xor_32,
     verify_bitwise_xor_12, verify_bitwise_xor_4, verify_bitwise_xor_7, verify_bitwise_xor_8,
@@ -48,14 +49,16 @@ impl BlakeContextClaimGenerator {
         verify_bitwise_xor_7_trace_generator: &verify_bitwise_xor_7::ClaimGenerator,
         verify_bitwise_xor_8_trace_generator: &verify_bitwise_xor_8::ClaimGenerator,
         verify_bitwise_xor_9_trace_generator: &verify_bitwise_xor_9::ClaimGenerator,
-    ) -> (BlakeContextClaim, BlakeContextInteractionClaimGenerator) {
+        hooks: &ProvingHooks,
+    ) -> Result<(BlakeContextClaim, BlakeContextInteractionClaimGenerator), CairoProvingError> {
         let span = span!(Level::INFO, "write blake context trace").entered();
         if self.blake_round.is_empty() {
-            return (
+            return Ok((
                 BlakeContextClaim { claim: None },
                 BlakeContextInteractionClaimGenerator { gen: None },
-            );
+            ));
         }
+        hooks.enter_base_trace("blake_round", true)?;
         let (blake_round_claim, blake_round_interaction_gen) = self.blake_round.write_trace(
             tree_builder,
             &mut self.blake_g,
@@ -64,6 +67,7 @@ impl BlakeContextClaimGenerator {
             memory_id_to_value_trace_generator,
             &range_checks_trace_generator.rc_7_2_5_trace_generator,
         );
+        hooks.enter_base_trace("blake_g", true)?;
         let (blake_g_claim, blake_g_interaction_gen) = self.blake_g.write_trace(
             tree_builder,
             &self.verify_bitwise_xor_12,
@@ -72,11 +76,14 @@ impl BlakeContextClaimGenerator {
             verify_bitwise_xor_8_trace_generator,
             verify_bitwise_xor_9_trace_generator,
         );
+        hooks.enter_base_trace("blake_round_sigma", true)?;
         let (blake_sigma_claim, blake_sigma_interaction_gen) =
             self.blake_sigma.write_trace(tree_builder);
+        hooks.enter_base_trace("triple_xor_32", true)?;
         let (triple_xor_32_claim, triple_xor_32_interaction_gen) = self
             .triple_xor_32
             .write_trace(tree_builder, verify_bitwise_xor_8_trace_generator);
+        hooks.enter_base_trace("verify_bitwise_xor_12", true)?;
         let (verify_bitwise_xor_12_claim, verify_bitwise_xor_12_interaction_gen) =
             self.verify_bitwise_xor_12.write_trace(tree_builder);
         span.exit();
@@ -95,10 +102,10 @@ impl BlakeContextClaimGenerator {
             triple_xor_32_interaction_gen,
             verify_bitwise_xor_12_interaction_gen,
         });
-        (
+        Ok((
             BlakeContextClaim { claim },
             BlakeContextInteractionClaimGenerator { gen },
-        )
+        ))
     }
 }
 
@@ -1,369 +1,284 @@
 use cairo_air::air::CairoInteractionElements;
-use cairo_air::builtins_air::{BuiltinsClaim, BuiltinsInteractionClaim};
-use stwo_cairo_adapter::builtins::{
-    BuiltinSegments, ADD_MOD_MEMORY_CELLS, BITWISE_MEMORY_CELLS, MUL_MOD_MEMORY_CELLS,
-    PEDERSEN_MEMORY_CELLS, POSEIDON_MEMORY_CELLS, RANGE_CHECK_MEMORY_CELLS,
+use cairo_air::builtins_air::{
+    AddModBuiltinFamily, BitwiseBuiltinFamily, BuiltinFamily, BuiltinsClaim,
+    BuiltinsInteractionClaim, MulModBuiltinFamily, PedersenBuiltinFamily, PoseidonBuiltinFamily,
+    RangeCheck128BuiltinFamily, RangeCheck96BuiltinFamily,
 };
+use stwo_cairo_adapter::builtins::{BuiltinSegments, MemorySegmentAddresses};
 use stwo_prover::core::backend::simd::SimdBackend;
 
-use super::components::pedersen::PedersenContextClaimGenerator;
-use super::components::poseidon::PoseidonContextClaimGenerator;
+use super::component_registry::{
+    ComponentFamilyInteractionWitness, ComponentFamilyWitness, SharedLookupTables,
+};
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::{
-    add_mod_builtin, bitwise_builtin, memory_address_to_id, memory_id_to_big, mul_mod_builtin,
-    pedersen_builtin, poseidon_builtin, range_check_12, range_check_18, range_check_3_3_3_3_3,
-    range_check_3_6_6_3, range_check_4_4, range_check_4_4_4_4, range_check_5_4, range_check_6,
-    range_check_8, range_check_builtin_bits_128, range_check_builtin_bits_96, verify_bitwise_xor_9,
+    add_mod_builtin, bitwise_builtin, mul_mod_builtin, pedersen_builtin, poseidon_builtin,
+    range_check_builtin_bits_128, range_check_builtin_bits_96,
 };
 use crate::witness::utils::TreeBuilder;
-pub struct BuiltinsClaimGenerator {
-    add_mod_builtin_trace_generator: Option<add_mod_builtin::ClaimGenerator>,
-    bitwise_builtin_trace_generator: Option<bitwise_builtin::ClaimGenerator>,
-    mul_mod_builtin_trace_generator: Option<mul_mod_builtin::ClaimGenerator>,
-    pedersen_builtin_trace_generator: Option<pedersen_builtin::ClaimGenerator>,
-    poseidon_builtin_trace_generator: Option<poseidon_builtin::ClaimGenerator>,
-    range_check_96_builtin_trace_generator: Option<range_check_builtin_bits_96::ClaimGenerator>,
-    range_check_128_builtin_trace_generator: Option<range_check_builtin_bits_128::ClaimGenerator>,
+
+/// The witness of a [`BuiltinFamily`] over a builtin segment, either a builtin of the Cairo AIR
+/// or a registered one.
+pub trait BuiltinWitness: ComponentFamilyWitness<Family: BuiltinFamily> + Sized {
+    fn new(log_size: u32, segment_start: u32) -> Self;
+
+    fn from_segment(segment: MemorySegmentAddresses) -> Self {
+        let name = <Self::Family as BuiltinFamily>::NAME;
+        let memory_cells = <Self::Family as BuiltinFamily>::MEMORY_CELLS;
+        let segment_length = segment.stop_ptr - segment.begin_addr;
+        assert!(
+            (segment_length % memory_cells) == 0,
+            "{name} segment length is not a multiple of it's cells_per_instance"
+        );
+        let n_instances = segment_length / memory_cells;
+        assert!(
+            n_instances.is_power_of_two(),
+            "{name} instances number is not a power of two"
+        );
+        Self::new(n_instances.ilog2(), segment.begin_addr as u32)
+    }
 }
-impl BuiltinsClaimGenerator {
-    pub fn new(builtin_segments: BuiltinSegments) -> Self {
-        let add_mod_builtin_trace_generator = builtin_segments.add_mod.map(|segment| {
-            let segment_length = segment.stop_ptr - segment.begin_addr;
-            assert!(
-                (segment_length % ADD_MOD_MEMORY_CELLS) == 0,
-                "add mod segment length is not a multiple of it's cells_per_instance"
-            );
-            let n_instances = segment_length / ADD_MOD_MEMORY_CELLS;
-            assert!(
-                n_instances.is_power_of_two(),
-                "add mod instances number is not a power of two"
-            );
-            add_mod_builtin::ClaimGenerator::new(n_instances.ilog2(), segment.begin_addr as u32)
-        });
-        let bitwise_builtin_trace_generator = builtin_segments.bitwise.map(|segment| {
-            let segment_length = segment.stop_ptr - segment.begin_addr;
-            assert!(
-                (segment_length % BITWISE_MEMORY_CELLS) == 0,
-                "bitwise segment length is not a multiple of it's cells_per_instance"
-            );
-            let n_instances = segment_length / BITWISE_MEMORY_CELLS;
-            assert!(
-                n_instances.is_power_of_two(),
-                "bitwise instances number is not a power of two"
-            );
-            bitwise_builtin::ClaimGenerator::new(n_instances.ilog2(), segment.begin_addr as u32)
-        });
-        let mul_mod_builtin_trace_generator = builtin_segments.mul_mod.map(|segment| {
-            let segment_length = segment.stop_ptr - segment.begin_addr;
-            assert!(
-                (segment_length % MUL_MOD_MEMORY_CELLS) == 0,
-                "mul mod segment length is not a multiple of it's cells_per_instance"
-            );
-            let n_instances = segment_length / MUL_MOD_MEMORY_CELLS;
-            assert!(
-                n_instances.is_power_of_two(),
-                "mul mod instances number is not a power of two"
-            );
-            mul_mod_builtin::ClaimGenerator::new(n_instances.ilog2(), segment.begin_addr as u32)
-        });
-        let pedersen_builtin_trace_generator = builtin_segments.pedersen.map(|segment| {
-            let segment_length = segment.stop_ptr - segment.begin_addr;
-            assert!(
-                (segment_length % PEDERSEN_MEMORY_CELLS) == 0,
-                "pedersen segment length is not a multiple of it's cells_per_instance"
-            );
-            let n_instances = segment_length / PEDERSEN_MEMORY_CELLS;
-            assert!(
-                n_instances.is_power_of_two(),
-                "pedersen instances number is not a power of two"
-            );
 
-            pedersen_builtin::ClaimGenerator::new(n_instances.ilog2(), segment.begin_addr as u32)
-        });
-        let poseidon_builtin_trace_generator = builtin_segments.poseidon.map(|segment| {
-            let segment_length = segment.stop_ptr - segment.begin_addr;
-            assert!(
-                (segment_length % POSEIDON_MEMORY_CELLS) == 0,
-                "poseidon segment length is not a multiple of it's cells_per_instance"
-            );
-            let n_instances = segment_length / POSEIDON_MEMORY_CELLS;
-            assert!(
-                n_instances.is_power_of_two(),
-                "poseidon instances number is not a power of two"
-            );
+/// Implements [`BuiltinWitness`] for the claim generator of a builtin, given the lookups of its
+/// component and their relations.
+macro_rules! builtin_witness {
+    (
+        $module:ident => $family:ident {
+            lookups: |$lookup_tables:ident| [$($lookup:expr,)*],
+            relations: |$interaction_elements:ident| [$($relation:expr,)*],
+        }
+    ) => {
+        impl ComponentFamilyWitness for $module::ClaimGenerator {
+            type Family = $family;
+            type InteractionWitness = $module::InteractionClaimGenerator;
 
-            poseidon_builtin::ClaimGenerator::new(n_instances.ilog2(), segment.begin_addr as u32)
-        });
-        let range_check_96_builtin_trace_generator =
-            builtin_segments.range_check_bits_96.map(|segment| {
-                let segment_length = segment.stop_ptr - segment.begin_addr;
-                let n_instances = segment_length / RANGE_CHECK_MEMORY_CELLS;
-                assert!(
-                    n_instances.is_power_of_two(),
-                    "range_check_bits_96 instances number is not a power of two"
-                );
-                range_check_builtin_bits_96::ClaimGenerator::new(
-                    n_instances.ilog2(),
-                    segment.begin_addr as u32,
-                )
-            });
-        let range_check_128_builtin_trace_generator =
-            builtin_segments.range_check_bits_128.map(|segment| {
-                let segment_length = segment.stop_ptr - segment.begin_addr;
-                let n_instances = segment_length / RANGE_CHECK_MEMORY_CELLS;
-                assert!(
-                    n_instances.is_power_of_two(),
-                    "range_check_bits_128 instances number is not a power of two"
-                );
-                range_check_builtin_bits_128::ClaimGenerator::new(
-                    n_instances.ilog2(),
-                    segment.begin_addr as u32,
-                )
-            });
-        Self {
-            add_mod_builtin_trace_generator,
-            bitwise_builtin_trace_generator,
-            mul_mod_builtin_trace_generator,
-            pedersen_builtin_trace_generator,
-            poseidon_builtin_trace_generator,
-            range_check_96_builtin_trace_generator,
-            range_check_128_builtin_trace_generator,
+            fn write_trace(
+                self,
+                tree_builder: &mut impl TreeBuilder<SimdBackend>,
+                $lookup_tables: &mut SharedLookupTables<'_>,
+            ) -> ($module::Claim, $module::InteractionClaimGenerator) {
+                $module::ClaimGenerator::write_trace(self, tree_builder, $($lookup,)*)
+            }
         }
-    }
 
-    pub fn write_trace(
-        self,
-        tree_builder: &mut impl TreeBuilder<SimdBackend>,
-        memory_address_to_id_trace_generator: &memory_address_to_id::ClaimGenerator,
-        memory_id_to_value_trace_generator: &memory_id_to_big::ClaimGenerator,
-        pedersen_context_trace_generator: &mut PedersenContextClaimGenerator,
-        range_check_5_4_trace_generator: &range_check_5_4::ClaimGenerator,
-        range_check_8_trace_generator: &range_check_8::ClaimGenerator,
-        poseidon_context_trace_generator: &mut PoseidonContextClaimGenerator,
-        range_check_6_trace_generator: &range_check_6::ClaimGenerator,
-        range_check_12_trace_generator: &range_check_12::ClaimGenerator,
-        range_check_18_trace_generator: &range_check_18::ClaimGenerator,
-        range_check_4_4_trace_generator: &range_check_4_4::ClaimGenerator,
-        range_check_3_6_6_3_trace_generator: &range_check_3_6_6_3::ClaimGenerator,
-        range_check_4_4_4_4_trace_generator: &range_check_4_4_4_4::ClaimGenerator,
-        range_check_3_3_3_3_3_trace_generator: &range_check_3_3_3_3_3::ClaimGenerator,
-        verify_bitwise_xor_9_trace_generator: &verify_bitwise_xor_9::ClaimGenerator,
-    ) -> (BuiltinsClaim, BuiltinsInteractionClaimGenerator) {
-        let (add_mod_builtin_claim, add_mod_builtin_interaction_gen) = self
-            .add_mod_builtin_trace_generator
-            .map(|add_mod_builtin_trace_generator| {
-                add_mod_builtin_trace_generator.write_trace(
-                    tree_builder,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
-                )
-            })
-            .unzip();
-        let (bitwise_builtin_claim, bitwise_builtin_interaction_gen) = self
-            .bitwise_builtin_trace_generator
-            .map(|bitwise_builtin_trace_generator| {
-                bitwise_builtin_trace_generator.write_trace(
-                    tree_builder,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
-                    verify_bitwise_xor_9_trace_generator,
-                )
-            })
-            .unzip();
-        let (mul_mod_builtin_claim, mul_mod_builtin_interaction_gen) = self
-            .mul_mod_builtin_trace_generator
-            .map(|mul_mod_builtin_trace_generator| {
-                mul_mod_builtin_trace_generator.write_trace(
-                    tree_builder,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
-                    range_check_12_trace_generator,
-                    range_check_18_trace_generator,
-                    range_check_3_6_6_3_trace_generator,
-                )
-            })
-            .unzip();
-        let (pedersen_builtin_claim, pedersen_builtin_interaction_gen) = self
-            .pedersen_builtin_trace_generator
-            .map(|pedersen_builtin_trace_generator| {
-                pedersen_builtin_trace_generator.write_trace(
-                    tree_builder,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
-                    &mut pedersen_context_trace_generator.partial_ec_mul_trace_generator,
-                    range_check_5_4_trace_generator,
-                    range_check_8_trace_generator,
-                )
-            })
-            .unzip();
-        let (poseidon_builtin_claim, poseidon_builtin_interaction_gen) = self
-            .poseidon_builtin_trace_generator
-            .map(|poseidon_builtin_trace_generator| {
-                poseidon_builtin_trace_generator.write_trace(
-                    tree_builder,
-                    &mut poseidon_context_trace_generator.cube_252_trace_generator,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
-                    &mut poseidon_context_trace_generator
-                        .poseidon_3_partial_rounds_chain_trace_generator,
-                    &mut poseidon_context_trace_generator.poseidon_full_round_chain_trace_generator,
-                    range_check_3_3_3_3_3_trace_generator,
-                    range_check_4_4_trace_generator,
-                    range_check_4_4_4_4_trace_generator,
-                    &mut poseidon_context_trace_generator
-                        .range_check_felt_252_width_27_trace_generator,
-                )
-            })
-            .unzip();
+        impl ComponentFamilyInteractionWitness for $module::InteractionClaimGenerator {
+            type InteractionClaim = $module::InteractionClaim;
 
-        let (range_check_96_builtin_claim, range_check_96_builtin_interaction_gen) = self
-            .range_check_96_builtin_trace_generator
-            .map(|range_check_96_builtin_trace_generator| {
-                range_check_96_builtin_trace_generator.write_trace(
-                    tree_builder,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
-                    range_check_6_trace_generator,
-                )
-            })
-            .unzip();
-        let (range_check_128_builtin_claim, range_check_128_builtin_interaction_gen) = self
-            .range_check_128_builtin_trace_generator
-            .map(|range_check_128_builtin_trace_generator| {
-                range_check_128_builtin_trace_generator.write_trace(
+            fn write_interaction_trace(
+                self,
+                tree_builder: &mut impl TreeBuilder<SimdBackend>,
+                $interaction_elements: &CairoInteractionElements,
+            ) -> $module::InteractionClaim {
+                $module::InteractionClaimGenerator::write_interaction_trace(
+                    self,
                     tree_builder,
-                    memory_address_to_id_trace_generator,
-                    memory_id_to_value_trace_generator,
+                    $($relation,)*
                 )
-            })
-            .unzip();
+            }
+        }
+
+        impl BuiltinWitness for $module::ClaimGenerator {
+            fn new(log_size: u32, segment_start: u32) -> Self {
+                $module::ClaimGenerator::new(log_size, segment_start)
+            }
+        }
+    };
+}
 
-        (
-            BuiltinsClaim {
-                add_mod_builtin: add_mod_builtin_claim,
-                bitwise_builtin: bitwise_builtin_claim,
-                mul_mod_builtin: mul_mod_builtin_claim,
-                pedersen_builtin: pedersen_builtin_claim,
-                poseidon_builtin: poseidon_builtin_claim,
-                range_check_96_builtin: range_check_96_builtin_claim,
-                range_check_128_builtin: range_check_128_builtin_claim,
-            },
-            BuiltinsInteractionClaimGenerator {
-                add_mod_builtin_interaction_gen,
-                bitwise_builtin_interaction_gen,
-                mul_mod_builtin_interaction_gen,
-                pedersen_builtin_interaction_gen,
-                poseidon_builtin_interaction_gen,
-                range_check_96_builtin_interaction_gen,
-                range_check_128_builtin_interaction_gen,
-            },
-        )
+builtin_witness! {
+    add_mod_builtin => AddModBuiltinFamily {
+        lookups: |lookup_tables| [
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+        ],
     }
 }
 
-pub struct BuiltinsInteractionClaimGenerator {
-    add_mod_builtin_interaction_gen: Option<add_mod_builtin::InteractionClaimGenerator>,
-    bitwise_builtin_interaction_gen: Option<bitwise_builtin::InteractionClaimGenerator>,
-    mul_mod_builtin_interaction_gen: Option<mul_mod_builtin::InteractionClaimGenerator>,
-    pedersen_builtin_interaction_gen: Option<pedersen_builtin::InteractionClaimGenerator>,
-    poseidon_builtin_interaction_gen: Option<poseidon_builtin::InteractionClaimGenerator>,
-    range_check_96_builtin_interaction_gen:
-        Option<range_check_builtin_bits_96::InteractionClaimGenerator>,
-    range_check_128_builtin_interaction_gen:
-        Option<range_check_builtin_bits_128::InteractionClaimGenerator>,
+builtin_witness! {
+    bitwise_builtin => BitwiseBuiltinFamily {
+        lookups: |lookup_tables| [
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+            lookup_tables.verify_bitwise_xor_9,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+            &interaction_elements.verify_bitwise_xor_9,
+        ],
+    }
 }
-impl BuiltinsInteractionClaimGenerator {
-    pub fn write_interaction_trace(
-        self,
-        tree_builder: &mut impl TreeBuilder<SimdBackend>,
-        interaction_elements: &CairoInteractionElements,
-    ) -> BuiltinsInteractionClaim {
-        let add_mod_builtin_interaction_claim =
-            self.add_mod_builtin_interaction_gen
-                .map(|add_mod_builtin_interaction_gen| {
-                    add_mod_builtin_interaction_gen.write_interaction_trace(
-                        tree_builder,
-                        &interaction_elements.memory_address_to_id,
-                        &interaction_elements.memory_id_to_value,
-                    )
-                });
-        let bitwise_builtin_interaction_claim =
-            self.bitwise_builtin_interaction_gen
-                .map(|bitwise_builtin_interaction_gen| {
-                    bitwise_builtin_interaction_gen.write_interaction_trace(
-                        tree_builder,
-                        &interaction_elements.memory_address_to_id,
-                        &interaction_elements.memory_id_to_value,
-                        &interaction_elements.verify_bitwise_xor_9,
-                    )
-                });
-        let mul_mod_builtin_interaction_claim =
-            self.mul_mod_builtin_interaction_gen
-                .map(|mul_mod_builtin_interaction_gen| {
-                    mul_mod_builtin_interaction_gen.write_interaction_trace(
-                        tree_builder,
-                        &interaction_elements.memory_address_to_id,
-                        &interaction_elements.memory_id_to_value,
-                        &interaction_elements.range_checks.rc_12,
-                        &interaction_elements.range_checks.rc_18,
-                        &interaction_elements.range_checks.rc_3_6_6_3,
-                    )
-                });
-        let pedersen_builtin_interaction_claim =
-            self.pedersen_builtin_interaction_gen
-                .map(|pedersen_builtin_interaction_gen| {
-                    pedersen_builtin_interaction_gen.write_interaction_trace(
-                        tree_builder,
-                        &interaction_elements.memory_address_to_id,
-                        &interaction_elements.memory_id_to_value,
-                        &interaction_elements.partial_ec_mul,
-                        &interaction_elements.range_checks.rc_5_4,
-                        &interaction_elements.range_checks.rc_8,
-                    )
-                });
-        let poseidon_builtin_interaction_claim =
-            self.poseidon_builtin_interaction_gen
-                .map(|poseidon_builtin_interaction_gen| {
-                    poseidon_builtin_interaction_gen.write_interaction_trace(
-                        tree_builder,
-                        &interaction_elements.cube_252,
-                        &interaction_elements.memory_address_to_id,
-                        &interaction_elements.memory_id_to_value,
-                        &interaction_elements.poseidon_3_partial_rounds_chain,
-                        &interaction_elements.poseidon_full_round_chain,
-                        &interaction_elements.range_check_felt_252_width_27,
-                        &interaction_elements.range_checks.rc_3_3_3_3_3,
-                        &interaction_elements.range_checks.rc_4_4,
-                        &interaction_elements.range_checks.rc_4_4_4_4,
-                    )
-                });
-        let range_check_96_builtin_interaction_claim = self
-            .range_check_96_builtin_interaction_gen
-            .map(|range_check_96_builtin_interaction_gen| {
-                range_check_96_builtin_interaction_gen.write_interaction_trace(
-                    tree_builder,
-                    &interaction_elements.memory_address_to_id,
-                    &interaction_elements.memory_id_to_value,
-                    &interaction_elements.range_checks.rc_6,
-                )
-            });
-        let range_check_128_builtin_interaction_claim = self
-            .range_check_128_builtin_interaction_gen
-            .map(|range_check_128_builtin_interaction_gen| {
-                range_check_128_builtin_interaction_gen.write_interaction_trace(
-                    tree_builder,
-                    &interaction_elements.memory_address_to_id,
-                    &interaction_elements.memory_id_to_value,
-                )
-            });
 
-        BuiltinsInteractionClaim {
-            add_mod_builtin: add_mod_builtin_interaction_claim,
-            bitwise_builtin: bitwise_builtin_interaction_claim,
-            mul_mod_builtin: mul_mod_builtin_interaction_claim,
-            pedersen_builtin: pedersen_builtin_interaction_claim,
-            poseidon_builtin: poseidon_builtin_interaction_claim,
-            range_check_96_builtin: range_check_96_builtin_interaction_claim,
-            range_check_128_builtin: range_check_128_builtin_interaction_claim,
-        }
+builtin_witness! {
+    mul_mod_builtin => MulModBuiltinFamily {
+        lookups: |lookup_tables| [
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+            &lookup_tables.range_checks.rc_12_trace_generator,
+            &lookup_tables.range_checks.rc_18_trace_generator,
+            &lookup_tables.range_checks.rc_3_6_6_3_trace_generator,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+            &interaction_elements.range_checks.rc_12,
+            &interaction_elements.range_checks.rc_18,
+            &interaction_elements.range_checks.rc_3_6_6_3,
+        ],
+    }
+}
+
+builtin_witness! {
+    pedersen_builtin => PedersenBuiltinFamily {
+        lookups: |lookup_tables| [
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+            &mut lookup_tables.pedersen_context.partial_ec_mul_trace_generator,
+            &lookup_tables.range_checks.rc_5_4_trace_generator,
+            &lookup_tables.range_checks.rc_8_trace_generator,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+            &interaction_elements.partial_ec_mul,
+            &interaction_elements.range_checks.rc_5_4,
+            &interaction_elements.range_checks.rc_8,
+        ],
+    }
+}
+
+builtin_witness! {
+    poseidon_builtin => PoseidonBuiltinFamily {
+        lookups: |lookup_tables| [
+            &mut lookup_tables.poseidon_context.cube_252_trace_generator,
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+            &mut lookup_tables
+                .poseidon_context
+                .poseidon_3_partial_rounds_chain_trace_generator,
+            &mut lookup_tables.poseidon_context.poseidon_full_round_chain_trace_generator,
+            &lookup_tables.range_checks.rc_3_3_3_3_3_trace_generator,
+            &lookup_tables.range_checks.rc_4_4_trace_generator,
+            &lookup_tables.range_checks.rc_4_4_4_4_trace_generator,
+            &mut lookup_tables
+                .poseidon_context
+                .range_check_felt_252_width_27_trace_generator,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.cube_252,
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+            &interaction_elements.poseidon_3_partial_rounds_chain,
+            &interaction_elements.poseidon_full_round_chain,
+            &interaction_elements.range_check_felt_252_width_27,
+            &interaction_elements.range_checks.rc_3_3_3_3_3,
+            &interaction_elements.range_checks.rc_4_4,
+            &interaction_elements.range_checks.rc_4_4_4_4,
+        ],
+    }
+}
+
+builtin_witness! {
+    range_check_builtin_bits_96 => RangeCheck96BuiltinFamily {
+        lookups: |lookup_tables| [
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+            &lookup_tables.range_checks.rc_6_trace_generator,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+            &interaction_elements.range_checks.rc_6,
+        ],
     }
 }
+
+builtin_witness! {
+    range_check_builtin_bits_128 => RangeCheck128BuiltinFamily {
+        lookups: |lookup_tables| [
+            lookup_tables.memory_address_to_id,
+            lookup_tables.memory_id_to_value,
+        ],
+        relations: |interaction_elements| [
+            &interaction_elements.memory_address_to_id,
+            &interaction_elements.memory_id_to_value,
+        ],
+    }
+}
+
+macro_rules! define_builtins_witness {
+    ($($field:ident: $module:ident => $family:ident,)*) => {
+        pub struct BuiltinsClaimGenerator {
+            $($field: Option<$module::ClaimGenerator>,)*
+        }
+        impl BuiltinsClaimGenerator {
+            pub fn new(builtin_segments: BuiltinSegments) -> Self {
+                Self {
+                    $(
+                        $field: $family::builtin_segment(&builtin_segments)
+                            .map(BuiltinWitness::from_segment),
+                    )*
+                }
+            }
+
+            pub fn write_trace(
+                self,
+                tree_builder: &mut impl TreeBuilder<SimdBackend>,
+                lookup_tables: &mut SharedLookupTables<'_>,
+                hooks: &ProvingHooks,
+            ) -> Result<(BuiltinsClaim, BuiltinsInteractionClaimGenerator), CairoProvingError> {
+                $(
+                    hooks.enter_base_trace($family::NAME, self.$field.is_some())?;
+                    let $field = self
+                        .$field
+                        .map(|generator| {
+                            ComponentFamilyWitness::write_trace(
+                                generator,
+                                tree_builder,
+                                lookup_tables,
+                            )
+                        })
+                        .unzip();
+                )*
+
+                Ok((
+                    BuiltinsClaim {
+                        $($field: $field.0,)*
+                    },
+                    BuiltinsInteractionClaimGenerator {
+                        $($field: $field.1,)*
+                    },
+                ))
+            }
+        }
+
+        pub struct BuiltinsInteractionClaimGenerator {
+            $($field: Option<$module::InteractionClaimGenerator>,)*
+        }
+        impl BuiltinsInteractionClaimGenerator {
+            pub fn write_interaction_trace(
+                self,
+                tree_builder: &mut impl TreeBuilder<SimdBackend>,
+                interaction_elements: &CairoInteractionElements,
+            ) -> BuiltinsInteractionClaim {
+                BuiltinsInteractionClaim {
+                    $(
+                        $field: self.$field.map(|interaction_gen| {
+                            ComponentFamilyInteractionWitness::write_interaction_trace(
+                                interaction_gen,
+                                tree_builder,
+                                interaction_elements,
+                            )
+                        }),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+cairo_air::builtin_families!(define_builtins_witness);
@@ -1,6 +1,11 @@
 use cairo_air::air::{
-    CairoClaim, CairoInteractionClaim, CairoInteractionElements, MemorySmallValue, PublicData,
-    PublicMemory, PublicSegmentRanges, SegmentRange,
+    accumulate_relation_uses, is_looked_up, CairoClaim, CairoInteractionClaim,
+    CairoInteractionElements, MemorySmallValue, PublicData, PublicMemory, PublicSegmentRanges,
+    RelationUsesDict, SegmentRange,
+};
+use cairo_air::component_registry::ComponentRegistry;
+use cairo_air::components::{
+    memory_id_to_big as memory_id_to_big_air, verify_instruction as verify_instruction_air,
 };
 use itertools::Itertools;
 use stwo_cairo_adapter::memory::Memory;
@@ -11,8 +16,12 @@ use tracing::{span, Level};
 
 use super::blake_context::{BlakeContextClaimGenerator, BlakeContextInteractionClaimGenerator};
 use super::builtins::{BuiltinsClaimGenerator, BuiltinsInteractionClaimGenerator};
+use super::component_registry::{
+    ExtensionInteractionWitness, ExtensionWitness, SharedLookupTables,
+};
 use super::opcodes::{OpcodesClaimGenerator, OpcodesInteractionClaimGenerator};
 use super::range_checks::{RangeChecksClaimGenerator, RangeChecksInteractionClaimGenerator};
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::pedersen::{
     PedersenContextClaimGenerator, PedersenContextInteractionClaimGenerator,
 };
@@ -31,7 +40,8 @@ fn extract_public_segments(
     final_ap: u32,
     public_segment_context: PublicSegmentContext,
 ) -> PublicSegmentRanges {
-    let n_public_segments = public_segment_context.iter().filter(|&b| *b).count() as u32;
+    let n_public_segments = (public_segment_context.iter().filter(|&b| *b).count()
+        + public_segment_context.n_extension_segments()) as u32;
 
     let to_memory_value = |addr: u32| {
         let id = memory.get_raw_id(addr);
@@ -39,6 +49,11 @@ fn extract_public_segments(
         MemorySmallValue { id, value }
     };
 
+    let gas = public_segment_context.has_gas().then(|| SegmentRange {
+        start_ptr: to_memory_value(initial_ap + n_public_segments),
+        stop_ptr: to_memory_value(final_ap - n_public_segments - 1),
+    });
+
     let start_ptrs = (initial_ap..initial_ap + n_public_segments).map(to_memory_value);
     let end_ptrs = (final_ap - n_public_segments..final_ap).map(to_memory_value);
     let mut ranges = start_ptrs
@@ -69,6 +84,9 @@ fn extract_public_segments(
         range_check_96: next(),
         add_mod: next(),
         mul_mod: next(),
+        gas,
+        // The segments of registered component families follow those of the builtins.
+        extensions: ranges.collect(),
     }
 }
 
@@ -129,6 +147,8 @@ pub struct CairoClaimGenerator {
     verify_bitwise_xor_7_trace_generator: verify_bitwise_xor_7::ClaimGenerator,
     verify_bitwise_xor_8_trace_generator: verify_bitwise_xor_8::ClaimGenerator,
     verify_bitwise_xor_9_trace_generator: verify_bitwise_xor_9::ClaimGenerator,
+    registry: ComponentRegistry,
+    extensions: Vec<ExtensionWitness>,
     // ...
 }
 impl CairoClaimGenerator {
@@ -205,13 +225,40 @@ impl CairoClaimGenerator {
             verify_bitwise_xor_7_trace_generator,
             verify_bitwise_xor_8_trace_generator,
             verify_bitwise_xor_9_trace_generator,
+            registry: ComponentRegistry::default(),
+            extensions: vec![],
         }
     }
 
-    pub fn write_trace(
+    /// Generates the traces of the families of a [`WitnessRegistry`] as well, in registration
+    /// order.
+    ///
+    /// [`WitnessRegistry`]: super::component_registry::WitnessRegistry
+    pub fn with_extensions(
         mut self,
+        registry: ComponentRegistry,
+        extensions: Vec<ExtensionWitness>,
+    ) -> Self {
+        self.registry = registry;
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn write_trace(
+        self,
         tree_builder: &mut impl TreeBuilder<SimdBackend>,
     ) -> (CairoClaim, CairoInteractionClaimGenerator) {
+        self.write_trace_with_hooks(tree_builder, &ProvingHooks::default())
+            .expect("Proving cannot be cancelled without a cancellation token")
+    }
+
+    /// Like [`Self::write_trace`], reporting each written component to `hooks` and stopping if
+    /// proving is cancelled.
+    pub fn write_trace_with_hooks(
+        mut self,
+        tree_builder: &mut impl TreeBuilder<SimdBackend>,
+        hooks: &ProvingHooks,
+    ) -> Result<(CairoClaim, CairoInteractionClaimGenerator), CairoProvingError> {
         let span = span!(Level::INFO, "write opcode trace").entered();
         let (opcodes_claim, opcodes_interaction_gen) = self.opcodes.write_trace(
             tree_builder,
@@ -221,17 +268,36 @@ impl CairoClaimGenerator {
             &self.range_checks_trace_generator,
             &self.verify_instruction_trace_generator,
             &mut self.verify_bitwise_xor_8_trace_generator,
-        );
+            hooks,
+        )?;
         span.exit();
         let span = span!(Level::INFO, "internal component trace").entered();
-        let (verify_instruction_claim, verify_instruction_interaction_gen) =
-            self.verify_instruction_trace_generator.write_trace(
-                tree_builder,
-                &self.memory_address_to_id_trace_generator,
-                &self.memory_id_to_value_trace_generator,
-                &self.range_checks_trace_generator.rc_4_3_trace_generator,
-                &self.range_checks_trace_generator.rc_7_2_5_trace_generator,
+        // The relation uses of the components written so far, which determine the lookup tables
+        // that are part of the claim, see [`is_looked_up`].
+        let mut relation_uses = RelationUsesDict::default();
+        opcodes_claim.accumulate_relation_uses(&mut relation_uses);
+        let (verify_instruction_claim, verify_instruction_interaction_gen) = hooks
+            .write_base_trace(
+                "verify_instruction",
+                is_looked_up(&relation_uses, "VerifyInstruction"),
+                || {
+                    self.verify_instruction_trace_generator.write_trace(
+                        tree_builder,
+                        &self.memory_address_to_id_trace_generator,
+                        &self.memory_id_to_value_trace_generator,
+                        &self.range_checks_trace_generator.rc_4_3_trace_generator,
+                        &self.range_checks_trace_generator.rc_7_2_5_trace_generator,
+                    )
+                },
+            )?
+            .unzip();
+        if let Some(verify_instruction_claim) = &verify_instruction_claim {
+            accumulate_relation_uses(
+                &mut relation_uses,
+                verify_instruction_air::RELATION_USES_PER_ROW,
+                verify_instruction_claim.log_size,
             );
+        }
         let (blake_context_claim, blake_context_interaction_gen) =
             self.blake_context_trace_generator.write_trace(
                 tree_builder,
@@ -242,60 +308,125 @@ impl CairoClaimGenerator {
                 &self.verify_bitwise_xor_7_trace_generator,
                 &self.verify_bitwise_xor_8_trace_generator,
                 &self.verify_bitwise_xor_9_trace_generator,
-            );
-        let (builtins_claim, builtins_interaction_gen) = self.builtins.write_trace(
-            tree_builder,
-            &self.memory_address_to_id_trace_generator,
-            &self.memory_id_to_value_trace_generator,
-            &mut self.pedersen_context_trace_generator,
-            &self.range_checks_trace_generator.rc_5_4_trace_generator,
-            &self.range_checks_trace_generator.rc_8_trace_generator,
-            &mut self.poseidon_context_trace_generator,
-            &self.range_checks_trace_generator.rc_6_trace_generator,
-            &self.range_checks_trace_generator.rc_12_trace_generator,
-            &self.range_checks_trace_generator.rc_18_trace_generator,
-            &self.range_checks_trace_generator.rc_4_4_trace_generator,
-            &self.range_checks_trace_generator.rc_3_6_6_3_trace_generator,
-            &self.range_checks_trace_generator.rc_4_4_4_4_trace_generator,
-            &self
-                .range_checks_trace_generator
-                .rc_3_3_3_3_3_trace_generator,
-            &self.verify_bitwise_xor_9_trace_generator,
-        );
+                hooks,
+            )?;
+        blake_context_claim.accumulate_relation_uses(&mut relation_uses);
+        // The lookup tables shared with the builtins and the registered families.
+        let mut lookup_tables = SharedLookupTables {
+            memory_address_to_id: &self.memory_address_to_id_trace_generator,
+            memory_id_to_value: &self.memory_id_to_value_trace_generator,
+            range_checks: &self.range_checks_trace_generator,
+            verify_bitwise_xor_4: &self.verify_bitwise_xor_4_trace_generator,
+            verify_bitwise_xor_7: &self.verify_bitwise_xor_7_trace_generator,
+            verify_bitwise_xor_8: &self.verify_bitwise_xor_8_trace_generator,
+            verify_bitwise_xor_9: &self.verify_bitwise_xor_9_trace_generator,
+            pedersen_context: &mut self.pedersen_context_trace_generator,
+            poseidon_context: &mut self.poseidon_context_trace_generator,
+        };
+        let (builtins_claim, builtins_interaction_gen) =
+            self.builtins
+                .write_trace(tree_builder, &mut lookup_tables, hooks)?;
+        builtins_claim.accumulate_relation_uses(&mut relation_uses);
+        // The extension traces are generated before the lookup tables they look up are written,
+        // but follow all the built-in components in the trace.
+        // Registered families are anonymous, and are reported as a whole.
+        hooks.enter_base_trace("extensions", !self.extensions.is_empty())?;
+        let (extension_claims, (extension_traces, extension_interaction_gens)): (
+            Vec<_>,
+            (Vec<_>, Vec<_>),
+        ) = self
+            .extensions
+            .into_iter()
+            .map(|extension| {
+                let (claim, trace, interaction_gen) = extension.write_trace(&mut lookup_tables);
+                (claim, (trace, interaction_gen))
+            })
+            .unzip();
+        self.registry
+            .accumulate_relation_uses(&extension_claims, &mut relation_uses);
         let (pedersen_context_claim, pedersen_context_interaction_gen) = self
             .pedersen_context_trace_generator
-            .write_trace(tree_builder, &self.range_checks_trace_generator);
+            .write_trace(tree_builder, &self.range_checks_trace_generator, hooks)?;
+        pedersen_context_claim.accumulate_relation_uses(&mut relation_uses);
         let (poseidon_context_claim, poseidon_context_interaction_gen) = self
             .poseidon_context_trace_generator
-            .write_trace(tree_builder, &self.range_checks_trace_generator);
+            .write_trace(tree_builder, &self.range_checks_trace_generator, hooks)?;
+        poseidon_context_claim.accumulate_relation_uses(&mut relation_uses);
+        hooks.enter_base_trace("memory_address_to_id", true)?;
         let (memory_address_to_id_claim, memory_address_to_id_interaction_gen) = self
             .memory_address_to_id_trace_generator
             .write_trace(tree_builder);
 
         // Memory uses "Sequence", split it according to `MAX_SEQUENCE_LOG_SIZE`.
         const LOG_MAX_BIG_SIZE: u32 = cairo_air::preprocessed::MAX_SEQUENCE_LOG_SIZE;
+        // The small value table is written along with the big ones.
+        hooks.enter_base_trace("memory_id_to_big", true)?;
         let (memory_id_to_value_claim, memory_id_to_value_interaction_gen) =
             self.memory_id_to_value_trace_generator.write_trace(
                 tree_builder,
                 &self.range_checks_trace_generator.rc_9_9_trace_generator,
                 LOG_MAX_BIG_SIZE,
             );
-        let (range_checks_claim, range_checks_interaction_gen) =
-            self.range_checks_trace_generator.write_trace(tree_builder);
-        let (verify_bitwise_xor_4_claim, verify_bitwise_xor_4_interaction_gen) = self
-            .verify_bitwise_xor_4_trace_generator
-            .write_trace(tree_builder);
-        let (verify_bitwise_xor_7_claim, verify_bitwise_xor_7_interaction_gen) = self
-            .verify_bitwise_xor_7_trace_generator
-            .write_trace(tree_builder);
-        let (verify_bitwise_xor_8_claim, verify_bitwise_xor_8_interaction_gen) = self
-            .verify_bitwise_xor_8_trace_generator
-            .write_trace(tree_builder);
-        let (verify_bitwise_xor_9_claim, verify_bitwise_xor_9_interaction_gen) = self
-            .verify_bitwise_xor_9_trace_generator
-            .write_trace(tree_builder);
+        for &log_size in &memory_id_to_value_claim.big_log_sizes {
+            accumulate_relation_uses(
+                &mut relation_uses,
+                memory_id_to_big_air::RELATION_USES_PER_ROW_BIG,
+                log_size,
+            );
+        }
+        accumulate_relation_uses(
+            &mut relation_uses,
+            memory_id_to_big_air::RELATION_USES_PER_ROW_SMALL,
+            memory_id_to_value_claim.small_log_size,
+        );
+        let (range_checks_claim, range_checks_interaction_gen) = self
+            .range_checks_trace_generator
+            .write_trace(tree_builder, &relation_uses, hooks)?;
+        let (verify_bitwise_xor_4_claim, verify_bitwise_xor_4_interaction_gen) = hooks
+            .write_base_trace(
+                "verify_bitwise_xor_4",
+                is_looked_up(&relation_uses, "VerifyBitwiseXor_4"),
+                || {
+                    self.verify_bitwise_xor_4_trace_generator
+                        .write_trace(tree_builder)
+                },
+            )?
+            .unzip();
+        let (verify_bitwise_xor_7_claim, verify_bitwise_xor_7_interaction_gen) = hooks
+            .write_base_trace(
+                "verify_bitwise_xor_7",
+                is_looked_up(&relation_uses, "VerifyBitwiseXor_7"),
+                || {
+                    self.verify_bitwise_xor_7_trace_generator
+                        .write_trace(tree_builder)
+                },
+            )?
+            .unzip();
+        let (verify_bitwise_xor_8_claim, verify_bitwise_xor_8_interaction_gen) = hooks
+            .write_base_trace(
+                "verify_bitwise_xor_8",
+                is_looked_up(&relation_uses, "VerifyBitwiseXor_8"),
+                || {
+                    self.verify_bitwise_xor_8_trace_generator
+                        .write_trace(tree_builder)
+                },
+            )?
+            .unzip();
+        let (verify_bitwise_xor_9_claim, verify_bitwise_xor_9_interaction_gen) = hooks
+            .write_base_trace(
+                "verify_bitwise_xor_9",
+                is_looked_up(&relation_uses, "VerifyBitwiseXor_9"),
+                || {
+                    self.verify_bitwise_xor_9_trace_generator
+                        .write_trace(tree_builder)
+                },
+            )?
+            .unzip();
+        for trace in extension_traces {
+            tree_builder.extend_evals(trace);
+        }
         span.exit();
-        (
+        Ok((
             CairoClaim {
                 public_data: self.public_data,
                 opcodes: opcodes_claim,
@@ -311,6 +442,7 @@ impl CairoClaimGenerator {
                 verify_bitwise_xor_7: verify_bitwise_xor_7_claim,
                 verify_bitwise_xor_8: verify_bitwise_xor_8_claim,
                 verify_bitwise_xor_9: verify_bitwise_xor_9_claim,
+                extensions: extension_claims,
             },
             CairoInteractionClaimGenerator {
                 opcodes_interaction_gen,
@@ -326,14 +458,15 @@ impl CairoClaimGenerator {
                 verify_bitwise_xor_7_interaction_gen,
                 verify_bitwise_xor_8_interaction_gen,
                 verify_bitwise_xor_9_interaction_gen,
+                extension_interaction_gens,
             },
-        )
+        ))
     }
 }
 
 pub struct CairoInteractionClaimGenerator {
     opcodes_interaction_gen: OpcodesInteractionClaimGenerator,
-    verify_instruction_interaction_gen: verify_instruction::InteractionClaimGenerator,
+    verify_instruction_interaction_gen: Option<verify_instruction::InteractionClaimGenerator>,
     blake_context_interaction_gen: BlakeContextInteractionClaimGenerator,
     builtins_interaction_gen: BuiltinsInteractionClaimGenerator,
     pedersen_context_interaction_gen: PedersenContextInteractionClaimGenerator,
@@ -341,10 +474,11 @@ pub struct CairoInteractionClaimGenerator {
     memory_address_to_id_interaction_gen: memory_address_to_id::InteractionClaimGenerator,
     memory_id_to_value_interaction_gen: memory_id_to_big::InteractionClaimGenerator,
     range_checks_interaction_gen: RangeChecksInteractionClaimGenerator,
-    verify_bitwise_xor_4_interaction_gen: verify_bitwise_xor_4::InteractionClaimGenerator,
-    verify_bitwise_xor_7_interaction_gen: verify_bitwise_xor_7::InteractionClaimGenerator,
-    verify_bitwise_xor_8_interaction_gen: verify_bitwise_xor_8::InteractionClaimGenerator,
-    verify_bitwise_xor_9_interaction_gen: verify_bitwise_xor_9::InteractionClaimGenerator,
+    verify_bitwise_xor_4_interaction_gen: Option<verify_bitwise_xor_4::InteractionClaimGenerator>,
+    verify_bitwise_xor_7_interaction_gen: Option<verify_bitwise_xor_7::InteractionClaimGenerator>,
+    verify_bitwise_xor_8_interaction_gen: Option<verify_bitwise_xor_8::InteractionClaimGenerator>,
+    verify_bitwise_xor_9_interaction_gen: Option<verify_bitwise_xor_9::InteractionClaimGenerator>,
+    extension_interaction_gens: Vec<ExtensionInteractionWitness>,
     // ...
 }
 impl CairoInteractionClaimGenerator {
@@ -356,16 +490,18 @@ impl CairoInteractionClaimGenerator {
         let opcodes_interaction_claims = self
             .opcodes_interaction_gen
             .write_interaction_trace(tree_builder, interaction_elements);
-        let verify_instruction_interaction_claim = self
-            .verify_instruction_interaction_gen
-            .write_interaction_trace(
-                tree_builder,
-                &interaction_elements.memory_address_to_id,
-                &interaction_elements.memory_id_to_value,
-                &interaction_elements.range_checks.rc_4_3,
-                &interaction_elements.range_checks.rc_7_2_5,
-                &interaction_elements.verify_instruction,
-            );
+        let verify_instruction_interaction_claim =
+            self.verify_instruction_interaction_gen
+                .map(|verify_instruction_interaction_gen| {
+                    verify_instruction_interaction_gen.write_interaction_trace(
+                        tree_builder,
+                        &interaction_elements.memory_address_to_id,
+                        &interaction_elements.memory_id_to_value,
+                        &interaction_elements.range_checks.rc_4_3,
+                        &interaction_elements.range_checks.rc_7_2_5,
+                        &interaction_elements.verify_instruction,
+                    )
+                });
         let blake_context_interaction_claim = self
             .blake_context_interaction_gen
             .write_interaction_trace(tree_builder, interaction_elements);
@@ -392,18 +528,48 @@ impl CairoInteractionClaimGenerator {
         let range_checks_interaction_claim = self
             .range_checks_interaction_gen
             .write_interaction_trace(tree_builder, &interaction_elements.range_checks);
-        let verify_bitwise_xor_4_interaction_claim = self
-            .verify_bitwise_xor_4_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.verify_bitwise_xor_4);
-        let verify_bitwise_xor_7_interaction_claim = self
-            .verify_bitwise_xor_7_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.verify_bitwise_xor_7);
-        let verify_bitwise_xor_8_interaction_claim = self
-            .verify_bitwise_xor_8_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.verify_bitwise_xor_8);
-        let verify_bitwise_xor_9_interaction_claim = self
-            .verify_bitwise_xor_9_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.verify_bitwise_xor_9);
+        let verify_bitwise_xor_4_interaction_claim =
+            self.verify_bitwise_xor_4_interaction_gen
+                .map(|verify_bitwise_xor_4_interaction_gen| {
+                    verify_bitwise_xor_4_interaction_gen.write_interaction_trace(
+                        tree_builder,
+                        &interaction_elements.verify_bitwise_xor_4,
+                    )
+                });
+        let verify_bitwise_xor_7_interaction_claim =
+            self.verify_bitwise_xor_7_interaction_gen
+                .map(|verify_bitwise_xor_7_interaction_gen| {
+                    verify_bitwise_xor_7_interaction_gen.write_interaction_trace(
+                        tree_builder,
+                        &interaction_elements.verify_bitwise_xor_7,
+                    )
+                });
+        let verify_bitwise_xor_8_interaction_claim =
+            self.verify_bitwise_xor_8_interaction_gen
+                .map(|verify_bitwise_xor_8_interaction_gen| {
+                    verify_bitwise_xor_8_interaction_gen.write_interaction_trace(
+                        tree_builder,
+                        &interaction_elements.verify_bitwise_xor_8,
+                    )
+                });
+        let verify_bitwise_xor_9_interaction_claim =
+            self.verify_bitwise_xor_9_interaction_gen
+                .map(|verify_bitwise_xor_9_interaction_gen| {
+                    verify_bitwise_xor_9_interaction_gen.write_interaction_trace(
+                        tree_builder,
+                        &interaction_elements.verify_bitwise_xor_9,
+                    )
+                });
+        let extension_interaction_claims = self
+            .extension_interaction_gens
+            .into_iter()
+            .map(|interaction_gen| {
+                let (interaction_claim, trace) =
+                    interaction_gen.write_interaction_trace(interaction_elements);
+                tree_builder.extend_evals(trace);
+                interaction_claim
+            })
+            .collect();
 
         CairoInteractionClaim {
             opcodes: opcodes_interaction_claims,
@@ -419,6 +585,7 @@ impl CairoInteractionClaimGenerator {
             verify_bitwise_xor_7: verify_bitwise_xor_7_interaction_claim,
             verify_bitwise_xor_8: verify_bitwise_xor_8_interaction_claim,
             verify_bitwise_xor_9: verify_bitwise_xor_9_interaction_claim,
+            extensions: extension_interaction_claims,
         }
     }
 }
@@ -0,0 +1,187 @@
+//! Witness generation for component families: the builtins of the Cairo AIR, and the families of
+//! a [`ComponentRegistry`].
+
+use cairo_air::air::CairoInteractionElements;
+use cairo_air::component_registry::{
+    ComponentFamily, ComponentRegistry, ExtensionClaim, ExtensionInteractionClaim, FamilyClaim,
+};
+use stwo_prover::constraint_framework::{INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX};
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::poly::circle::CircleEvaluation;
+use stwo_prover::core::poly::BitReversedOrder;
+
+use super::range_checks::RangeChecksClaimGenerator;
+use crate::witness::components::pedersen::PedersenContextClaimGenerator;
+use crate::witness::components::poseidon::PoseidonContextClaimGenerator;
+use crate::witness::components::{
+    memory_address_to_id, memory_id_to_big, verify_bitwise_xor_4, verify_bitwise_xor_7,
+    verify_bitwise_xor_8, verify_bitwise_xor_9,
+};
+use crate::witness::utils::{TraceBuffer, TreeBuilder};
+
+pub type TraceColumns = Vec<CircleEvaluation<SimdBackend, M31, BitReversedOrder>>;
+
+/// The components of the Cairo AIR that the components of a family may look up.
+pub struct SharedLookupTables<'a> {
+    pub memory_address_to_id: &'a memory_address_to_id::ClaimGenerator,
+    pub memory_id_to_value: &'a memory_id_to_big::ClaimGenerator,
+    pub range_checks: &'a RangeChecksClaimGenerator,
+    pub verify_bitwise_xor_4: &'a verify_bitwise_xor_4::ClaimGenerator,
+    pub verify_bitwise_xor_7: &'a verify_bitwise_xor_7::ClaimGenerator,
+    pub verify_bitwise_xor_8: &'a verify_bitwise_xor_8::ClaimGenerator,
+    pub verify_bitwise_xor_9: &'a verify_bitwise_xor_9::ClaimGenerator,
+    pub pedersen_context: &'a mut PedersenContextClaimGenerator,
+    pub poseidon_context: &'a mut PoseidonContextClaimGenerator,
+}
+
+/// Generates the trace of a [`ComponentFamily`].
+pub trait ComponentFamilyWitness: Send {
+    type Family: ComponentFamily;
+    type InteractionWitness: ComponentFamilyInteractionWitness + 'static;
+
+    /// Writes the family's base trace, in the order of [`ComponentFamily::evals`], adding its
+    /// lookups to `lookup_tables`.
+    fn write_trace(
+        self,
+        tree_builder: &mut impl TreeBuilder<SimdBackend>,
+        lookup_tables: &mut SharedLookupTables<'_>,
+    ) -> (
+        <Self::Family as ComponentFamily>::Claim,
+        Self::InteractionWitness,
+    );
+}
+
+/// Generates the interaction trace of a [`ComponentFamily`].
+pub trait ComponentFamilyInteractionWitness: Send {
+    /// The interaction claim of the family, with a claimed sum per component.
+    type InteractionClaim: Into<ExtensionInteractionClaim>;
+
+    fn write_interaction_trace(
+        self,
+        tree_builder: &mut impl TreeBuilder<SimdBackend>,
+        interaction_elements: &CairoInteractionElements,
+    ) -> Self::InteractionClaim;
+}
+
+/// A [`ComponentFamilyWitness`] of a registered family, whose claims are in their
+/// [`ExtensionClaim`] encoding.
+trait RegisteredFamilyWitness: Send {
+    fn write_trace(
+        self: Box<Self>,
+        lookup_tables: &mut SharedLookupTables<'_>,
+    ) -> (
+        Vec<u32>,
+        TraceColumns,
+        Box<dyn RegisteredFamilyInteractionWitness>,
+    );
+}
+impl<W: ComponentFamilyWitness> RegisteredFamilyWitness for W {
+    fn write_trace(
+        self: Box<Self>,
+        lookup_tables: &mut SharedLookupTables<'_>,
+    ) -> (
+        Vec<u32>,
+        TraceColumns,
+        Box<dyn RegisteredFamilyInteractionWitness>,
+    ) {
+        let mut trace = TraceBuffer::new(ORIGINAL_TRACE_IDX);
+        let (claim, interaction_witness) =
+            ComponentFamilyWitness::write_trace(*self, &mut trace, lookup_tables);
+        (
+            claim.encode(),
+            trace.into_evals(),
+            Box::new(interaction_witness),
+        )
+    }
+}
+
+trait RegisteredFamilyInteractionWitness: Send {
+    fn write_interaction_trace(
+        self: Box<Self>,
+        interaction_elements: &CairoInteractionElements,
+    ) -> (ExtensionInteractionClaim, TraceColumns);
+}
+impl<W: ComponentFamilyInteractionWitness> RegisteredFamilyInteractionWitness for W {
+    fn write_interaction_trace(
+        self: Box<Self>,
+        interaction_elements: &CairoInteractionElements,
+    ) -> (ExtensionInteractionClaim, TraceColumns) {
+        let mut trace = TraceBuffer::new(INTERACTION_TRACE_IDX);
+        let interaction_claim = ComponentFamilyInteractionWitness::write_interaction_trace(
+            *self,
+            &mut trace,
+            interaction_elements,
+        );
+        (interaction_claim.into(), trace.into_evals())
+    }
+}
+
+/// The witness of a registered family, for a single proof.
+pub struct ExtensionWitness {
+    family_id: u32,
+    witness: Box<dyn RegisteredFamilyWitness>,
+}
+impl ExtensionWitness {
+    /// Generates the family's base trace, to be appended after the built-in components.
+    pub fn write_trace(
+        self,
+        lookup_tables: &mut SharedLookupTables<'_>,
+    ) -> (ExtensionClaim, TraceColumns, ExtensionInteractionWitness) {
+        let (values, trace, interaction_witness) = self.witness.write_trace(lookup_tables);
+        (
+            ExtensionClaim {
+                family_id: self.family_id,
+                values,
+            },
+            trace,
+            ExtensionInteractionWitness(interaction_witness),
+        )
+    }
+}
+
+/// The interaction witness of a registered family, see [`ExtensionWitness`].
+pub struct ExtensionInteractionWitness(Box<dyn RegisteredFamilyInteractionWitness>);
+impl ExtensionInteractionWitness {
+    pub fn write_interaction_trace(
+        self,
+        interaction_elements: &CairoInteractionElements,
+    ) -> (ExtensionInteractionClaim, TraceColumns) {
+        self.0.write_interaction_trace(interaction_elements)
+    }
+}
+
+/// A [`ComponentRegistry`], together with the witness of each of its families for a single
+/// proof.
+#[derive(Default)]
+pub struct WitnessRegistry {
+    registry: ComponentRegistry,
+    witnesses: Vec<ExtensionWitness>,
+}
+impl WitnessRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `family`, whose trace is generated by `witness`.
+    pub fn register<F: ComponentFamily + 'static>(
+        &mut self,
+        family: F,
+        witness: impl ComponentFamilyWitness<Family = F> + 'static,
+    ) -> &mut Self {
+        self.witnesses.push(ExtensionWitness {
+            family_id: family.id(),
+            witness: Box::new(witness),
+        });
+        self.registry.register(family);
+        self
+    }
+
+    pub fn registry(&self) -> &ComponentRegistry {
+        &self.registry
+    }
+
+    pub fn into_parts(self) -> (ComponentRegistry, Vec<ExtensionWitness>) {
+        (self.registry, self.witnesses)
+    }
+}
@@ -442,7 +442,7 @@ impl InteractionClaimGenerator {
 mod tests {
     use cairo_air::air::CairoInteractionElements;
     use cairo_air::components::memory_id_to_big::{self, SmallEval};
-    use cairo_air::PreProcessedTraceVariant;
+    use cairo_air::preprocessed::PreProcessedTrace;
     use itertools::Itertools;
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
@@ -486,11 +486,7 @@ mod tests {
 
         // Preprocessed trace.
         let mut tree_builder = commitment_scheme.tree_builder();
-        tree_builder.extend_evals(
-            PreProcessedTraceVariant::CanonicalWithoutPedersen
-                .to_preprocessed_trace()
-                .gen_trace(),
-        );
+        tree_builder.extend_evals(PreProcessedTrace::canonical_without_pedersen().gen_trace());
         tree_builder.finalize_interaction();
 
         // Base trace.
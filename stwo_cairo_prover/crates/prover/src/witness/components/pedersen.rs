@@ -4,6 +4,7 @@ use cairo_air::pedersen::air::{
 };
 use tracing::{span, Level};
 
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::{partial_ec_mul, pedersen_points_table};
 use crate::witness::prelude::*;
 use crate::witness::range_checks::RangeChecksClaimGenerator;
@@ -34,17 +35,22 @@ impl PedersenContextClaimGenerator {
         self,
         tree_builder: &mut impl TreeBuilder<SimdBackend>,
         range_checks_trace_generator: &RangeChecksClaimGenerator,
-    ) -> (
-        PedersenContextClaim,
-        PedersenContextInteractionClaimGenerator,
-    ) {
+        hooks: &ProvingHooks,
+    ) -> Result<
+        (
+            PedersenContextClaim,
+            PedersenContextInteractionClaimGenerator,
+        ),
+        CairoProvingError,
+    > {
         let span = span!(Level::INFO, "write pedersen context trace").entered();
         if self.partial_ec_mul_trace_generator.is_empty() {
-            return (
+            return Ok((
                 PedersenContextClaim { claim: None },
                 PedersenContextInteractionClaimGenerator { gen: None },
-            );
+            ));
         }
+        hooks.enter_base_trace("partial_ec_mul", true)?;
         let (partial_ec_mul_claim, partial_ec_mul_interaction_gen) =
             self.partial_ec_mul_trace_generator.write_trace(
                 tree_builder,
@@ -52,6 +58,7 @@ impl PedersenContextClaimGenerator {
                 &range_checks_trace_generator.rc_19_trace_generator,
                 &range_checks_trace_generator.rc_9_9_trace_generator,
             );
+        hooks.enter_base_trace("pedersen_points_table", true)?;
         let (pedersen_points_table_claim, pedersen_points_table_interaction_gen) = self
             .pedersen_points_table_trace_generator
             .write_trace(tree_builder);
@@ -65,10 +72,10 @@ impl PedersenContextClaimGenerator {
             partial_ec_mul_interaction_gen,
             pedersen_points_table_interaction_gen,
         });
-        (
+        Ok((
             PedersenContextClaim { claim },
             PedersenContextInteractionClaimGenerator { gen },
-        )
+        ))
     }
 }
 
@@ -4,6 +4,7 @@ use cairo_air::poseidon::air::{
 };
 use tracing::{span, Level};
 
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::{
     cube_252, poseidon_3_partial_rounds_chain, poseidon_full_round_chain, poseidon_round_keys,
     range_check_felt_252_width_27,
@@ -51,20 +52,25 @@ impl PoseidonContextClaimGenerator {
         mut self,
         tree_builder: &mut impl TreeBuilder<SimdBackend>,
         range_checks_trace_generator: &RangeChecksClaimGenerator,
-    ) -> (
-        PoseidonContextClaim,
-        PoseidonContextInteractionClaimGenerator,
-    ) {
+        hooks: &ProvingHooks,
+    ) -> Result<
+        (
+            PoseidonContextClaim,
+            PoseidonContextInteractionClaimGenerator,
+        ),
+        CairoProvingError,
+    > {
         let span = span!(Level::INFO, "write poseidon context trace").entered();
         if self
             .poseidon_3_partial_rounds_chain_trace_generator
             .is_empty()
         {
-            return (
+            return Ok((
                 PoseidonContextClaim { claim: None },
                 PoseidonContextInteractionClaimGenerator { gen: None },
-            );
+            ));
         }
+        hooks.enter_base_trace("poseidon_3_partial_rounds_chain", true)?;
         let (
             poseidon_3_partial_rounds_chain_claim,
             poseidon_3_partial_rounds_chain_interaction_gen,
@@ -78,6 +84,7 @@ impl PoseidonContextClaimGenerator {
                 &range_checks_trace_generator.rc_4_4_4_4_trace_generator,
                 &mut self.range_check_felt_252_width_27_trace_generator,
             );
+        hooks.enter_base_trace("poseidon_full_round_chain", true)?;
         let (poseidon_full_round_chain_claim, poseidon_full_round_chain_interaction_gen) =
             self.poseidon_full_round_chain_trace_generator.write_trace(
                 tree_builder,
@@ -85,14 +92,17 @@ impl PoseidonContextClaimGenerator {
                 &self.poseidon_round_keys_trace_generator,
                 &range_checks_trace_generator.rc_3_3_3_3_3_trace_generator,
             );
+        hooks.enter_base_trace("cube_252", true)?;
         let (cube_252_claim, cube_252_interaction_gen) = self.cube_252_trace_generator.write_trace(
             tree_builder,
             &range_checks_trace_generator.rc_19_trace_generator,
             &range_checks_trace_generator.rc_9_9_trace_generator,
         );
+        hooks.enter_base_trace("poseidon_round_keys", true)?;
         let (poseidon_round_keys_claim, poseidon_round_keys_interaction_gen) = self
             .poseidon_round_keys_trace_generator
             .write_trace(tree_builder);
+        hooks.enter_base_trace("range_check_felt_252_width_27", true)?;
         let (range_check_felt_252_width_27_claim, range_check_felt_252_width_27_interaction_gen) =
             self.range_check_felt_252_width_27_trace_generator
                 .write_trace(
@@ -116,10 +126,10 @@ impl PoseidonContextClaimGenerator {
             poseidon_round_keys_interaction_gen,
             range_check_felt_252_width_27_interaction_gen,
         });
-        (
+        Ok((
             PoseidonContextClaim { claim },
             PoseidonContextInteractionClaimGenerator { gen },
-        )
+        ))
     }
 }
 
@@ -144,11 +144,30 @@ impl BlakeRound {
 
 #[cfg(test)]
 mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
     use stwo_cairo_adapter::memory::{MemoryBuilder, MemoryConfig, MemoryEntry};
     use stwo_prover::core::fields::m31::M31;
 
     use super::*;
 
+    /// The scalar G function of the BLAKE2s spec, a CPU reference for [`PackedBlakeG`].
+    fn blake_g_reference([mut a, mut b, mut c, mut d, m0, m1]: [u32; 6]) -> [u32; 4] {
+        a = a.wrapping_add(b).wrapping_add(m0);
+        d = (d ^ a).rotate_right(16);
+        c = c.wrapping_add(d);
+        b = (b ^ c).rotate_right(12);
+        a = a.wrapping_add(b).wrapping_add(m1);
+        d = (d ^ a).rotate_right(8);
+        c = c.wrapping_add(d);
+        b = (b ^ c).rotate_right(7);
+        [a, b, c, d]
+    }
+
+    fn rand_packed_u32(rng: &mut SmallRng) -> PackedUInt32 {
+        PackedUInt32::from_simd(u32x16::from_array(rng.gen()))
+    }
+
     #[test]
     fn test_g() {
         let input0 = [
@@ -176,6 +195,32 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_g_matches_cpu_reference() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let input: [PackedUInt32; NUM_INPUT_WORDS_G] = from_fn(|_| rand_packed_u32(&mut rng));
+
+        let actual = PackedBlakeG::deduce_output(input);
+
+        for lane in 0..N_LANES {
+            let expected = blake_g_reference(input.map(|x| x.simd[lane]));
+            assert_eq!(actual.map(|x| x.simd[lane]), expected, "lane {lane}");
+        }
+    }
+
+    #[test]
+    fn test_triple_xor_matches_cpu_reference() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let input: [PackedUInt32; 3] = from_fn(|_| rand_packed_u32(&mut rng));
+
+        let actual = PackedTripleXor32::deduce_output(input);
+
+        for lane in 0..N_LANES {
+            let [a, b, c] = input.map(|x| x.simd[lane]);
+            assert_eq!(actual.simd[lane], a ^ b ^ c, "lane {lane}");
+        }
+    }
+
     #[test]
     fn test_round_sigma() {
         let input =
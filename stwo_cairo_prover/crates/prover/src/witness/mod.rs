@@ -1,6 +1,7 @@
 pub mod blake_context;
 pub mod builtins;
 pub mod cairo;
+pub mod component_registry;
 pub mod components;
 pub mod fast_deduction;
 pub mod opcodes;
@@ -1,10 +1,12 @@
 use cairo_air::air::CairoInteractionElements;
 use cairo_air::opcodes_air::{OpcodeClaim, OpcodeInteractionClaim};
-use stwo_cairo_adapter::opcodes::StateTransitions;
+use stwo_cairo_adapter::opcode_components;
+use stwo_cairo_adapter::opcodes::{CasmStatesByOpcode, StateTransitions};
 use stwo_prover::core::backend::simd::SimdBackend;
 
 use super::blake_context::BlakeContextClaimGenerator;
 use super::range_checks::RangeChecksClaimGenerator;
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::{
     add_ap_opcode, add_opcode, add_opcode_small, assert_eq_opcode, assert_eq_opcode_double_deref,
     assert_eq_opcode_imm, blake_compress_opcode, call_opcode, call_opcode_rel_imm, generic_opcode,
@@ -39,157 +41,21 @@ pub struct OpcodesClaimGenerator {
 impl OpcodesClaimGenerator {
     pub fn new(input: StateTransitions) -> Self {
         // TODO(Ohad): decide split sizes for opcode traces.
-        let mut add = vec![];
-        let mut add_small = vec![];
-        let mut add_ap = vec![];
-        let mut assert_eq = vec![];
-        let mut assert_eq_imm = vec![];
-        let mut assert_eq_double_deref = vec![];
-        let mut blake = vec![];
-        let mut call = vec![];
-        let mut call_rel_imm = vec![];
-        let mut generic = vec![];
-        let mut jnz = vec![];
-        let mut jnz_taken = vec![];
-        let mut jump = vec![];
-        let mut jump_double_deref = vec![];
-        let mut jump_rel = vec![];
-        let mut jump_rel_imm = vec![];
-        let mut mul = vec![];
-        let mut mul_small = vec![];
-        let mut qm31 = vec![];
-        let mut ret = vec![];
-        if !input.casm_states_by_opcode.add_opcode.is_empty() {
-            add.push(add_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.add_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.add_opcode_small.is_empty() {
-            add_small.push(add_opcode_small::ClaimGenerator::new(
-                input.casm_states_by_opcode.add_opcode_small,
-            ));
-        }
-        if !input.casm_states_by_opcode.add_ap_opcode.is_empty() {
-            add_ap.push(add_ap_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.add_ap_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.assert_eq_opcode.is_empty() {
-            assert_eq.push(assert_eq_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.assert_eq_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.assert_eq_opcode_imm.is_empty() {
-            assert_eq_imm.push(assert_eq_opcode_imm::ClaimGenerator::new(
-                input.casm_states_by_opcode.assert_eq_opcode_imm,
-            ));
-        }
-        if !input
-            .casm_states_by_opcode
-            .assert_eq_opcode_double_deref
-            .is_empty()
-        {
-            assert_eq_double_deref.push(assert_eq_opcode_double_deref::ClaimGenerator::new(
-                input.casm_states_by_opcode.assert_eq_opcode_double_deref,
-            ));
-        }
-        if !input.casm_states_by_opcode.blake_compress_opcode.is_empty() {
-            blake.push(blake_compress_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.blake_compress_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.call_opcode.is_empty() {
-            call.push(call_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.call_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.call_opcode_rel_imm.is_empty() {
-            call_rel_imm.push(call_opcode_rel_imm::ClaimGenerator::new(
-                input.casm_states_by_opcode.call_opcode_rel_imm,
-            ));
-        }
-        if !input.casm_states_by_opcode.generic_opcode.is_empty() {
-            generic.push(generic_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.generic_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.jnz_opcode.is_empty() {
-            jnz.push(jnz_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.jnz_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.jnz_opcode_taken.is_empty() {
-            jnz_taken.push(jnz_opcode_taken::ClaimGenerator::new(
-                input.casm_states_by_opcode.jnz_opcode_taken,
-            ));
-        }
-        if !input.casm_states_by_opcode.jump_opcode.is_empty() {
-            jump.push(jump_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.jump_opcode,
-            ));
-        }
-        if !input
-            .casm_states_by_opcode
-            .jump_opcode_double_deref
-            .is_empty()
-        {
-            jump_double_deref.push(jump_opcode_double_deref::ClaimGenerator::new(
-                input.casm_states_by_opcode.jump_opcode_double_deref,
-            ));
-        }
-        if !input.casm_states_by_opcode.jump_opcode_rel.is_empty() {
-            jump_rel.push(jump_opcode_rel::ClaimGenerator::new(
-                input.casm_states_by_opcode.jump_opcode_rel,
-            ));
-        }
-        if !input.casm_states_by_opcode.jump_opcode_rel_imm.is_empty() {
-            jump_rel_imm.push(jump_opcode_rel_imm::ClaimGenerator::new(
-                input.casm_states_by_opcode.jump_opcode_rel_imm,
-            ));
-        }
-        // Handle small mul in big mul component. Temporary until airs are written with Rc_3_6_6.
-        if !input.casm_states_by_opcode.mul_opcode.is_empty() {
-            mul.push(mul_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.mul_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.mul_opcode_small.is_empty() {
-            mul_small.push(mul_opcode_small::ClaimGenerator::new(
-                input.casm_states_by_opcode.mul_opcode_small,
-            ));
-        }
-        if !input.casm_states_by_opcode.qm_31_add_mul_opcode.is_empty() {
-            qm31.push(qm_31_add_mul_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.qm_31_add_mul_opcode,
-            ));
-        }
-        if !input.casm_states_by_opcode.ret_opcode.is_empty() {
-            ret.push(ret_opcode::ClaimGenerator::new(
-                input.casm_states_by_opcode.ret_opcode,
-            ));
-        }
-        Self {
-            add,
-            add_small,
-            add_ap,
-            assert_eq,
-            assert_eq_imm,
-            assert_eq_double_deref,
-            blake,
-            call,
-            call_rel_imm,
-            generic,
-            jnz,
-            jnz_taken,
-            jump,
-            jump_double_deref,
-            jump_rel,
-            jump_rel_imm,
-            mul,
-            mul_small,
-            qm31,
-            ret,
+        macro_rules! claim_generators {
+            ($($field:ident: $module:ident,)*) => {{
+                let CasmStatesByOpcode { $($module,)* } = input.casm_states_by_opcode;
+                Self {
+                    $(
+                        $field: if $module.is_empty() {
+                            vec![]
+                        } else {
+                            vec![$module::ClaimGenerator::new($module)]
+                        },
+                    )*
+                }
+            }};
         }
+        opcode_components!(claim_generators)
     }
 
     pub fn write_trace(
@@ -201,7 +67,9 @@ impl OpcodesClaimGenerator {
         range_checks_trace_generator: &RangeChecksClaimGenerator,
         verify_instruction_trace_generator: &verify_instruction::ClaimGenerator,
         verify_bitwise_xor_8_trace_generator: &mut verify_bitwise_xor_8::ClaimGenerator,
-    ) -> (OpcodeClaim, OpcodesInteractionClaimGenerator) {
+        hooks: &ProvingHooks,
+    ) -> Result<(OpcodeClaim, OpcodesInteractionClaimGenerator), CairoProvingError> {
+        enter_component(hooks, "add_opcode", &self.add)?;
         let (add_claims, add_interaction_gens) = self
             .add
             .into_iter()
@@ -214,6 +82,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "add_opcode_small", &self.add_small)?;
         let (add_small_claims, add_small_interaction_gens) = self
             .add_small
             .into_iter()
@@ -226,6 +95,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "add_ap_opcode", &self.add_ap)?;
         let (add_ap_claims, add_ap_interaction_gens) = self
             .add_ap
             .into_iter()
@@ -240,6 +110,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "assert_eq_opcode", &self.assert_eq)?;
         let (assert_eq_claims, assert_eq_interaction_gens) = self
             .assert_eq
             .into_iter()
@@ -252,6 +123,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "assert_eq_opcode_imm", &self.assert_eq_imm)?;
         let (assert_eq_imm_claims, assert_eq_imm_interaction_gens) = self
             .assert_eq_imm
             .into_iter()
@@ -264,6 +136,11 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(
+            hooks,
+            "assert_eq_opcode_double_deref",
+            &self.assert_eq_double_deref,
+        )?;
         let (assert_eq_double_deref_claims, assert_eq_double_deref_interaction_gens) = self
             .assert_eq_double_deref
             .into_iter()
@@ -276,6 +153,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "blake_compress_opcode", &self.blake)?;
         let (blake_claims, blake_interaction_gens) = self
             .blake
             .into_iter()
@@ -292,6 +170,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "call_opcode", &self.call)?;
         let (call_claims, call_interaction_gens) = self
             .call
             .into_iter()
@@ -304,6 +183,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "call_opcode_rel_imm", &self.call_rel_imm)?;
         let (call_rel_imm_claims, call_rel_imm_interaction_gens) = self
             .call_rel_imm
             .into_iter()
@@ -316,6 +196,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "generic_opcode", &self.generic)?;
         let (generic_opcode_claims, generic_opcode_interaction_gens) = self
             .generic
             .into_iter()
@@ -330,6 +211,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "jnz_opcode", &self.jnz)?;
         let (jnz_claims, jnz_interaction_gens) = self
             .jnz
             .into_iter()
@@ -342,6 +224,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "jnz_opcode_taken", &self.jnz_taken)?;
         let (jnz_taken_claims, jnz_taken_interaction_gens) = self
             .jnz_taken
             .into_iter()
@@ -354,6 +237,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "jump_opcode", &self.jump)?;
         let (jump_claims, jump_interaction_gens) = self
             .jump
             .into_iter()
@@ -366,6 +250,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "jump_opcode_double_deref", &self.jump_double_deref)?;
         let (jump_double_deref_claims, jump_double_deref_interaction_gens) = self
             .jump_double_deref
             .into_iter()
@@ -378,6 +263,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "jump_opcode_rel", &self.jump_rel)?;
         let (jump_rel_claims, jump_rel_interaction_gens) = self
             .jump_rel
             .into_iter()
@@ -390,6 +276,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "jump_opcode_rel_imm", &self.jump_rel_imm)?;
         let (jump_rel_imm_claims, jump_rel_imm_interaction_gens) = self
             .jump_rel_imm
             .into_iter()
@@ -402,6 +289,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "mul_opcode", &self.mul)?;
         let (mul_claims, mul_interaction_gens) = self
             .mul
             .into_iter()
@@ -415,6 +303,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "mul_opcode_small", &self.mul_small)?;
         let (mul_small_claims, mul_small_interaction_gens) = self
             .mul_small
             .into_iter()
@@ -428,6 +317,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "qm_31_add_mul_opcode", &self.qm31)?;
         let (qm31_claims, qm31_interaction_gens) = self
             .qm31
             .into_iter()
@@ -441,6 +331,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
+        enter_component(hooks, "ret_opcode", &self.ret)?;
         let (ret_claims, ret_interaction_gens) = self
             .ret
             .into_iter()
@@ -453,7 +344,7 @@ impl OpcodesClaimGenerator {
                 )
             })
             .unzip();
-        (
+        Ok((
             OpcodeClaim {
                 add: add_claims,
                 add_small: add_small_claims,
@@ -498,10 +389,19 @@ impl OpcodesClaimGenerator {
                 qm31: qm31_interaction_gens,
                 ret_interaction_gens,
             },
-        )
+        ))
     }
 }
 
+/// Reports the base trace phase of an opcode component, if it has any instances.
+fn enter_component<T>(
+    hooks: &ProvingHooks,
+    component: &'static str,
+    claim_generators: &[T],
+) -> Result<(), CairoProvingError> {
+    hooks.enter_base_trace(component, !claim_generators.is_empty())
+}
+
 pub struct OpcodesInteractionClaimGenerator {
     add: Vec<add_opcode::InteractionClaimGenerator>,
     add_small: Vec<add_opcode_small::InteractionClaimGenerator>,
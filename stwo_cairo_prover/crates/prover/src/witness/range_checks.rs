@@ -1,8 +1,11 @@
+use cairo_air::air::RelationUsesDict;
 use cairo_air::range_checks_air::{
     RangeChecksClaim, RangeChecksInteractionClaim, RangeChecksInteractionElements,
 };
+use paste::paste;
 use stwo_prover::core::backend::simd::SimdBackend;
 
+use crate::hooks::{CairoProvingError, ProvingHooks};
 use crate::witness::components::{
     range_check_11, range_check_12, range_check_18, range_check_19, range_check_3_3_3_3_3,
     range_check_3_6_6_3, range_check_4_3, range_check_4_4, range_check_4_4_4_4, range_check_5_4,
@@ -10,6 +13,28 @@ use crate::witness::components::{
 };
 use crate::witness::utils::TreeBuilder;
 
+/// Lists the range-check tables as `rc_<ranges>: range_check_<ranges>`, in trace order.
+macro_rules! range_checks {
+    ($callback:ident) => {
+        $callback! {
+            rc_6: range_check_6,
+            rc_8: range_check_8,
+            rc_11: range_check_11,
+            rc_12: range_check_12,
+            rc_18: range_check_18,
+            rc_19: range_check_19,
+            rc_4_3: range_check_4_3,
+            rc_4_4: range_check_4_4,
+            rc_5_4: range_check_5_4,
+            rc_9_9: range_check_9_9,
+            rc_7_2_5: range_check_7_2_5,
+            rc_3_6_6_3: range_check_3_6_6_3,
+            rc_4_4_4_4: range_check_4_4_4_4,
+            rc_3_3_3_3_3: range_check_3_3_3_3_3,
+        }
+    };
+}
+
 pub struct RangeChecksClaimGenerator {
     pub rc_6_trace_generator: range_check_6::ClaimGenerator,
     pub rc_8_trace_generator: range_check_8::ClaimGenerator,
@@ -51,90 +76,56 @@ impl RangeChecksClaimGenerator {
             rc_3_3_3_3_3_trace_generator: range_check_3_3_3_3_3::ClaimGenerator::new(),
         }
     }
+    /// Writes the traces of the range-check tables that are looked up by the components
+    /// accumulated in `relation_uses`. The other tables are omitted from the claim, see
+    /// [`RangeChecksClaim::looked_up`].
     pub fn write_trace(
         self,
         tree_builder: &mut impl TreeBuilder<SimdBackend>,
-    ) -> (RangeChecksClaim, RangeChecksInteractionClaimGenerator) {
-        let (rc_6_claim, rc_6_interaction_gen) =
-            self.rc_6_trace_generator.write_trace(tree_builder);
-        let (rc_8_claim, rc_8_interaction_gen) =
-            self.rc_8_trace_generator.write_trace(tree_builder);
-        let (rc_11_claim, rc_11_interaction_gen) =
-            self.rc_11_trace_generator.write_trace(tree_builder);
-        let (rc_12_claim, rc_12_interaction_gen) =
-            self.rc_12_trace_generator.write_trace(tree_builder);
-        let (rc_18_claim, rc_18_interaction_gen) =
-            self.rc_18_trace_generator.write_trace(tree_builder);
-        let (rc_19_claim, rc_19_interaction_gen) =
-            self.rc_19_trace_generator.write_trace(tree_builder);
-        let (rc_4_3_claim, rc_4_3_interaction_gen) =
-            self.rc_4_3_trace_generator.write_trace(tree_builder);
-        let (rc_4_4_claim, rc_4_4_interaction_gen) =
-            self.rc_4_4_trace_generator.write_trace(tree_builder);
-        let (rc_5_4_claim, rc_5_4_interaction_gen) =
-            self.rc_5_4_trace_generator.write_trace(tree_builder);
-        let (rc_9_9_claim, rc_9_9_interaction_gen) =
-            self.rc_9_9_trace_generator.write_trace(tree_builder);
-        let (rc_7_2_5_claim, rc_7_2_5_interaction_gen) =
-            self.rc_7_2_5_trace_generator.write_trace(tree_builder);
-        let (rc_3_6_6_3_claim, rc_3_6_6_3_interaction_gen) =
-            self.rc_3_6_6_3_trace_generator.write_trace(tree_builder);
-        let (rc_4_4_4_4_claim, rc_4_4_4_4_interaction_gen) =
-            self.rc_4_4_4_4_trace_generator.write_trace(tree_builder);
-        let (rc_3_3_3_3_3_claim, rc_3_3_3_3_3_interaction_gen) =
-            self.rc_3_3_3_3_3_trace_generator.write_trace(tree_builder);
-        (
-            RangeChecksClaim {
-                rc_6: rc_6_claim,
-                rc_8: rc_8_claim,
-                rc_11: rc_11_claim,
-                rc_12: rc_12_claim,
-                rc_18: rc_18_claim,
-                rc_19: rc_19_claim,
-                rc_4_3: rc_4_3_claim,
-                rc_4_4: rc_4_4_claim,
-                rc_5_4: rc_5_4_claim,
-                rc_9_9: rc_9_9_claim,
-                rc_7_2_5: rc_7_2_5_claim,
-                rc_3_6_6_3: rc_3_6_6_3_claim,
-                rc_4_4_4_4: rc_4_4_4_4_claim,
-                rc_3_3_3_3_3: rc_3_3_3_3_3_claim,
-            },
-            RangeChecksInteractionClaimGenerator {
-                rc_6_interaction_gen,
-                rc_8_interaction_gen,
-                rc_11_interaction_gen,
-                rc_12_interaction_gen,
-                rc_18_interaction_gen,
-                rc_19_interaction_gen,
-                rc_4_3_interaction_gen,
-                rc_4_4_interaction_gen,
-                rc_5_4_interaction_gen,
-                rc_9_9_interaction_gen,
-                rc_7_2_5_interaction_gen,
-                rc_3_6_6_3_interaction_gen,
-                rc_4_4_4_4_interaction_gen,
-                rc_3_3_3_3_3_interaction_gen,
-            },
-        )
+        relation_uses: &RelationUsesDict,
+        hooks: &ProvingHooks,
+    ) -> Result<(RangeChecksClaim, RangeChecksInteractionClaimGenerator), CairoProvingError> {
+        let looked_up = RangeChecksClaim::looked_up(relation_uses);
+        macro_rules! write_traces {
+            ($($rc:ident: $component:ident,)*) => {
+                paste! {
+                    $(
+                        let ([<$rc _claim>], [<$rc _interaction_gen>]) = hooks
+                            .write_base_trace(stringify!($component), looked_up.$rc.is_some(), || {
+                                self.[<$rc _trace_generator>].write_trace(tree_builder)
+                            })?
+                            .unzip();
+                    )*
+                    Ok((
+                        RangeChecksClaim {
+                            $($rc: [<$rc _claim>],)*
+                        },
+                        RangeChecksInteractionClaimGenerator {
+                            $([<$rc _interaction_gen>],)*
+                        },
+                    ))
+                }
+            };
+        }
+        range_checks!(write_traces)
     }
 }
 
 pub struct RangeChecksInteractionClaimGenerator {
-    rc_6_interaction_gen: range_check_6::InteractionClaimGenerator,
-    rc_8_interaction_gen: range_check_8::InteractionClaimGenerator,
-    rc_11_interaction_gen: range_check_11::InteractionClaimGenerator,
-    rc_12_interaction_gen: range_check_12::InteractionClaimGenerator,
-    rc_18_interaction_gen: range_check_18::InteractionClaimGenerator,
-    rc_19_interaction_gen: range_check_19::InteractionClaimGenerator,
-    rc_4_3_interaction_gen: range_check_4_3::InteractionClaimGenerator,
-    rc_4_4_interaction_gen: range_check_4_4::InteractionClaimGenerator,
-    rc_5_4_interaction_gen: range_check_5_4::InteractionClaimGenerator,
-    rc_9_9_interaction_gen: range_check_9_9::InteractionClaimGenerator,
-    rc_7_2_5_interaction_gen: range_check_7_2_5::InteractionClaimGenerator,
-    rc_3_6_6_3_interaction_gen: range_check_3_6_6_3::InteractionClaimGenerator,
-    rc_4_4_4_4_interaction_gen: range_check_4_4_4_4::InteractionClaimGenerator,
-    rc_3_3_3_3_3_interaction_gen: range_check_3_3_3_3_3::InteractionClaimGenerator,
+    rc_6_interaction_gen: Option<range_check_6::InteractionClaimGenerator>,
+    rc_8_interaction_gen: Option<range_check_8::InteractionClaimGenerator>,
+    rc_11_interaction_gen: Option<range_check_11::InteractionClaimGenerator>,
+    rc_12_interaction_gen: Option<range_check_12::InteractionClaimGenerator>,
+    rc_18_interaction_gen: Option<range_check_18::InteractionClaimGenerator>,
+    rc_19_interaction_gen: Option<range_check_19::InteractionClaimGenerator>,
+    rc_4_3_interaction_gen: Option<range_check_4_3::InteractionClaimGenerator>,
+    rc_4_4_interaction_gen: Option<range_check_4_4::InteractionClaimGenerator>,
+    rc_5_4_interaction_gen: Option<range_check_5_4::InteractionClaimGenerator>,
+    rc_9_9_interaction_gen: Option<range_check_9_9::InteractionClaimGenerator>,
+    rc_7_2_5_interaction_gen: Option<range_check_7_2_5::InteractionClaimGenerator>,
+    rc_3_6_6_3_interaction_gen: Option<range_check_3_6_6_3::InteractionClaimGenerator>,
+    rc_4_4_4_4_interaction_gen: Option<range_check_4_4_4_4::InteractionClaimGenerator>,
+    rc_3_3_3_3_3_interaction_gen: Option<range_check_3_3_3_3_3::InteractionClaimGenerator>,
 }
 impl RangeChecksInteractionClaimGenerator {
     pub fn write_interaction_trace(
@@ -142,63 +133,20 @@ impl RangeChecksInteractionClaimGenerator {
         tree_builder: &mut impl TreeBuilder<SimdBackend>,
         interaction_elements: &RangeChecksInteractionElements,
     ) -> RangeChecksInteractionClaim {
-        let rc_6_interaction_claim = self
-            .rc_6_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_6);
-        let rc_8_interaction_claim = self
-            .rc_8_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_8);
-        let rc_11_interaction_claim = self
-            .rc_11_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_11);
-        let rc_12_interaction_claim = self
-            .rc_12_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_12);
-        let rc_18_interaction_claim = self
-            .rc_18_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_18);
-        let rc_19_interaction_claim = self
-            .rc_19_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_19);
-        let rc_4_3_interaction_claim = self
-            .rc_4_3_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_4_3);
-        let rc_4_4_interaction_claim = self
-            .rc_4_4_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_4_4);
-        let rc_5_4_interaction_claim = self
-            .rc_5_4_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_5_4);
-        let rc_9_9_interaction_claim = self
-            .rc_9_9_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_9_9);
-        let rc_7_2_5_interaction_claim = self
-            .rc_7_2_5_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_7_2_5);
-        let rc_3_6_6_3_interaction_claim = self
-            .rc_3_6_6_3_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_3_6_6_3);
-        let rc_4_4_4_4_interaction_claim = self
-            .rc_4_4_4_4_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_4_4_4_4);
-        let rc_3_3_3_3_3_interaction_claim = self
-            .rc_3_3_3_3_3_interaction_gen
-            .write_interaction_trace(tree_builder, &interaction_elements.rc_3_3_3_3_3);
-        RangeChecksInteractionClaim {
-            rc_6: rc_6_interaction_claim,
-            rc_8: rc_8_interaction_claim,
-            rc_11: rc_11_interaction_claim,
-            rc_12: rc_12_interaction_claim,
-            rc_18: rc_18_interaction_claim,
-            rc_19: rc_19_interaction_claim,
-            rc_4_3: rc_4_3_interaction_claim,
-            rc_4_4: rc_4_4_interaction_claim,
-            rc_5_4: rc_5_4_interaction_claim,
-            rc_9_9: rc_9_9_interaction_claim,
-            rc_7_2_5: rc_7_2_5_interaction_claim,
-            rc_3_6_6_3: rc_3_6_6_3_interaction_claim,
-            rc_4_4_4_4: rc_4_4_4_4_interaction_claim,
-            rc_3_3_3_3_3: rc_3_3_3_3_3_interaction_claim,
+        macro_rules! write_interaction_traces {
+            ($($rc:ident: $component:ident,)*) => {
+                paste! {
+                    RangeChecksInteractionClaim {
+                        $(
+                            $rc: self.[<$rc _interaction_gen>].map(|interaction_gen| {
+                                interaction_gen
+                                    .write_interaction_trace(tree_builder, &interaction_elements.$rc)
+                            }),
+                        )*
+                    }
+                }
+            };
         }
+        range_checks!(write_interaction_traces)
     }
 }
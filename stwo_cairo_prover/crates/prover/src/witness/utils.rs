@@ -2,7 +2,6 @@ use std::sync::atomic::{AtomicU32, Ordering};
 
 use cairo_air::air::CairoClaim;
 use cairo_air::preprocessed::PreProcessedTrace;
-use cairo_air::PreProcessedTraceVariant;
 use itertools::Itertools;
 use num_traits::{One, Zero};
 use stwo_prover::constraint_framework::PREPROCESSED_TRACE_IDX;
@@ -110,7 +109,40 @@ impl<B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<B>
     }
 }
 
-fn tree_trace_cells(tree_log_sizes: TreeVec<Vec<u32>>) -> Vec<u64> {
+/// A [`TreeBuilder`] that buffers the evaluations, to extend a commitment tree with them later.
+pub struct TraceBuffer<B: Backend> {
+    tree_index: usize,
+    evals: Vec<CircleEvaluation<B, M31, BitReversedOrder>>,
+}
+impl<B: Backend> TraceBuffer<B> {
+    pub fn new(tree_index: usize) -> Self {
+        Self {
+            tree_index,
+            evals: vec![],
+        }
+    }
+
+    pub fn into_evals(self) -> Vec<CircleEvaluation<B, M31, BitReversedOrder>> {
+        self.evals
+    }
+}
+
+impl<B: Backend> TreeBuilder<B> for TraceBuffer<B> {
+    fn extend_evals(
+        &mut self,
+        columns: impl IntoIterator<Item = CircleEvaluation<B, M31, BitReversedOrder>>,
+    ) -> TreeSubspan {
+        let col_start = self.evals.len();
+        self.evals.extend(columns);
+        TreeSubspan {
+            tree_index: self.tree_index,
+            col_start,
+            col_end: self.evals.len(),
+        }
+    }
+}
+
+pub(crate) fn tree_trace_cells(tree_log_sizes: TreeVec<Vec<u32>>) -> Vec<u64> {
     tree_log_sizes
         .iter()
         .map(|tree| {
@@ -132,7 +164,7 @@ pub fn witness_trace_cells(claim: &CairoClaim, pp_trace: &PreProcessedTrace) ->
 
 fn get_preprocessed_roots<MC: MerkleChannel>(
     max_log_blowup_factor: u32,
-    preprocessed_trace: PreProcessedTraceVariant,
+    preprocessed_trace: &PreProcessedTrace,
 ) -> Vec<<MC::H as MerkleHasher>::Hash>
 where
     stwo_prover::core::backend::simd::SimdBackend: BackendForChannel<MC>,
@@ -151,7 +183,7 @@ pub fn export_preprocessed_roots() {
     // Blake2s roots.
     let blake_roots = get_preprocessed_roots::<Blake2sMerkleChannel>(
         max_log_blowup_factor,
-        PreProcessedTraceVariant::Canonical,
+        &PreProcessedTrace::canonical(),
     );
     blake_roots.iter().enumerate().for_each(|(i, root)| {
         let root_bytes = root.0;
@@ -167,7 +199,7 @@ pub fn export_preprocessed_roots() {
     // Poseidon252 roots.
     get_preprocessed_roots::<Poseidon252MerkleChannel>(
         max_log_blowup_factor,
-        PreProcessedTraceVariant::CanonicalWithoutPedersen,
+        &PreProcessedTrace::canonical_without_pedersen(),
     )
     .into_iter()
     .enumerate()
@@ -601,6 +601,7 @@ pub fn verify_cairo(proof: CairoProof) -> Result<(), CairoVerificationError> {
     let pcs_config = stark_proof.commitment_scheme_proof.config;
 
     verify_claim(@claim);
+    verify_lookup_tables(@claim, @interaction_claim);
 
     let mut channel: Channel = Default::default();
     pcs_config.mix_into(ref channel);
@@ -657,7 +658,9 @@ pub fn lookup_sum(
 
     // TODO(Andrew): double check this is correct order.
     sum += interaction_claim.opcodes.sum();
-    sum += *interaction_claim.verify_instruction.claimed_sum;
+    if let Some(verify_instruction) = interaction_claim.verify_instruction {
+        sum += *verify_instruction.claimed_sum;
+    }
     sum += interaction_claim.blake_context.sum();
     sum += interaction_claim.builtins.sum();
     sum += interaction_claim.pedersen_context.sum();
@@ -665,10 +668,18 @@ pub fn lookup_sum(
     sum += *interaction_claim.memory_address_to_id.claimed_sum;
     sum += interaction_claim.memory_id_to_value.sum();
     sum += interaction_claim.range_checks.sum();
-    sum += *interaction_claim.verify_bitwise_xor_4.claimed_sum;
-    sum += *interaction_claim.verify_bitwise_xor_7.claimed_sum;
-    sum += *interaction_claim.verify_bitwise_xor_8.claimed_sum;
-    sum += *interaction_claim.verify_bitwise_xor_9.claimed_sum;
+    if let Some(verify_bitwise_xor_4) = interaction_claim.verify_bitwise_xor_4 {
+        sum += *verify_bitwise_xor_4.claimed_sum;
+    }
+    if let Some(verify_bitwise_xor_7) = interaction_claim.verify_bitwise_xor_7 {
+        sum += *verify_bitwise_xor_7.claimed_sum;
+    }
+    if let Some(verify_bitwise_xor_8) = interaction_claim.verify_bitwise_xor_8 {
+        sum += *verify_bitwise_xor_8.claimed_sum;
+    }
+    if let Some(verify_bitwise_xor_9) = interaction_claim.verify_bitwise_xor_9 {
+        sum += *verify_bitwise_xor_9.claimed_sum;
+    }
     sum
 }
 
@@ -978,110 +989,203 @@ impl BuiltinsInteractionClaimImpl of BuiltinsInteractionClaimTrait {
     }
 }
 
+// Each range-check table is omitted when none of the components look it up.
 #[derive(Drop, Serde, Clone)]
 pub struct RangeChecksClaim {
-    pub rc_6: components::range_check_6::Claim,
-    pub rc_8: components::range_check_8::Claim,
-    pub rc_11: components::range_check_11::Claim,
-    pub rc_12: components::range_check_12::Claim,
-    pub rc_18: components::range_check_18::Claim,
-    pub rc_19: components::range_check_19::Claim,
-    pub rc_4_3: components::range_check_4_3::Claim,
-    pub rc_4_4: components::range_check_4_4::Claim,
-    pub rc_5_4: components::range_check_5_4::Claim,
-    pub rc_9_9: components::range_check_9_9::Claim,
-    pub rc_7_2_5: components::range_check_7_2_5::Claim,
-    pub rc_3_6_6_3: components::range_check_3_6_6_3::Claim,
-    pub rc_4_4_4_4: components::range_check_4_4_4_4::Claim,
-    pub rc_3_3_3_3_3: components::range_check_3_3_3_3_3::Claim,
+    pub rc_6: Option<components::range_check_6::Claim>,
+    pub rc_8: Option<components::range_check_8::Claim>,
+    pub rc_11: Option<components::range_check_11::Claim>,
+    pub rc_12: Option<components::range_check_12::Claim>,
+    pub rc_18: Option<components::range_check_18::Claim>,
+    pub rc_19: Option<components::range_check_19::Claim>,
+    pub rc_4_3: Option<components::range_check_4_3::Claim>,
+    pub rc_4_4: Option<components::range_check_4_4::Claim>,
+    pub rc_5_4: Option<components::range_check_5_4::Claim>,
+    pub rc_9_9: Option<components::range_check_9_9::Claim>,
+    pub rc_7_2_5: Option<components::range_check_7_2_5::Claim>,
+    pub rc_3_6_6_3: Option<components::range_check_3_6_6_3::Claim>,
+    pub rc_4_4_4_4: Option<components::range_check_4_4_4_4::Claim>,
+    pub rc_3_3_3_3_3: Option<components::range_check_3_3_3_3_3::Claim>,
 }
 
 #[generate_trait]
 impl RangeChecksClaimImpl of RangeChecksClaimTrait {
     fn mix_into(self: @RangeChecksClaim, ref channel: Channel) {
-        self.rc_6.mix_into(ref channel);
-        self.rc_8.mix_into(ref channel);
-        self.rc_11.mix_into(ref channel);
-        self.rc_12.mix_into(ref channel);
-        self.rc_18.mix_into(ref channel);
-        self.rc_19.mix_into(ref channel);
-        self.rc_4_3.mix_into(ref channel);
-        self.rc_4_4.mix_into(ref channel);
-        self.rc_5_4.mix_into(ref channel);
-        self.rc_9_9.mix_into(ref channel);
-        self.rc_7_2_5.mix_into(ref channel);
-        self.rc_3_6_6_3.mix_into(ref channel);
-        self.rc_4_4_4_4.mix_into(ref channel);
-        self.rc_3_3_3_3_3.mix_into(ref channel);
+        // The range-check claims are empty, so their presence is mixed explicitly.
+        mix_presence(self.rc_6, ref channel);
+        mix_presence(self.rc_8, ref channel);
+        mix_presence(self.rc_11, ref channel);
+        mix_presence(self.rc_12, ref channel);
+        mix_presence(self.rc_18, ref channel);
+        mix_presence(self.rc_19, ref channel);
+        mix_presence(self.rc_4_3, ref channel);
+        mix_presence(self.rc_4_4, ref channel);
+        mix_presence(self.rc_5_4, ref channel);
+        mix_presence(self.rc_9_9, ref channel);
+        mix_presence(self.rc_7_2_5, ref channel);
+        mix_presence(self.rc_3_6_6_3, ref channel);
+        mix_presence(self.rc_4_4_4_4, ref channel);
+        mix_presence(self.rc_3_3_3_3_3, ref channel);
     }
 
     fn log_sizes(self: @RangeChecksClaim) -> TreeArray<Span<u32>> {
-        utils::tree_array_concat_cols(
-            array![
-                self.rc_6.log_sizes(), self.rc_8.log_sizes(), self.rc_11.log_sizes(),
-                self.rc_12.log_sizes(), self.rc_18.log_sizes(), self.rc_19.log_sizes(),
-                self.rc_4_3.log_sizes(), self.rc_4_4.log_sizes(), self.rc_5_4.log_sizes(),
-                self.rc_9_9.log_sizes(), self.rc_7_2_5.log_sizes(), self.rc_3_6_6_3.log_sizes(),
-                self.rc_4_4_4_4.log_sizes(), self.rc_3_3_3_3_3.log_sizes(),
-            ],
-        )
+        let mut log_sizes = array![];
+        if let Some(claim) = self.rc_6 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_8 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_11 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_12 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_18 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_19 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_4_3 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_4_4 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_5_4 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_9_9 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_7_2_5 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_3_6_6_3 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_4_4_4_4 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.rc_3_3_3_3_3 {
+            log_sizes.append(claim.log_sizes());
+        }
+        utils::tree_array_concat_cols(log_sizes)
     }
 }
 
 
 #[derive(Drop, Serde, Clone)]
 pub struct RangeChecksInteractionClaim {
-    pub rc_6: components::range_check_6::InteractionClaim,
-    pub rc_8: components::range_check_8::InteractionClaim,
-    pub rc_11: components::range_check_11::InteractionClaim,
-    pub rc_12: components::range_check_12::InteractionClaim,
-    pub rc_18: components::range_check_18::InteractionClaim,
-    pub rc_19: components::range_check_19::InteractionClaim,
-    pub rc_4_3: components::range_check_4_3::InteractionClaim,
-    pub rc_4_4: components::range_check_4_4::InteractionClaim,
-    pub rc_5_4: components::range_check_5_4::InteractionClaim,
-    pub rc_9_9: components::range_check_9_9::InteractionClaim,
-    pub rc_7_2_5: components::range_check_7_2_5::InteractionClaim,
-    pub rc_3_6_6_3: components::range_check_3_6_6_3::InteractionClaim,
-    pub rc_4_4_4_4: components::range_check_4_4_4_4::InteractionClaim,
-    pub rc_3_3_3_3_3: components::range_check_3_3_3_3_3::InteractionClaim,
+    pub rc_6: Option<components::range_check_6::InteractionClaim>,
+    pub rc_8: Option<components::range_check_8::InteractionClaim>,
+    pub rc_11: Option<components::range_check_11::InteractionClaim>,
+    pub rc_12: Option<components::range_check_12::InteractionClaim>,
+    pub rc_18: Option<components::range_check_18::InteractionClaim>,
+    pub rc_19: Option<components::range_check_19::InteractionClaim>,
+    pub rc_4_3: Option<components::range_check_4_3::InteractionClaim>,
+    pub rc_4_4: Option<components::range_check_4_4::InteractionClaim>,
+    pub rc_5_4: Option<components::range_check_5_4::InteractionClaim>,
+    pub rc_9_9: Option<components::range_check_9_9::InteractionClaim>,
+    pub rc_7_2_5: Option<components::range_check_7_2_5::InteractionClaim>,
+    pub rc_3_6_6_3: Option<components::range_check_3_6_6_3::InteractionClaim>,
+    pub rc_4_4_4_4: Option<components::range_check_4_4_4_4::InteractionClaim>,
+    pub rc_3_3_3_3_3: Option<components::range_check_3_3_3_3_3::InteractionClaim>,
 }
 
 #[generate_trait]
 impl RangeChecksInteractionClaimImpl of RangeChecksInteractionClaimTrait {
     fn mix_into(self: @RangeChecksInteractionClaim, ref channel: Channel) {
-        self.rc_6.mix_into(ref channel);
-        self.rc_8.mix_into(ref channel);
-        self.rc_11.mix_into(ref channel);
-        self.rc_12.mix_into(ref channel);
-        self.rc_18.mix_into(ref channel);
-        self.rc_19.mix_into(ref channel);
-        self.rc_4_3.mix_into(ref channel);
-        self.rc_4_4.mix_into(ref channel);
-        self.rc_5_4.mix_into(ref channel);
-        self.rc_9_9.mix_into(ref channel);
-        self.rc_7_2_5.mix_into(ref channel);
-        self.rc_3_6_6_3.mix_into(ref channel);
-        self.rc_4_4_4_4.mix_into(ref channel);
-        self.rc_3_3_3_3_3.mix_into(ref channel);
+        if let Some(interaction_claim) = self.rc_6 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_8 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_11 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_12 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_18 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_19 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_4_3 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_4_4 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_5_4 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_9_9 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_7_2_5 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_3_6_6_3 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_4_4_4_4 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.rc_3_3_3_3_3 {
+            interaction_claim.mix_into(ref channel);
+        }
     }
 
     fn sum(self: @RangeChecksInteractionClaim) -> QM31 {
         let mut sum = Zero::zero();
-        sum += *self.rc_6.claimed_sum;
-        sum += *self.rc_8.claimed_sum;
-        sum += *self.rc_11.claimed_sum;
-        sum += *self.rc_12.claimed_sum;
-        sum += *self.rc_18.claimed_sum;
-        sum += *self.rc_19.claimed_sum;
-        sum += *self.rc_4_3.claimed_sum;
-        sum += *self.rc_4_4.claimed_sum;
-        sum += *self.rc_5_4.claimed_sum;
-        sum += *self.rc_9_9.claimed_sum;
-        sum += *self.rc_7_2_5.claimed_sum;
-        sum += *self.rc_3_6_6_3.claimed_sum;
-        sum += *self.rc_4_4_4_4.claimed_sum;
-        sum += *self.rc_3_3_3_3_3.claimed_sum;
+        if let Some(interaction_claim) = self.rc_6 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_8 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_11 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_12 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_18 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_19 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_4_3 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_4_4 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_5_4 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_9_9 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_7_2_5 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_3_6_6_3 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_4_4_4_4 {
+            sum += *interaction_claim.claimed_sum;
+        }
+        if let Some(interaction_claim) = self.rc_3_3_3_3_3 {
+            sum += *interaction_claim.claimed_sum;
+        }
         sum
     }
 }
@@ -1450,7 +1554,7 @@ impl BlakeContextClaimImpl of BlakeContextClaimTrait {
 pub struct CairoClaim {
     pub public_data: PublicData,
     pub opcodes: OpcodeClaim,
-    pub verify_instruction: components::verify_instruction::Claim,
+    pub verify_instruction: Option<components::verify_instruction::Claim>,
     pub blake_context: BlakeContextClaim,
     pub builtins: BuiltinsClaim,
     pub pedersen_context: PedersenContextClaim,
@@ -1458,27 +1562,53 @@ pub struct CairoClaim {
     pub memory_address_to_id: components::memory_address_to_id::Claim,
     pub memory_id_to_value: components::memory_id_to_big::Claim,
     pub range_checks: RangeChecksClaim,
-    pub verify_bitwise_xor_4: components::verify_bitwise_xor_4::Claim,
-    pub verify_bitwise_xor_7: components::verify_bitwise_xor_7::Claim,
-    pub verify_bitwise_xor_8: components::verify_bitwise_xor_8::Claim,
-    pub verify_bitwise_xor_9: components::verify_bitwise_xor_9::Claim,
+    pub verify_bitwise_xor_4: Option<components::verify_bitwise_xor_4::Claim>,
+    pub verify_bitwise_xor_7: Option<components::verify_bitwise_xor_7::Claim>,
+    pub verify_bitwise_xor_8: Option<components::verify_bitwise_xor_8::Claim>,
+    pub verify_bitwise_xor_9: Option<components::verify_bitwise_xor_9::Claim>,
+    /// The claims of component families registered with the prover. Not supported.
+    pub extensions: Array<ExtensionClaim>,
     // ...
 }
 
+#[derive(Drop, Serde)]
+pub struct ExtensionClaim {
+    pub family_id: u32,
+    pub values: Array<u32>,
+}
+
+#[derive(Drop, Serde)]
+pub struct ExtensionInteractionClaim {
+    pub claimed_sums: Array<QM31>,
+}
+
 #[generate_trait]
 impl CairoClaimImpl of CairoClaimTrait {
     fn log_sizes(self: @CairoClaim) -> TreeArray<Span<u32>> {
-        let mut aggregated_log_sizes = utils::tree_array_concat_cols(
-            array![
-                self.opcodes.log_sizes(), self.verify_instruction.log_sizes(),
-                self.blake_context.log_sizes(), self.builtins.log_sizes(),
-                self.pedersen_context.log_sizes(), self.poseidon_context.log_sizes(),
-                self.memory_address_to_id.log_sizes(), self.memory_id_to_value.log_sizes(),
-                self.range_checks.log_sizes(), self.verify_bitwise_xor_4.log_sizes(),
-                self.verify_bitwise_xor_7.log_sizes(), self.verify_bitwise_xor_8.log_sizes(),
-                self.verify_bitwise_xor_9.log_sizes(),
-            ],
-        );
+        let mut log_sizes = array![self.opcodes.log_sizes()];
+        if let Some(claim) = self.verify_instruction {
+            log_sizes.append(claim.log_sizes());
+        }
+        log_sizes.append(self.blake_context.log_sizes());
+        log_sizes.append(self.builtins.log_sizes());
+        log_sizes.append(self.pedersen_context.log_sizes());
+        log_sizes.append(self.poseidon_context.log_sizes());
+        log_sizes.append(self.memory_address_to_id.log_sizes());
+        log_sizes.append(self.memory_id_to_value.log_sizes());
+        log_sizes.append(self.range_checks.log_sizes());
+        if let Some(claim) = self.verify_bitwise_xor_4 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.verify_bitwise_xor_7 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.verify_bitwise_xor_8 {
+            log_sizes.append(claim.log_sizes());
+        }
+        if let Some(claim) = self.verify_bitwise_xor_9 {
+            log_sizes.append(claim.log_sizes());
+        }
+        let mut aggregated_log_sizes = utils::tree_array_concat_cols(log_sizes);
 
         // Overwrite the preprocessed trace log sizes.
         let _invalid_preprocessed_trace_log_sizes = aggregated_log_sizes.pop_front();
@@ -1499,7 +1629,12 @@ impl CairoClaimImpl of CairoClaimTrait {
     fn mix_into(self: @CairoClaim, ref channel: Channel) {
         self.public_data.mix_into(ref channel);
         self.opcodes.mix_into(ref channel);
-        self.verify_instruction.mix_into(ref channel);
+        // Lookup tables are omitted when unused, and some of them have empty claims, so their
+        // presence is mixed explicitly.
+        mix_presence(self.verify_instruction, ref channel);
+        if let Some(claim) = self.verify_instruction {
+            claim.mix_into(ref channel);
+        }
         self.blake_context.mix_into(ref channel);
         self.builtins.mix_into(ref channel);
         self.pedersen_context.mix_into(ref channel);
@@ -1507,10 +1642,11 @@ impl CairoClaimImpl of CairoClaimTrait {
         self.memory_address_to_id.mix_into(ref channel);
         self.memory_id_to_value.mix_into(ref channel);
         self.range_checks.mix_into(ref channel);
-        self.verify_bitwise_xor_4.mix_into(ref channel);
-        self.verify_bitwise_xor_7.mix_into(ref channel);
-        self.verify_bitwise_xor_8.mix_into(ref channel);
-        self.verify_bitwise_xor_9.mix_into(ref channel);
+        mix_presence(self.verify_bitwise_xor_4, ref channel);
+        mix_presence(self.verify_bitwise_xor_7, ref channel);
+        mix_presence(self.verify_bitwise_xor_8, ref channel);
+        mix_presence(self.verify_bitwise_xor_9, ref channel);
+        channel.mix_u64(self.extensions.len().into());
     }
     fn accumulate_relation_uses(self: @CairoClaim, ref relation_uses: RelationUsesDict) {
         let CairoClaim {
@@ -1528,6 +1664,7 @@ impl CairoClaimImpl of CairoClaimTrait {
             verify_bitwise_xor_7: _,
             verify_bitwise_xor_8: _,
             verify_bitwise_xor_9: _,
+            extensions: _,
         } = self;
         // NOTE: The following components do not USE relations:
         // - range_checks
@@ -1540,11 +1677,13 @@ impl CairoClaimImpl of CairoClaimTrait {
         pedersen_context.accumulate_relation_uses(ref relation_uses);
         poseidon_context.accumulate_relation_uses(ref relation_uses);
 
-        accumulate_relation_uses(
-            ref relation_uses,
-            verify_instruction::RELATION_USES_PER_ROW.span(),
-            *verify_instruction.log_size,
-        );
+        if let Some(verify_instruction) = verify_instruction {
+            accumulate_relation_uses(
+                ref relation_uses,
+                verify_instruction::RELATION_USES_PER_ROW.span(),
+                *verify_instruction.log_size,
+            );
+        }
         for log_size in memory_id_to_value.big_log_sizes.span() {
             accumulate_relation_uses(
                 ref relation_uses, memory_id_to_big::RELATION_USES_PER_ROW_BIG.span(), *log_size,
@@ -1576,6 +1715,10 @@ fn verify_claim(claim: @CairoClaim) {
 
     verify_builtins(claim.builtins, public_segments);
 
+    // Component families registered with the prover are not supported.
+    assert!(claim.extensions.is_empty());
+    assert!(public_segments.extensions.is_empty());
+
     // Currently only bootloader context is supported.
     // TODO: Change that fact post MVP.
     public_segments.assert_bootloader_context();
@@ -1633,6 +1776,42 @@ fn verify_claim(claim: @CairoClaim) {
     assert!(largest_id < P_U32);
 }
 
+/// Lookup tables may be omitted from the claim, in which case they must also be omitted from the
+/// interaction claim, as their claimed sums would otherwise be added to the lookup sum without a
+/// component backing them.
+///
+/// # Panics
+///
+/// Panics if the presence of a table differs between the claim and the interaction claim.
+fn verify_lookup_tables(claim: @CairoClaim, interaction_claim: @CairoInteractionClaim) {
+    assert_same_presence(claim.verify_instruction, interaction_claim.verify_instruction);
+    assert_same_presence(claim.range_checks.rc_6, interaction_claim.range_checks.rc_6);
+    assert_same_presence(claim.range_checks.rc_8, interaction_claim.range_checks.rc_8);
+    assert_same_presence(claim.range_checks.rc_11, interaction_claim.range_checks.rc_11);
+    assert_same_presence(claim.range_checks.rc_12, interaction_claim.range_checks.rc_12);
+    assert_same_presence(claim.range_checks.rc_18, interaction_claim.range_checks.rc_18);
+    assert_same_presence(claim.range_checks.rc_19, interaction_claim.range_checks.rc_19);
+    assert_same_presence(claim.range_checks.rc_4_3, interaction_claim.range_checks.rc_4_3);
+    assert_same_presence(claim.range_checks.rc_4_4, interaction_claim.range_checks.rc_4_4);
+    assert_same_presence(claim.range_checks.rc_5_4, interaction_claim.range_checks.rc_5_4);
+    assert_same_presence(claim.range_checks.rc_9_9, interaction_claim.range_checks.rc_9_9);
+    assert_same_presence(claim.range_checks.rc_7_2_5, interaction_claim.range_checks.rc_7_2_5);
+    assert_same_presence(claim.range_checks.rc_3_6_6_3, interaction_claim.range_checks.rc_3_6_6_3);
+    assert_same_presence(claim.range_checks.rc_4_4_4_4, interaction_claim.range_checks.rc_4_4_4_4);
+    assert_same_presence(
+        claim.range_checks.rc_3_3_3_3_3, interaction_claim.range_checks.rc_3_3_3_3_3,
+    );
+    assert_same_presence(claim.verify_bitwise_xor_4, interaction_claim.verify_bitwise_xor_4);
+    assert_same_presence(claim.verify_bitwise_xor_7, interaction_claim.verify_bitwise_xor_7);
+    assert_same_presence(claim.verify_bitwise_xor_8, interaction_claim.verify_bitwise_xor_8);
+    assert_same_presence(claim.verify_bitwise_xor_9, interaction_claim.verify_bitwise_xor_9);
+    assert!(interaction_claim.extensions.is_empty());
+}
+
+fn assert_same_presence<C, I>(claim: @Option<C>, interaction_claim: @Option<I>) {
+    assert!(claim.is_some() == interaction_claim.is_some());
+}
+
 fn verify_builtins(builtins_claim: @BuiltinsClaim, segment_ranges: @PublicSegmentRanges) {
     // Check that non-supported builtins aren't used.
     if let Some(ec_op) = segment_ranges.ec_op {
@@ -1645,6 +1824,11 @@ fn verify_builtins(builtins_claim: @BuiltinsClaim, segment_ranges: @PublicSegmen
         assert!(keccak.start_ptr.value == keccak.stop_ptr.value);
     }
 
+    // A program can't gain gas.
+    if let Some(gas) = segment_ranges.gas {
+        assert!(gas.stop_ptr.value <= gas.start_ptr.value);
+    }
+
     // Output builtin.
     assert!(segment_ranges.output.stop_ptr.value <= @pow2(31));
     assert!(segment_ranges.output.start_ptr.value <= segment_ranges.output.stop_ptr.value);
@@ -1787,7 +1971,13 @@ fn verify_program(program: @MemorySection, public_segments: @PublicSegmentRanges
     let (_, program_value_4) = program[4];
     let (_, program_value_5) = program[5];
 
-    let n_builtins = public_segments.present_segments().len();
+    // The gas counter, if present, is allocated with the builtin pointers.
+    let n_builtins = public_segments.present_segments().len()
+        + if public_segments.gas.is_some() {
+            1
+        } else {
+            0
+        };
     assert!(program_value_0 == @[0x7fff7fff, 0x4078001, 0, 0, 0, 0, 0, 0]); // ap += N_BUILTINS.
     assert!(
         program_value_1 == @[n_builtins, 0, 0, 0, 0, 0, 0, 0],
@@ -1805,7 +1995,7 @@ fn verify_program(program: @MemorySection, public_segments: @PublicSegmentRanges
 #[derive(Drop, Serde)]
 pub struct CairoInteractionClaim {
     pub opcodes: OpcodeInteractionClaim,
-    pub verify_instruction: components::verify_instruction::InteractionClaim,
+    pub verify_instruction: Option<components::verify_instruction::InteractionClaim>,
     pub blake_context: BlakeContextInteractionClaim,
     pub builtins: BuiltinsInteractionClaim,
     pub pedersen_context: PedersenContextInteractionClaim,
@@ -1813,17 +2003,21 @@ pub struct CairoInteractionClaim {
     pub memory_address_to_id: components::memory_address_to_id::InteractionClaim,
     pub memory_id_to_value: components::memory_id_to_big::InteractionClaim,
     pub range_checks: RangeChecksInteractionClaim,
-    pub verify_bitwise_xor_4: components::verify_bitwise_xor_4::InteractionClaim,
-    pub verify_bitwise_xor_7: components::verify_bitwise_xor_7::InteractionClaim,
-    pub verify_bitwise_xor_8: components::verify_bitwise_xor_8::InteractionClaim,
-    pub verify_bitwise_xor_9: components::verify_bitwise_xor_9::InteractionClaim,
+    pub verify_bitwise_xor_4: Option<components::verify_bitwise_xor_4::InteractionClaim>,
+    pub verify_bitwise_xor_7: Option<components::verify_bitwise_xor_7::InteractionClaim>,
+    pub verify_bitwise_xor_8: Option<components::verify_bitwise_xor_8::InteractionClaim>,
+    pub verify_bitwise_xor_9: Option<components::verify_bitwise_xor_9::InteractionClaim>,
+    /// The interaction claims of component families registered with the prover. Not supported.
+    pub extensions: Array<ExtensionInteractionClaim>,
 }
 
 #[generate_trait]
 impl CairoInteractionClaimImpl of CairoInteractionClaimTrace {
     fn mix_into(self: @CairoInteractionClaim, ref channel: Channel) {
         self.opcodes.mix_into(ref channel);
-        self.verify_instruction.mix_into(ref channel);
+        if let Some(interaction_claim) = self.verify_instruction {
+            interaction_claim.mix_into(ref channel);
+        }
         self.blake_context.mix_into(ref channel);
         self.builtins.mix_into(ref channel);
         self.pedersen_context.mix_into(ref channel);
@@ -1831,10 +2025,18 @@ impl CairoInteractionClaimImpl of CairoInteractionClaimTrace {
         self.memory_address_to_id.mix_into(ref channel);
         self.memory_id_to_value.mix_into(ref channel);
         self.range_checks.mix_into(ref channel);
-        self.verify_bitwise_xor_4.mix_into(ref channel);
-        self.verify_bitwise_xor_7.mix_into(ref channel);
-        self.verify_bitwise_xor_8.mix_into(ref channel);
-        self.verify_bitwise_xor_9.mix_into(ref channel);
+        if let Some(interaction_claim) = self.verify_bitwise_xor_4 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.verify_bitwise_xor_7 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.verify_bitwise_xor_8 {
+            interaction_claim.mix_into(ref channel);
+        }
+        if let Some(interaction_claim) = self.verify_bitwise_xor_9 {
+            interaction_claim.mix_into(ref channel);
+        }
     }
 }
 
@@ -2095,7 +2297,7 @@ impl SegmentRangeImpl of SegmentRangeTrait {
     }
 }
 
-#[derive(Clone, Debug, Serde, Copy, Drop)]
+#[derive(Clone, Debug, Serde, Drop)]
 pub struct PublicSegmentRanges {
     pub output: SegmentRange,
     pub pedersen: Option<SegmentRange>,
@@ -2108,6 +2310,13 @@ pub struct PublicSegmentRanges {
     pub range_check_96: Option<SegmentRange>,
     pub add_mod: Option<SegmentRange>,
     pub mul_mod: Option<SegmentRange>,
+    /// The initial and remaining gas of gas-enabled programs. `main` takes the gas counter after
+    /// the builtin pointers, and returns the remaining gas before them. Serialized after the other
+    /// ranges, so proofs serialized without it don't deserialize.
+    pub gas: Option<SegmentRange>,
+    /// The segments of the component families registered with the prover, whose pointers `main`
+    /// takes after the builtin pointers and before the gas counter.
+    pub extensions: Array<SegmentRange>,
 }
 
 #[generate_trait]
@@ -2116,6 +2325,9 @@ impl PublicSegmentRangesImpl of PublicSegmentRangesTrait {
         for segment in self.present_segments() {
             segment.mix_into(ref channel);
         }
+        if let Some(gas) = self.gas {
+            gas.mix_into(ref channel);
+        }
     }
 
     fn present_segments(self: @PublicSegmentRanges) -> Array<@SegmentRange> {
@@ -2152,6 +2364,9 @@ impl PublicSegmentRangesImpl of PublicSegmentRangesTrait {
         if let Some(mul_mod) = self.mul_mod {
             segments.append(mul_mod);
         }
+        for extension in self.extensions.span() {
+            segments.append(extension);
+        }
         segments
     }
 
@@ -2262,6 +2477,24 @@ pub impl PublicMemoryImpl of PublicMemoryTrait {
                 );
             i += 1;
         }
+        if let Some(gas) = self.public_segments.gas {
+            entries
+                .append(
+                    (
+                        initial_ap + n_segments,
+                        *gas.start_ptr.id,
+                        [*gas.start_ptr.value, 0, 0, 0, 0, 0, 0, 0],
+                    ),
+                );
+            entries
+                .append(
+                    (
+                        final_ap - n_segments - 1,
+                        *gas.stop_ptr.id,
+                        [*gas.stop_ptr.value, 0, 0, 0, 0, 0, 0, 0],
+                    ),
+                );
+        }
 
         entries
     }
@@ -2781,6 +3014,15 @@ pub fn accumulate_relation_uses(
     }
 }
 
+/// Mixes whether an optional claim is present, as some claims are empty.
+fn mix_presence<T>(claim: @Option<T>, ref channel: Channel) {
+    if claim.is_some() {
+        channel.mix_u64(1);
+    } else {
+        channel.mix_u64(0);
+    }
+}
+
 #[derive(Drop, Debug)]
 pub enum CairoVerificationError {
     InteractionProofOfWork,
@@ -2792,7 +3034,7 @@ pub enum CairoVerificationError {
 #[derive(Drop)]
 pub struct CairoAir {
     opcodes: OpcodeComponents,
-    verify_instruction: components::verify_instruction::Component,
+    verify_instruction: Option<components::verify_instruction::Component>,
     blake_context: BlakeContextComponents,
     builtins: BuiltinComponents,
     pedersen_context: PedersenContextComponents,
@@ -2803,10 +3045,10 @@ pub struct CairoAir {
         components::memory_id_to_big::SmallComponent,
     ),
     range_checks: RangeChecksComponents,
-    verify_bitwise_xor_4: components::verify_bitwise_xor_4::Component,
-    verify_bitwise_xor_7: components::verify_bitwise_xor_7::Component,
-    verify_bitwise_xor_8: components::verify_bitwise_xor_8::Component,
-    verify_bitwise_xor_9: components::verify_bitwise_xor_9::Component,
+    verify_bitwise_xor_4: Option<components::verify_bitwise_xor_4::Component>,
+    verify_bitwise_xor_7: Option<components::verify_bitwise_xor_7::Component>,
+    verify_bitwise_xor_8: Option<components::verify_bitwise_xor_8::Component>,
+    verify_bitwise_xor_9: Option<components::verify_bitwise_xor_9::Component>,
 }
 
 #[generate_trait]
@@ -2836,15 +3078,33 @@ impl CairoAirNewImpl of CairoAirNewTrait {
             cairo_claim.poseidon_context, interaction_elements, interaction_claim.poseidon_context,
         );
 
-        let verifyinstruction_component = components::verify_instruction::Component {
-            claim: *cairo_claim.verify_instruction,
-            interaction_claim: *interaction_claim.verify_instruction,
-            memory_address_to_id_lookup_elements: interaction_elements.memory_address_to_id.clone(),
-            memory_id_to_big_lookup_elements: interaction_elements.memory_id_to_value.clone(),
-            range_check_4_3_lookup_elements: interaction_elements.range_checks.rc_4_3.clone(),
-            range_check_7_2_5_lookup_elements: interaction_elements.range_checks.rc_7_2_5.clone(),
-            verify_instruction_lookup_elements: interaction_elements.verify_instruction.clone(),
-        };
+        let mut verifyinstruction_component = Option::None;
+        if let Option::Some(claim) = cairo_claim.verify_instruction {
+            verifyinstruction_component =
+                Option::Some(
+                    components::verify_instruction::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.verify_instruction).unwrap(),
+                        memory_address_to_id_lookup_elements: interaction_elements
+                            .memory_address_to_id
+                            .clone(),
+                        memory_id_to_big_lookup_elements: interaction_elements
+                            .memory_id_to_value
+                            .clone(),
+                        range_check_4_3_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_4_3
+                            .clone(),
+                        range_check_7_2_5_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_7_2_5
+                            .clone(),
+                        verify_instruction_lookup_elements: interaction_elements
+                            .verify_instruction
+                            .clone(),
+                    },
+                );
+        }
 
         let memory_address_to_id_component = components::memory_address_to_id::Component {
             claim: *cairo_claim.memory_address_to_id,
@@ -2890,29 +3150,61 @@ impl CairoAirNewImpl of CairoAirNewTrait {
             cairo_claim.range_checks, interaction_elements, interaction_claim.range_checks,
         );
 
-        let verify_bitwise_xor_4_component = components::verify_bitwise_xor_4::Component {
-            claim: *cairo_claim.verify_bitwise_xor_4,
-            interaction_claim: *interaction_claim.verify_bitwise_xor_4,
-            verify_bitwise_xor_4_lookup_elements: interaction_elements.verify_bitwise_xor_4.clone(),
-        };
+        let mut verify_bitwise_xor_4_component = Option::None;
+        if let Option::Some(claim) = cairo_claim.verify_bitwise_xor_4 {
+            verify_bitwise_xor_4_component =
+                Option::Some(
+                    components::verify_bitwise_xor_4::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.verify_bitwise_xor_4).unwrap(),
+                        verify_bitwise_xor_4_lookup_elements: interaction_elements
+                            .verify_bitwise_xor_4
+                            .clone(),
+                    },
+                );
+        }
 
-        let verify_bitwise_xor_7_component = components::verify_bitwise_xor_7::Component {
-            claim: *cairo_claim.verify_bitwise_xor_7,
-            interaction_claim: *interaction_claim.verify_bitwise_xor_7,
-            verify_bitwise_xor_7_lookup_elements: interaction_elements.verify_bitwise_xor_7.clone(),
-        };
+        let mut verify_bitwise_xor_7_component = Option::None;
+        if let Option::Some(claim) = cairo_claim.verify_bitwise_xor_7 {
+            verify_bitwise_xor_7_component =
+                Option::Some(
+                    components::verify_bitwise_xor_7::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.verify_bitwise_xor_7).unwrap(),
+                        verify_bitwise_xor_7_lookup_elements: interaction_elements
+                            .verify_bitwise_xor_7
+                            .clone(),
+                    },
+                );
+        }
 
-        let verify_bitwise_xor_8_component = components::verify_bitwise_xor_8::Component {
-            claim: *cairo_claim.verify_bitwise_xor_8,
-            interaction_claim: *interaction_claim.verify_bitwise_xor_8,
-            verify_bitwise_xor_8_lookup_elements: interaction_elements.verify_bitwise_xor_8.clone(),
-        };
+        let mut verify_bitwise_xor_8_component = Option::None;
+        if let Option::Some(claim) = cairo_claim.verify_bitwise_xor_8 {
+            verify_bitwise_xor_8_component =
+                Option::Some(
+                    components::verify_bitwise_xor_8::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.verify_bitwise_xor_8).unwrap(),
+                        verify_bitwise_xor_8_lookup_elements: interaction_elements
+                            .verify_bitwise_xor_8
+                            .clone(),
+                    },
+                );
+        }
 
-        let verify_bitwise_xor_9_component = components::verify_bitwise_xor_9::Component {
-            claim: *cairo_claim.verify_bitwise_xor_9,
-            interaction_claim: *interaction_claim.verify_bitwise_xor_9,
-            verify_bitwise_xor_9_lookup_elements: interaction_elements.verify_bitwise_xor_9.clone(),
-        };
+        let mut verify_bitwise_xor_9_component = Option::None;
+        if let Option::Some(claim) = cairo_claim.verify_bitwise_xor_9 {
+            verify_bitwise_xor_9_component =
+                Option::Some(
+                    components::verify_bitwise_xor_9::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.verify_bitwise_xor_9).unwrap(),
+                        verify_bitwise_xor_9_lookup_elements: interaction_elements
+                            .verify_bitwise_xor_9
+                            .clone(),
+                    },
+                );
+        }
 
         CairoAir {
             opcodes: opcode_components,
@@ -2935,8 +3227,9 @@ impl CairoAirNewImpl of CairoAirNewTrait {
 impl CairoAirImpl of Air<CairoAir> {
     fn composition_log_degree_bound(self: @CairoAir) -> u32 {
         let mut max_degree = self.opcodes.max_constraint_log_degree_bound();
-        max_degree =
-            core::cmp::max(max_degree, self.verify_instruction.max_constraint_log_degree_bound());
+        if let Option::Some(component) = self.verify_instruction.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
         max_degree =
             core::cmp::max(max_degree, self.blake_context.max_constraint_log_degree_bound());
         max_degree = core::cmp::max(max_degree, self.builtins.max_constraint_log_degree_bound());
@@ -2957,14 +3250,18 @@ impl CairoAirImpl of Air<CairoAir> {
             core::cmp::max(max_degree, memory_id_to_value_small.max_constraint_log_degree_bound());
         max_degree =
             core::cmp::max(max_degree, self.range_checks.max_constraint_log_degree_bound());
-        max_degree =
-            core::cmp::max(max_degree, self.verify_bitwise_xor_4.max_constraint_log_degree_bound());
-        max_degree =
-            core::cmp::max(max_degree, self.verify_bitwise_xor_7.max_constraint_log_degree_bound());
-        max_degree =
-            core::cmp::max(max_degree, self.verify_bitwise_xor_8.max_constraint_log_degree_bound());
-        max_degree =
-            core::cmp::max(max_degree, self.verify_bitwise_xor_9.max_constraint_log_degree_bound());
+        if let Option::Some(component) = self.verify_bitwise_xor_4.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_7.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_8.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_9.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
         max_degree
     }
 
@@ -2982,14 +3279,15 @@ impl CairoAirImpl of Air<CairoAir> {
                 ref interaction_trace_mask_points,
                 point,
             );
-        self
-            .verify_instruction
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
+        if let Option::Some(component) = self.verify_instruction.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
         self
             .blake_context
             .mask_points(
@@ -3056,38 +3354,42 @@ impl CairoAirImpl of Air<CairoAir> {
                 ref interaction_trace_mask_points,
                 point,
             );
-        self
-            .verify_bitwise_xor_4
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .verify_bitwise_xor_7
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .verify_bitwise_xor_8
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .verify_bitwise_xor_9
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
+        if let Option::Some(component) = self.verify_bitwise_xor_4.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_7.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_8.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_9.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
 
         let preprocessed_trace_mask_points = preprocessed_trace_mask_points(
             preprocessed_column_set, point,
@@ -3129,16 +3431,17 @@ impl CairoAirImpl of Air<CairoAir> {
                 random_coeff,
                 point,
             );
-        self
-            .verify_instruction
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
+        if let Option::Some(component) = self.verify_instruction.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
         self
             .blake_context
             .evaluate_constraints_at_point(
@@ -3220,46 +3523,50 @@ impl CairoAirImpl of Air<CairoAir> {
                 random_coeff,
                 point,
             );
-        self
-            .verify_bitwise_xor_4
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .verify_bitwise_xor_7
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .verify_bitwise_xor_8
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .verify_bitwise_xor_9
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
+        if let Option::Some(component) = self.verify_bitwise_xor_4.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_7.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_8.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.verify_bitwise_xor_9.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
         sum
     }
 }
@@ -3294,20 +3601,20 @@ fn preprocessed_trace_mask_points(
 
 #[derive(Drop)]
 pub struct RangeChecksComponents {
-    rc_6: components::range_check_6::Component,
-    rc_8: components::range_check_8::Component,
-    rc_11: components::range_check_11::Component,
-    rc_12: components::range_check_12::Component,
-    rc_18: components::range_check_18::Component,
-    rc_19: components::range_check_19::Component,
-    rc_4_3: components::range_check_4_3::Component,
-    rc_4_4: components::range_check_4_4::Component,
-    rc_5_4: components::range_check_5_4::Component,
-    rc_9_9: components::range_check_9_9::Component,
-    rc_7_2_5: components::range_check_7_2_5::Component,
-    rc_3_6_6_3: components::range_check_3_6_6_3::Component,
-    rc_4_4_4_4: components::range_check_4_4_4_4::Component,
-    rc_3_3_3_3_3: components::range_check_3_3_3_3_3::Component,
+    rc_6: Option<components::range_check_6::Component>,
+    rc_8: Option<components::range_check_8::Component>,
+    rc_11: Option<components::range_check_11::Component>,
+    rc_12: Option<components::range_check_12::Component>,
+    rc_18: Option<components::range_check_18::Component>,
+    rc_19: Option<components::range_check_19::Component>,
+    rc_4_3: Option<components::range_check_4_3::Component>,
+    rc_4_4: Option<components::range_check_4_4::Component>,
+    rc_5_4: Option<components::range_check_5_4::Component>,
+    rc_9_9: Option<components::range_check_9_9::Component>,
+    rc_7_2_5: Option<components::range_check_7_2_5::Component>,
+    rc_3_6_6_3: Option<components::range_check_3_6_6_3::Component>,
+    rc_4_4_4_4: Option<components::range_check_4_4_4_4::Component>,
+    rc_3_3_3_3_3: Option<components::range_check_3_3_3_3_3::Component>,
 }
 
 #[generate_trait]
@@ -3317,382 +3624,579 @@ impl RangeChecksComponentsImpl of RangeChecksComponentsTrait {
         interaction_elements: @CairoInteractionElements,
         interaction_claim: @RangeChecksInteractionClaim,
     ) -> RangeChecksComponents {
-        RangeChecksComponents {
-            rc_6: components::range_check_6::Component {
-                claim: *claim.rc_6,
-                interaction_claim: *interaction_claim.rc_6,
-                range_check_6_lookup_elements: interaction_elements.range_checks.rc_6.clone(),
-            },
-            rc_8: components::range_check_8::Component {
-                claim: *claim.rc_8,
-                interaction_claim: *interaction_claim.rc_8,
-                range_check_8_lookup_elements: interaction_elements.range_checks.rc_8.clone(),
-            },
-            rc_11: components::range_check_11::Component {
-                claim: *claim.rc_11,
-                interaction_claim: *interaction_claim.rc_11,
-                range_check_11_lookup_elements: interaction_elements.range_checks.rc_11.clone(),
-            },
-            rc_12: components::range_check_12::Component {
-                claim: *claim.rc_12,
-                interaction_claim: *interaction_claim.rc_12,
-                range_check_12_lookup_elements: interaction_elements.range_checks.rc_12.clone(),
-            },
-            rc_18: components::range_check_18::Component {
-                claim: *claim.rc_18,
-                interaction_claim: *interaction_claim.rc_18,
-                range_check_18_lookup_elements: interaction_elements.range_checks.rc_18.clone(),
-            },
-            rc_19: components::range_check_19::Component {
-                claim: *claim.rc_19,
-                interaction_claim: *interaction_claim.rc_19,
-                range_check_19_lookup_elements: interaction_elements.range_checks.rc_19.clone(),
-            },
-            rc_4_3: components::range_check_4_3::Component {
-                claim: *claim.rc_4_3,
-                interaction_claim: *interaction_claim.rc_4_3,
-                range_check_4_3_lookup_elements: interaction_elements.range_checks.rc_4_3.clone(),
-            },
-            rc_4_4: components::range_check_4_4::Component {
-                claim: *claim.rc_4_4,
-                interaction_claim: *interaction_claim.rc_4_4,
-                range_check_4_4_lookup_elements: interaction_elements.range_checks.rc_4_4.clone(),
-            },
-            rc_5_4: components::range_check_5_4::Component {
-                claim: *claim.rc_5_4,
-                interaction_claim: *interaction_claim.rc_5_4,
-                range_check_5_4_lookup_elements: interaction_elements.range_checks.rc_5_4.clone(),
-            },
-            rc_9_9: components::range_check_9_9::Component {
-                claim: *claim.rc_9_9,
-                interaction_claim: *interaction_claim.rc_9_9,
-                range_check_9_9_lookup_elements: interaction_elements.range_checks.rc_9_9.clone(),
-            },
-            rc_7_2_5: components::range_check_7_2_5::Component {
-                claim: *claim.rc_7_2_5,
-                interaction_claim: *interaction_claim.rc_7_2_5,
-                range_check_7_2_5_lookup_elements: interaction_elements
-                    .range_checks
-                    .rc_7_2_5
-                    .clone(),
-            },
-            rc_3_6_6_3: components::range_check_3_6_6_3::Component {
-                claim: *claim.rc_3_6_6_3,
-                interaction_claim: *interaction_claim.rc_3_6_6_3,
-                range_check_3_6_6_3_lookup_elements: interaction_elements
-                    .range_checks
-                    .rc_3_6_6_3
-                    .clone(),
-            },
-            rc_4_4_4_4: components::range_check_4_4_4_4::Component {
-                claim: *claim.rc_4_4_4_4,
-                interaction_claim: *interaction_claim.rc_4_4_4_4,
-                range_check_4_4_4_4_lookup_elements: interaction_elements
-                    .range_checks
-                    .rc_4_4_4_4
-                    .clone(),
-            },
-            rc_3_3_3_3_3: components::range_check_3_3_3_3_3::Component {
-                claim: *claim.rc_3_3_3_3_3,
-                interaction_claim: *interaction_claim.rc_3_3_3_3_3,
-                range_check_3_3_3_3_3_lookup_elements: interaction_elements
-                    .range_checks
-                    .rc_3_3_3_3_3
-                    .clone(),
-            },
+        let mut rc_6_component = Option::None;
+        if let Option::Some(claim) = claim.rc_6 {
+            rc_6_component =
+                Option::Some(
+                    components::range_check_6::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_6).unwrap(),
+                        range_check_6_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_6
+                            .clone(),
+                    },
+                );
         }
-    }
 
-    fn mask_points(
-        self: @RangeChecksComponents,
-        ref preprocessed_column_set: PreprocessedColumnSet,
-        ref trace_mask_points: Array<Array<CirclePoint<QM31>>>,
-        ref interaction_trace_mask_points: Array<Array<CirclePoint<QM31>>>,
-        point: CirclePoint<QM31>,
-    ) {
-        self
-            .rc_6
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_8
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_11
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_12
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_18
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_19
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_4_3
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_4_4
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_5_4
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_9_9
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_7_2_5
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_3_6_6_3
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_4_4_4_4
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-        self
-            .rc_3_3_3_3_3
-            .mask_points(
-                ref preprocessed_column_set,
-                ref trace_mask_points,
-                ref interaction_trace_mask_points,
-                point,
-            );
-    }
+        let mut rc_8_component = Option::None;
+        if let Option::Some(claim) = claim.rc_8 {
+            rc_8_component =
+                Option::Some(
+                    components::range_check_8::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_8).unwrap(),
+                        range_check_8_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_8
+                            .clone(),
+                    },
+                );
+        }
 
-    fn max_constraint_log_degree_bound(self: @RangeChecksComponents) -> u32 {
-        let mut max_degree = 0;
-        max_degree = core::cmp::max(max_degree, self.rc_6.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_8.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_11.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_12.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_18.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_19.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_4_3.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_4_4.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_5_4.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_9_9.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_7_2_5.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_3_6_6_3.max_constraint_log_degree_bound());
-        max_degree = core::cmp::max(max_degree, self.rc_4_4_4_4.max_constraint_log_degree_bound());
-        max_degree =
-            core::cmp::max(max_degree, self.rc_3_3_3_3_3.max_constraint_log_degree_bound());
-        max_degree
+        let mut rc_11_component = Option::None;
+        if let Option::Some(claim) = claim.rc_11 {
+            rc_11_component =
+                Option::Some(
+                    components::range_check_11::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_11).unwrap(),
+                        range_check_11_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_11
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_12_component = Option::None;
+        if let Option::Some(claim) = claim.rc_12 {
+            rc_12_component =
+                Option::Some(
+                    components::range_check_12::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_12).unwrap(),
+                        range_check_12_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_12
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_18_component = Option::None;
+        if let Option::Some(claim) = claim.rc_18 {
+            rc_18_component =
+                Option::Some(
+                    components::range_check_18::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_18).unwrap(),
+                        range_check_18_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_18
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_19_component = Option::None;
+        if let Option::Some(claim) = claim.rc_19 {
+            rc_19_component =
+                Option::Some(
+                    components::range_check_19::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_19).unwrap(),
+                        range_check_19_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_19
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_4_3_component = Option::None;
+        if let Option::Some(claim) = claim.rc_4_3 {
+            rc_4_3_component =
+                Option::Some(
+                    components::range_check_4_3::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_4_3).unwrap(),
+                        range_check_4_3_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_4_3
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_4_4_component = Option::None;
+        if let Option::Some(claim) = claim.rc_4_4 {
+            rc_4_4_component =
+                Option::Some(
+                    components::range_check_4_4::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_4_4).unwrap(),
+                        range_check_4_4_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_4_4
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_5_4_component = Option::None;
+        if let Option::Some(claim) = claim.rc_5_4 {
+            rc_5_4_component =
+                Option::Some(
+                    components::range_check_5_4::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_5_4).unwrap(),
+                        range_check_5_4_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_5_4
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_9_9_component = Option::None;
+        if let Option::Some(claim) = claim.rc_9_9 {
+            rc_9_9_component =
+                Option::Some(
+                    components::range_check_9_9::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_9_9).unwrap(),
+                        range_check_9_9_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_9_9
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_7_2_5_component = Option::None;
+        if let Option::Some(claim) = claim.rc_7_2_5 {
+            rc_7_2_5_component =
+                Option::Some(
+                    components::range_check_7_2_5::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_7_2_5).unwrap(),
+                        range_check_7_2_5_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_7_2_5
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_3_6_6_3_component = Option::None;
+        if let Option::Some(claim) = claim.rc_3_6_6_3 {
+            rc_3_6_6_3_component =
+                Option::Some(
+                    components::range_check_3_6_6_3::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_3_6_6_3).unwrap(),
+                        range_check_3_6_6_3_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_3_6_6_3
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_4_4_4_4_component = Option::None;
+        if let Option::Some(claim) = claim.rc_4_4_4_4 {
+            rc_4_4_4_4_component =
+                Option::Some(
+                    components::range_check_4_4_4_4::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_4_4_4_4).unwrap(),
+                        range_check_4_4_4_4_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_4_4_4_4
+                            .clone(),
+                    },
+                );
+        }
+
+        let mut rc_3_3_3_3_3_component = Option::None;
+        if let Option::Some(claim) = claim.rc_3_3_3_3_3 {
+            rc_3_3_3_3_3_component =
+                Option::Some(
+                    components::range_check_3_3_3_3_3::Component {
+                        claim: *claim,
+                        interaction_claim: (*interaction_claim.rc_3_3_3_3_3).unwrap(),
+                        range_check_3_3_3_3_3_lookup_elements: interaction_elements
+                            .range_checks
+                            .rc_3_3_3_3_3
+                            .clone(),
+                    },
+                );
+        }
+
+        RangeChecksComponents {
+            rc_6: rc_6_component,
+            rc_8: rc_8_component,
+            rc_11: rc_11_component,
+            rc_12: rc_12_component,
+            rc_18: rc_18_component,
+            rc_19: rc_19_component,
+            rc_4_3: rc_4_3_component,
+            rc_4_4: rc_4_4_component,
+            rc_5_4: rc_5_4_component,
+            rc_9_9: rc_9_9_component,
+            rc_7_2_5: rc_7_2_5_component,
+            rc_3_6_6_3: rc_3_6_6_3_component,
+            rc_4_4_4_4: rc_4_4_4_4_component,
+            rc_3_3_3_3_3: rc_3_3_3_3_3_component,
+        }
     }
 
-    fn evaluate_constraints_at_point(
+    fn mask_points(
         self: @RangeChecksComponents,
-        ref sum: QM31,
-        ref preprocessed_mask_values: PreprocessedMaskValues,
-        ref trace_mask_values: ColumnSpan<Span<QM31>>,
-        ref interaction_trace_mask_values: ColumnSpan<Span<QM31>>,
-        random_coeff: QM31,
+        ref preprocessed_column_set: PreprocessedColumnSet,
+        ref trace_mask_points: Array<Array<CirclePoint<QM31>>>,
+        ref interaction_trace_mask_points: Array<Array<CirclePoint<QM31>>>,
         point: CirclePoint<QM31>,
     ) {
-        self
-            .rc_6
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_8
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_11
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_12
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_18
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_19
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_4_3
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_4_4
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_5_4
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_9_9
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_7_2_5
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_3_6_6_3
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_4_4_4_4
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
-        self
-            .rc_3_3_3_3_3
-            .evaluate_constraints_at_point(
-                ref sum,
-                ref preprocessed_mask_values,
-                ref trace_mask_values,
-                ref interaction_trace_mask_values,
-                random_coeff,
-                point,
-            );
+        if let Option::Some(component) = self.rc_6.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_8.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_11.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_12.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_18.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_19.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_4_3.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_4_4.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_5_4.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_9_9.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_7_2_5.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_3_6_6_3.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_4_4_4_4.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_3_3_3_3_3.as_snap() {
+            component
+                .mask_points(
+                    ref preprocessed_column_set,
+                    ref trace_mask_points,
+                    ref interaction_trace_mask_points,
+                    point,
+                );
+        }
+    }
+
+    fn max_constraint_log_degree_bound(self: @RangeChecksComponents) -> u32 {
+        let mut max_degree = 0;
+        if let Option::Some(component) = self.rc_6.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_8.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_11.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_12.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_18.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_19.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_4_3.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_4_4.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_5_4.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_9_9.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_7_2_5.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_3_6_6_3.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_4_4_4_4.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        if let Option::Some(component) = self.rc_3_3_3_3_3.as_snap() {
+            max_degree = core::cmp::max(max_degree, component.max_constraint_log_degree_bound());
+        }
+        max_degree
+    }
+
+    fn evaluate_constraints_at_point(
+        self: @RangeChecksComponents,
+        ref sum: QM31,
+        ref preprocessed_mask_values: PreprocessedMaskValues,
+        ref trace_mask_values: ColumnSpan<Span<QM31>>,
+        ref interaction_trace_mask_values: ColumnSpan<Span<QM31>>,
+        random_coeff: QM31,
+        point: CirclePoint<QM31>,
+    ) {
+        if let Option::Some(component) = self.rc_6.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_8.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_11.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_12.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_18.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_19.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_4_3.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_4_4.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_5_4.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_9_9.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_7_2_5.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_3_6_6_3.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_4_4_4_4.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
+        if let Option::Some(component) = self.rc_3_3_3_3_3.as_snap() {
+            component
+                .evaluate_constraints_at_point(
+                    ref sum,
+                    ref preprocessed_mask_values,
+                    ref trace_mask_values,
+                    ref interaction_trace_mask_values,
+                    random_coeff,
+                    point,
+                );
+        }
     }
 }
 